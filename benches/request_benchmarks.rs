@@ -61,10 +61,10 @@ fn benchmark_json_parsing(c: &mut Criterion) {
 fn benchmark_http_method_from_str(c: &mut Criterion) {
     c.bench_function("http_method_from_str", |b| {
         b.iter(|| {
-            black_box(HttpMethod::parse(black_box("GET")));
-            black_box(HttpMethod::parse(black_box("POST")));
-            black_box(HttpMethod::parse(black_box("PUT")));
-            black_box(HttpMethod::parse(black_box("DELETE")));
+            let _ = black_box(HttpMethod::parse(black_box("GET")));
+            let _ = black_box(HttpMethod::parse(black_box("POST")));
+            let _ = black_box(HttpMethod::parse(black_box("PUT")));
+            let _ = black_box(HttpMethod::parse(black_box("DELETE")));
         });
     });
 }
@@ -1,6 +1,7 @@
 //! Assertion definitions and results
 
-use crate::assertions::matcher::Matcher;
+use crate::assertions::diff::diff;
+use crate::assertions::matcher::{Matcher, MatcherType};
 use serde::{Deserialize, Serialize};
 
 /// Type of assertion
@@ -21,12 +22,40 @@ pub enum AssertionType {
     /// Assert on JSON path value
     JsonPath(String),
 
-    /// Custom assertion with description
+    /// Assert on a Server-Timing metric duration (ms)
+    ServerTiming(String),
+
+    /// Assert on the value of a cookie set by the response
+    Cookie(String),
+
+    /// Rhai boolean expression over the response (needs the `scripts` feature)
     Custom(String),
+
+    /// Assert on the body size in bytes
+    BodySize,
+
+    /// Assert on the Content-Type media type, without parameters like `charset`
+    ContentType,
+
+    /// The body parses as JSON
+    ValidJson,
+
+    /// The body parses as XML
+    ValidXml,
+}
+
+impl AssertionType {
+    /// Whether the assertion is a yes/no check with no expected value to show
+    pub fn is_check(&self) -> bool {
+        matches!(
+            self,
+            AssertionType::Custom(_) | AssertionType::ValidJson | AssertionType::ValidXml
+        )
+    }
 }
 
 /// An assertion to validate
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Assertion {
     /// Type of assertion
     pub assertion_type: AssertionType,
@@ -35,12 +64,18 @@ pub struct Assertion {
     pub matcher: Matcher,
 
     /// Description (optional)
+    #[serde(default)]
     pub description: Option<String>,
 
     /// Whether assertion is enabled
+    #[serde(default = "default_enabled")]
     pub enabled: bool,
 }
 
+fn default_enabled() -> bool {
+    true
+}
+
 impl Assertion {
     /// Create a new assertion
     pub fn new(assertion_type: AssertionType, matcher: Matcher) -> Self {
@@ -77,6 +112,53 @@ impl Assertion {
         Self::new(AssertionType::JsonPath(path), matcher)
     }
 
+    /// Assert Server-Timing metric duration
+    pub fn server_timing(metric: String, matcher: Matcher) -> Self {
+        Self::new(AssertionType::ServerTiming(metric), matcher)
+    }
+
+    /// Assert a cookie value from the response's Set-Cookie headers
+    pub fn cookie(name: String, matcher: Matcher) -> Self {
+        Self::new(AssertionType::Cookie(name), matcher)
+    }
+
+    /// Assert a Rhai boolean expression, e.g. `json.items.len() > 0 && status == 200`
+    ///
+    /// `status`, `headers`, `body`, `json` and `duration_ms` are in scope.
+    pub fn custom_expr(expression: impl Into<String>) -> Self {
+        let expression = expression.into();
+        Self::new(
+            AssertionType::Custom(expression.clone()),
+            Matcher::equals_str("true"),
+        )
+        .with_description(expression)
+    }
+
+    /// Assert the body size in bytes
+    pub fn body_size(matcher: Matcher) -> Self {
+        Self::new(AssertionType::BodySize, matcher)
+    }
+
+    /// Assert the media type, e.g. `application/json` also matches
+    /// `application/json; charset=utf-8` (case-insensitive)
+    pub fn content_type(media_type: &str) -> Self {
+        let media_type = media_type.trim().to_ascii_lowercase();
+        Self::new(AssertionType::ContentType, Matcher::equals_str(&media_type))
+            .with_description(format!("content-type == {}", media_type))
+    }
+
+    /// Assert the body is valid JSON
+    pub fn is_valid_json() -> Self {
+        Self::new(AssertionType::ValidJson, Matcher::equals_str("true"))
+            .with_description("valid JSON".to_string())
+    }
+
+    /// Assert the body is well-formed XML
+    pub fn is_valid_xml() -> Self {
+        Self::new(AssertionType::ValidXml, Matcher::equals_str("true"))
+            .with_description("valid XML".to_string())
+    }
+
     /// Set description
     pub fn with_description(mut self, description: String) -> Self {
         self.description = Some(description);
@@ -132,12 +214,38 @@ impl AssertionResult {
         }
     }
 
+    /// Diff between the expected and actual value of a failed equality check
+    ///
+    /// JSON values are compared key by key, naming each mismatched field;
+    /// anything else gets a line diff. `None` for passing results and for
+    /// matchers other than equals.
+    pub fn detailed_diff(&self) -> Option<String> {
+        if self.passed
+            || self.assertion.matcher.matcher_type != MatcherType::Equals
+            || self.assertion.assertion_type.is_check()
+        {
+            return None;
+        }
+        let lines = diff(&self.assertion.matcher.expected, &self.actual_value);
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+
     /// Get a summary of the result
     pub fn summary(&self) -> String {
         let desc = self.assertion.description.as_deref().unwrap_or("Assertion");
 
         if self.passed {
             format!("✓ {}: PASS", desc)
+        } else if let (true, Some(message)) = (
+            self.assertion.assertion_type.is_check(),
+            &self.error_message,
+        ) {
+            // A check has no expected value worth showing, only why it failed
+            format!("✗ {}: FAIL - {}", desc, message)
         } else {
             format!(
                 "✗ {}: FAIL - Expected {}, got {}",
@@ -191,6 +299,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_assertion_server_timing() {
+        let assertion = Assertion::server_timing("db".to_string(), Matcher::less_than(50));
+        assert_eq!(
+            assertion.assertion_type,
+            AssertionType::ServerTiming("db".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convenience_assertions() {
+        let content_type = Assertion::content_type("Application/JSON ");
+        assert_eq!(content_type.assertion_type, AssertionType::ContentType);
+        assert_eq!(content_type.matcher.expected, "application/json");
+        assert_eq!(
+            content_type.description.as_deref(),
+            Some("content-type == application/json")
+        );
+
+        let size = Assertion::body_size(Matcher::less_than(1024));
+        assert_eq!(size.assertion_type, AssertionType::BodySize);
+
+        // Workflow steps store assertions, so the new types must round-trip
+        for assertion in [
+            content_type,
+            size,
+            Assertion::is_valid_json(),
+            Assertion::is_valid_xml(),
+        ] {
+            let json = serde_json::to_string(&assertion).unwrap();
+            assert_eq!(serde_json::from_str::<Assertion>(&json).unwrap(), assertion);
+        }
+        assert!(AssertionType::ValidXml.is_check());
+        assert!(!AssertionType::BodySize.is_check());
+    }
+
     #[test]
     fn test_assertion_with_description() {
         let assertion = Assertion::status_code(Matcher::equals(200))
@@ -270,4 +414,39 @@ mod tests {
         assert_eq!(assertion.assertion_type, deserialized.assertion_type);
         assert_eq!(assertion.description, deserialized.description);
     }
+
+    #[test]
+    fn test_detailed_diff_names_nested_field() {
+        let expected = r#"{"user":{"name":"Ada","roles":["admin"]}}"#;
+        let actual = r#"{"user":{"name":"Ada","roles":["viewer"]}}"#;
+        let result = AssertionResult::fail(
+            Assertion::body(Matcher::equals_str(expected)),
+            actual.to_string(),
+            "equals".to_string(),
+            "Body does not match".to_string(),
+        );
+
+        let diff = result.detailed_diff().unwrap();
+        assert!(diff.contains("$.user.roles[0]"));
+        assert!(diff.contains(r#"expected "admin", got "viewer""#));
+        assert!(!diff.contains("name"));
+    }
+
+    #[test]
+    fn test_detailed_diff_only_for_failed_equality() {
+        let passed = AssertionResult::pass(
+            Assertion::body(Matcher::equals_str("a")),
+            "a".to_string(),
+            "equals 'a'".to_string(),
+        );
+        assert!(passed.detailed_diff().is_none());
+
+        let contains = AssertionResult::fail(
+            Assertion::body(Matcher::contains("a".to_string())),
+            "b".to_string(),
+            "contains 'a'".to_string(),
+            "Body does not match".to_string(),
+        );
+        assert!(contains.detailed_diff().is_none());
+    }
 }
@@ -0,0 +1,234 @@
+//! Diffs between expected and actual values of failed assertions
+
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Longest value shown on one diff line
+const MAX_VALUE_LEN: usize = 80;
+
+/// Largest line-count product diffed line by line; bigger inputs are shown whole
+const MAX_LCS_CELLS: usize = 1_000_000;
+
+/// Diff two values, key by key when both are JSON and line by line otherwise
+///
+/// Returns one line per difference, or an empty list if the values are equal.
+pub fn diff(expected: &str, actual: &str) -> Vec<String> {
+    if let (Ok(expected_json), Ok(actual_json)) = (
+        serde_json::from_str::<Value>(expected),
+        serde_json::from_str::<Value>(actual),
+    ) {
+        let is_structured = |v: &Value| v.is_object() || v.is_array();
+        if is_structured(&expected_json) || is_structured(&actual_json) {
+            let mut lines = Vec::new();
+            json_diff("$", &expected_json, &actual_json, &mut lines);
+            if !lines.is_empty() {
+                return lines;
+            }
+        }
+    }
+    text_diff(expected, actual)
+}
+
+/// Compare two JSON values, naming each mismatched field by its path
+pub fn json_diff(path: &str, expected: &Value, actual: &Value, out: &mut Vec<String>) {
+    let mut changes = Vec::new();
+    json_changes(path, expected, actual, &mut changes);
+    out.extend(changes.into_iter().map(|change| match change {
+        JsonChange {
+            path,
+            old: Some(e),
+            new: Some(a),
+        } => format!("~ {}: expected {}, got {}", path, show(&e), show(&a)),
+        JsonChange {
+            path, old: Some(e), ..
+        } => format!("- {}: missing (expected {})", path, show(&e)),
+        JsonChange { path, new: a, .. } => {
+            format!("+ {}: unexpected {}", path, show(&a.unwrap_or(Value::Null)))
+        }
+    }));
+}
+
+/// One difference between two JSON documents
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonChange {
+    /// Path of the value, e.g. `$.items[2].price`
+    pub path: String,
+
+    /// Value on the old side (`None` if added)
+    pub old: Option<Value>,
+
+    /// Value on the new side (`None` if removed)
+    pub new: Option<Value>,
+}
+
+/// Collect every differing leaf between two JSON values, in key order
+pub fn json_changes(path: &str, old: &Value, new: &Value, out: &mut Vec<JsonChange>) {
+    let change = |path: String, old: Option<&Value>, new: Option<&Value>| JsonChange {
+        path,
+        old: old.cloned(),
+        new: new.cloned(),
+    };
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                let child = child_path(path, key);
+                match (old.get(key), new.get(key)) {
+                    (Some(o), Some(n)) => json_changes(&child, o, n, out),
+                    (o, n) => out.push(change(child, o, n)),
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for i in 0..old.len().max(new.len()) {
+                let child = format!("{}[{}]", path, i);
+                match (old.get(i), new.get(i)) {
+                    (Some(o), Some(n)) => json_changes(&child, o, n, out),
+                    (o, n) => out.push(change(child, o, n)),
+                }
+            }
+        }
+        _ if old != new => out.push(change(path.to_string(), Some(old), Some(new))),
+        _ => {}
+    }
+}
+
+/// Line diff with one line of context around each change
+pub fn text_diff(expected: &str, actual: &str) -> Vec<String> {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    if expected.len() * actual.len() > MAX_LCS_CELLS {
+        return expected
+            .iter()
+            .map(|line| format!("- {}", line))
+            .chain(actual.iter().map(|line| format!("+ {}", line)))
+            .collect();
+    }
+
+    // lcs[i][j] is the common subsequence length of expected[i..] and actual[j..]
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            ops.push(('=', expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', expected[i]));
+            i += 1;
+        } else {
+            ops.push(('+', actual[j]));
+            j += 1;
+        }
+    }
+
+    if ops.iter().all(|(op, _)| *op == '=') {
+        return Vec::new();
+    }
+
+    let changed = |k: usize| ops.get(k).is_some_and(|(op, _)| *op != '=');
+    let mut lines = Vec::new();
+    let mut skipped = false;
+    for (k, (op, line)) in ops.iter().enumerate() {
+        if *op != '=' {
+            lines.push(format!("{} {}", op, line));
+            skipped = false;
+        } else if changed(k + 1) || (k > 0 && changed(k - 1)) {
+            lines.push(format!("  {}", line));
+            skipped = false;
+        } else if !skipped {
+            lines.push("  ...".to_string());
+            skipped = true;
+        }
+    }
+    lines
+}
+
+/// Path of an object member, bracketed when the key isn't a plain identifier
+fn child_path(path: &str, key: &str) -> String {
+    let plain = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}[{}]", path, Value::String(key.to_string()))
+    }
+}
+
+/// Compact JSON for a diff line, truncated if long
+pub fn show(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() > MAX_VALUE_LEN {
+        let truncated: String = text.chars().take(MAX_VALUE_LEN).collect();
+        format!("{}…", truncated)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_diff_names_nested_field() {
+        let expected = r#"{"user":{"id":1,"address":{"city":"Paris","zip":"75001"}},"ok":true}"#;
+        let actual = r#"{"ok":true,"user":{"id":1,"address":{"city":"London","zip":"75001"}}}"#;
+
+        assert_eq!(
+            diff(expected, actual),
+            vec![r#"~ $.user.address.city: expected "Paris", got "London""#]
+        );
+    }
+
+    #[test]
+    fn test_json_diff_missing_and_extra() {
+        let expected = r#"{"a":1,"items":[1,2,3],"my key":null}"#;
+        let actual = r#"{"a":1,"items":[1,5],"b":true}"#;
+
+        assert_eq!(
+            diff(expected, actual),
+            vec![
+                "+ $.b: unexpected true",
+                "~ $.items[1]: expected 2, got 5",
+                "- $.items[2]: missing (expected 3)",
+                r#"- $["my key"]: missing (expected null)"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_equal_json_with_different_formatting_falls_back_to_text() {
+        let lines = diff(r#"{"a": 1}"#, r#"{"a":1}"#);
+        assert_eq!(lines, vec![r#"- {"a": 1}"#, r#"+ {"a":1}"#]);
+    }
+
+    #[test]
+    fn test_text_diff_with_context() {
+        let expected = "one\ntwo\nthree\nfour\nfive\nsix";
+        let actual = "one\ntwo\nthree\nFOUR\nfive\nsix";
+
+        assert_eq!(
+            text_diff(expected, actual),
+            vec!["  ...", "  three", "- four", "+ FOUR", "  five", "  ..."]
+        );
+    }
+
+    #[test]
+    fn test_text_diff_single_line() {
+        assert_eq!(text_diff("hello", "world"), vec!["- hello", "+ world"]);
+        assert!(text_diff("same", "same").is_empty());
+    }
+}
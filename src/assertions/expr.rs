@@ -0,0 +1,381 @@
+//! Mini expression syntax for assertions (e.g. `status == 200`)
+
+use crate::assertions::{Assertion, AssertionType, Matcher, MatcherType};
+use crate::error::{Error, Result};
+
+/// Comparison operators, longest first so `>=` wins over `>`
+const OPERATORS: &[(&str, MatcherType)] = &[
+    ("==", MatcherType::Equals),
+    ("!=", MatcherType::NotEquals),
+    ("~=", MatcherType::Contains),
+    ("!~", MatcherType::NotContains),
+    (">=", MatcherType::GreaterThanOrEqual),
+    ("<=", MatcherType::LessThanOrEqual),
+    (">", MatcherType::GreaterThan),
+    ("<", MatcherType::LessThan),
+];
+
+/// Split `lhs <op> value` into its target and a matcher
+pub fn parse_comparison(expr: &str) -> Result<(String, Matcher)> {
+    let (position, symbol, matcher_type) = find_operator(expr).ok_or_else(|| {
+        invalid(
+            expr,
+            "expected one of ==, !=, ~=, !~, >=, <=, >, < between target and value",
+        )
+    })?;
+
+    let target = expr[..position].trim();
+    let value = unquote(expr[position + symbol.len()..].trim());
+    if target.is_empty() {
+        return Err(invalid(expr, "missing left-hand side"));
+    }
+
+    Ok((target.to_string(), Matcher::new(matcher_type, value)))
+}
+
+/// Split `target exists` or `target absent` into its target and a matcher
+fn parse_presence(expr: &str) -> Option<(String, Matcher)> {
+    if find_operator(expr).is_some() {
+        return None;
+    }
+
+    let (target, keyword) = expr.trim().rsplit_once(char::is_whitespace)?;
+    let matcher = if keyword.eq_ignore_ascii_case("exists") {
+        Matcher::exists()
+    } else if keyword.eq_ignore_ascii_case("absent") {
+        Matcher::not_exists()
+    } else {
+        return None;
+    };
+    let target = target.trim();
+    (!target.is_empty()).then(|| (target.to_string(), matcher))
+}
+
+/// Parse a full assertion expression
+///
+/// Targets: `status`, `time` (ms), `body`, `size` (bytes), `content_type`
+/// (media type), `$.json.path`, `header <name>`, `cookie <name>`,
+/// `server_timing <metric>`. Headers, cookies and JSON paths can also be
+/// checked with `<target> exists` or `<target> absent`, `valid json` and
+/// `valid xml` check the body parses, and `expr <rhai>` is a Rhai boolean
+/// expression over the response.
+pub fn parse_assertion(expr: &str) -> Result<Assertion> {
+    if let Some(expression) = strip_keyword(expr.trim(), "expr") {
+        return Ok(Assertion::custom_expr(expression));
+    }
+    if let Some(format) = strip_keyword(expr.trim(), "valid") {
+        if format.eq_ignore_ascii_case("json") {
+            return Ok(Assertion::is_valid_json());
+        } else if format.eq_ignore_ascii_case("xml") {
+            return Ok(Assertion::is_valid_xml());
+        }
+    }
+
+    let (target, matcher) = match parse_presence(expr) {
+        Some(presence) => presence,
+        None => parse_comparison(expr)?,
+    };
+    let lower = target.to_ascii_lowercase();
+
+    let assertion = if lower == "status" {
+        Assertion::status_code(matcher)
+    } else if lower == "time" || lower == "duration" || lower == "response_time" {
+        Assertion::response_time(matcher)
+    } else if lower == "body" {
+        Assertion::body(matcher)
+    } else if lower == "size" || lower == "body_size" {
+        Assertion::body_size(matcher)
+    } else if lower == "content_type" || lower == "content-type" {
+        Assertion::new(AssertionType::ContentType, matcher)
+    } else if target.starts_with('$') {
+        Assertion::json_path(target.clone(), matcher)
+    } else if let Some(name) = strip_keyword(&target, "header") {
+        Assertion::header(name, matcher)
+    } else if let Some(name) = strip_keyword(&target, "cookie") {
+        Assertion::cookie(name, matcher)
+    } else if let Some(metric) =
+        strip_keyword(&target, "server_timing").or_else(|| strip_keyword(&target, "server-timing"))
+    {
+        Assertion::server_timing(metric, matcher)
+    } else {
+        return Err(invalid(
+            expr,
+            &format!(
+                "unknown target '{}' (use status, time, body, size, content_type, $.path, header <name>, cookie <name> or server_timing <metric>)",
+                target
+            ),
+        ));
+    };
+
+    Ok(assertion.with_description(expr.trim().to_string()))
+}
+
+/// Parse a header check such as `content-type~=json`
+///
+/// A bare name checks the header is present and `!name` that it is absent.
+pub fn parse_header_assertion(expr: &str) -> Result<Assertion> {
+    let trimmed = expr.trim();
+    if find_operator(trimmed).is_none() {
+        let (name, matcher, description) = match trimmed.strip_prefix('!') {
+            Some(name) => (name.trim(), Matcher::not_exists(), "absent"),
+            None => (trimmed, Matcher::exists(), "exists"),
+        };
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(invalid(expr, "expected a header name, or name<op>value"));
+        }
+        return Ok(Assertion::header(name.to_string(), matcher)
+            .with_description(format!("header {} {}", name, description)));
+    }
+
+    let (name, matcher) = parse_comparison(expr)?;
+    Ok(Assertion::header(name, matcher).with_description(format!("header {}", trimmed)))
+}
+
+/// Parse a JSONPath check such as `$.count>=1`
+pub fn parse_json_path_assertion(expr: &str) -> Result<Assertion> {
+    let (path, matcher) = parse_comparison(expr)?;
+    if !path.starts_with('$') {
+        return Err(invalid(expr, "JSONPath must start with '$'"));
+    }
+    Ok(Assertion::json_path(path, matcher).with_description(expr.trim().to_string()))
+}
+
+/// Find the first operator outside quotes
+fn find_operator(expr: &str) -> Option<(usize, &'static str, MatcherType)> {
+    let mut in_quotes = false;
+
+    for (i, c) in expr.char_indices() {
+        if c == '"' || c == '\'' {
+            in_quotes = !in_quotes;
+            continue;
+        }
+        if in_quotes {
+            continue;
+        }
+        for (symbol, matcher_type) in OPERATORS {
+            if expr[i..].starts_with(symbol) {
+                return Some((i, symbol, matcher_type.clone()));
+            }
+        }
+    }
+
+    None
+}
+
+/// Strip `keyword <name>` or `keyword:<name>` and return the name
+fn strip_keyword(target: &str, keyword: &str) -> Option<String> {
+    let prefix = target.get(..keyword.len())?;
+    if !prefix.eq_ignore_ascii_case(keyword) {
+        return None;
+    }
+
+    let rest = &target[keyword.len()..];
+    let name = rest
+        .strip_prefix(':')
+        .or_else(|| rest.strip_prefix(char::is_whitespace))?
+        .trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Remove matching surrounding quotes
+fn unquote(value: &str) -> String {
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted {
+        value[1..value.len() - 1].to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn invalid(expr: &str, reason: &str) -> Error {
+    Error::InvalidCommand(format!("Invalid assertion '{}': {}", expr, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_comparison_operators() {
+        let cases = [
+            ("a == 1", MatcherType::Equals, "1"),
+            ("a != 1", MatcherType::NotEquals, "1"),
+            ("a ~= json", MatcherType::Contains, "json"),
+            ("a !~ error", MatcherType::NotContains, "error"),
+            ("a >= 1", MatcherType::GreaterThanOrEqual, "1"),
+            ("a <= 1", MatcherType::LessThanOrEqual, "1"),
+            ("a>1", MatcherType::GreaterThan, "1"),
+            ("a < 1", MatcherType::LessThan, "1"),
+        ];
+
+        for (expr, matcher_type, expected) in cases {
+            let (target, matcher) = parse_comparison(expr).unwrap();
+            assert_eq!(target, "a", "{}", expr);
+            assert_eq!(matcher.matcher_type, matcher_type, "{}", expr);
+            assert_eq!(matcher.expected, expected, "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_parse_comparison_quoted_value() {
+        let (target, matcher) = parse_comparison(r#"body ~= "a == b""#).unwrap();
+        assert_eq!(target, "body");
+        assert_eq!(matcher.expected, "a == b");
+    }
+
+    #[test]
+    fn test_parse_comparison_missing_operator() {
+        assert!(parse_comparison("status 200").is_err());
+        assert!(parse_comparison("== 200").is_err());
+    }
+
+    #[test]
+    fn test_parse_assertion_targets() {
+        let status = parse_assertion("status == 200").unwrap();
+        assert_eq!(status.assertion_type, AssertionType::StatusCode);
+        assert_eq!(status.description.as_deref(), Some("status == 200"));
+
+        let time = parse_assertion("time < 800").unwrap();
+        assert_eq!(time.assertion_type, AssertionType::ResponseTime);
+
+        let json = parse_assertion("$.count >= 1").unwrap();
+        assert_eq!(
+            json.assertion_type,
+            AssertionType::JsonPath("$.count".to_string())
+        );
+
+        let cookie = parse_assertion("cookie session != \"\"").unwrap();
+        assert_eq!(
+            cookie.assertion_type,
+            AssertionType::Cookie("session".to_string())
+        );
+
+        let header = parse_assertion("header content-type ~= json").unwrap();
+        assert_eq!(
+            header.assertion_type,
+            AssertionType::Header("content-type".to_string())
+        );
+
+        let timing = parse_assertion("server_timing db < 50").unwrap();
+        assert_eq!(
+            timing.assertion_type,
+            AssertionType::ServerTiming("db".to_string())
+        );
+
+        assert!(parse_assertion("nonsense == 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_header_assertion() {
+        let cases = [
+            ("content-type~=json", MatcherType::Contains, "json"),
+            ("x-cache == HIT", MatcherType::Equals, "HIT"),
+            ("server!=nginx", MatcherType::NotEquals, "nginx"),
+            ("content-length>=10", MatcherType::GreaterThanOrEqual, "10"),
+            ("age<=60", MatcherType::LessThanOrEqual, "60"),
+        ];
+
+        for (expr, matcher_type, expected) in cases {
+            let assertion = parse_header_assertion(expr).unwrap();
+            assert!(
+                matches!(assertion.assertion_type, AssertionType::Header(_)),
+                "{}",
+                expr
+            );
+            assert_eq!(assertion.matcher.matcher_type, matcher_type, "{}", expr);
+            assert_eq!(assertion.matcher.expected, expected, "{}", expr);
+        }
+
+        let assertion = parse_header_assertion("content-type~=json").unwrap();
+        assert_eq!(
+            assertion.assertion_type,
+            AssertionType::Header("content-type".to_string())
+        );
+        assert!(parse_header_assertion("").is_err());
+        assert!(parse_header_assertion("content type").is_err());
+    }
+
+    #[test]
+    fn test_parse_custom_expression() {
+        let assertion = parse_assertion("expr json.items.len() > 0 && status == 200").unwrap();
+        assert_eq!(
+            assertion.assertion_type,
+            AssertionType::Custom("json.items.len() > 0 && status == 200".to_string())
+        );
+        assert!(parse_assertion("expr").is_err());
+    }
+
+    #[test]
+    fn test_parse_body_checks() {
+        let size = parse_assertion("size < 1024").unwrap();
+        assert_eq!(size.assertion_type, AssertionType::BodySize);
+        assert_eq!(size.matcher.matcher_type, MatcherType::LessThan);
+
+        let content_type = parse_assertion("content_type == application/json").unwrap();
+        assert_eq!(content_type.assertion_type, AssertionType::ContentType);
+
+        assert_eq!(
+            parse_assertion("valid JSON").unwrap().assertion_type,
+            AssertionType::ValidJson
+        );
+        assert_eq!(
+            parse_assertion("valid xml").unwrap().assertion_type,
+            AssertionType::ValidXml
+        );
+        assert!(parse_assertion("valid yaml").is_err());
+    }
+
+    #[test]
+    fn test_parse_header_presence() {
+        let present = parse_header_assertion("X-Request-Id").unwrap();
+        assert_eq!(
+            present.assertion_type,
+            AssertionType::Header("X-Request-Id".to_string())
+        );
+        assert_eq!(present.matcher.matcher_type, MatcherType::Exists);
+        assert_eq!(
+            present.description.as_deref(),
+            Some("header X-Request-Id exists")
+        );
+
+        let absent = parse_header_assertion("!server").unwrap();
+        assert_eq!(absent.matcher.matcher_type, MatcherType::NotExists);
+
+        let absent = parse_assertion("header X-Debug absent").unwrap();
+        assert_eq!(
+            absent.assertion_type,
+            AssertionType::Header("X-Debug".to_string())
+        );
+        assert_eq!(absent.matcher.matcher_type, MatcherType::NotExists);
+
+        let exists = parse_assertion("$.id exists").unwrap();
+        assert_eq!(exists.matcher.matcher_type, MatcherType::Exists);
+        let contains = parse_assertion("header x ~= exists").unwrap();
+        assert_eq!(contains.matcher.matcher_type, MatcherType::Contains);
+    }
+
+    #[test]
+    fn test_parse_json_path_assertion() {
+        let assertion = parse_json_path_assertion("$.count>=1").unwrap();
+        assert_eq!(
+            assertion.assertion_type,
+            AssertionType::JsonPath("$.count".to_string())
+        );
+        assert_eq!(
+            assertion.matcher.matcher_type,
+            MatcherType::GreaterThanOrEqual
+        );
+        assert_eq!(assertion.matcher.expected, "1");
+
+        let assertion = parse_json_path_assertion("$.user.name == 'Ada'").unwrap();
+        assert_eq!(assertion.matcher.matcher_type, MatcherType::Equals);
+        assert_eq!(assertion.matcher.expected, "Ada");
+
+        let assertion = parse_json_path_assertion("$.status != failed").unwrap();
+        assert_eq!(assertion.matcher.matcher_type, MatcherType::NotEquals);
+
+        assert!(parse_json_path_assertion("count>=1").is_err());
+    }
+}
@@ -1,6 +1,8 @@
 //! Matchers for assertion validation
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
 /// Type of matcher
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -26,6 +28,9 @@ pub enum MatcherType {
     /// Matches regex
     Regex,
 
+    /// Does not match regex
+    NotRegex,
+
     /// Less than (numeric)
     LessThan,
 
@@ -38,6 +43,18 @@ pub enum MatcherType {
     /// Greater than or equal
     GreaterThanOrEqual,
 
+    /// Numerically equal within `tolerance`
+    ApproxEquals,
+
+    /// Numerically within `expected..=upper`
+    Between,
+
+    /// RFC 3339 timestamp before the expected one ("now" is the current time)
+    DateBefore,
+
+    /// RFC 3339 timestamp after the expected one ("now" is the current time)
+    DateAfter,
+
     /// Is empty
     IsEmpty,
 
@@ -52,16 +69,37 @@ pub enum MatcherType {
 
     /// Is not null
     IsNotNull,
+
+    /// Is present, whatever its value (even empty)
+    Exists,
+
+    /// Is absent
+    NotExists,
+
+    /// Every element of a JSON array matches `inner`
+    Each,
 }
 
 /// A matcher for validating values
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Matcher {
     /// Type of matcher
     pub matcher_type: MatcherType,
 
-    /// Expected value (stringified)
+    /// Expected value (stringified); the lower bound for `Between`
     pub expected: String,
+
+    /// Allowed difference for `ApproxEquals`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance: Option<f64>,
+
+    /// Inclusive upper bound for `Between`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upper: Option<String>,
+
+    /// Matcher applied to each element for `Each`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner: Option<Box<Matcher>>,
 }
 
 impl Matcher {
@@ -70,6 +108,9 @@ impl Matcher {
         Self {
             matcher_type,
             expected,
+            tolerance: None,
+            upper: None,
+            inner: None,
         }
     }
 
@@ -118,6 +159,11 @@ impl Matcher {
         Self::new(MatcherType::Regex, pattern)
     }
 
+    /// Negative regex matcher
+    pub fn not_regex(pattern: String) -> Self {
+        Self::new(MatcherType::NotRegex, pattern)
+    }
+
     /// Less than matcher
     pub fn less_than(value: i64) -> Self {
         Self::new(MatcherType::LessThan, value.to_string())
@@ -138,6 +184,32 @@ impl Matcher {
         Self::new(MatcherType::GreaterThanOrEqual, value.to_string())
     }
 
+    /// Approximately equals matcher, for fractional values
+    pub fn approx_equals(value: f64, tolerance: f64) -> Self {
+        Self {
+            tolerance: Some(tolerance.abs()),
+            ..Self::new(MatcherType::ApproxEquals, value.to_string())
+        }
+    }
+
+    /// Inclusive numeric range matcher
+    pub fn between(min: f64, max: f64) -> Self {
+        Self {
+            upper: Some(max.to_string()),
+            ..Self::new(MatcherType::Between, min.to_string())
+        }
+    }
+
+    /// Timestamp before matcher (RFC 3339, or "now")
+    pub fn date_before(timestamp: &str) -> Self {
+        Self::new(MatcherType::DateBefore, timestamp.to_string())
+    }
+
+    /// Timestamp after matcher (RFC 3339, or "now")
+    pub fn date_after(timestamp: &str) -> Self {
+        Self::new(MatcherType::DateAfter, timestamp.to_string())
+    }
+
     /// Is empty matcher
     pub fn is_empty() -> Self {
         Self::new(MatcherType::IsEmpty, String::new())
@@ -163,9 +235,37 @@ impl Matcher {
         Self::new(MatcherType::IsNotNull, String::new())
     }
 
+    /// Exists matcher
+    pub fn exists() -> Self {
+        Self::new(MatcherType::Exists, String::new())
+    }
+
+    /// Not exists matcher
+    pub fn not_exists() -> Self {
+        Self::new(MatcherType::NotExists, String::new())
+    }
+
+    /// Matcher for every element of a JSON array
+    pub fn each(inner: Box<Matcher>) -> Self {
+        Self {
+            inner: Some(inner),
+            ..Self::new(MatcherType::Each, String::new())
+        }
+    }
+
     /// Test if actual value matches expected
+    ///
+    /// Values that can't be compared (see [`Matcher::evaluate`]) don't match.
     pub fn matches(&self, actual: &str) -> bool {
-        match self.matcher_type {
+        self.evaluate(actual).unwrap_or(false)
+    }
+
+    /// Test if actual value matches expected, explaining values that can't be compared
+    ///
+    /// Ordering matchers need numbers (or timestamps); `ApproxEquals` and
+    /// `Between` need numbers. Anything else is an `Err` describing the problem.
+    pub fn evaluate(&self, actual: &str) -> std::result::Result<bool, String> {
+        let matched = match self.matcher_type {
             MatcherType::Equals => actual == self.expected,
             MatcherType::NotEquals => actual != self.expected,
             MatcherType::Contains => actual.contains(&self.expected),
@@ -179,34 +279,30 @@ impl Matcher {
                     false
                 }
             }
-            MatcherType::LessThan => {
-                if let (Ok(a), Ok(e)) = (actual.parse::<i64>(), self.expected.parse::<i64>()) {
-                    a < e
-                } else {
-                    false
-                }
-            }
-            MatcherType::LessThanOrEqual => {
-                if let (Ok(a), Ok(e)) = (actual.parse::<i64>(), self.expected.parse::<i64>()) {
-                    a <= e
+            MatcherType::NotRegex => {
+                if let Ok(re) = regex::Regex::new(&self.expected) {
+                    !re.is_match(actual)
                 } else {
-                    false
+                    true
                 }
             }
-            MatcherType::GreaterThan => {
-                if let (Ok(a), Ok(e)) = (actual.parse::<i64>(), self.expected.parse::<i64>()) {
-                    a > e
-                } else {
-                    false
-                }
+            MatcherType::LessThan => self.compare(actual)? == Ordering::Less,
+            MatcherType::LessThanOrEqual => self.compare(actual)? != Ordering::Greater,
+            MatcherType::GreaterThan => self.compare(actual)? == Ordering::Greater,
+            MatcherType::GreaterThanOrEqual => self.compare(actual)? != Ordering::Less,
+            MatcherType::ApproxEquals => {
+                let actual = parse_number(actual, "actual value")?;
+                let expected = parse_number(&self.expected, "expected value")?;
+                (actual - expected).abs() <= self.tolerance.unwrap_or(f64::EPSILON)
             }
-            MatcherType::GreaterThanOrEqual => {
-                if let (Ok(a), Ok(e)) = (actual.parse::<i64>(), self.expected.parse::<i64>()) {
-                    a >= e
-                } else {
-                    false
-                }
+            MatcherType::Between => {
+                let actual = parse_number(actual, "actual value")?;
+                let min = parse_number(&self.expected, "lower bound")?;
+                let max = parse_number(self.upper.as_deref().unwrap_or_default(), "upper bound")?;
+                (min..=max).contains(&actual)
             }
+            MatcherType::DateBefore => self.compare_dates(actual) == Some(Ordering::Less),
+            MatcherType::DateAfter => self.compare_dates(actual) == Some(Ordering::Greater),
             MatcherType::IsEmpty => actual.is_empty(),
             MatcherType::IsNotEmpty => !actual.is_empty(),
             MatcherType::HasLength => {
@@ -218,9 +314,101 @@ impl Matcher {
             }
             MatcherType::IsNull => actual.is_empty() || actual == "null",
             MatcherType::IsNotNull => !actual.is_empty() && actual != "null",
+            MatcherType::Exists => true,
+            MatcherType::NotExists => false,
+            MatcherType::Each => {
+                return Err(format!("actual value '{}' is not an array", actual));
+            }
+        };
+        Ok(matched)
+    }
+
+    /// Like [`Matcher::evaluate`], for a JSON value
+    ///
+    /// On arrays, `HasLength` and the ordering matchers compare the length,
+    /// `Contains` and `NotContains` test membership of a scalar, and `Each`
+    /// applies the inner matcher to every element. Anything else is matched
+    /// against the value as text.
+    pub fn evaluate_json(&self, actual: &serde_json::Value) -> std::result::Result<bool, String> {
+        let serde_json::Value::Array(items) = actual else {
+            return self.evaluate(&json_text(actual));
+        };
+
+        let member = || items.iter().any(|item| json_text(item) == self.expected);
+        match self.matcher_type {
+            MatcherType::HasLength => Ok(self.expected.parse() == Ok(items.len())),
+            MatcherType::LessThan
+            | MatcherType::LessThanOrEqual
+            | MatcherType::GreaterThan
+            | MatcherType::GreaterThanOrEqual => self.evaluate(&items.len().to_string()),
+            MatcherType::Contains => Ok(member()),
+            MatcherType::NotContains => Ok(!member()),
+            MatcherType::IsEmpty => Ok(items.is_empty()),
+            MatcherType::IsNotEmpty => Ok(!items.is_empty()),
+            MatcherType::Each => Ok(self.failing_elements(items).is_empty()),
+            _ => self.evaluate(&actual.to_string()),
         }
     }
 
+    /// Indices of the elements the inner matcher of `Each` rejects
+    ///
+    /// Elements it can't compare count as rejected.
+    pub fn failing_elements(&self, items: &[serde_json::Value]) -> Vec<usize> {
+        let Some(inner) = &self.inner else {
+            return Vec::new();
+        };
+        items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| inner.evaluate_json(item) != Ok(true))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Like [`Matcher::evaluate`], for a value that may be missing
+    ///
+    /// `Exists` and `NotExists` only look at presence; other matchers
+    /// treat a missing value as empty.
+    pub fn evaluate_present(&self, actual: Option<&str>) -> std::result::Result<bool, String> {
+        match (actual, &self.matcher_type) {
+            (Some(actual), _) => self.evaluate(actual),
+            (None, MatcherType::Exists) => Ok(false),
+            (None, MatcherType::NotExists) => Ok(true),
+            (None, _) => self.evaluate(""),
+        }
+    }
+
+    /// Order the actual value against the expected one, as numbers or else as timestamps
+    fn compare(&self, actual: &str) -> std::result::Result<Ordering, String> {
+        if let (Ok(a), Ok(e)) = (
+            actual.trim().parse::<f64>(),
+            self.expected.trim().parse::<f64>(),
+        ) {
+            return a
+                .partial_cmp(&e)
+                .ok_or_else(|| format!("cannot compare {} with {}", actual, self.expected));
+        }
+        if let Some(ordering) = self.compare_dates(actual) {
+            return Ok(ordering);
+        }
+
+        let unorderable =
+            |value: &str| value.trim().parse::<f64>().is_err() && parse_timestamp(value).is_none();
+        let culprit = if unorderable(actual) {
+            format!("actual value '{}'", actual)
+        } else if unorderable(&self.expected) {
+            format!("expected value '{}'", self.expected)
+        } else {
+            format!("'{}' and '{}'", actual, self.expected)
+        };
+        Err(format!("{} is not a number or RFC 3339 timestamp", culprit))
+    }
+
+    /// Order the actual timestamp against the expected one
+    fn compare_dates(&self, actual: &str) -> Option<Ordering> {
+        Some(parse_timestamp(actual)?.cmp(&parse_timestamp(&self.expected)?))
+    }
+
     /// Get description of what this matcher expects
     pub fn description(&self) -> String {
         match self.matcher_type {
@@ -231,19 +419,65 @@ impl Matcher {
             MatcherType::StartsWith => format!("starts with '{}'", self.expected),
             MatcherType::EndsWith => format!("ends with '{}'", self.expected),
             MatcherType::Regex => format!("matches regex '{}'", self.expected),
+            MatcherType::NotRegex => format!("does not match regex '{}'", self.expected),
             MatcherType::LessThan => format!("< {}", self.expected),
             MatcherType::LessThanOrEqual => format!("<= {}", self.expected),
             MatcherType::GreaterThan => format!("> {}", self.expected),
             MatcherType::GreaterThanOrEqual => format!(">= {}", self.expected),
+            MatcherType::ApproxEquals => format!(
+                "≈ {} (±{})",
+                self.expected,
+                self.tolerance.unwrap_or(f64::EPSILON)
+            ),
+            MatcherType::Between => format!(
+                "between {} and {}",
+                self.expected,
+                self.upper.as_deref().unwrap_or("?")
+            ),
+            MatcherType::DateBefore => format!("before {}", self.expected),
+            MatcherType::DateAfter => format!("after {}", self.expected),
             MatcherType::IsEmpty => "is empty".to_string(),
             MatcherType::IsNotEmpty => "is not empty".to_string(),
             MatcherType::HasLength => format!("has length {}", self.expected),
             MatcherType::IsNull => "is null".to_string(),
             MatcherType::IsNotNull => "is not null".to_string(),
+            MatcherType::Exists => "is present".to_string(),
+            MatcherType::NotExists => "is not present".to_string(),
+            MatcherType::Each => match &self.inner {
+                Some(inner) => format!("each {}", inner.description()),
+                None => "each (no matcher)".to_string(),
+            },
         }
     }
 }
 
+/// A JSON value as matchers see it: strings unquoted, everything else as JSON
+pub fn json_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Parse a number, naming `what` in the error
+fn parse_number(value: &str, what: &str) -> std::result::Result<f64, String> {
+    value
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("{} '{}' is not a number", what, value))
+}
+
+/// Parse an RFC 3339 timestamp, or "now" for the current time
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("now") {
+        return Some(Utc::now());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,6 +540,23 @@ mod tests {
         assert!(!matcher.matches("2000"));
     }
 
+    #[test]
+    fn test_matcher_not_regex() {
+        let matcher = Matcher::not_regex(r"(?i)error|exception".to_string());
+        assert!(matcher.matches(r#"{"ok":true}"#));
+        assert!(!matcher.matches("Internal Server Error"));
+        assert_eq!(
+            matcher.description(),
+            "does not match regex '(?i)error|exception'"
+        );
+    }
+
+    #[test]
+    fn test_matcher_invalid_regex() {
+        assert!(!Matcher::regex("([".to_string()).matches("anything"));
+        assert!(Matcher::not_regex("([".to_string()).matches("anything"));
+    }
+
     #[test]
     fn test_matcher_less_than() {
         let matcher = Matcher::less_than(1000);
@@ -314,6 +565,13 @@ mod tests {
         assert!(!matcher.matches("1000"));
     }
 
+    #[test]
+    fn test_matcher_less_than_fractional() {
+        let matcher = Matcher::less_than(50);
+        assert!(matcher.matches("12.3"));
+        assert!(!matcher.matches("50.1"));
+    }
+
     #[test]
     fn test_matcher_less_than_or_equal() {
         let matcher = Matcher::less_than_or_equal(1000);
@@ -375,6 +633,21 @@ mod tests {
         assert!(!matcher.matches("null"));
     }
 
+    #[test]
+    fn test_matcher_presence() {
+        let exists = Matcher::exists();
+        assert_eq!(exists.evaluate_present(Some("")), Ok(true));
+        assert_eq!(exists.evaluate_present(Some("abc")), Ok(true));
+        assert_eq!(exists.evaluate_present(None), Ok(false));
+
+        let not_exists = Matcher::not_exists();
+        assert_eq!(not_exists.evaluate_present(Some("")), Ok(false));
+        assert_eq!(not_exists.evaluate_present(None), Ok(true));
+
+        assert_eq!(Matcher::is_empty().evaluate_present(None), Ok(true));
+        assert_eq!(exists.description(), "is present");
+    }
+
     #[test]
     fn test_matcher_description() {
         assert_eq!(Matcher::equals(200).description(), "equals '200'");
@@ -395,4 +668,105 @@ mod tests {
         assert_eq!(matcher.matcher_type, deserialized.matcher_type);
         assert_eq!(matcher.expected, deserialized.expected);
     }
+
+    #[test]
+    fn test_matcher_date_before_and_after() {
+        let earlier = "2024-01-02T03:04:05Z";
+        let later = "2024-01-02T05:04:05+01:00";
+
+        assert!(Matcher::date_before(later).matches(earlier));
+        assert!(!Matcher::date_before(earlier).matches(later));
+        assert!(Matcher::date_after(earlier).matches(later));
+        assert!(!Matcher::date_after(later).matches(earlier));
+        assert!(!Matcher::date_after(earlier).matches(earlier));
+        assert!(!Matcher::date_after(earlier).matches("yesterday"));
+    }
+
+    #[test]
+    fn test_matcher_date_relative_to_now() {
+        assert!(Matcher::date_after("now").matches("2999-01-01T00:00:00Z"));
+        assert!(Matcher::date_before("now").matches("2000-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_numeric_matchers_compare_timestamps() {
+        let expires_at = "2030-06-01T12:00:00.500Z";
+        assert!(
+            Matcher::new(MatcherType::GreaterThan, "2030-06-01T12:00:00Z".to_string())
+                .matches(expires_at)
+        );
+        assert!(
+            Matcher::new(MatcherType::LessThan, "2030-06-01T12:00:01Z".to_string())
+                .matches(expires_at)
+        );
+        assert!(
+            Matcher::new(MatcherType::GreaterThanOrEqual, expires_at.to_string())
+                .matches(expires_at)
+        );
+        assert!(!Matcher::new(MatcherType::LessThan, "1".to_string()).matches(expires_at));
+    }
+
+    #[test]
+    fn test_matcher_fractional_comparisons() {
+        let price = Matcher::new(MatcherType::LessThan, "19.99".to_string());
+        assert!(price.matches("19.5"));
+        assert!(!price.matches("19.99"));
+        assert!(Matcher::new(MatcherType::GreaterThanOrEqual, "0.5".to_string()).matches("0.5"));
+    }
+
+    #[test]
+    fn test_matcher_approx_equals() {
+        let matcher = Matcher::approx_equals(0.3, 0.001);
+        assert!(matcher.matches(&(0.1 + 0.2).to_string()));
+        assert!(matcher.matches("0.3009"));
+        assert!(!matcher.matches("0.302"));
+        assert_eq!(matcher.description(), "≈ 0.3 (±0.001)");
+    }
+
+    #[test]
+    fn test_matcher_between() {
+        let matcher = Matcher::between(1.5, 3.0);
+        assert!(matcher.matches("1.5"));
+        assert!(matcher.matches("2"));
+        assert!(matcher.matches("3"));
+        assert!(!matcher.matches("3.01"));
+        assert!(!matcher.matches("1"));
+        assert_eq!(matcher.description(), "between 1.5 and 3");
+    }
+
+    #[test]
+    fn test_matcher_unparseable_numbers_explain_why() {
+        let err = Matcher::between(1.0, 2.0).evaluate("abc").unwrap_err();
+        assert_eq!(err, "actual value 'abc' is not a number");
+
+        let err = Matcher::new(MatcherType::ApproxEquals, "x".to_string())
+            .evaluate("1")
+            .unwrap_err();
+        assert_eq!(err, "expected value 'x' is not a number");
+
+        let err = Matcher::less_than(10).evaluate("ten").unwrap_err();
+        assert_eq!(
+            err,
+            "actual value 'ten' is not a number or RFC 3339 timestamp"
+        );
+        assert!(!Matcher::less_than(10).matches("ten"));
+    }
+
+    #[test]
+    fn test_numeric_matcher_serialization_round_trip() {
+        for matcher in [
+            Matcher::approx_equals(19.99, 0.01),
+            Matcher::between(100.0, 250.5),
+            Matcher::less_than(800),
+        ] {
+            let json = serde_json::to_string(&matcher).unwrap();
+            let deserialized: Matcher = serde_json::from_str(&json).unwrap();
+            assert_eq!(deserialized, matcher, "{}", json);
+        }
+
+        // Matchers saved before these fields existed still load
+        let legacy: Matcher =
+            serde_json::from_str(r#"{"matcher_type":"LessThan","expected":"5"}"#).unwrap();
+        assert_eq!(legacy, Matcher::less_than(5));
+    }
 }
@@ -1,11 +1,16 @@
 //! Request and response assertion system
 
 pub mod assertion;
+pub mod diff;
+pub mod expr;
 pub mod matcher;
+pub mod snapshot;
 pub mod validator;
 
 pub use assertion::{Assertion, AssertionResult, AssertionType};
+pub use expr::{parse_assertion, parse_header_assertion, parse_json_path_assertion};
 pub use matcher::{Matcher, MatcherType};
+pub use snapshot::{Snapshot, SnapshotOutcome, SnapshotRules, SnapshotStore};
 pub use validator::{ResponseValidator, ValidationReport};
 
 use crate::error::Result;
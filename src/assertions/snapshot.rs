@@ -0,0 +1,452 @@
+//! Golden-file snapshots of responses
+//!
+//! The first check of a named snapshot saves a normalized copy of the
+//! response; later checks diff against it and fail on any change.
+
+use crate::assertions::diff::{json_diff, text_diff};
+use crate::assertions::validator::{parse_json_path, PathStep};
+use crate::error::{Error, Result};
+use crate::http::HttpResponse;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Headers kept in a snapshot when no others are selected
+pub const DEFAULT_SNAPSHOT_HEADERS: &[&str] = &["content-type"];
+
+/// Placeholder for values hidden by an ignore rule
+pub const IGNORED_VALUE: &str = "<ignored>";
+
+/// What a snapshot keeps and what it leaves out
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotRules {
+    /// Header names to keep (case-insensitive); `DEFAULT_SNAPSHOT_HEADERS` if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<String>,
+
+    /// JSON paths of volatile values, e.g. `$.id` or `$.items[*].created_at`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+}
+
+impl SnapshotRules {
+    /// Create empty rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep a header
+    pub fn with_header(mut self, name: impl Into<String>) -> Self {
+        self.headers.push(name.into());
+        self
+    }
+
+    /// Ignore the values at a JSON path
+    pub fn with_ignore(mut self, path: impl Into<String>) -> Self {
+        self.ignore.push(path.into());
+        self
+    }
+
+    /// Add the other rules' headers and paths that aren't here yet
+    pub fn merge(&mut self, other: &SnapshotRules) {
+        for header in &other.headers {
+            if !self.headers.iter().any(|h| h.eq_ignore_ascii_case(header)) {
+                self.headers.push(header.clone());
+            }
+        }
+        for path in &other.ignore {
+            if !self.ignore.contains(path) {
+                self.ignore.push(path.clone());
+            }
+        }
+    }
+}
+
+/// A normalized response: status, selected headers, and body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Status code
+    pub status: u16,
+
+    /// Selected headers by lowercase name, repeated headers joined with ", "
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Parsed JSON body, or the body text if it isn't JSON
+    pub body: Value,
+
+    /// Rules the snapshot was taken with
+    #[serde(default)]
+    pub rules: SnapshotRules,
+}
+
+impl Snapshot {
+    /// Normalize a response under the given rules
+    pub fn capture(response: &HttpResponse, rules: &SnapshotRules) -> Self {
+        let wanted: Vec<String> = if rules.headers.is_empty() {
+            DEFAULT_SNAPSHOT_HEADERS
+                .iter()
+                .map(|h| h.to_string())
+                .collect()
+        } else {
+            rules.headers.iter().map(|h| h.to_lowercase()).collect()
+        };
+
+        let mut headers: BTreeMap<String, String> = BTreeMap::new();
+        for (name, value) in &response.headers {
+            let name = name.as_str().to_lowercase();
+            if !wanted.contains(&name) {
+                continue;
+            }
+            let value = value.to_str().unwrap_or_default();
+            headers
+                .entry(name)
+                .and_modify(|v| {
+                    v.push_str(", ");
+                    v.push_str(value);
+                })
+                .or_insert_with(|| value.to_string());
+        }
+
+        let mut body = serde_json::from_str(&response.body)
+            .unwrap_or_else(|_| Value::String(response.body.clone()));
+        for path in &rules.ignore {
+            if let Some(steps) = parse_json_path(path) {
+                mask_steps(&mut body, &steps);
+            }
+        }
+
+        Self {
+            status: response.status.as_u16(),
+            headers,
+            body,
+            rules: rules.clone(),
+        }
+    }
+
+    /// Differences from this (expected) snapshot to an actual one, one per line
+    ///
+    /// Empty if they match. Rules aren't compared.
+    pub fn diff(&self, actual: &Snapshot) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.status != actual.status {
+            lines.push(format!(
+                "~ status: expected {}, got {}",
+                self.status, actual.status
+            ));
+        }
+
+        let names: std::collections::BTreeSet<&String> =
+            self.headers.keys().chain(actual.headers.keys()).collect();
+        for name in names {
+            match (self.headers.get(name), actual.headers.get(name)) {
+                (Some(e), Some(a)) if e != a => {
+                    lines.push(format!("~ header {}: expected {:?}, got {:?}", name, e, a))
+                }
+                (Some(e), None) => {
+                    lines.push(format!("- header {}: missing (expected {:?})", name, e))
+                }
+                (None, Some(a)) => lines.push(format!("+ header {}: unexpected {:?}", name, a)),
+                _ => {}
+            }
+        }
+
+        match (&self.body, &actual.body) {
+            (Value::String(expected), Value::String(actual)) => {
+                lines.extend(text_diff(expected, actual))
+            }
+            (expected, actual) => json_diff("$", expected, actual, &mut lines),
+        }
+        lines
+    }
+}
+
+/// Replace every value a path reaches with `IGNORED_VALUE`
+fn mask_steps(value: &mut Value, steps: &[PathStep]) {
+    let Some((step, rest)) = steps.split_first() else {
+        *value = Value::String(IGNORED_VALUE.to_string());
+        return;
+    };
+
+    match (step, value) {
+        (PathStep::Key(key), Value::Object(map)) => {
+            if let Some(child) = map.get_mut(*key) {
+                mask_steps(child, rest);
+            }
+        }
+        (PathStep::Index(index), Value::Array(items)) => {
+            if let Some(child) = items.get_mut(*index) {
+                mask_steps(child, rest);
+            }
+        }
+        (PathStep::All, Value::Array(items)) => {
+            for item in items {
+                mask_steps(item, rest);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Result of checking a response against its snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotOutcome {
+    /// No snapshot existed; the response was saved
+    Created,
+
+    /// The snapshot was overwritten (`--update-snapshots`)
+    Updated,
+
+    /// The response matches the snapshot
+    Matched,
+
+    /// The response differs; one diff line per change
+    Changed(Vec<String>),
+}
+
+impl SnapshotOutcome {
+    /// Whether the check passed
+    pub fn passed(&self) -> bool {
+        !matches!(self, SnapshotOutcome::Changed(_))
+    }
+}
+
+/// Snapshots on disk, one JSON file per name
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+    update: bool,
+}
+
+impl SnapshotStore {
+    /// Create a store in a directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            update: false,
+        }
+    }
+
+    /// Open the store in the default data directory
+    pub fn open_default() -> Result<Self> {
+        Ok(Self::new(Self::default_path()?))
+    }
+
+    /// Default snapshot directory
+    pub fn default_path() -> Result<PathBuf> {
+        let dirs = directories::ProjectDirs::from("com", "bazzoun", "bazzounquester").ok_or_else(
+            || {
+                Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Could not determine data directory",
+                ))
+            },
+        )?;
+
+        Ok(dirs.data_dir().join("snapshots"))
+    }
+
+    /// Overwrite snapshots with the new response instead of comparing
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Directory the snapshots live in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File a snapshot is stored in
+    ///
+    /// Characters other than letters, digits, `-`, `_` and `.` become `_`, so
+    /// a name can't escape the store.
+    pub fn path(&self, name: &str) -> PathBuf {
+        let file: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir
+            .join(format!("{}.json", file.trim_start_matches('.')))
+    }
+
+    /// Load a snapshot, `None` if it hasn't been taken yet
+    pub fn load(&self, name: &str) -> Result<Option<Snapshot>> {
+        let path = self.path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Save a snapshot, replacing any previous one
+    pub fn save(&self, name: &str, snapshot: &Snapshot) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path(name), serde_json::to_string_pretty(snapshot)?)?;
+        Ok(())
+    }
+
+    /// Check a response against a named snapshot
+    ///
+    /// The stored rules and `rules` both apply. A missing snapshot is
+    /// created; with updates on, a changed one is overwritten.
+    pub fn check(
+        &self,
+        name: &str,
+        response: &HttpResponse,
+        rules: &SnapshotRules,
+    ) -> Result<SnapshotOutcome> {
+        let Some(stored) = self.load(name)? else {
+            self.save(name, &Snapshot::capture(response, rules))?;
+            return Ok(SnapshotOutcome::Created);
+        };
+
+        let mut merged = stored.rules.clone();
+        merged.merge(rules);
+        let actual = Snapshot::capture(response, &merged);
+        let changes = stored.diff(&actual);
+
+        if changes.is_empty() && merged == stored.rules {
+            Ok(SnapshotOutcome::Matched)
+        } else if self.update {
+            self.save(name, &actual)?;
+            Ok(SnapshotOutcome::Updated)
+        } else if changes.is_empty() {
+            Ok(SnapshotOutcome::Matched)
+        } else {
+            Ok(SnapshotOutcome::Changed(changes))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    fn response(status: u16, content_type: &str, body: &str) -> HttpResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_str(content_type).unwrap());
+        headers.insert(
+            "date",
+            HeaderValue::from_static("Mon, 01 Jan 2024 00:00:00 GMT"),
+        );
+        HttpResponse {
+            status: StatusCode::from_u16(status).unwrap(),
+            headers,
+            body: body.to_string(),
+            body_bytes: body.as_bytes().to_vec(),
+            duration: Duration::ZERO,
+            timing: None,
+        }
+    }
+
+    #[test]
+    fn test_capture_normalizes() {
+        let rules = SnapshotRules::new()
+            .with_ignore("$.id")
+            .with_ignore("$.items[*].created_at");
+        let snapshot = Snapshot::capture(
+            &response(
+                200,
+                "application/json",
+                r#"{"id":7,"items":[{"n":1,"created_at":"t1"},{"n":2,"created_at":"t2"}]}"#,
+            ),
+            &rules,
+        );
+
+        assert_eq!(snapshot.status, 200);
+        assert_eq!(
+            snapshot.headers.keys().collect::<Vec<_>>(),
+            vec!["content-type"]
+        );
+        assert_eq!(
+            snapshot.body,
+            serde_json::json!({
+                "id": "<ignored>",
+                "items": [
+                    {"n": 1, "created_at": "<ignored>"},
+                    {"n": 2, "created_at": "<ignored>"}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_diff_lists_changes() {
+        let rules = SnapshotRules::new();
+        let old = Snapshot::capture(&response(200, "application/json", r#"{"a":1}"#), &rules);
+        let new = Snapshot::capture(&response(404, "text/plain", r#"{"a":2}"#), &rules);
+
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                "~ status: expected 200, got 404",
+                r#"~ header content-type: expected "application/json", got "text/plain""#,
+                "~ $.a: expected 1, got 2",
+            ]
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_store_check_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path());
+        let rules = SnapshotRules::new().with_ignore("$.id");
+
+        let first = response(200, "application/json", r#"{"id":1,"name":"a"}"#);
+        assert_eq!(
+            store.check("users/get", &first, &rules).unwrap(),
+            SnapshotOutcome::Created
+        );
+        assert!(dir.path().join("users_get.json").exists());
+
+        // A new id is ignored, and the stored rules apply without being repeated
+        let same = response(200, "application/json", r#"{"id":2,"name":"a"}"#);
+        assert_eq!(
+            store
+                .check("users/get", &same, &SnapshotRules::new())
+                .unwrap(),
+            SnapshotOutcome::Matched
+        );
+
+        let changed = response(200, "application/json", r#"{"id":3,"name":"b"}"#);
+        let outcome = store.check("users/get", &changed, &rules).unwrap();
+        assert_eq!(
+            outcome,
+            SnapshotOutcome::Changed(vec![r#"~ $.name: expected "a", got "b""#.to_string()])
+        );
+        assert!(!outcome.passed());
+
+        let updating = store.clone().with_update(true);
+        assert_eq!(
+            updating.check("users/get", &changed, &rules).unwrap(),
+            SnapshotOutcome::Updated
+        );
+        assert_eq!(
+            store.check("users/get", &changed, &rules).unwrap(),
+            SnapshotOutcome::Matched
+        );
+    }
+
+    #[test]
+    fn test_text_body_snapshot() {
+        let rules = SnapshotRules::new();
+        let old = Snapshot::capture(&response(200, "text/plain", "one\ntwo"), &rules);
+        let new = Snapshot::capture(&response(200, "text/plain", "one\nthree"), &rules);
+
+        assert_eq!(old.body, Value::String("one\ntwo".to_string()));
+        assert_eq!(old.diff(&new), vec!["  one", "- two", "+ three"]);
+    }
+}
@@ -1,8 +1,12 @@
 //! Response validation engine
 
-use crate::assertions::{Assertion, AssertionResult, AssertionType};
+use crate::assertions::matcher::json_text;
+use crate::assertions::{Assertion, AssertionResult, AssertionType, MatcherType};
 use crate::http::HttpResponse;
+use crate::report::{JUnitTestCase, JUnitTestSuite};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
 
 /// Validation report
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,18 +60,85 @@ impl ValidationReport {
         }
     }
 
+    /// Turn a failed report into `Error::AssertionFailed`
+    pub fn ensure_success(&self) -> crate::Result<()> {
+        if self.success {
+            Ok(())
+        } else {
+            Err(crate::Error::AssertionFailed(self.summary()))
+        }
+    }
+
     /// Get detailed report
     pub fn detailed_report(&self) -> String {
         let mut report = self.summary();
         report.push_str("\n\n");
 
         for result in &self.results {
-            report.push_str(&result.summary());
-            report.push('\n');
+            match result.detailed_diff() {
+                Some(diff) => {
+                    let desc = result
+                        .assertion
+                        .description
+                        .as_deref()
+                        .unwrap_or("Assertion");
+                    report.push_str(&format!("✗ {}: FAIL - expected and actual differ:\n", desc));
+                    for line in diff.lines() {
+                        report.push_str(&format!("    {}\n", line));
+                    }
+                }
+                None => {
+                    report.push_str(&result.summary());
+                    report.push('\n');
+                }
+            }
         }
 
         report
     }
+
+    /// Build a test suite with one test case per assertion
+    ///
+    /// Failures carry the expected and actual values.
+    pub fn test_suite(&self) -> JUnitTestSuite {
+        let mut suite = JUnitTestSuite::new("assertions".to_string());
+
+        for (i, result) in self.results.iter().enumerate() {
+            let name = result
+                .assertion
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("assertion {}", i + 1));
+            let mut case = JUnitTestCase::new(name, suite.name.clone(), Duration::ZERO);
+            if !result.passed {
+                let message = match &result.error_message {
+                    Some(message) if result.assertion.assertion_type.is_check() => message.clone(),
+                    Some(message) => format!(
+                        "{}: expected {}, got {}",
+                        message, result.expected_value, result.actual_value
+                    ),
+                    None => format!(
+                        "Expected {}, got {}",
+                        result.expected_value, result.actual_value
+                    ),
+                };
+                case = case.with_failure(message);
+            }
+            suite.add_case(case);
+        }
+
+        suite
+    }
+
+    /// Render as a JUnit XML test suite with one test case per assertion
+    pub fn to_junit_xml(&self) -> String {
+        self.test_suite().to_xml()
+    }
+
+    /// Render as a JSON report with one test case per assertion
+    pub fn to_json(&self) -> crate::Result<String> {
+        self.test_suite().to_json()
+    }
 }
 
 impl Default for ValidationReport {
@@ -113,7 +184,21 @@ impl ResponseValidator {
             AssertionType::Body => self.validate_body(response, assertion),
             AssertionType::ResponseTime => self.validate_response_time(response, assertion),
             AssertionType::JsonPath(path) => self.validate_json_path(response, path, assertion),
+            AssertionType::ServerTiming(metric) => {
+                self.validate_server_timing(response, metric, assertion)
+            }
+            AssertionType::Cookie(name) => self.validate_cookie(response, name, assertion),
             AssertionType::Custom(desc) => self.validate_custom(response, desc, assertion),
+            AssertionType::BodySize => self.validate_body_size(response, assertion),
+            AssertionType::ContentType => self.validate_content_type(response, assertion),
+            AssertionType::ValidJson => {
+                let parsed = serde_json::from_slice::<Value>(&response.body_bytes);
+                judge_check(assertion, parsed.err(), "JSON")
+            }
+            AssertionType::ValidXml => {
+                let parsed = roxmltree::Document::parse(&response.body);
+                judge_check(assertion, parsed.err(), "XML")
+            }
         }
     }
 
@@ -126,19 +211,12 @@ impl ResponseValidator {
         let actual = response.status.as_u16().to_string();
         let expected = assertion.matcher.description();
 
-        if assertion.matcher.matches(&actual) {
-            AssertionResult::pass(assertion.clone(), actual, expected)
-        } else {
-            AssertionResult::fail(
-                assertion.clone(),
-                actual,
-                expected,
-                "Status code does not match".to_string(),
-            )
-        }
+        judge(assertion, &actual, actual.clone(), expected, || {
+            "Status code does not match".to_string()
+        })
     }
 
-    /// Validate header
+    /// Validate header; names are matched case-insensitively
     fn validate_header(
         &self,
         response: &HttpResponse,
@@ -147,24 +225,16 @@ impl ResponseValidator {
     ) -> AssertionResult {
         let expected = assertion.matcher.description();
 
-        // Find header value
+        let name = header_name.trim();
         let actual = response
             .headers
-            .get(header_name)
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("")
-            .to_string();
+            .iter()
+            .find(|(key, _)| key.as_str().eq_ignore_ascii_case(name))
+            .map(|(_, value)| String::from_utf8_lossy(value.as_bytes()).into_owned());
 
-        if assertion.matcher.matches(&actual) {
-            AssertionResult::pass(assertion.clone(), actual, expected)
-        } else {
-            AssertionResult::fail(
-                assertion.clone(),
-                actual,
-                expected,
-                format!("Header '{}' does not match", header_name),
-            )
-        }
+        judge_present(assertion, actual, expected, "header", header_name, || {
+            format!("Header '{}' does not match", header_name)
+        })
     }
 
     /// Validate body
@@ -172,16 +242,54 @@ impl ResponseValidator {
         let actual = &response.body;
         let expected = assertion.matcher.description();
 
-        if assertion.matcher.matches(actual) {
-            AssertionResult::pass(assertion.clone(), actual.clone(), expected)
-        } else {
-            AssertionResult::fail(
-                assertion.clone(),
-                actual.clone(),
-                expected,
-                "Body does not match".to_string(),
-            )
-        }
+        judge(assertion, actual, actual.clone(), expected, || {
+            "Body does not match".to_string()
+        })
+    }
+
+    /// Validate the body size in bytes
+    fn validate_body_size(
+        &self,
+        response: &HttpResponse,
+        assertion: &Assertion,
+    ) -> AssertionResult {
+        let actual = response.body_bytes.len().to_string();
+        let expected = assertion.matcher.description();
+
+        judge(
+            assertion,
+            &actual,
+            format!("{} bytes", actual),
+            expected,
+            || "Body size does not match".to_string(),
+        )
+    }
+
+    /// Validate the Content-Type media type, ignoring parameters and case
+    fn validate_content_type(
+        &self,
+        response: &HttpResponse,
+        assertion: &Assertion,
+    ) -> AssertionResult {
+        let expected = assertion.matcher.description();
+
+        let actual = response
+            .headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+            .map(|value| {
+                let media_type = value.split(';').next().unwrap_or_default();
+                media_type.trim().to_ascii_lowercase()
+            });
+
+        judge_present(
+            assertion,
+            actual,
+            expected,
+            "header",
+            "content-type",
+            || "Content type does not match".to_string(),
+        )
     }
 
     /// Validate response time
@@ -190,22 +298,75 @@ impl ResponseValidator {
         response: &HttpResponse,
         assertion: &Assertion,
     ) -> AssertionResult {
-        let actual = response.duration.as_millis().to_string();
+        // Fractional milliseconds, so `time < 0.5` works on fast local servers
+        let actual = (response.duration.as_micros() as f64 / 1000.0).to_string();
         let expected = assertion.matcher.description();
 
-        if assertion.matcher.matches(&actual) {
-            AssertionResult::pass(assertion.clone(), format!("{}ms", actual), expected)
-        } else {
-            AssertionResult::fail(
+        judge(
+            assertion,
+            &actual,
+            format!("{}ms", actual),
+            expected,
+            || "Response time does not match".to_string(),
+        )
+    }
+
+    /// Validate a Server-Timing metric duration
+    fn validate_server_timing(
+        &self,
+        response: &HttpResponse,
+        metric: &str,
+        assertion: &Assertion,
+    ) -> AssertionResult {
+        let expected = assertion.matcher.description();
+
+        let duration = response
+            .server_timing()
+            .into_iter()
+            .find(|entry| entry.name.eq_ignore_ascii_case(metric))
+            .and_then(|entry| entry.duration);
+
+        match duration {
+            Some(duration) => {
+                let actual = duration.to_string();
+                judge(
+                    assertion,
+                    &actual,
+                    format!("{}ms", actual),
+                    expected,
+                    || format!("Server-Timing '{}' does not match", metric),
+                )
+            }
+            None => AssertionResult::fail(
                 assertion.clone(),
-                format!("{}ms", actual),
+                String::new(),
                 expected,
-                "Response time does not match".to_string(),
-            )
+                format!("Server-Timing metric '{}' has no duration", metric),
+            ),
         }
     }
 
-    /// Validate JSON path
+    /// Validate a cookie value (empty when the cookie was not set)
+    fn validate_cookie(
+        &self,
+        response: &HttpResponse,
+        name: &str,
+        assertion: &Assertion,
+    ) -> AssertionResult {
+        let expected = assertion.matcher.description();
+
+        let actual = response
+            .cookies()
+            .into_iter()
+            .find(|cookie| cookie.name == name)
+            .map(|cookie| cookie.value);
+
+        judge_present(assertion, actual, expected, "cookie", name, || {
+            format!("Cookie '{}' does not match", name)
+        })
+    }
+
+    /// Validate JSON path, matching arrays by length, membership or element
     fn validate_json_path(
         &self,
         response: &HttpResponse,
@@ -215,24 +376,13 @@ impl ResponseValidator {
         let expected = assertion.matcher.description();
 
         // Try to parse response as JSON
-        let json_result: Result<serde_json::Value, _> = serde_json::from_str(&response.body);
+        let json_result: Result<Value, _> = serde_json::from_str(&response.body);
 
         match json_result {
-            Ok(json) => {
-                // Extract value at path
-                let actual = self.extract_json_path(&json, path);
-
-                if assertion.matcher.matches(&actual) {
-                    AssertionResult::pass(assertion.clone(), actual, expected)
-                } else {
-                    AssertionResult::fail(
-                        assertion.clone(),
-                        actual,
-                        expected,
-                        format!("JSON path '{}' does not match", path),
-                    )
-                }
-            }
+            Ok(json) => match resolve_json_path(&json, path) {
+                Some(actual) => judge_json(assertion, &actual, expected, path),
+                None => judge_present(assertion, None, expected, "JSON path", path, String::new),
+            },
             Err(e) => AssertionResult::fail(
                 assertion.clone(),
                 response.body.clone(),
@@ -242,52 +392,209 @@ impl ResponseValidator {
         }
     }
 
-    /// Extract value from JSON using simplified path syntax
-    fn extract_json_path(&self, json: &serde_json::Value, path: &str) -> String {
-        // Simplified JSON path extraction (supports $.field and $.field.subfield)
-        let path = path.trim_start_matches("$.");
-        let parts: Vec<&str> = path.split('.').collect();
-
-        let mut current = json;
-        for part in parts {
-            match current {
-                serde_json::Value::Object(map) => {
-                    if let Some(value) = map.get(part) {
-                        current = value;
-                    } else {
-                        return String::new();
-                    }
-                }
-                _ => return String::new(),
-            }
-        }
+    /// Validate a custom assertion by evaluating its Rhai expression
+    #[cfg(feature = "scripts")]
+    fn validate_custom(
+        &self,
+        response: &HttpResponse,
+        expression: &str,
+        assertion: &Assertion,
+    ) -> AssertionResult {
+        let expected = "true".to_string();
 
-        match current {
-            serde_json::Value::String(s) => s.clone(),
-            serde_json::Value::Number(n) => n.to_string(),
-            serde_json::Value::Bool(b) => b.to_string(),
-            serde_json::Value::Null => "null".to_string(),
-            _ => current.to_string(),
+        match crate::scripts::ScriptEngine::new().evaluate_response_expr(expression, response) {
+            Ok(true) => AssertionResult::pass(assertion.clone(), "true".to_string(), expected),
+            Ok(false) => AssertionResult::fail(
+                assertion.clone(),
+                "false".to_string(),
+                expected,
+                format!("Expression '{}' is false", expression),
+            ),
+            Err(problem) => {
+                AssertionResult::fail(assertion.clone(), String::new(), expected, problem)
+            }
         }
     }
 
-    /// Validate custom assertion
+    /// Custom assertions can't be evaluated without a script engine
+    #[cfg(not(feature = "scripts"))]
     fn validate_custom(
         &self,
         _response: &HttpResponse,
-        description: &str,
+        expression: &str,
         assertion: &Assertion,
     ) -> AssertionResult {
-        // Custom assertions would need custom logic
-        // For now, we'll just mark them as passed
-        AssertionResult::pass(
+        AssertionResult::fail(
             assertion.clone(),
-            "custom".to_string(),
-            description.to_string(),
+            String::new(),
+            expression.to_string(),
+            "Custom assertions need the `scripts` feature".to_string(),
         )
     }
 }
 
+/// Pass or fail an assertion on `actual`, recorded as `shown`
+///
+/// A value the matcher can't compare fails with the reason instead of `failure`.
+fn judge(
+    assertion: &Assertion,
+    actual: &str,
+    shown: String,
+    expected: String,
+    failure: impl FnOnce() -> String,
+) -> AssertionResult {
+    match assertion.matcher.evaluate(actual) {
+        Ok(true) => AssertionResult::pass(assertion.clone(), shown, expected),
+        Ok(false) => AssertionResult::fail(assertion.clone(), shown, expected, failure()),
+        Err(problem) => AssertionResult::fail(assertion.clone(), shown, expected, problem),
+    }
+}
+
+/// Pass or fail a yes/no check on whether the body parses as `format`
+fn judge_check(
+    assertion: &Assertion,
+    error: Option<impl std::fmt::Display>,
+    format: &str,
+) -> AssertionResult {
+    let expected = format!("valid {}", format);
+    match error {
+        None => AssertionResult::pass(assertion.clone(), expected.clone(), expected),
+        Some(e) => AssertionResult::fail(
+            assertion.clone(),
+            format!("invalid {}", format),
+            expected,
+            format!("Body is not valid {}: {}", format, e),
+        ),
+    }
+}
+
+/// Pass or fail an assertion on a resolved JSON value
+///
+/// A failed `Each` names the indices of the elements that didn't match.
+fn judge_json(
+    assertion: &Assertion,
+    actual: &Value,
+    expected: String,
+    path: &str,
+) -> AssertionResult {
+    let shown = json_text(actual);
+
+    match assertion.matcher.evaluate_json(actual) {
+        Ok(true) => AssertionResult::pass(assertion.clone(), shown, expected),
+        Ok(false) => {
+            let message = match actual {
+                Value::Array(items) if assertion.matcher.matcher_type == MatcherType::Each => {
+                    let failed: Vec<String> = assertion
+                        .matcher
+                        .failing_elements(items)
+                        .iter()
+                        .map(|index| index.to_string())
+                        .collect();
+                    format!(
+                        "JSON path '{}' elements at index {} do not match",
+                        path,
+                        failed.join(", ")
+                    )
+                }
+                _ => format!("JSON path '{}' does not match", path),
+            };
+            AssertionResult::fail(assertion.clone(), shown, expected, message)
+        }
+        Err(problem) => AssertionResult::fail(assertion.clone(), shown, expected, problem),
+    }
+}
+
+/// One step of a JSON path
+pub(crate) enum PathStep<'a> {
+    Key(&'a str),
+    Index(usize),
+    All,
+}
+
+/// Resolve a simplified JSON path such as `$.user.name`, `$.items[0]` or `$.items[*].id`
+///
+/// `[*]` collects the rest of the path from every element into an array,
+/// with `null` for elements that lack it. `None` if the path is missing.
+fn resolve_json_path(json: &Value, path: &str) -> Option<Value> {
+    resolve_steps(json, &parse_json_path(path)?)
+}
+
+/// Split a simplified JSON path into steps, `None` if an index is malformed
+pub(crate) fn parse_json_path(path: &str) -> Option<Vec<PathStep<'_>>> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+
+    let mut steps = Vec::new();
+    for part in path.split('.').filter(|part| !part.is_empty()) {
+        let (key, indices) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !key.is_empty() {
+            steps.push(PathStep::Key(key));
+        }
+        for index in indices.split_terminator(']') {
+            let index = index.strip_prefix('[')?.trim();
+            steps.push(if index == "*" {
+                PathStep::All
+            } else {
+                PathStep::Index(index.parse().ok()?)
+            });
+        }
+    }
+    Some(steps)
+}
+
+fn resolve_steps(value: &Value, steps: &[PathStep]) -> Option<Value> {
+    let Some((step, rest)) = steps.split_first() else {
+        return Some(value.clone());
+    };
+
+    match (step, value) {
+        (PathStep::Key(key), Value::Object(map)) => resolve_steps(map.get(*key)?, rest),
+        (PathStep::Index(index), Value::Array(items)) => resolve_steps(items.get(*index)?, rest),
+        (PathStep::All, Value::Array(items)) => Some(Value::Array(
+            items
+                .iter()
+                .map(|item| resolve_steps(item, rest).unwrap_or(Value::Null))
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+/// Like [`judge`], for a `kind` of value (header, cookie...) that may be missing
+///
+/// A missing value fails with "<kind> not present" rather than `failure`.
+fn judge_present(
+    assertion: &Assertion,
+    actual: Option<String>,
+    expected: String,
+    kind: &str,
+    name: &str,
+    failure: impl FnOnce() -> String,
+) -> AssertionResult {
+    let present = actual.is_some();
+    let shown = actual.clone().unwrap_or_default();
+
+    match assertion.matcher.evaluate_present(actual.as_deref()) {
+        Ok(true) => AssertionResult::pass(assertion.clone(), shown, expected),
+        Ok(false) | Err(_) if !present => AssertionResult::fail(
+            assertion.clone(),
+            shown,
+            expected,
+            format!("{} not present: {}", kind, name),
+        ),
+        Ok(false) if assertion.matcher.matcher_type == MatcherType::NotExists => {
+            AssertionResult::fail(
+                assertion.clone(),
+                shown,
+                expected,
+                format!("{} is present: {}", kind, name),
+            )
+        }
+        Ok(false) => AssertionResult::fail(assertion.clone(), shown, expected, failure()),
+        Err(problem) => AssertionResult::fail(assertion.clone(), shown, expected, problem),
+    }
+}
+
 impl Default for ResponseValidator {
     fn default() -> Self {
         Self::new()
@@ -312,15 +619,146 @@ mod tests {
             HeaderName::from_static("x-custom"),
             HeaderValue::from_static("test-value"),
         );
+        headers.insert(
+            HeaderName::from_static("server-timing"),
+            HeaderValue::from_static("db;dur=12.3, cache;desc=hit"),
+        );
+
+        headers.append(
+            HeaderName::from_static("set-cookie"),
+            HeaderValue::from_static("session=abc123; Path=/; HttpOnly"),
+        );
+        headers.append(
+            HeaderName::from_static("set-cookie"),
+            HeaderValue::from_static("theme=dark"),
+        );
 
         HttpResponse {
             status: StatusCode::OK,
             headers,
             body: r#"{"status":"ok","count":42}"#.to_string(),
+            body_bytes: br#"{"status":"ok","count":42}"#.to_vec(),
             duration: Duration::from_millis(150),
+            timing: None,
         }
     }
 
+    #[test]
+    fn test_validate_size_and_content_type() {
+        let validator = ResponseValidator::new();
+        let mut response = create_mock_response();
+        response.headers.insert(
+            HeaderName::from_static("content-type"),
+            HeaderValue::from_static("Application/JSON; charset=utf-8"),
+        );
+
+        let report = validator.validate(
+            &response,
+            &[
+                Assertion::body_size(Matcher::less_than(100)),
+                Assertion::body_size(Matcher::greater_than(100)),
+                Assertion::content_type("application/json"),
+                Assertion::content_type("text/html"),
+            ],
+        );
+        assert!(report.results[0].passed);
+        assert!(!report.results[1].passed);
+        assert_eq!(report.results[1].actual_value, "26 bytes");
+        assert!(report.results[2].passed);
+        assert!(!report.results[3].passed);
+        assert_eq!(report.results[3].actual_value, "application/json");
+
+        response.headers.remove("content-type");
+        let missing = validator.validate(&response, &[Assertion::content_type("text/html")]);
+        assert_eq!(
+            missing.results[0].error_message.as_deref(),
+            Some("header not present: content-type")
+        );
+    }
+
+    #[test]
+    fn test_validate_body_parses() {
+        let validator = ResponseValidator::new();
+        let mut response = create_mock_response();
+
+        let report = validator.validate(
+            &response,
+            &[Assertion::is_valid_json(), Assertion::is_valid_xml()],
+        );
+        assert!(report.results[0].passed);
+        assert!(!report.results[1].passed);
+        assert!(report.results[1]
+            .summary()
+            .starts_with("✗ valid XML: FAIL - Body is not valid XML: "));
+
+        response.body = "<a><b/></a>".to_string();
+        response.body_bytes = response.body.as_bytes().to_vec();
+        let report = validator.validate(
+            &response,
+            &[Assertion::is_valid_json(), Assertion::is_valid_xml()],
+        );
+        assert!(!report.results[0].passed);
+        assert!(report.results[1].passed);
+        assert_eq!(report.results[0].detailed_diff(), None);
+    }
+
+    #[test]
+    fn test_validate_server_timing() {
+        let validator = ResponseValidator::new();
+        let response = create_mock_response();
+
+        let assertions = vec![
+            Assertion::server_timing("db".to_string(), Matcher::less_than(50)),
+            Assertion::server_timing("db".to_string(), Matcher::greater_than(20)),
+            Assertion::server_timing("cache".to_string(), Matcher::less_than(50)),
+        ];
+        let report = validator.validate(&response, &assertions);
+
+        assert!(report.results[0].passed);
+        assert!(!report.results[1].passed);
+        assert!(!report.results[2].passed);
+        assert!(report.results[2]
+            .error_message
+            .as_ref()
+            .unwrap()
+            .contains("no duration"));
+    }
+
+    #[test]
+    fn test_validate_cookie_present() {
+        let validator = ResponseValidator::new();
+        let response = create_mock_response();
+
+        let assertions = vec![
+            Assertion::cookie("session".to_string(), Matcher::equals_str("abc123")),
+            Assertion::cookie("theme".to_string(), Matcher::equals_str("light")),
+        ];
+        let report = validator.validate(&response, &assertions);
+
+        assert!(report.results[0].passed);
+        assert_eq!(report.results[0].actual_value, "abc123");
+        assert!(!report.results[1].passed);
+        assert!(report.results[1]
+            .error_message
+            .as_ref()
+            .unwrap()
+            .contains("Cookie 'theme'"));
+    }
+
+    #[test]
+    fn test_validate_cookie_missing() {
+        let validator = ResponseValidator::new();
+        let response = create_mock_response();
+
+        let missing = Assertion::cookie("csrf".to_string(), Matcher::is_not_empty());
+        let result = validator.validate_assertion(&response, &missing);
+        assert!(!result.passed);
+        assert_eq!(result.actual_value, "");
+
+        let absent = Assertion::cookie("csrf".to_string(), Matcher::is_empty());
+        assert!(validator.validate_assertion(&response, &absent).passed);
+    }
+
     #[test]
     fn test_validation_report_new() {
         let report = ValidationReport::new();
@@ -361,6 +799,68 @@ mod tests {
         assert_eq!(report.passed, 0);
         assert_eq!(report.failed, 1);
         assert!(!report.success);
+        match report.ensure_success() {
+            Err(crate::Error::AssertionFailed(summary)) => {
+                assert_eq!(summary, "✗ 1 of 1 assertions failed")
+            }
+            other => panic!("expected AssertionFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_detailed_report_shows_json_diff() {
+        let mut report = ValidationReport::new();
+        let assertion = Assertion::body(Matcher::equals_str(r#"{"data":{"count":3}}"#))
+            .with_description("Body".to_string());
+        report.add_result(AssertionResult::fail(
+            assertion,
+            r#"{"data":{"count":4}}"#.to_string(),
+            "equals".to_string(),
+            "Body does not match".to_string(),
+        ));
+
+        let detailed = report.detailed_report();
+        assert!(detailed.contains("✗ Body: FAIL - expected and actual differ"));
+        assert!(detailed.contains("    ~ $.data.count: expected 3, got 4"));
+    }
+
+    #[test]
+    fn test_validation_report_to_junit_xml() {
+        let validator = ResponseValidator::new();
+        let response = create_mock_response();
+
+        let assertions = vec![
+            Assertion::status_code(Matcher::equals(200))
+                .with_description("status is 200".to_string()),
+            Assertion::status_code(Matcher::equals(404)),
+        ];
+        let report = validator.validate(&response, &assertions);
+        let xml = report.to_junit_xml();
+        crate::report::junit::assert_junit_schema(&xml);
+
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let suite = doc.root_element();
+        assert_eq!(suite.attribute("tests"), Some("2"));
+        assert_eq!(suite.attribute("failures"), Some("1"));
+
+        let cases: Vec<_> = suite
+            .children()
+            .filter(|n| n.has_tag_name("testcase"))
+            .collect();
+        assert_eq!(cases[0].attribute("name"), Some("status is 200"));
+        assert_eq!(cases[1].attribute("name"), Some("assertion 2"));
+        let failure = cases[1]
+            .children()
+            .find(|n| n.has_tag_name("failure"))
+            .unwrap();
+        assert!(failure
+            .attribute("message")
+            .unwrap()
+            .ends_with("expected equals '404', got 200"));
+
+        let json: Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["tests"], 2);
+        assert_eq!(json["testcases"][1]["status"], "failed");
     }
 
     #[test]
@@ -409,6 +909,142 @@ mod tests {
         assert!(!result.passed);
     }
 
+    #[test]
+    fn test_validator_header_presence() {
+        let validator = ResponseValidator::new();
+        let mut response = create_mock_response();
+        response.headers.insert(
+            HeaderName::from_static("x-empty"),
+            HeaderValue::from_static(""),
+        );
+        let check = |name: &str, matcher: Matcher| {
+            validator.validate_assertion(&response, &Assertion::header(name.to_string(), matcher))
+        };
+
+        // Present but empty
+        assert!(check("X-Empty", Matcher::exists()).passed);
+        assert!(check("X-Empty", Matcher::is_empty()).passed);
+        let result = check("x-empty", Matcher::not_exists());
+        assert!(!result.passed);
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("header is present: x-empty")
+        );
+
+        // Present with a value, any case
+        assert!(check("X-CUSTOM", Matcher::exists()).passed);
+        assert!(check("x-Custom", Matcher::equals_str("test-value")).passed);
+        assert!(!check("X-Custom", Matcher::not_exists()).passed);
+
+        // Absent
+        assert!(check("X-Request-Id", Matcher::not_exists()).passed);
+        for matcher in [Matcher::exists(), Matcher::contains("abc".to_string())] {
+            let result = check("X-Request-Id", matcher);
+            assert!(!result.passed);
+            assert_eq!(
+                result.error_message.as_deref(),
+                Some("header not present: X-Request-Id")
+            );
+        }
+    }
+
+    fn json_array_response() -> HttpResponse {
+        let body =
+            r#"{"items":[{"id":1},{"id":null},{"id":3},{}],"tags":[],"mixed":[1,"two",true,null]}"#;
+        HttpResponse {
+            body: body.to_string(),
+            body_bytes: body.as_bytes().to_vec(),
+            ..create_mock_response()
+        }
+    }
+
+    #[test]
+    fn test_validator_json_path_arrays() {
+        let validator = ResponseValidator::new();
+        let response = json_array_response();
+        let check = |path: &str, matcher: Matcher| {
+            validator
+                .validate_assertion(&response, &Assertion::json_path(path.to_string(), matcher))
+        };
+
+        assert!(check("$.items", Matcher::has_length(4)).passed);
+        assert!(check("$.items", Matcher::greater_than_or_equal(3)).passed);
+        assert!(!check("$.items", Matcher::less_than(4)).passed);
+        assert!(check("$.items[2].id", Matcher::equals(3)).passed);
+        assert!(!check("$.items[9].id", Matcher::exists()).passed);
+
+        let ids = check(
+            "$.items[*].id",
+            Matcher::each(Box::new(Matcher::is_not_null())),
+        );
+        assert!(!ids.passed);
+        assert_eq!(ids.actual_value, "[1,null,3,null]");
+        assert_eq!(
+            ids.error_message.as_deref(),
+            Some("JSON path '$.items[*].id' elements at index 1, 3 do not match")
+        );
+    }
+
+    #[test]
+    fn test_validator_json_path_empty_array() {
+        let validator = ResponseValidator::new();
+        let response = json_array_response();
+        let check = |matcher: Matcher| {
+            validator
+                .validate_assertion(
+                    &response,
+                    &Assertion::json_path("$.tags".to_string(), matcher),
+                )
+                .passed
+        };
+
+        assert!(check(Matcher::has_length(0)));
+        assert!(check(Matcher::is_empty()));
+        assert!(!check(Matcher::greater_than(0)));
+        assert!(!check(Matcher::contains("a".to_string())));
+        assert!(check(Matcher::each(Box::new(Matcher::equals(1)))));
+    }
+
+    #[test]
+    fn test_validator_json_path_mixed_array() {
+        let validator = ResponseValidator::new();
+        let response = json_array_response();
+        let check = |matcher: Matcher| {
+            validator.validate_assertion(
+                &response,
+                &Assertion::json_path("$.mixed".to_string(), matcher),
+            )
+        };
+
+        assert!(check(Matcher::contains("two".to_string())).passed);
+        assert!(check(Matcher::contains("true".to_string())).passed);
+        assert!(check(Matcher::contains("1".to_string())).passed);
+        assert!(!check(Matcher::contains("tw".to_string())).passed);
+        assert!(check(Matcher::not_contains("3".to_string())).passed);
+
+        let numbers = check(Matcher::each(Box::new(Matcher::greater_than(0))));
+        assert_eq!(
+            numbers.error_message.as_deref(),
+            Some("JSON path '$.mixed' elements at index 1, 2, 3 do not match")
+        );
+    }
+
+    #[test]
+    fn test_validator_json_path_exists() {
+        let validator = ResponseValidator::new();
+        let response = create_mock_response();
+
+        let present = Assertion::json_path("$.status".to_string(), Matcher::exists());
+        assert!(validator.validate_assertion(&response, &present).passed);
+
+        let missing = Assertion::json_path("$.missing".to_string(), Matcher::exists());
+        let result = validator.validate_assertion(&response, &missing);
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("JSON path not present: $.missing")
+        );
+    }
+
     #[test]
     fn test_validator_body_pass() {
         let validator = ResponseValidator::new();
@@ -449,6 +1085,32 @@ mod tests {
         assert!(result.passed);
     }
 
+    #[test]
+    fn test_validator_json_path_not_a_number() {
+        let validator = ResponseValidator::new();
+        let response = create_mock_response();
+        let assertion = Assertion::json_path("$.status".to_string(), Matcher::between(1.0, 10.0));
+
+        let result = validator.validate_assertion(&response, &assertion);
+        assert!(!result.passed);
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("actual value 'ok' is not a number")
+        );
+    }
+
+    #[test]
+    fn test_validator_response_time_fractional() {
+        let validator = ResponseValidator::new();
+        let mut response = create_mock_response();
+        response.duration = Duration::from_micros(1_500);
+
+        let assertion = Assertion::response_time(Matcher::between(1.0, 2.0));
+        let result = validator.validate_assertion(&response, &assertion);
+        assert!(result.passed);
+        assert_eq!(result.actual_value, "1.5ms");
+    }
+
     #[test]
     fn test_validator_validate_multiple() {
         let validator = ResponseValidator::new();
@@ -490,6 +1152,42 @@ mod tests {
         assert!(!report.success);
     }
 
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_validator_custom_expression() {
+        let validator = ResponseValidator::new();
+        let response = json_array_response();
+        let check = |expression: &str| {
+            validator.validate_assertion(&response, &Assertion::custom_expr(expression))
+        };
+
+        assert!(check("status == 200 && duration_ms < 1000").passed);
+        assert!(check("json.items.len() == 4 && json.items[0].id == 1").passed);
+        assert!(check(r#"headers["content-type"].contains("json")"#).passed);
+
+        let result = check("json.tags.len() > 0");
+        assert!(!result.passed);
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("Expression 'json.tags.len() > 0' is false")
+        );
+        assert_eq!(
+            result.summary(),
+            "✗ json.tags.len() > 0: FAIL - Expression 'json.tags.len() > 0' is false"
+        );
+        assert_eq!(result.detailed_diff(), None);
+
+        let result = check("json.missing.len() > 0");
+        assert!(!result.passed);
+        assert!(result.error_message.unwrap().starts_with("Script error: "));
+
+        let result = check("status");
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("Expression returned i64, expected a boolean")
+        );
+    }
+
     #[test]
     fn test_validator_skip_disabled() {
         let validator = ResponseValidator::new();
@@ -1,5 +1,6 @@
 //! Bearer token authentication (JWT, API tokens)
 
+use crate::auth::jwt::JwtConfig;
 use serde::{Deserialize, Serialize};
 
 /// Bearer token authentication
@@ -10,6 +11,10 @@ pub struct BearerAuth {
 
     /// Optional token prefix (default: "Bearer")
     pub prefix: Option<String>,
+
+    /// Generate a signed JWT at request time instead of using `token`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt: Option<JwtConfig>,
 }
 
 impl BearerAuth {
@@ -18,6 +23,7 @@ impl BearerAuth {
         Self {
             token,
             prefix: Some("Bearer".to_string()),
+            jwt: None,
         }
     }
 
@@ -26,6 +32,7 @@ impl BearerAuth {
         Self {
             token,
             prefix: Some(prefix),
+            jwt: None,
         }
     }
 
@@ -34,17 +41,45 @@ impl BearerAuth {
         Self {
             token,
             prefix: None,
+            jwt: None,
+        }
+    }
+
+    /// Create a bearer auth that signs a fresh JWT for each request
+    pub fn from_jwt(config: JwtConfig) -> Self {
+        Self {
+            token: String::new(),
+            prefix: Some("Bearer".to_string()),
+            jwt: Some(config),
+        }
+    }
+
+    /// Format the Authorization header value for a token
+    fn header_value(&self, token: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{} {}", prefix, token),
+            None => token.to_string(),
         }
     }
 
-    /// Apply to headers
+    /// Apply to headers (JWT tokens are added at send time instead)
     pub fn apply_to_headers(&self, headers: &mut Vec<String>) {
-        let auth_value = match &self.prefix {
-            Some(prefix) => format!("{} {}", prefix, self.token),
-            None => self.token.clone(),
-        };
+        if self.jwt.is_some() {
+            return;
+        }
 
-        headers.push(format!("Authorization:{}", auth_value));
+        headers.push(format!("Authorization:{}", self.header_value(&self.token)));
+    }
+
+    /// Authorization header for a generated JWT, if configured
+    pub fn jwt_header(&self) -> crate::Result<Option<(String, String)>> {
+        match &self.jwt {
+            Some(config) => Ok(Some((
+                "Authorization".to_string(),
+                self.header_value(&config.token()?),
+            ))),
+            None => Ok(None),
+        }
     }
 
     /// Create from header value
@@ -111,6 +146,29 @@ mod tests {
         assert_eq!(headers[0], "Authorization:xyz789");
     }
 
+    #[test]
+    fn test_bearer_auth_from_jwt() {
+        let auth = BearerAuth::from_jwt(JwtConfig::new(
+            crate::auth::JwtAlgorithm::Hs256,
+            "secret".to_string(),
+        ));
+        let mut headers = Vec::new();
+        auth.apply_to_headers(&mut headers);
+        assert!(headers.is_empty());
+
+        let (name, value) = auth.jwt_header().unwrap().unwrap();
+        assert_eq!(name, "Authorization");
+        assert!(value.starts_with("Bearer ey"));
+
+        let json = serde_json::to_string(&auth).unwrap();
+        let restored: BearerAuth = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, auth);
+        assert!(BearerAuth::new("t".to_string())
+            .jwt_header()
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn test_bearer_auth_from_header() {
         let auth = BearerAuth::from_header("Bearer mytoken").unwrap();
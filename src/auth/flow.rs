@@ -0,0 +1,548 @@
+//! Interactive OAuth 2.0 authorization code flow (with PKCE)
+
+use crate::auth::oauth2::{OAuth2Auth, OAuth2Token};
+use crate::env::Environment;
+use crate::error::{Error, Result};
+use base64::Engine as _;
+use sha2::{Digest, Sha256};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Default port for the local redirect listener
+pub const DEFAULT_CALLBACK_PORT: u16 = 8765;
+
+/// Path the authorization server redirects back to
+const CALLBACK_PATH: &str = "/callback";
+
+/// PKCE code verifier and its S256 challenge (RFC 7636)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PkceChallenge {
+    /// Code verifier (sent with the token request)
+    pub verifier: String,
+
+    /// Code challenge (sent with the authorization request)
+    pub challenge: String,
+}
+
+impl PkceChallenge {
+    /// Generate a new random verifier and challenge
+    pub fn generate() -> Self {
+        let verifier = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        Self::from_verifier(verifier)
+    }
+
+    /// Derive the challenge for an existing verifier
+    pub fn from_verifier(verifier: String) -> Self {
+        let digest = Sha256::digest(verifier.as_bytes());
+        let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest);
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+
+    /// Challenge method sent to the authorization server
+    pub fn method(&self) -> &str {
+        "S256"
+    }
+}
+
+/// Authorization code flow with a temporary localhost redirect listener
+pub struct AuthorizationCodeFlow {
+    auth: OAuth2Auth,
+    port: u16,
+    timeout: Duration,
+    state: String,
+    pkce: PkceChallenge,
+}
+
+impl AuthorizationCodeFlow {
+    /// Create a new flow for the given OAuth2 configuration
+    pub fn new(auth: OAuth2Auth) -> Self {
+        Self {
+            auth,
+            port: DEFAULT_CALLBACK_PORT,
+            timeout: Duration::from_secs(300),
+            state: Uuid::new_v4().simple().to_string(),
+            pkce: PkceChallenge::generate(),
+        }
+    }
+
+    /// Set the local redirect listener port
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Set how long to wait for the browser redirect
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Get the anti-forgery state value
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    /// Get the PKCE challenge
+    pub fn pkce(&self) -> &PkceChallenge {
+        &self.pkce
+    }
+
+    /// Redirect URI registered with the authorization server
+    pub fn redirect_uri(&self) -> String {
+        format!("http://localhost:{}{}", self.port, CALLBACK_PATH)
+    }
+
+    /// Build the authorization URL the user must open
+    pub fn authorize_url(&self) -> Result<String> {
+        let auth_url = self
+            .auth
+            .auth_url
+            .as_deref()
+            .ok_or_else(|| Error::Auth("No authorization URL configured".to_string()))?;
+
+        let mut params = vec![
+            ("response_type", "code".to_string()),
+            ("client_id", self.auth.client_id.clone()),
+            ("redirect_uri", self.redirect_uri()),
+            ("state", self.state.clone()),
+            ("code_challenge", self.pkce.challenge.clone()),
+            ("code_challenge_method", self.pkce.method().to_string()),
+        ];
+        if !self.auth.scopes.is_empty() {
+            params.push(("scope", self.auth.scopes.join(" ")));
+        }
+
+        let url = reqwest::Url::parse_with_params(auth_url, &params)
+            .map_err(|e| Error::Auth(format!("Invalid authorization URL '{}': {}", auth_url, e)))?;
+        Ok(url.to_string())
+    }
+
+    /// Bind the local redirect listener
+    pub fn bind(&self) -> Result<TcpListener> {
+        TcpListener::bind(("127.0.0.1", self.port)).map_err(|e| {
+            if e.kind() == ErrorKind::AddrInUse {
+                Error::Auth(format!(
+                    "Port {} is already in use. Pick another one with --port (it must match a redirect URI registered with the provider)",
+                    self.port
+                ))
+            } else {
+                Error::Io(e)
+            }
+        })
+    }
+
+    /// Wait for the browser redirect and return the authorization code
+    pub fn wait_for_callback(&self, listener: &TcpListener) -> Result<String> {
+        listener.set_nonblocking(true)?;
+        let deadline = Instant::now() + self.timeout;
+
+        loop {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if let Some(result) = self.handle_connection(stream)? {
+                        return result;
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    if Instant::now() >= deadline {
+                        return Err(Error::Auth(format!(
+                            "Timed out after {:?} waiting for the authorization redirect",
+                            self.timeout
+                        )));
+                    }
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                Err(e) => return Err(Error::Io(e)),
+            }
+        }
+    }
+
+    /// Handle one connection; returns None for requests that aren't the callback
+    fn handle_connection(&self, mut stream: TcpStream) -> Result<Option<Result<String>>> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+        let target = match read_request_target(&mut stream) {
+            Some(target) => target,
+            None => return Ok(None),
+        };
+
+        if !target.starts_with(CALLBACK_PATH) {
+            write_response(&mut stream, "404 Not Found", "Not found")?;
+            return Ok(None);
+        }
+
+        let result = parse_callback(&target, &self.state);
+        let message = match &result {
+            Ok(_) => "Login complete. You can close this window and return to the terminal.",
+            Err(_) => "Login failed. Check the terminal for details.",
+        };
+        write_response(&mut stream, "200 OK", message)?;
+
+        Ok(Some(result))
+    }
+
+    /// Exchange an authorization code for a token
+    pub fn exchange_code(&self, code: &str) -> Result<OAuth2Token> {
+        let token_url = self
+            .auth
+            .token_url
+            .as_deref()
+            .ok_or_else(|| Error::Auth("No token URL configured".to_string()))?;
+
+        let redirect_uri = self.redirect_uri();
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("client_id", self.auth.client_id.as_str()),
+            ("code_verifier", self.pkce.verifier.as_str()),
+        ];
+        if let Some(secret) = &self.auth.client_secret {
+            params.push(("client_secret", secret.as_str()));
+        }
+
+        let response = reqwest::blocking::Client::new()
+            .post(token_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&params)
+            .send()?;
+
+        let status = response.status();
+        let body = response.text()?;
+        if !status.is_success() {
+            return Err(Error::Auth(format!(
+                "Token endpoint returned {}: {}",
+                status, body
+            )));
+        }
+
+        parse_token_response(&body)
+    }
+
+    /// Run the whole flow: listen, hand out the URL, wait, exchange
+    pub fn run<F: FnOnce(&str)>(&self, on_authorize_url: F) -> Result<OAuth2Token> {
+        let listener = self.bind()?;
+        let url = self.authorize_url()?;
+        on_authorize_url(&url);
+
+        let code = self.wait_for_callback(&listener)?;
+        self.exchange_code(&code)
+    }
+}
+
+/// Build an OAuth2 configuration from `OAUTH2_*` environment variables
+pub fn oauth2_from_environment(env: &Environment) -> Result<OAuth2Auth> {
+    let required = |key: &str| {
+        env.get_variable(key).map(str::to_string).ok_or_else(|| {
+            Error::MissingArgument(format!(
+                "Environment '{}' has no {} variable",
+                env.name, key
+            ))
+        })
+    };
+
+    let mut auth = OAuth2Auth::new(
+        crate::auth::oauth2::GrantType::AuthorizationCode,
+        required("OAUTH2_CLIENT_ID")?,
+    )
+    .with_auth_url(required("OAUTH2_AUTH_URL")?)
+    .with_token_url(required("OAUTH2_TOKEN_URL")?);
+
+    if let Some(secret) = env.get_variable("OAUTH2_CLIENT_SECRET") {
+        auth = auth.with_client_secret(secret.to_string());
+    }
+    if let Some(scopes) = env.get_variable("OAUTH2_SCOPES") {
+        auth = auth.with_scopes(scopes.split_whitespace().map(str::to_string).collect());
+    }
+
+    Ok(auth)
+}
+
+/// Parse the redirect request target and return the authorization code
+pub fn parse_callback(target: &str, expected_state: &str) -> Result<String> {
+    let url = reqwest::Url::parse(&format!("http://localhost{}", target))
+        .map_err(|e| Error::Auth(format!("Malformed redirect: {}", e)))?;
+
+    let mut code = None;
+    let mut state = None;
+    let mut error = None;
+    let mut error_description = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "code" => code = Some(value.into_owned()),
+            "state" => state = Some(value.into_owned()),
+            "error" => error = Some(value.into_owned()),
+            "error_description" => error_description = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+
+    if let Some(error) = error {
+        return Err(if error == "access_denied" {
+            Error::Auth("Login was cancelled in the browser".to_string())
+        } else {
+            Error::Auth(match error_description {
+                Some(desc) => format!("{}: {}", error, desc),
+                None => error,
+            })
+        });
+    }
+
+    if state.as_deref() != Some(expected_state) {
+        return Err(Error::Auth(
+            "State mismatch in redirect; the response may not belong to this login attempt"
+                .to_string(),
+        ));
+    }
+
+    code.ok_or_else(|| Error::Auth("Redirect did not contain an authorization code".to_string()))
+}
+
+/// Parse a token endpoint JSON response
+pub fn parse_token_response(body: &str) -> Result<OAuth2Token> {
+    let json: serde_json::Value = serde_json::from_str(body)?;
+
+    let access_token = json
+        .get("access_token")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::Auth("Token response has no access_token".to_string()))?;
+    let token_type = json
+        .get("token_type")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Bearer");
+
+    let mut token = OAuth2Token::new(access_token.to_string(), token_type.to_string());
+    if let Some(expires_in) = json.get("expires_in").and_then(|v| v.as_i64()) {
+        token = token.with_expiration(expires_in);
+    }
+    if let Some(refresh) = json.get("refresh_token").and_then(|v| v.as_str()) {
+        token = token.with_refresh_token(refresh.to_string());
+    }
+    if let Some(scope) = json.get("scope").and_then(|v| v.as_str()) {
+        token = token.with_scopes(scope.split_whitespace().map(str::to_string).collect());
+    }
+
+    Ok(token)
+}
+
+/// Try to open a URL in the user's browser
+pub fn open_browser(url: &str) -> bool {
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open").arg(url).status();
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("cmd")
+        .args(["/C", "start", "", url])
+        .status();
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let result = std::process::Command::new("xdg-open").arg(url).status();
+
+    result.map(|s| s.success()).unwrap_or(false)
+}
+
+/// Read the request line of an HTTP request and return its target
+fn read_request_target(stream: &mut TcpStream) -> Option<String> {
+    let mut buffer = [0u8; 8192];
+    let mut data = Vec::new();
+
+    while !data.windows(4).any(|w| w == b"\r\n\r\n") && data.len() < buffer.len() {
+        match stream.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => data.extend_from_slice(&buffer[..n]),
+            Err(_) => break,
+        }
+    }
+
+    let text = String::from_utf8_lossy(&data);
+    let request_line = text.lines().next()?;
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    }
+}
+
+/// Write a minimal HTML response to the browser
+fn write_response(stream: &mut TcpStream, status: &str, message: &str) -> Result<()> {
+    let body = format!(
+        "<html><body><h3>Bazzounquester</h3><p>{}</p></body></html>",
+        message
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())?;
+    stream.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::oauth2::GrantType;
+
+    fn test_auth() -> OAuth2Auth {
+        OAuth2Auth::new(GrantType::AuthorizationCode, "client123".to_string())
+            .with_auth_url("https://auth.example.com/authorize".to_string())
+            .with_token_url("https://auth.example.com/token".to_string())
+            .with_scopes(vec!["read".to_string(), "write".to_string()])
+    }
+
+    #[test]
+    fn test_pkce_s256_challenge() {
+        let pkce = PkceChallenge::from_verifier(
+            "bazzounquester-test-verifier-0123456789abcdef".to_string(),
+        );
+        assert_eq!(
+            pkce.challenge,
+            "N13gwQIBfPTA8YBG5zJI16-3oLNTx67KLchr3XHPqWY"
+        );
+        assert_eq!(pkce.method(), "S256");
+    }
+
+    #[test]
+    fn test_pkce_generate() {
+        let pkce = PkceChallenge::generate();
+        assert!(pkce.verifier.len() >= 43 && pkce.verifier.len() <= 128);
+        assert_ne!(pkce.verifier, PkceChallenge::generate().verifier);
+    }
+
+    #[test]
+    fn test_authorize_url() {
+        let flow = AuthorizationCodeFlow::new(test_auth()).with_port(9000);
+        let url = flow.authorize_url().unwrap();
+
+        assert!(url.starts_with("https://auth.example.com/authorize?"));
+        assert!(url.contains("response_type=code"));
+        assert!(url.contains("client_id=client123"));
+        assert!(url.contains("redirect_uri=http%3A%2F%2Flocalhost%3A9000%2Fcallback"));
+        assert!(url.contains(&format!("state={}", flow.state())));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains("scope=read+write"));
+    }
+
+    #[test]
+    fn test_authorize_url_missing() {
+        let auth = OAuth2Auth::new(GrantType::AuthorizationCode, "client".to_string());
+        let flow = AuthorizationCodeFlow::new(auth);
+        assert!(flow.authorize_url().is_err());
+    }
+
+    #[test]
+    fn test_parse_callback_success() {
+        let code = parse_callback("/callback?code=abc123&state=xyz", "xyz").unwrap();
+        assert_eq!(code, "abc123");
+    }
+
+    #[test]
+    fn test_parse_callback_state_mismatch() {
+        let result = parse_callback("/callback?code=abc123&state=other", "xyz");
+        assert!(result.unwrap_err().to_string().contains("State mismatch"));
+    }
+
+    #[test]
+    fn test_parse_callback_cancelled() {
+        let result = parse_callback("/callback?error=access_denied&state=xyz", "xyz");
+        assert!(result.unwrap_err().to_string().contains("cancelled"));
+    }
+
+    #[test]
+    fn test_parse_token_response() {
+        let body = r#"{"access_token":"tok","token_type":"Bearer","expires_in":3600,"refresh_token":"ref","scope":"read write"}"#;
+        let token = parse_token_response(body).unwrap();
+
+        assert_eq!(token.access_token, "tok");
+        assert_eq!(token.refresh_token, Some("ref".to_string()));
+        assert_eq!(token.scopes.len(), 2);
+        assert!(!token.is_expired());
+    }
+
+    #[test]
+    fn test_parse_token_response_missing_token() {
+        assert!(parse_token_response(r#"{"error":"invalid_grant"}"#).is_err());
+    }
+
+    #[test]
+    fn test_bind_busy_port() {
+        let taken = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        let flow = AuthorizationCodeFlow::new(test_auth()).with_port(port);
+        let err = flow.bind().unwrap_err();
+        assert!(err.to_string().contains("already in use"));
+    }
+
+    #[test]
+    fn test_wait_for_callback() {
+        let flow = AuthorizationCodeFlow::new(test_auth())
+            .with_port(0)
+            .with_timeout(Duration::from_secs(5));
+        let listener = flow.bind().unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = flow.state().to_string();
+
+        let client = std::thread::spawn(move || {
+            let mut favicon = TcpStream::connect(addr).unwrap();
+            favicon
+                .write_all(b"GET /favicon.ico HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .unwrap();
+            let mut ignored = String::new();
+            favicon.read_to_string(&mut ignored).unwrap();
+
+            let mut stream = TcpStream::connect(addr).unwrap();
+            let request = format!(
+                "GET /callback?code=the-code&state={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                state
+            );
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+
+        let code = flow.wait_for_callback(&listener).unwrap();
+        assert_eq!(code, "the-code");
+        assert!(client.join().unwrap().contains("Login complete"));
+    }
+
+    #[test]
+    fn test_wait_for_callback_timeout() {
+        let flow = AuthorizationCodeFlow::new(test_auth())
+            .with_port(0)
+            .with_timeout(Duration::from_millis(200));
+        let listener = flow.bind().unwrap();
+
+        let err = flow.wait_for_callback(&listener).unwrap_err();
+        assert!(err.to_string().contains("Timed out"));
+    }
+
+    #[test]
+    fn test_oauth2_from_environment() {
+        let mut env = Environment::new("dev".to_string());
+        env.set_variable("OAUTH2_CLIENT_ID".to_string(), "cid".to_string());
+        env.set_variable(
+            "OAUTH2_AUTH_URL".to_string(),
+            "https://a.example.com".to_string(),
+        );
+        env.set_variable(
+            "OAUTH2_TOKEN_URL".to_string(),
+            "https://t.example.com".to_string(),
+        );
+        env.set_variable("OAUTH2_SCOPES".to_string(), "openid profile".to_string());
+
+        let auth = oauth2_from_environment(&env).unwrap();
+        assert_eq!(auth.client_id, "cid");
+        assert_eq!(auth.scopes, vec!["openid", "profile"]);
+
+        env.remove_variable("OAUTH2_TOKEN_URL");
+        assert!(oauth2_from_environment(&env).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+//! HMAC request signing
+
+use crate::error::{Error, Result};
+use crate::http::HostInfo;
+use ::hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Sha512};
+
+/// Default string-to-sign template
+pub const DEFAULT_TEMPLATE: &str = "{method}\n{path}\n{timestamp}\n{body}";
+
+/// Hash algorithm used for the HMAC
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HmacAlgorithm {
+    /// HMAC-SHA256
+    #[default]
+    Sha256,
+
+    /// HMAC-SHA512
+    Sha512,
+}
+
+impl HmacAlgorithm {
+    /// Get the algorithm name
+    pub fn as_str(&self) -> &str {
+        match self {
+            HmacAlgorithm::Sha256 => "sha256",
+            HmacAlgorithm::Sha512 => "sha512",
+        }
+    }
+}
+
+impl std::str::FromStr for HmacAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "sha256" => Ok(HmacAlgorithm::Sha256),
+            "sha512" => Ok(HmacAlgorithm::Sha512),
+            _ => Err(Error::InvalidCommand(format!(
+                "Unsupported HMAC algorithm '{}' (use sha256 or sha512)",
+                s
+            ))),
+        }
+    }
+}
+
+/// HMAC signature authentication
+///
+/// The signature is computed over a string built from `template`, in which
+/// `{method}`, `{path}`, `{query}`, `{host}`, `{timestamp}` and `{body}` are
+/// replaced with values from the final request. `{host}` is the Host header
+/// value: IPv6 literals in brackets, with the port unless it is the default.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HmacAuth {
+    /// Shared secret
+    pub secret: String,
+
+    /// Hash algorithm
+    pub algorithm: HmacAlgorithm,
+
+    /// Header that carries the hex signature
+    pub header_name: String,
+
+    /// Header that carries the Unix timestamp (None to omit it)
+    pub timestamp_header: Option<String>,
+
+    /// String-to-sign template
+    pub template: String,
+}
+
+impl HmacAuth {
+    /// Create HMAC-SHA256 auth with the default headers and template
+    pub fn new(secret: String) -> Self {
+        Self {
+            secret,
+            algorithm: HmacAlgorithm::Sha256,
+            header_name: "X-Signature".to_string(),
+            timestamp_header: Some("X-Timestamp".to_string()),
+            template: DEFAULT_TEMPLATE.to_string(),
+        }
+    }
+
+    /// Set the hash algorithm
+    pub fn with_algorithm(mut self, algorithm: HmacAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Set the signature header name
+    pub fn with_header_name(mut self, header_name: String) -> Self {
+        self.header_name = header_name;
+        self
+    }
+
+    /// Set the timestamp header name
+    pub fn with_timestamp_header(mut self, timestamp_header: Option<String>) -> Self {
+        self.timestamp_header = timestamp_header;
+        self
+    }
+
+    /// Set the string-to-sign template
+    pub fn with_template(mut self, template: String) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Build the string to sign for a request
+    pub fn string_to_sign(
+        &self,
+        method: &str,
+        url: &str,
+        body: &[u8],
+        timestamp: i64,
+    ) -> Result<Vec<u8>> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| Error::Auth(format!("Cannot sign invalid URL '{}': {}", url, e)))?;
+
+        let mut output = Vec::new();
+        let mut rest = self.template.as_str();
+        while let Some(start) = rest.find('{') {
+            output.extend_from_slice(&rest.as_bytes()[..start]);
+            let end = rest[start..].find('}').map(|i| start + i).ok_or_else(|| {
+                Error::Auth(format!(
+                    "Unclosed placeholder in HMAC template '{}'",
+                    self.template
+                ))
+            })?;
+
+            match &rest[start + 1..end] {
+                "method" => output.extend_from_slice(method.to_uppercase().as_bytes()),
+                "path" => output.extend_from_slice(parsed.path().as_bytes()),
+                "query" => output.extend_from_slice(parsed.query().unwrap_or("").as_bytes()),
+                "host" => {
+                    let authority = HostInfo::from_url(&parsed).map(|h| h.authority());
+                    output.extend_from_slice(authority.unwrap_or_default().as_bytes())
+                }
+                "timestamp" => output.extend_from_slice(timestamp.to_string().as_bytes()),
+                "body" => output.extend_from_slice(body),
+                other => {
+                    return Err(Error::Auth(format!(
+                        "Unknown placeholder '{{{}}}' in HMAC template",
+                        other
+                    )))
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+        output.extend_from_slice(rest.as_bytes());
+
+        Ok(output)
+    }
+
+    /// Compute the hex signature at a given timestamp
+    pub fn sign_at(&self, method: &str, url: &str, body: &[u8], timestamp: i64) -> Result<String> {
+        let message = self.string_to_sign(method, url, body, timestamp)?;
+        let digest = match self.algorithm {
+            HmacAlgorithm::Sha256 => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+                    .map_err(|e| Error::Auth(e.to_string()))?;
+                mac.update(&message);
+                mac.finalize().into_bytes().to_vec()
+            }
+            HmacAlgorithm::Sha512 => {
+                let mut mac = Hmac::<Sha512>::new_from_slice(self.secret.as_bytes())
+                    .map_err(|e| Error::Auth(e.to_string()))?;
+                mac.update(&message);
+                mac.finalize().into_bytes().to_vec()
+            }
+        };
+
+        Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Headers to add for a request signed at the given timestamp
+    pub fn signed_headers_at(
+        &self,
+        method: &str,
+        url: &str,
+        body: &[u8],
+        timestamp: i64,
+    ) -> Result<Vec<(String, String)>> {
+        let mut headers = vec![(
+            self.header_name.clone(),
+            self.sign_at(method, url, body, timestamp)?,
+        )];
+        if let Some(timestamp_header) = &self.timestamp_header {
+            headers.push((timestamp_header.clone(), timestamp.to_string()));
+        }
+        Ok(headers)
+    }
+
+    /// Headers to add for a request signed now
+    pub fn signed_headers(
+        &self,
+        method: &str,
+        url: &str,
+        body: &[u8],
+    ) -> Result<Vec<(String, String)>> {
+        self.signed_headers_at(method, url, body, chrono::Utc::now().timestamp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://api.example.com/api/orders";
+    const BODY: &[u8] = br#"{"id":42}"#;
+
+    #[test]
+    fn test_string_to_sign_default_template() {
+        let auth = HmacAuth::new("topsecret".to_string());
+        let message = auth.string_to_sign("post", URL, BODY, 1700000000).unwrap();
+        assert_eq!(
+            String::from_utf8(message).unwrap(),
+            "POST\n/api/orders\n1700000000\n{\"id\":42}"
+        );
+    }
+
+    #[test]
+    fn test_known_answer_sha256() {
+        let auth = HmacAuth::new("topsecret".to_string());
+        assert_eq!(
+            auth.sign_at("POST", URL, BODY, 1700000000).unwrap(),
+            "65670f895a8885f19d21c4782f64619122086ec7aedbcb2297d5cedc0e5f5f21"
+        );
+    }
+
+    #[test]
+    fn test_known_answer_sha512() {
+        let auth = HmacAuth::new("topsecret".to_string()).with_algorithm(HmacAlgorithm::Sha512);
+        assert_eq!(
+            auth.sign_at("POST", URL, BODY, 1700000000).unwrap(),
+            "de8144c300062606852d16dcb730209909fdc7e1041f0888af86493d7a192ad22b2868de19e65f3e6befd42cd19df36ffb4a577929451d1570406d0e1c0070e0"
+        );
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let auth = HmacAuth::new("k".to_string())
+            .with_template("{method} {path}?{query} {host} {timestamp}".to_string());
+        assert_eq!(
+            auth.sign_at(
+                "GET",
+                "https://example.com/v1/items?page=2",
+                b"",
+                1700000000
+            )
+            .unwrap(),
+            "592a3c7e292b10240b776b415435a0c4ec5b61cd75b34388a32f90420a3c062e"
+        );
+    }
+
+    #[test]
+    fn test_host_placeholder_includes_port_and_brackets() {
+        let auth = HmacAuth::new("k".to_string()).with_template("{host}".to_string());
+        let host = |url: &str| String::from_utf8(auth.string_to_sign("GET", url, b"", 0).unwrap());
+
+        assert_eq!(host("http://[::1]:8080/health").unwrap(), "[::1]:8080");
+        assert_eq!(
+            host("https://API.Example.com:443/").unwrap(),
+            "api.example.com"
+        );
+        assert_eq!(
+            host("http://example.com:8080/").unwrap(),
+            "example.com:8080"
+        );
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        let auth = HmacAuth::new("k".to_string()).with_template("{verb}".to_string());
+        assert!(auth.sign_at("GET", URL, b"", 0).is_err());
+
+        let auth = HmacAuth::new("k".to_string()).with_template("{method".to_string());
+        assert!(auth.sign_at("GET", URL, b"", 0).is_err());
+    }
+
+    #[test]
+    fn test_signed_headers() {
+        let auth = HmacAuth::new("topsecret".to_string())
+            .with_header_name("X-Sig".to_string())
+            .with_timestamp_header(Some("X-Ts".to_string()));
+        let headers = auth
+            .signed_headers_at("POST", URL, BODY, 1700000000)
+            .unwrap();
+
+        assert_eq!(headers[0].0, "X-Sig");
+        assert_eq!(headers[1], ("X-Ts".to_string(), "1700000000".to_string()));
+
+        let auth = auth.with_timestamp_header(None);
+        assert_eq!(
+            auth.signed_headers_at("POST", URL, BODY, 0).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_algorithm_from_str() {
+        assert_eq!(
+            "SHA512".parse::<HmacAlgorithm>().unwrap(),
+            HmacAlgorithm::Sha512
+        );
+        assert!("md5".parse::<HmacAlgorithm>().is_err());
+    }
+}
@@ -0,0 +1,309 @@
+//! Self-signed JWT generation for bearer auth
+
+use crate::error::{Error, Result};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Tokens this close to expiry are regenerated
+pub const REFRESH_MARGIN_SECS: i64 = 30;
+
+/// Signing algorithm for generated JWTs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum JwtAlgorithm {
+    /// HMAC-SHA256 with a shared secret
+    Hs256,
+
+    /// RSA-SHA256 with a PEM private key
+    #[default]
+    Rs256,
+
+    /// ECDSA P-256 with a PEM (PKCS#8) private key
+    Es256,
+}
+
+impl JwtAlgorithm {
+    /// Get the algorithm name
+    pub fn as_str(&self) -> &str {
+        match self {
+            JwtAlgorithm::Hs256 => "HS256",
+            JwtAlgorithm::Rs256 => "RS256",
+            JwtAlgorithm::Es256 => "ES256",
+        }
+    }
+}
+
+impl std::str::FromStr for JwtAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "hs256" => Ok(JwtAlgorithm::Hs256),
+            "rs256" => Ok(JwtAlgorithm::Rs256),
+            "es256" => Ok(JwtAlgorithm::Es256),
+            _ => Err(Error::InvalidCommand(format!(
+                "Unsupported JWT algorithm '{}' (use hs256, rs256 or es256)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Last generated token and its expiry, shared between clones
+#[derive(Debug, Clone, Default)]
+struct TokenSlot(Arc<Mutex<Option<(String, i64)>>>);
+
+impl PartialEq for TokenSlot {
+    fn eq(&self, _other: &Self) -> bool {
+        // A cached token is not part of the configuration
+        true
+    }
+}
+
+/// Configuration for generating a signed JWT at request time
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JwtConfig {
+    /// Signing algorithm
+    pub algorithm: JwtAlgorithm,
+
+    /// Signing key: the raw secret for HS256, otherwise a PEM file path (or inline PEM)
+    pub key: String,
+
+    /// Issuer (`iss`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+
+    /// Subject (`sub`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+
+    /// Audience (`aud`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+
+    /// Token lifetime in seconds
+    pub ttl_secs: u64,
+
+    /// Additional claims
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub claims: serde_json::Map<String, serde_json::Value>,
+
+    #[serde(skip)]
+    cache: TokenSlot,
+}
+
+impl JwtConfig {
+    /// Create a config with a 5 minute lifetime
+    pub fn new(algorithm: JwtAlgorithm, key: String) -> Self {
+        Self {
+            algorithm,
+            key,
+            issuer: None,
+            subject: None,
+            audience: None,
+            ttl_secs: 300,
+            claims: serde_json::Map::new(),
+            cache: TokenSlot::default(),
+        }
+    }
+
+    /// Set the issuer
+    pub fn with_issuer(mut self, issuer: String) -> Self {
+        self.issuer = Some(issuer);
+        self
+    }
+
+    /// Set the subject
+    pub fn with_subject(mut self, subject: String) -> Self {
+        self.subject = Some(subject);
+        self
+    }
+
+    /// Set the audience
+    pub fn with_audience(mut self, audience: String) -> Self {
+        self.audience = Some(audience);
+        self
+    }
+
+    /// Set the token lifetime
+    pub fn with_ttl(mut self, ttl_secs: u64) -> Self {
+        self.ttl_secs = ttl_secs;
+        self
+    }
+
+    /// Add an extra claim
+    pub fn with_claim(mut self, name: String, value: serde_json::Value) -> Self {
+        self.claims.insert(name, value);
+        self
+    }
+
+    /// Load the encoding key
+    fn encoding_key(&self) -> Result<EncodingKey> {
+        if self.algorithm == JwtAlgorithm::Hs256 {
+            return Ok(EncodingKey::from_secret(self.key.as_bytes()));
+        }
+
+        let pem = if self.key.trim_start().starts_with("-----BEGIN") {
+            self.key.clone().into_bytes()
+        } else {
+            std::fs::read(&self.key)
+                .map_err(|e| Error::Auth(format!("Could not read JWT key '{}': {}", self.key, e)))?
+        };
+
+        let key = match self.algorithm {
+            JwtAlgorithm::Rs256 => EncodingKey::from_rsa_pem(&pem),
+            _ => EncodingKey::from_ec_pem(&pem),
+        };
+        key.map_err(|e| {
+            Error::Auth(format!(
+                "Invalid {} key '{}': {}",
+                self.algorithm.as_str(),
+                self.key,
+                e
+            ))
+        })
+    }
+
+    /// Generate a token issued at `now` (Unix seconds)
+    pub fn generate_at(&self, now: i64) -> Result<String> {
+        let mut claims = self.claims.clone();
+        let mut set = |name: &str, value: Option<&String>| {
+            if let Some(value) = value {
+                claims.insert(name.to_string(), value.clone().into());
+            }
+        };
+        set("iss", self.issuer.as_ref());
+        set("sub", self.subject.as_ref());
+        set("aud", self.audience.as_ref());
+        claims.insert("iat".to_string(), now.into());
+        claims.insert("exp".to_string(), (now + self.ttl_secs as i64).into());
+
+        let algorithm = match self.algorithm {
+            JwtAlgorithm::Hs256 => Algorithm::HS256,
+            JwtAlgorithm::Rs256 => Algorithm::RS256,
+            JwtAlgorithm::Es256 => Algorithm::ES256,
+        };
+
+        jsonwebtoken::encode(&Header::new(algorithm), &claims, &self.encoding_key()?)
+            .map_err(|e| Error::Auth(format!("Could not sign JWT: {}", e)))
+    }
+
+    /// Get a token valid at `now`, reusing the last one unless it is near expiry
+    pub fn token_at(&self, now: i64) -> Result<String> {
+        let mut slot = self.cache.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((token, expires_at)) = slot.as_ref() {
+            if expires_at - now > REFRESH_MARGIN_SECS {
+                return Ok(token.clone());
+            }
+        }
+
+        let token = self.generate_at(now)?;
+        *slot = Some((token.clone(), now + self.ttl_secs as i64));
+        Ok(token)
+    }
+
+    /// Get a token valid now
+    pub fn token(&self) -> Result<String> {
+        self.token_at(chrono::Utc::now().timestamp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{DecodingKey, Validation};
+
+    fn fixture(name: &str) -> String {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn decode(token: &str, algorithm: Algorithm, key: &DecodingKey) -> serde_json::Value {
+        let mut validation = Validation::new(algorithm);
+        validation.set_audience(&["api"]);
+        jsonwebtoken::decode::<serde_json::Value>(token, key, &validation)
+            .unwrap()
+            .claims
+    }
+
+    fn config(algorithm: JwtAlgorithm, key: String) -> JwtConfig {
+        JwtConfig::new(algorithm, key)
+            .with_issuer("my-app".to_string())
+            .with_subject("svc".to_string())
+            .with_audience("api".to_string())
+            .with_ttl(600)
+            .with_claim("scope".to_string(), "read".into())
+    }
+
+    #[test]
+    fn test_hs256_token_verifies() {
+        let now = chrono::Utc::now().timestamp();
+        let token = config(JwtAlgorithm::Hs256, "s3cret".to_string())
+            .generate_at(now)
+            .unwrap();
+
+        let claims = decode(
+            &token,
+            Algorithm::HS256,
+            &DecodingKey::from_secret(b"s3cret"),
+        );
+        assert_eq!(claims["iss"], "my-app");
+        assert_eq!(claims["sub"], "svc");
+        assert_eq!(claims["scope"], "read");
+        assert_eq!(claims["iat"], now);
+        assert_eq!(claims["exp"], now + 600);
+    }
+
+    #[test]
+    fn test_rs256_token_verifies() {
+        let token = config(JwtAlgorithm::Rs256, fixture("jwt_rs256.key"))
+            .token()
+            .unwrap();
+
+        let public = std::fs::read(fixture("jwt_rs256.pub")).unwrap();
+        let claims = decode(
+            &token,
+            Algorithm::RS256,
+            &DecodingKey::from_rsa_pem(&public).unwrap(),
+        );
+        assert_eq!(claims["aud"], "api");
+    }
+
+    #[test]
+    fn test_es256_token_verifies() {
+        let token = config(JwtAlgorithm::Es256, fixture("jwt_es256.key"))
+            .token()
+            .unwrap();
+
+        let public = std::fs::read(fixture("jwt_es256.pub")).unwrap();
+        let claims = decode(
+            &token,
+            Algorithm::ES256,
+            &DecodingKey::from_ec_pem(&public).unwrap(),
+        );
+        assert_eq!(claims["sub"], "svc");
+    }
+
+    #[test]
+    fn test_token_regenerated_near_expiry() {
+        let config = config(JwtAlgorithm::Hs256, "s3cret".to_string()).with_ttl(100);
+
+        let first = config.token_at(1_000).unwrap();
+        assert_eq!(config.token_at(1_069).unwrap(), first);
+
+        // Within 30 seconds of expiry (exp = 1100)
+        let refreshed = config.token_at(1_071).unwrap();
+        assert_ne!(refreshed, first);
+    }
+
+    #[test]
+    fn test_missing_key_file() {
+        let config = JwtConfig::new(JwtAlgorithm::Rs256, "/nonexistent/key.pem".to_string());
+        assert!(config.generate_at(0).is_err());
+    }
+}
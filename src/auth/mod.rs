@@ -3,14 +3,26 @@
 pub mod api_key;
 pub mod basic;
 pub mod bearer;
+pub mod flow;
+pub mod hmac;
+pub mod jwt;
 pub mod oauth2;
+pub mod profile;
+pub mod token_cache;
 
 pub use api_key::ApiKeyAuth;
 pub use basic::BasicAuth;
 pub use bearer::BearerAuth;
+pub use flow::{AuthorizationCodeFlow, PkceChallenge};
+pub use hmac::{HmacAlgorithm, HmacAuth};
+pub use jwt::{JwtAlgorithm, JwtConfig};
 pub use oauth2::OAuth2Auth;
+pub use profile::{AuthProfile, AuthProfileStore};
+pub use token_cache::TokenCache;
 
+use crate::env::VariableSubstitutor;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Authentication scheme types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -30,6 +42,9 @@ pub enum AuthScheme {
 
     /// OAuth 2.0 authentication
     OAuth2(OAuth2Auth),
+
+    /// HMAC request signature
+    Hmac(HmacAuth),
 }
 
 impl AuthScheme {
@@ -41,9 +56,57 @@ impl AuthScheme {
             AuthScheme::Bearer(auth) => auth.apply_to_headers(headers),
             AuthScheme::ApiKey(auth) => auth.apply(headers, query_params),
             AuthScheme::OAuth2(auth) => auth.apply_to_headers(headers),
+            // Signatures need the final request; see `signing_headers`
+            AuthScheme::Hmac(_) => {}
+        }
+    }
+
+    /// Compute headers generated at send time (signatures, fresh JWTs)
+    ///
+    /// Called by the HTTP client once the URL and body are final.
+    pub fn signing_headers(
+        &self,
+        method: &str,
+        url: &str,
+        body: &[u8],
+    ) -> crate::Result<Vec<(String, String)>> {
+        match self {
+            AuthScheme::Hmac(auth) => auth.signed_headers(method, url, body),
+            AuthScheme::Bearer(auth) => Ok(auth.jwt_header()?.into_iter().collect()),
+            _ => Ok(Vec::new()),
         }
     }
 
+    /// Fill {{variable}} references in credentials from `variables`
+    ///
+    /// Lets saved requests refer to secrets (e.g. `{{API_TOKEN}}`) instead of
+    /// storing them in plaintext.
+    pub fn with_variables(&self, variables: &HashMap<&str, &str>) -> Self {
+        let substitutor = VariableSubstitutor::new();
+        let substitute = |text: &mut String| *text = substitutor.substitute(text, variables);
+
+        let mut auth = self.clone();
+        match &mut auth {
+            AuthScheme::None => {}
+            AuthScheme::Basic(basic) => {
+                substitute(&mut basic.username);
+                substitute(&mut basic.password);
+            }
+            AuthScheme::Bearer(bearer) => substitute(&mut bearer.token),
+            AuthScheme::ApiKey(api_key) => substitute(&mut api_key.key),
+            AuthScheme::OAuth2(oauth2) => {
+                if let Some(secret) = &mut oauth2.client_secret {
+                    substitute(secret);
+                }
+                if let Some(token) = &mut oauth2.token {
+                    substitute(&mut token.access_token);
+                }
+            }
+            AuthScheme::Hmac(hmac) => substitute(&mut hmac.secret),
+        }
+        auth
+    }
+
     /// Check if authentication is configured
     pub fn is_configured(&self) -> bool {
         !matches!(self, AuthScheme::None)
@@ -72,4 +135,27 @@ mod tests {
         assert_eq!(headers.len(), 0);
         assert_eq!(query_params.len(), 0);
     }
+
+    #[test]
+    fn test_with_variables_fills_secret_references() {
+        let variables = HashMap::from([("API_TOKEN", "s3cret"), ("USER", "alice")]);
+
+        let bearer = AuthScheme::Bearer(BearerAuth::new("{{API_TOKEN}}".to_string()));
+        assert_eq!(
+            bearer.with_variables(&variables),
+            AuthScheme::Bearer(BearerAuth::new("s3cret".to_string()))
+        );
+
+        let basic = AuthScheme::Basic(BasicAuth::new(
+            "{{USER}}".to_string(),
+            "{{MISSING}}".to_string(),
+        ));
+        assert_eq!(
+            basic.with_variables(&variables),
+            AuthScheme::Basic(BasicAuth::new(
+                "alice".to_string(),
+                "{{MISSING}}".to_string()
+            ))
+        );
+    }
 }
@@ -0,0 +1,222 @@
+//! Named authentication profiles
+
+use crate::auth::{ApiKeyAuth, AuthScheme, BasicAuth, BearerAuth, JwtConfig};
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// A saved authentication configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuthProfile {
+    /// Profile name
+    pub name: String,
+
+    /// Authentication scheme
+    pub scheme: AuthScheme,
+
+    /// Created timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuthProfile {
+    /// Create a new profile
+    pub fn new(name: String, scheme: AuthScheme) -> Self {
+        Self {
+            name,
+            scheme,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Create a bearer token profile
+    pub fn bearer(name: String, token: String) -> Self {
+        Self::new(name, AuthScheme::Bearer(BearerAuth::new(token)))
+    }
+
+    /// Create a profile that signs a JWT for each request
+    pub fn jwt(name: String, config: JwtConfig) -> Self {
+        Self::new(name, AuthScheme::Bearer(BearerAuth::from_jwt(config)))
+    }
+
+    /// Create a basic auth profile from "user:pass"
+    pub fn basic(name: String, credentials: &str) -> Result<Self> {
+        let (username, password) = credentials.split_once(':').ok_or_else(|| {
+            Error::InvalidCommand("Basic credentials must be in format 'user:pass'".to_string())
+        })?;
+        Ok(Self::new(
+            name,
+            AuthScheme::Basic(BasicAuth::new(username.to_string(), password.to_string())),
+        ))
+    }
+
+    /// Create an API key profile from "Name:value"
+    pub fn api_key(name: String, key: &str, in_query: bool) -> Result<Self> {
+        let (key_name, value) = key.split_once(':').ok_or_else(|| {
+            Error::InvalidCommand("API key must be in format 'Name:value'".to_string())
+        })?;
+        let auth = if in_query {
+            ApiKeyAuth::query(key_name.trim().to_string(), value.trim().to_string())
+        } else {
+            ApiKeyAuth::header(key_name.trim().to_string(), value.trim().to_string())
+        };
+        Ok(Self::new(name, AuthScheme::ApiKey(auth)))
+    }
+
+    /// Short description of the scheme (never includes secrets)
+    pub fn kind(&self) -> String {
+        match &self.scheme {
+            AuthScheme::None => "none".to_string(),
+            AuthScheme::Basic(auth) => format!("basic ({})", auth.username),
+            AuthScheme::Bearer(auth) => match &auth.jwt {
+                Some(jwt) => format!("jwt ({})", jwt.algorithm.as_str()),
+                None => "bearer".to_string(),
+            },
+            AuthScheme::ApiKey(auth) => format!("api key ({})", auth.name),
+            AuthScheme::OAuth2(auth) => format!("oauth2 ({})", auth.client_id),
+            AuthScheme::Hmac(auth) => format!("hmac ({})", auth.algorithm.as_str()),
+        }
+    }
+}
+
+/// Persistent store of auth profiles
+pub struct AuthProfileStore {
+    path: PathBuf,
+    profiles: HashMap<String, AuthProfile>,
+}
+
+impl AuthProfileStore {
+    /// Load profiles from a file (missing file yields an empty store)
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let profiles = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content)?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self { path, profiles })
+    }
+
+    /// Get default profiles file path
+    pub fn default_path() -> Result<PathBuf> {
+        let dirs = directories::ProjectDirs::from("com", "bazzoun", "bazzounquester").ok_or_else(
+            || {
+                Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Could not determine data directory",
+                ))
+            },
+        )?;
+
+        let path = dirs.data_dir().join("auth").join("profiles.json");
+        Ok(path)
+    }
+
+    /// Add or replace a profile
+    pub fn add(&mut self, profile: AuthProfile) {
+        self.profiles.insert(profile.name.clone(), profile);
+    }
+
+    /// Get a profile by name
+    pub fn get(&self, name: &str) -> Option<&AuthProfile> {
+        self.profiles.get(name)
+    }
+
+    /// Get a mutable profile by name
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut AuthProfile> {
+        self.profiles.get_mut(name)
+    }
+
+    /// Remove a profile
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.profiles.remove(name).is_some()
+    }
+
+    /// List profiles sorted by name
+    pub fn list(&self) -> Vec<&AuthProfile> {
+        let mut profiles: Vec<&AuthProfile> = self.profiles.values().collect();
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        profiles
+    }
+
+    /// Look up the auth scheme for a profile, failing if it doesn't exist
+    pub fn resolve(&self, name: &str) -> Result<AuthScheme> {
+        self.get(name)
+            .map(|profile| profile.scheme.clone())
+            .ok_or_else(|| Error::InvalidCommand(format!("Auth profile '{}' not found", name)))
+    }
+
+    /// Save profiles to disk (owner-only permissions on Unix)
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&self.profiles)?;
+        std::fs::write(&self.path, json)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::api_key::ApiKeyLocation;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_profile_constructors() {
+        let profile = AuthProfile::bearer("github".to_string(), "tok".to_string());
+        assert_eq!(profile.kind(), "bearer");
+
+        let profile = AuthProfile::basic("stage".to_string(), "user:p:ss").unwrap();
+        match profile.scheme {
+            AuthScheme::Basic(auth) => {
+                assert_eq!(auth.username, "user");
+                assert_eq!(auth.password, "p:ss");
+            }
+            _ => panic!("expected basic auth"),
+        }
+
+        let profile = AuthProfile::api_key("svc".to_string(), "X-Api-Key:abc", true).unwrap();
+        match profile.scheme {
+            AuthScheme::ApiKey(auth) => {
+                assert_eq!(auth.name, "X-Api-Key");
+                assert_eq!(auth.location, ApiKeyLocation::Query);
+            }
+            _ => panic!("expected api key auth"),
+        }
+
+        assert!(AuthProfile::basic("bad".to_string(), "nocolon").is_err());
+    }
+
+    #[test]
+    fn test_profile_store_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("profiles.json");
+
+        let mut store = AuthProfileStore::load(path.clone()).unwrap();
+        store.add(AuthProfile::bearer("b".to_string(), "tok".to_string()));
+        store.add(AuthProfile::basic("a".to_string(), "u:p").unwrap());
+        store.save().unwrap();
+
+        let mut loaded = AuthProfileStore::load(path).unwrap();
+        let names: Vec<&str> = loaded.list().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert!(matches!(
+            loaded.resolve("b").unwrap(),
+            AuthScheme::Bearer(_)
+        ));
+
+        assert!(loaded.remove("b"));
+        assert!(loaded.resolve("b").is_err());
+    }
+}
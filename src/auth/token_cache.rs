@@ -0,0 +1,123 @@
+//! Persistent cache for OAuth 2.0 tokens
+
+use crate::auth::oauth2::OAuth2Token;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Cache of OAuth2 tokens keyed by environment or profile name
+pub struct TokenCache {
+    path: PathBuf,
+    tokens: HashMap<String, OAuth2Token>,
+}
+
+impl TokenCache {
+    /// Load the cache from a file (missing file yields an empty cache)
+    pub fn load(path: PathBuf) -> crate::Result<Self> {
+        let tokens = if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content)?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self { path, tokens })
+    }
+
+    /// Get default cache file path
+    pub fn default_path() -> crate::Result<PathBuf> {
+        let dirs = directories::ProjectDirs::from("com", "bazzoun", "bazzounquester").ok_or_else(
+            || {
+                crate::Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Could not determine data directory",
+                ))
+            },
+        )?;
+
+        let path = dirs.data_dir().join("auth").join("tokens.json");
+        Ok(path)
+    }
+
+    /// Get a cached token
+    pub fn get(&self, name: &str) -> Option<&OAuth2Token> {
+        self.tokens.get(name)
+    }
+
+    /// Store a token
+    pub fn insert(&mut self, name: String, token: OAuth2Token) {
+        self.tokens.insert(name, token);
+    }
+
+    /// Remove a token
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.tokens.remove(name).is_some()
+    }
+
+    /// Save the cache to disk (owner-only permissions on Unix)
+    pub fn save(&self) -> crate::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&self.tokens)?;
+        std::fs::write(&self.path, json)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_token_cache_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = TokenCache::load(temp_dir.path().join("tokens.json")).unwrap();
+        assert!(cache.get("dev").is_none());
+    }
+
+    #[test]
+    fn test_token_cache_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("auth").join("tokens.json");
+
+        let mut cache = TokenCache::load(path.clone()).unwrap();
+        let token = OAuth2Token::new("abc".to_string(), "Bearer".to_string())
+            .with_refresh_token("ref".to_string());
+        cache.insert("dev".to_string(), token);
+        cache.save().unwrap();
+
+        let mut loaded = TokenCache::load(path).unwrap();
+        let token = loaded.get("dev").unwrap();
+        assert_eq!(token.access_token, "abc");
+        assert_eq!(token.refresh_token, Some("ref".to_string()));
+
+        assert!(loaded.remove("dev"));
+        assert!(loaded.get("dev").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_token_cache_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tokens.json");
+        let mut cache = TokenCache::load(path.clone()).unwrap();
+        cache.insert(
+            "dev".to_string(),
+            OAuth2Token::new("abc".to_string(), "Bearer".to_string()),
+        );
+        cache.save().unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,91 @@
+//! Process exit codes
+
+use reqwest::StatusCode;
+
+/// Success (also used for 4xx/5xx responses without `--fail`)
+pub const SUCCESS: i32 = 0;
+
+/// Transport error, invalid input, or failed assertion
+pub const FAILURE: i32 = 1;
+
+/// Transport error for a request with `--assert-*` checks, so it can be told
+/// apart from a failed assertion
+pub const TRANSPORT_ERROR: i32 = 2;
+
+/// 4xx response with `--fail` or `--fail-with-body`
+pub const CLIENT_ERROR: i32 = 4;
+
+/// 5xx response with `--fail` or `--fail-with-body`
+pub const SERVER_ERROR: i32 = 5;
+
+/// The `--max-time` budget ran out (same code as coreutils `timeout`)
+pub const DEADLINE_EXCEEDED: i32 = 124;
+
+/// Exit code for a response status; 4xx/5xx only fail when `fail` is set
+pub fn for_status(status: StatusCode, fail: bool) -> i32 {
+    if !fail {
+        SUCCESS
+    } else if status.is_client_error() {
+        CLIENT_ERROR
+    } else if status.is_server_error() {
+        SERVER_ERROR
+    } else {
+        SUCCESS
+    }
+}
+
+/// Exit code for a response checked against `--assert-*` flags
+///
+/// A failed assertion wins over the status, so "the API answered wrongly"
+/// stays distinguishable from a plain 4xx/5xx.
+pub fn for_response(status: StatusCode, fail: bool, assertions_passed: bool) -> i32 {
+    if assertions_passed {
+        for_status(status, fail)
+    } else {
+        FAILURE
+    }
+}
+
+/// Whether the response body is withheld: `--fail` drops it for 4xx/5xx
+/// responses like curl does, `--fail-with-body` keeps it
+pub fn hides_body(status: StatusCode, fail: bool, with_body: bool) -> bool {
+    fail && !with_body && for_status(status, true) != SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_status() {
+        assert_eq!(for_status(StatusCode::OK, true), SUCCESS);
+        assert_eq!(for_status(StatusCode::MOVED_PERMANENTLY, true), SUCCESS);
+        assert_eq!(for_status(StatusCode::NOT_FOUND, true), CLIENT_ERROR);
+        assert_eq!(for_status(StatusCode::BAD_GATEWAY, true), SERVER_ERROR);
+        assert_eq!(for_status(StatusCode::NOT_FOUND, false), SUCCESS);
+    }
+
+    #[test]
+    fn test_for_response() {
+        assert_eq!(for_response(StatusCode::OK, true, true), SUCCESS);
+        assert_eq!(for_response(StatusCode::OK, false, false), FAILURE);
+        assert_eq!(for_response(StatusCode::NOT_FOUND, true, false), FAILURE);
+        assert_eq!(
+            for_response(StatusCode::NOT_FOUND, true, true),
+            CLIENT_ERROR
+        );
+        assert_eq!(
+            for_response(StatusCode::SERVICE_UNAVAILABLE, true, true),
+            SERVER_ERROR
+        );
+        assert_eq!(for_response(StatusCode::NOT_FOUND, false, true), SUCCESS);
+    }
+
+    #[test]
+    fn test_hides_body() {
+        assert!(hides_body(StatusCode::NOT_FOUND, true, false));
+        assert!(!hides_body(StatusCode::NOT_FOUND, true, true));
+        assert!(!hides_body(StatusCode::NOT_FOUND, false, false));
+        assert!(!hides_body(StatusCode::OK, true, false));
+    }
+}
@@ -1,7 +1,12 @@
 //! CLI command parsing and handling
 
 pub mod commands;
+pub mod exit;
 pub mod parser;
 
-pub use commands::{Cli, Commands};
-pub use parser::CommandParser;
+pub use commands::{
+    AssertArgs, AuthCommands, Cli, CollectionCacheCommands, CollectionCommands, Commands,
+    ConfigCommands, ExportFormat, HistoryCommands, HistoryFilterArgs, OutputFormat,
+    SessionCommands, WorkflowCommands, WorkflowVariableArgs,
+};
+pub use parser::{CommandParser, UseTarget};
@@ -3,6 +3,15 @@
 use crate::error::{Error, Result};
 use crate::http::{HttpMethod, RequestBuilder};
 
+/// What a REPL `use` command switches to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UseTarget {
+    /// `use env <name>`
+    Environment(String),
+    /// `use session <name>`
+    Session(String),
+}
+
 /// Parser for HTTP commands in interactive mode
 pub struct CommandParser;
 
@@ -52,7 +61,7 @@ impl CommandParser {
                 }
                 "-b" | "--body" => {
                     if i + 1 < args.len() {
-                        builder = builder.body(args[i + 1].clone());
+                        builder = builder.body_arg(&args[i + 1])?;
                         i += 2;
                     } else {
                         return Err(Error::MissingArgument(
@@ -71,6 +80,22 @@ impl CommandParser {
 
         Ok(builder)
     }
+
+    /// Parse the arguments of `use env <name>` or `use session <name>`
+    pub fn parse_use_command(args: &[String]) -> Result<UseTarget> {
+        let usage = || Error::MissingArgument("Usage: use <env|session> <name>".to_string());
+        match args {
+            [kind, name] => match kind.to_lowercase().as_str() {
+                "env" | "environment" => Ok(UseTarget::Environment(name.clone())),
+                "session" => Ok(UseTarget::Session(name.clone())),
+                _ => Err(Error::InvalidCommand(format!(
+                    "Can't use '{}'; expected 'env' or 'session'",
+                    kind
+                ))),
+            },
+            _ => Err(usage()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -93,6 +118,28 @@ mod tests {
         assert_eq!(result[3], "Content-Type:application/json");
     }
 
+    #[test]
+    fn test_parse_use_command() {
+        let args = |line: &str| CommandParser::parse_line(line).unwrap();
+
+        assert_eq!(
+            CommandParser::parse_use_command(&args("env dev")).unwrap(),
+            UseTarget::Environment("dev".to_string())
+        );
+        assert_eq!(
+            CommandParser::parse_use_command(&args("Session 'my login'")).unwrap(),
+            UseTarget::Session("my login".to_string())
+        );
+        assert!(matches!(
+            CommandParser::parse_use_command(&args("env")),
+            Err(Error::MissingArgument(_))
+        ));
+        assert!(matches!(
+            CommandParser::parse_use_command(&args("profile dev")),
+            Err(Error::InvalidCommand(_))
+        ));
+    }
+
     #[test]
     fn test_parse_line_invalid_quotes() {
         let result = CommandParser::parse_line(r#"get "unclosed quote"#);
@@ -0,0 +1,294 @@
+//! Conditional-request cache for collection runs
+
+use crate::error::{Error, Result};
+use crate::http::HttpResponse;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// A cached response with its validators
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacheEntry {
+    /// ETag validator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+
+    /// Last-Modified validator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+
+    /// Status code of the cached response
+    pub status: u16,
+
+    /// Response headers
+    pub headers: Vec<(String, String)>,
+
+    /// Base64-encoded response body
+    pub body: String,
+
+    /// When the entry was stored
+    pub stored_at: DateTime<Utc>,
+}
+
+impl CacheEntry {
+    /// Build an entry from a response, if it has validators and may be stored
+    pub fn from_response(response: &HttpResponse) -> Option<Self> {
+        let header = |name: &str| {
+            response
+                .headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+
+        let no_store = header("cache-control")
+            .is_some_and(|value| value.to_ascii_lowercase().contains("no-store"));
+        let etag = header("etag");
+        let last_modified = header("last-modified");
+        if no_store || !response.is_success() || (etag.is_none() && last_modified.is_none()) {
+            return None;
+        }
+
+        let headers = response
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|v| (name.to_string(), v.to_string()))
+            })
+            .collect();
+
+        Some(Self {
+            etag,
+            last_modified,
+            status: response.status.as_u16(),
+            headers,
+            body: base64::engine::general_purpose::STANDARD.encode(&response.body_bytes),
+            stored_at: Utc::now(),
+        })
+    }
+
+    /// Headers that make a request conditional on this entry
+    pub fn conditional_headers(&self) -> Vec<String> {
+        let mut headers = Vec::new();
+        if let Some(etag) = &self.etag {
+            headers.push(format!("If-None-Match:{}", etag));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.push(format!("If-Modified-Since:{}", last_modified));
+        }
+        headers
+    }
+
+    /// Rebuild the cached response after a 304 Not Modified
+    ///
+    /// Headers sent with the 304 replace the stored ones; the duration is the
+    /// 304 round trip.
+    pub fn to_response(&self, not_modified: &HttpResponse) -> Result<HttpResponse> {
+        let body_bytes = base64::engine::general_purpose::STANDARD
+            .decode(&self.body)
+            .map_err(|e| Error::Io(std::io::Error::other(format!("Corrupt cache entry: {}", e))))?;
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        for (name, value) in &not_modified.headers {
+            headers.insert(name.clone(), value.clone());
+        }
+
+        Ok(HttpResponse {
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK),
+            headers,
+            body: String::from_utf8_lossy(&body_bytes).into_owned(),
+            body_bytes,
+            duration: not_modified.duration,
+            timing: not_modified.timing.clone(),
+        })
+    }
+}
+
+/// Response cache keyed by request id
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    /// Create a cache stored in a directory
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Get default cache directory
+    pub fn default_path() -> Result<PathBuf> {
+        let dirs = directories::ProjectDirs::from("com", "bazzoun", "bazzounquester").ok_or_else(
+            || {
+                Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Could not determine data directory",
+                ))
+            },
+        )?;
+
+        let path = dirs.data_dir().join("cache");
+        Ok(path)
+    }
+
+    fn entry_path(&self, id: &Uuid) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    /// Get the cached entry for a request (unreadable entries are ignored)
+    pub fn get(&self, id: &Uuid) -> Option<CacheEntry> {
+        let content = std::fs::read_to_string(self.entry_path(id)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Store a response, or drop the old entry if the response can't be cached
+    ///
+    /// Returns whether the response was stored.
+    pub fn store(&self, id: &Uuid, response: &HttpResponse) -> Result<bool> {
+        match CacheEntry::from_response(response) {
+            Some(entry) => {
+                std::fs::create_dir_all(&self.dir)?;
+                std::fs::write(self.entry_path(id), serde_json::to_string(&entry)?)?;
+                Ok(true)
+            }
+            None => {
+                self.remove(id)?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Remove the entry for a request
+    pub fn remove(&self, id: &Uuid) -> Result<()> {
+        match std::fs::remove_file(self.entry_path(id)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove all entries, returning how many were removed
+    pub fn clear(&self) -> Result<usize> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+
+        let mut removed = 0;
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                std::fs::remove_file(path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &str) -> HttpResponse {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(
+                HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        HttpResponse {
+            status: StatusCode::from_u16(status).unwrap(),
+            headers: header_map,
+            body: body.to_string(),
+            body_bytes: body.as_bytes().to_vec(),
+            duration: Duration::from_millis(100),
+            timing: None,
+        }
+    }
+
+    #[test]
+    fn test_entry_requires_validators() {
+        assert!(CacheEntry::from_response(&response(200, &[], "x")).is_none());
+        assert!(CacheEntry::from_response(&response(404, &[("etag", "\"a\"")], "x")).is_none());
+        assert!(CacheEntry::from_response(&response(
+            200,
+            &[("etag", "\"a\""), ("cache-control", "no-store")],
+            "x"
+        ))
+        .is_none());
+
+        let entry = CacheEntry::from_response(&response(
+            200,
+            &[
+                ("etag", "\"a\""),
+                ("last-modified", "Tue, 01 Oct 2024 00:00:00 GMT"),
+            ],
+            "x",
+        ))
+        .unwrap();
+        assert_eq!(
+            entry.conditional_headers(),
+            vec![
+                "If-None-Match:\"a\"".to_string(),
+                "If-Modified-Since:Tue, 01 Oct 2024 00:00:00 GMT".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_entry_to_response() {
+        let entry = CacheEntry::from_response(&response(
+            200,
+            &[("etag", "\"a\""), ("content-type", "application/json")],
+            r#"{"v":1}"#,
+        ))
+        .unwrap();
+
+        let mut not_modified = response(304, &[("etag", "\"a\"")], "");
+        not_modified.duration = Duration::from_millis(2);
+        let rebuilt = entry.to_response(&not_modified).unwrap();
+
+        assert_eq!(rebuilt.status, StatusCode::OK);
+        assert_eq!(rebuilt.body, r#"{"v":1}"#);
+        assert_eq!(rebuilt.content_type(), Some("application/json"));
+        assert_eq!(rebuilt.duration, Duration::from_millis(2));
+    }
+
+    #[test]
+    fn test_cache_store_and_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ResponseCache::new(temp_dir.path().join("cache"));
+        let id = Uuid::new_v4();
+
+        assert!(cache
+            .store(&id, &response(200, &[("etag", "\"a\"")], "x"))
+            .unwrap());
+        assert!(cache.get(&id).is_some());
+
+        // A response without validators drops the stale entry
+        assert!(!cache.store(&id, &response(200, &[], "y")).unwrap());
+        assert!(cache.get(&id).is_none());
+
+        cache
+            .store(&id, &response(200, &[("etag", "\"a\"")], "x"))
+            .unwrap();
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(cache.get(&id).is_none());
+    }
+}
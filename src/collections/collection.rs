@@ -1,8 +1,12 @@
 //! Collection data structure
 
-use crate::collections::{Folder, RequestItem};
+use crate::collections::{Folder, MergeReport, MergeStrategy, RequestItem};
+use crate::env::Environment;
+use crate::error::{Error, Result};
+use crate::history::unique_name;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 use uuid::Uuid;
 
@@ -40,6 +44,10 @@ pub struct Collection {
     #[serde(default)]
     pub folders: Vec<Folder>,
 
+    /// Collection-level variables
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, String>,
+
     /// Created timestamp
     pub created_at: DateTime<Utc>,
 
@@ -61,6 +69,7 @@ impl Collection {
             },
             requests: Vec::new(),
             folders: Vec::new(),
+            variables: HashMap::new(),
             created_at: now,
             updated_at: now,
         }
@@ -84,6 +93,77 @@ impl Collection {
         self.updated_at = Utc::now();
     }
 
+    /// Set a collection variable
+    pub fn set_variable(&mut self, key: String, value: String) {
+        self.variables.insert(key, value);
+        self.updated_at = Utc::now();
+    }
+
+    /// Get a collection variable
+    pub fn get_variable(&self, key: &str) -> Option<&String> {
+        self.variables.get(key)
+    }
+
+    /// Resolve the variables visible inside a folder, given by folder names
+    ///
+    /// Collection variables come first and each folder along the path
+    /// overrides them, so the innermost folder wins. An empty path gives the
+    /// collection's own variables; resolution stops at the first name that
+    /// doesn't match a folder. The active environment, applied on top by the
+    /// caller, overrides all of these.
+    pub fn resolve_variables(&self, folder_path: &[&str]) -> HashMap<String, String> {
+        let mut variables = self.variables.clone();
+        let mut folders = &self.folders;
+        for name in folder_path {
+            let Some(folder) = folders.iter().find(|f| f.name == *name) else {
+                break;
+            };
+            variables.extend(folder.variables.clone());
+            folders = &folder.folders;
+        }
+        variables
+    }
+
+    /// Get the names of the folders leading to a request (empty at the root)
+    pub fn folder_path(&self, id: &Uuid) -> Option<Vec<&str>> {
+        if self.requests.iter().any(|r| r.id == *id) {
+            return Some(Vec::new());
+        }
+        self.folders.iter().find_map(|f| f.path_to_request(id))
+    }
+
+    /// Resolve the variables visible to a request, based on where it is saved
+    pub fn request_variables(&self, id: &Uuid) -> HashMap<String, String> {
+        let path = self.folder_path(id).unwrap_or_default();
+        self.resolve_variables(&path)
+    }
+
+    /// Variables referenced by requests but defined neither by the
+    /// environment's enabled variables nor by the collection or the folders
+    /// the request is saved in
+    ///
+    /// Names are sorted and listed once.
+    pub fn undefined_variables(&self, env: &Environment) -> Vec<String> {
+        let defined = env.enabled_variables();
+        let defined = &defined;
+        let mut missing: Vec<String> = self
+            .list_all_requests()
+            .into_iter()
+            .flat_map(|request| {
+                let scoped = self.request_variables(&request.id);
+                request
+                    .variable_references()
+                    .into_iter()
+                    .filter(move |name| {
+                        !defined.contains_key(name.as_str()) && !scoped.contains_key(name)
+                    })
+            })
+            .collect();
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+
     /// Get a request by ID (searches all folders)
     pub fn get_request(&self, id: &Uuid) -> Option<&RequestItem> {
         // Check root-level requests
@@ -138,6 +218,253 @@ impl Collection {
         false
     }
 
+    /// Get a folder by ID (searches all folders)
+    pub fn get_folder(&self, id: &Uuid) -> Option<&Folder> {
+        self.folders.iter().find_map(|f| {
+            if f.id == *id {
+                Some(f)
+            } else {
+                f.get_folder(id)
+            }
+        })
+    }
+
+    /// Get a mutable folder by ID
+    pub fn get_folder_mut(&mut self, id: &Uuid) -> Option<&mut Folder> {
+        for folder in &mut self.folders {
+            if folder.id == *id {
+                return Some(folder);
+            }
+            if let Some(found) = folder.get_folder_mut(id) {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    /// Move a request to the end of a folder, or of the root level for `None`
+    pub fn move_request(&mut self, id: &Uuid, target_folder_id: Option<&Uuid>) -> Result<()> {
+        if self.get_request(id).is_none() {
+            return Err(not_found("Request", id));
+        }
+        if let Some(target) = target_folder_id {
+            if self.get_folder(target).is_none() {
+                return Err(not_found("Folder", target));
+            }
+        }
+
+        let mut request = self
+            .take_request(id)
+            .ok_or_else(|| not_found("Request", id))?;
+        request.touch();
+        match target_folder_id.and_then(|target| self.get_folder_mut(target)) {
+            Some(folder) => folder.add_request(request),
+            None => self.requests.push(request),
+        }
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Move a request to a new position among its siblings (clamped to the end)
+    pub fn reorder_request(&mut self, id: &Uuid, new_index: usize) -> Result<()> {
+        let requests = if self.requests.iter().any(|r| r.id == *id) {
+            &mut self.requests
+        } else {
+            let folder_id = self
+                .find_request_parent(id)
+                .ok_or_else(|| not_found("Request", id))?;
+            let folder = self
+                .get_folder_mut(&folder_id)
+                .ok_or_else(|| not_found("Request", id))?;
+            folder.updated_at = Utc::now();
+            &mut folder.requests
+        };
+
+        let pos = requests
+            .iter()
+            .position(|r| r.id == *id)
+            .ok_or_else(|| not_found("Request", id))?;
+        let request = requests.remove(pos);
+        let index = new_index.min(requests.len());
+        requests.insert(index, request);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Move a folder (with its contents) into another folder, or to the root level for `None`
+    pub fn move_folder(&mut self, id: &Uuid, target_folder_id: Option<&Uuid>) -> Result<()> {
+        let folder = self.get_folder(id).ok_or_else(|| not_found("Folder", id))?;
+        if let Some(target) = target_folder_id {
+            if target == id || folder.get_folder(target).is_some() {
+                return Err(Error::InvalidCommand(
+                    "Cannot move a folder into itself".to_string(),
+                ));
+            }
+            if self.get_folder(target).is_none() {
+                return Err(not_found("Folder", target));
+            }
+        }
+
+        let mut folder = self
+            .take_folder(id)
+            .ok_or_else(|| not_found("Folder", id))?;
+        folder.updated_at = Utc::now();
+        match target_folder_id.and_then(|target| self.get_folder_mut(target)) {
+            Some(parent) => parent.add_folder(folder),
+            None => self.folders.push(folder),
+        }
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Move a folder to a new position among its siblings (clamped to the end)
+    pub fn reorder_folder(&mut self, id: &Uuid, new_index: usize) -> Result<()> {
+        let folders = if self.folders.iter().any(|f| f.id == *id) {
+            &mut self.folders
+        } else {
+            let parent_id = self
+                .find_folder_parent(id)
+                .ok_or_else(|| not_found("Folder", id))?;
+            let parent = self
+                .get_folder_mut(&parent_id)
+                .ok_or_else(|| not_found("Folder", id))?;
+            parent.updated_at = Utc::now();
+            &mut parent.folders
+        };
+
+        let pos = folders
+            .iter()
+            .position(|f| f.id == *id)
+            .ok_or_else(|| not_found("Folder", id))?;
+        let folder = folders.remove(pos);
+        let index = new_index.min(folders.len());
+        folders.insert(index, folder);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Remove a request by ID from wherever it is and return it
+    fn take_request(&mut self, id: &Uuid) -> Option<RequestItem> {
+        if let Some(pos) = self.requests.iter().position(|r| r.id == *id) {
+            return Some(self.requests.remove(pos));
+        }
+        self.folders.iter_mut().find_map(|f| f.take_request(id))
+    }
+
+    /// Remove a folder by ID from wherever it is and return it
+    fn take_folder(&mut self, id: &Uuid) -> Option<Folder> {
+        if let Some(pos) = self.folders.iter().position(|f| f.id == *id) {
+            return Some(self.folders.remove(pos));
+        }
+        self.folders.iter_mut().find_map(|f| f.take_folder(id))
+    }
+
+    /// ID of the folder directly containing a request (`None` at the root or if missing)
+    fn find_request_parent(&self, id: &Uuid) -> Option<Uuid> {
+        fn search(folders: &[Folder], id: &Uuid) -> Option<Uuid> {
+            folders.iter().find_map(|f| {
+                if f.requests.iter().any(|r| r.id == *id) {
+                    Some(f.id)
+                } else {
+                    search(&f.folders, id)
+                }
+            })
+        }
+        search(&self.folders, id)
+    }
+
+    /// ID of the folder directly containing a folder (`None` at the root or if missing)
+    fn find_folder_parent(&self, id: &Uuid) -> Option<Uuid> {
+        fn search(folders: &[Folder], id: &Uuid) -> Option<Uuid> {
+            folders.iter().find_map(|f| {
+                if f.folders.iter().any(|child| child.id == *id) {
+                    Some(f.id)
+                } else {
+                    search(&f.folders, id)
+                }
+            })
+        }
+        search(&self.folders, id)
+    }
+
+    /// Merge another collection's requests, folders, and variables into this one
+    ///
+    /// A request matching an existing one by method and URL is skipped or
+    /// overwritten in place per `strategy`; anything else is added under the
+    /// same folder path.
+    pub fn merge(&mut self, incoming: Collection, strategy: MergeStrategy) -> MergeReport {
+        let mut report = MergeReport::default();
+        merge_variables(&mut self.variables, incoming.variables, strategy);
+        for request in incoming.requests {
+            self.merge_request(request, &[], strategy, &mut report);
+        }
+        for folder in incoming.folders {
+            self.merge_folder(folder, &mut Vec::new(), strategy, &mut report);
+        }
+        if report.added + report.updated > 0 {
+            self.updated_at = Utc::now();
+        }
+        report
+    }
+
+    fn merge_folder(
+        &mut self,
+        folder: Folder,
+        path: &mut Vec<String>,
+        strategy: MergeStrategy,
+        report: &mut MergeReport,
+    ) {
+        path.push(folder.name.clone());
+        let target = folder_at_path(&mut self.folders, path);
+        merge_variables(&mut target.variables, folder.variables, strategy);
+        if target.description.is_none() {
+            target.description = folder.description;
+        }
+
+        for request in folder.requests {
+            self.merge_request(request, path, strategy, report);
+        }
+        for child in folder.folders {
+            self.merge_folder(child, path, strategy, report);
+        }
+        path.pop();
+    }
+
+    fn merge_request(
+        &mut self,
+        mut request: RequestItem,
+        path: &[String],
+        strategy: MergeStrategy,
+        report: &mut MergeReport,
+    ) {
+        let duplicate = self
+            .list_all_requests()
+            .into_iter()
+            .find(|r| r.method.eq_ignore_ascii_case(&request.method) && r.url == request.url)
+            .map(|r| r.id);
+
+        match (duplicate, strategy) {
+            (Some(_), MergeStrategy::Skip) => report.skipped += 1,
+            (Some(id), MergeStrategy::Update) => {
+                if let Some(existing) = self.get_request_mut(&id) {
+                    request.id = existing.id;
+                    request.created_at = existing.created_at;
+                    request.updated_at = Utc::now();
+                    *existing = request;
+                }
+                report.updated += 1;
+            }
+            (None, _) if path.is_empty() => {
+                self.requests.push(request);
+                report.added += 1;
+            }
+            (None, _) => {
+                folder_at_path(&mut self.folders, path).add_request(request);
+                report.added += 1;
+            }
+        }
+    }
+
     /// Get total count of requests
     pub fn total_requests(&self) -> usize {
         let mut count = self.requests.len();
@@ -162,6 +489,69 @@ impl Collection {
         all_requests
     }
 
+    /// Find a request by name (searches all folders)
+    pub fn find_request_by_name(&self, name: &str) -> Option<&RequestItem> {
+        self.list_all_requests()
+            .into_iter()
+            .find(|r| r.name == name)
+    }
+
+    /// Find requests whose name, URL, or a tag contains the query (case-insensitive)
+    pub fn search(&self, query: &str) -> Vec<&RequestItem> {
+        let query = query.to_lowercase();
+        self.list_all_requests()
+            .into_iter()
+            .filter(|r| {
+                r.name.to_lowercase().contains(&query)
+                    || r.url.to_lowercase().contains(&query)
+                    || r.tags.iter().any(|t| t.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// Find a request by its folder path, e.g. `Auth/Login` or `Users/Admin/Reset`
+    pub fn find_request_by_path(&self, path: &str) -> Option<&RequestItem> {
+        self.request_at_path(path).ok()
+    }
+
+    /// Get a request by its folder path, naming the first segment that doesn't exist
+    pub fn request_at_path(&self, path: &str) -> Result<&RequestItem> {
+        let mut segments: Vec<&str> = path.split('/').collect();
+        let name = segments.pop().unwrap_or_default();
+
+        let mut requests = &self.requests;
+        let mut folders = &self.folders;
+        let mut location = self.info.name.clone();
+        for segment in segments {
+            let folder = folders.iter().find(|f| f.name == segment).ok_or_else(|| {
+                Error::InvalidCommand(format!("Folder '{}' not found in '{}'", segment, location))
+            })?;
+            location = format!("{}/{}", location, folder.name);
+            requests = &folder.requests;
+            folders = &folder.folders;
+        }
+
+        requests.iter().find(|r| r.name == name).ok_or_else(|| {
+            Error::InvalidCommand(format!("Request '{}' not found in '{}'", name, location))
+        })
+    }
+
+    /// Get saved requests as `<collection>/<request name>` (e.g. for completion)
+    pub fn request_specs(&self) -> Vec<String> {
+        self.list_all_requests()
+            .iter()
+            .map(|r| format!("{}/{}", self.info.name, r.name))
+            .collect()
+    }
+
+    /// Make a request name unique within the collection by appending a counter
+    pub fn unique_request_name(&self, name: &str) -> String {
+        unique_name(
+            name,
+            self.list_all_requests().iter().map(|r| r.name.as_str()),
+        )
+    }
+
     /// Save collection to file
     pub fn save_to_file(&self, path: &Path) -> crate::Result<()> {
         let json = serde_json::to_string_pretty(self)?;
@@ -177,6 +567,45 @@ impl Collection {
     }
 }
 
+/// Find the folder at a name path, creating any missing folders along the way
+fn folder_at_path<'a>(folders: &'a mut Vec<Folder>, path: &[String]) -> &'a mut Folder {
+    let (name, rest) = path.split_first().expect("folder path is never empty");
+    let index = match folders.iter().position(|f| f.name == *name) {
+        Some(index) => index,
+        None => {
+            folders.push(Folder::new(name.clone()));
+            folders.len() - 1
+        }
+    };
+    let folder = &mut folders[index];
+    if rest.is_empty() {
+        folder
+    } else {
+        folder_at_path(&mut folder.folders, rest)
+    }
+}
+
+fn merge_variables(
+    target: &mut HashMap<String, String>,
+    incoming: HashMap<String, String>,
+    strategy: MergeStrategy,
+) {
+    for (key, value) in incoming {
+        match strategy {
+            MergeStrategy::Skip => {
+                target.entry(key).or_insert(value);
+            }
+            MergeStrategy::Update => {
+                target.insert(key, value);
+            }
+        }
+    }
+}
+
+fn not_found(kind: &str, id: &Uuid) -> Error {
+    Error::InvalidCommand(format!("{} {} not found", kind, id))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,6 +662,48 @@ mod tests {
         assert_eq!(collection.total_requests(), 2);
     }
 
+    #[test]
+    fn test_unique_request_name() {
+        let mut collection = Collection::new("Test".to_string());
+        let mut folder = Folder::new("Users".to_string());
+        folder.add_request(RequestItem::new(
+            "GET /users".to_string(),
+            HttpMethod::Get,
+            "https://example.com/users".to_string(),
+        ));
+        collection.add_folder(folder);
+
+        assert_eq!(collection.unique_request_name("POST /users"), "POST /users");
+        assert_eq!(
+            collection.unique_request_name("GET /users"),
+            "GET /users (2)"
+        );
+    }
+
+    #[test]
+    fn test_find_request_by_name_and_specs() {
+        let mut collection = Collection::new("API".to_string());
+        collection.add_request(RequestItem::new(
+            "Health".to_string(),
+            HttpMethod::Get,
+            "https://example.com/health".to_string(),
+        ));
+        let mut folder = Folder::new("Users".to_string());
+        folder.add_request(RequestItem::new(
+            "List users".to_string(),
+            HttpMethod::Get,
+            "https://example.com/users".to_string(),
+        ));
+        collection.add_folder(folder);
+
+        assert!(collection.find_request_by_name("List users").is_some());
+        assert!(collection.find_request_by_name("list users").is_none());
+        assert_eq!(
+            collection.request_specs(),
+            vec!["API/Health", "API/List users"]
+        );
+    }
+
     #[test]
     fn test_serialization() {
         let collection = Collection::new("Test API".to_string());
@@ -242,4 +713,318 @@ mod tests {
         assert_eq!(collection.info.name, deserialized.info.name);
         assert_eq!(collection.info.id, deserialized.info.id);
     }
+
+    /// Collection "API" with BASE_URL and TIMEOUT, and folder Admin/Users
+    /// where Admin overrides BASE_URL and Users adds ROLE
+    fn scoped_collection() -> (Collection, Uuid) {
+        let mut collection = Collection::new("API".to_string());
+        collection.set_variable(
+            "BASE_URL".to_string(),
+            "https://api.example.com".to_string(),
+        );
+        collection.set_variable("TIMEOUT".to_string(), "30".to_string());
+
+        let request = RequestItem::new(
+            "List users".to_string(),
+            HttpMethod::Get,
+            "{{BASE_URL}}/users".to_string(),
+        );
+        let id = request.id;
+
+        let mut users = Folder::new("Users".to_string());
+        users.set_variable("ROLE".to_string(), "viewer".to_string());
+        users.add_request(request);
+
+        let mut admin = Folder::new("Admin".to_string());
+        admin.set_variable(
+            "BASE_URL".to_string(),
+            "https://admin.example.com".to_string(),
+        );
+        admin.add_folder(users);
+        collection.add_folder(admin);
+
+        (collection, id)
+    }
+
+    #[test]
+    fn test_folder_variable_overrides_collection_variable() {
+        let (collection, id) = scoped_collection();
+
+        let root = collection.resolve_variables(&[]);
+        assert_eq!(root["BASE_URL"], "https://api.example.com");
+        assert!(!root.contains_key("ROLE"));
+
+        assert_eq!(collection.folder_path(&id), Some(vec!["Admin", "Users"]));
+        let variables = collection.request_variables(&id);
+        assert_eq!(variables["BASE_URL"], "https://admin.example.com");
+        assert_eq!(variables["TIMEOUT"], "30");
+        assert_eq!(variables["ROLE"], "viewer");
+
+        let request = collection.get_request(&id).unwrap();
+        let variables = variables
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(
+            request.to_request_builder_with(&variables).url,
+            "https://admin.example.com/users"
+        );
+    }
+
+    #[test]
+    fn test_resolve_variables_stops_at_unknown_folder() {
+        let (collection, _) = scoped_collection();
+        let variables = collection.resolve_variables(&["Missing", "Users"]);
+        assert_eq!(variables, collection.variables);
+    }
+
+    #[test]
+    fn test_variables_roundtrip() {
+        let (collection, _) = scoped_collection();
+        let json = serde_json::to_string(&collection).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Collection>(&json).unwrap(),
+            collection
+        );
+
+        let empty = serde_json::to_string(&Collection::new("Empty".to_string())).unwrap();
+        assert!(!empty.contains("variables"));
+    }
+
+    fn request(name: &str) -> RequestItem {
+        RequestItem::new(
+            name.to_string(),
+            HttpMethod::Get,
+            format!("https://example.com/{}", name),
+        )
+    }
+
+    #[test]
+    fn test_undefined_variables() {
+        let (mut collection, _) = scoped_collection();
+        collection.add_request(
+            RequestItem::new(
+                "Create order".to_string(),
+                HttpMethod::Post,
+                "{{API_HOST}}/orders".to_string(),
+            )
+            .with_header("Authorization".to_string(), "Bearer {{TOKEN}}".to_string())
+            .with_body(r#"{"role": "{{ROLE}}"}"#.to_string(), None),
+        );
+
+        let mut env = Environment::new("dev".to_string());
+        env.set_variable(
+            "API_HOST".to_string(),
+            "https://dev.example.com".to_string(),
+        );
+        // ROLE is only defined for the Users folder, so it's missing at the root
+        assert_eq!(collection.undefined_variables(&env), vec!["ROLE", "TOKEN"]);
+
+        env.set_variable("TOKEN".to_string(), "secret".to_string());
+        env.set_variable("ROLE".to_string(), "admin".to_string());
+        assert!(collection.undefined_variables(&env).is_empty());
+
+        env.set_variable_enabled("TOKEN", false);
+        assert_eq!(collection.undefined_variables(&env), vec!["TOKEN"]);
+    }
+
+    #[test]
+    fn test_search() {
+        let mut collection = Collection::new("API".to_string());
+        collection.add_request(request("health"));
+        let mut users = Folder::new("Users".to_string());
+        users.add_request(
+            RequestItem::new(
+                "Create user".to_string(),
+                HttpMethod::Post,
+                "https://example.com/accounts".to_string(),
+            )
+            .with_tag("Smoke".to_string()),
+        );
+        let mut admin = Folder::new("Admin".to_string());
+        admin.add_request(request("reset-password"));
+        users.add_folder(admin);
+        collection.add_folder(users);
+
+        let names = |query: &str| -> Vec<String> {
+            collection
+                .search(query)
+                .into_iter()
+                .map(|r| r.name.clone())
+                .collect()
+        };
+        // Name, in a nested folder
+        assert_eq!(names("RESET"), vec!["reset-password"]);
+        // URL
+        assert_eq!(names("/accounts"), vec!["Create user"]);
+        // Tag
+        assert_eq!(names("smoke"), vec!["Create user"]);
+        assert!(names("graphql").is_empty());
+    }
+
+    #[test]
+    fn test_move_request_between_folders() {
+        let mut collection = Collection::new("API".to_string());
+        let mut users = Folder::new("Users".to_string());
+        let item = request("list");
+        let id = item.id;
+        users.add_request(item);
+        let users_id = users.id;
+        let admin = Folder::new("Admin".to_string());
+        let admin_id = admin.id;
+        collection.add_folder(users);
+        collection.add_folder(admin);
+        let before = collection.updated_at;
+
+        collection.move_request(&id, Some(&admin_id)).unwrap();
+        assert!(collection
+            .get_folder(&users_id)
+            .unwrap()
+            .requests
+            .is_empty());
+        assert_eq!(collection.get_folder(&admin_id).unwrap().requests[0].id, id);
+        assert!(collection.updated_at >= before);
+
+        collection.move_request(&id, None).unwrap();
+        assert_eq!(collection.requests[0].id, id);
+        assert_eq!(collection.total_requests(), 1);
+
+        assert!(collection.move_request(&Uuid::new_v4(), None).is_err());
+        // An unknown target leaves the request where it was
+        assert!(collection.move_request(&id, Some(&Uuid::new_v4())).is_err());
+        assert_eq!(collection.requests[0].id, id);
+    }
+
+    #[test]
+    fn test_move_request_from_root_into_nested_folder() {
+        let mut collection = Collection::new("API".to_string());
+        let item = request("login");
+        let id = item.id;
+        collection.add_request(item);
+        let mut api = Folder::new("API".to_string());
+        let auth = Folder::new("Auth".to_string());
+        let auth_id = auth.id;
+        api.add_folder(auth);
+        collection.add_folder(api);
+
+        collection.move_request(&id, Some(&auth_id)).unwrap();
+        assert!(collection.requests.is_empty());
+        assert_eq!(collection.get_folder(&auth_id).unwrap().requests[0].id, id);
+        assert_eq!(collection.request_at_path("API/Auth/login").unwrap().id, id);
+    }
+
+    #[test]
+    fn test_reorder_root_requests() {
+        let mut collection = Collection::new("API".to_string());
+        let first = request("first");
+        let second = request("second");
+        let first_id = first.id;
+        collection.add_request(first);
+        collection.add_request(second);
+
+        collection.reorder_request(&first_id, 1).unwrap();
+        let names: Vec<&str> = collection
+            .requests
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["second", "first"]);
+
+        // Out-of-range indexes move to the end
+        collection
+            .reorder_request(&collection.requests[1].id.clone(), 0)
+            .unwrap();
+        collection.reorder_request(&first_id, 99).unwrap();
+        assert_eq!(collection.requests[1].id, first_id);
+
+        assert!(collection.reorder_request(&Uuid::new_v4(), 0).is_err());
+    }
+
+    #[test]
+    fn test_move_and_reorder_folders() {
+        let mut collection = Collection::new("API".to_string());
+        let parent = Folder::new("Parent".to_string());
+        let parent_id = parent.id;
+        let mut child = Folder::new("Child".to_string());
+        child.add_request(request("nested"));
+        let child_id = child.id;
+        collection.add_folder(parent);
+        collection.add_folder(child);
+
+        collection.move_folder(&child_id, Some(&parent_id)).unwrap();
+        assert_eq!(collection.folders.len(), 1);
+        assert_eq!(
+            collection.get_folder(&parent_id).unwrap().folders[0].id,
+            child_id
+        );
+        assert_eq!(collection.total_requests(), 1);
+
+        // A folder cannot move into itself or its own descendants
+        assert!(collection.move_folder(&parent_id, Some(&child_id)).is_err());
+        assert!(collection
+            .move_folder(&parent_id, Some(&parent_id))
+            .is_err());
+
+        let sibling = Folder::new("Sibling".to_string());
+        let sibling_id = sibling.id;
+        collection
+            .get_folder_mut(&parent_id)
+            .unwrap()
+            .add_folder(sibling);
+        collection.reorder_folder(&sibling_id, 0).unwrap();
+        let order: Vec<Uuid> = collection
+            .get_folder(&parent_id)
+            .unwrap()
+            .folders
+            .iter()
+            .map(|f| f.id)
+            .collect();
+        assert_eq!(order, vec![sibling_id, child_id]);
+
+        collection.move_folder(&child_id, None).unwrap();
+        assert_eq!(collection.folders[1].id, child_id);
+        assert!(collection.reorder_folder(&Uuid::new_v4(), 0).is_err());
+    }
+
+    #[test]
+    fn test_find_request_by_path() {
+        let mut collection = Collection::new("API".to_string());
+        collection.add_request(request("health"));
+        let mut auth = Folder::new("Auth".to_string());
+        let mut oauth = Folder::new("OAuth".to_string());
+        oauth.add_request(request("Login"));
+        auth.add_folder(oauth);
+        auth.add_request(request("Login"));
+        collection.add_folder(auth);
+
+        let nested = collection.find_request_by_path("Auth/OAuth/Login").unwrap();
+        assert_eq!(
+            Some(nested.id),
+            collection.folders[0].folders[0]
+                .requests
+                .first()
+                .map(|r| r.id)
+        );
+        let direct = collection.find_request_by_path("Auth/Login").unwrap();
+        assert_eq!(direct.id, collection.folders[0].requests[0].id);
+        assert_eq!(
+            collection.find_request_by_path("health").unwrap().name,
+            "health"
+        );
+
+        // Matching is case-sensitive
+        assert!(collection.find_request_by_path("auth/Login").is_none());
+        assert!(collection.find_request_by_path("Login").is_none());
+
+        let err = collection
+            .request_at_path("Auth/Missing/Login")
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Folder 'Missing' not found in 'API/Auth'"));
+        let err = collection.request_at_path("Auth/Logout").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Request 'Logout' not found in 'API/Auth'"));
+    }
 }
@@ -3,6 +3,7 @@
 use crate::collections::RequestItem;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// A folder containing requests and sub-folders
@@ -26,6 +27,10 @@ pub struct Folder {
     #[serde(default)]
     pub folders: Vec<Folder>,
 
+    /// Folder-level variables, overriding those of enclosing folders and the collection
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, String>,
+
     /// Created timestamp
     pub created_at: DateTime<Utc>,
 
@@ -43,6 +48,7 @@ impl Folder {
             description: None,
             requests: Vec::new(),
             folders: Vec::new(),
+            variables: HashMap::new(),
             created_at: now,
             updated_at: now,
         }
@@ -66,6 +72,30 @@ impl Folder {
         self.updated_at = Utc::now();
     }
 
+    /// Set a folder variable
+    pub fn set_variable(&mut self, key: String, value: String) {
+        self.variables.insert(key, value);
+        self.updated_at = Utc::now();
+    }
+
+    /// Get a folder variable
+    pub fn get_variable(&self, key: &str) -> Option<&String> {
+        self.variables.get(key)
+    }
+
+    /// Get the names of the folders leading to a request, starting with this one
+    pub fn path_to_request(&self, id: &Uuid) -> Option<Vec<&str>> {
+        if self.requests.iter().any(|r| r.id == *id) {
+            return Some(vec![self.name.as_str()]);
+        }
+
+        self.folders.iter().find_map(|folder| {
+            let mut path = folder.path_to_request(id)?;
+            path.insert(0, self.name.as_str());
+            Some(path)
+        })
+    }
+
     /// Get a request by ID
     pub fn get_request(&self, id: &Uuid) -> Option<&RequestItem> {
         // Check requests in this folder
@@ -120,6 +150,58 @@ impl Folder {
         false
     }
 
+    /// Get a sub-folder by ID (searches recursively)
+    pub fn get_folder(&self, id: &Uuid) -> Option<&Folder> {
+        self.folders.iter().find_map(|f| {
+            if f.id == *id {
+                Some(f)
+            } else {
+                f.get_folder(id)
+            }
+        })
+    }
+
+    /// Get a mutable sub-folder by ID (searches recursively)
+    pub fn get_folder_mut(&mut self, id: &Uuid) -> Option<&mut Folder> {
+        for folder in &mut self.folders {
+            if folder.id == *id {
+                return Some(folder);
+            }
+            if let Some(found) = folder.get_folder_mut(id) {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    /// Remove a request by ID and return it
+    pub fn take_request(&mut self, id: &Uuid) -> Option<RequestItem> {
+        if let Some(pos) = self.requests.iter().position(|r| r.id == *id) {
+            self.updated_at = Utc::now();
+            return Some(self.requests.remove(pos));
+        }
+
+        let taken = self.folders.iter_mut().find_map(|f| f.take_request(id));
+        if taken.is_some() {
+            self.updated_at = Utc::now();
+        }
+        taken
+    }
+
+    /// Remove a sub-folder by ID (searching recursively) and return it
+    pub fn take_folder(&mut self, id: &Uuid) -> Option<Folder> {
+        if let Some(pos) = self.folders.iter().position(|f| f.id == *id) {
+            self.updated_at = Utc::now();
+            return Some(self.folders.remove(pos));
+        }
+
+        let taken = self.folders.iter_mut().find_map(|f| f.take_folder(id));
+        if taken.is_some() {
+            self.updated_at = Utc::now();
+        }
+        taken
+    }
+
     /// Get total count of requests (including in sub-folders)
     pub fn total_requests(&self) -> usize {
         let mut count = self.requests.len();
@@ -237,4 +319,22 @@ mod tests {
 
         assert_eq!(parent.total_requests(), 2);
     }
+
+    #[test]
+    fn test_path_to_request() {
+        let request = RequestItem::new(
+            "Request".to_string(),
+            HttpMethod::Get,
+            "https://example.com".to_string(),
+        );
+        let id = request.id;
+
+        let mut inner = Folder::new("Inner".to_string());
+        inner.add_request(request);
+        let mut outer = Folder::new("Outer".to_string());
+        outer.add_folder(inner);
+
+        assert_eq!(outer.path_to_request(&id), Some(vec!["Outer", "Inner"]));
+        assert_eq!(outer.path_to_request(&Uuid::new_v4()), None);
+    }
 }
@@ -0,0 +1,388 @@
+//! Concurrent runs of saved requests across several environments
+
+use crate::auth::AuthProfileStore;
+use crate::collections::RequestItem;
+use crate::env::Environment;
+use crate::error::Result;
+use crate::http::{HttpClient, HttpResponse};
+use crate::style::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Outcome of one request against one environment
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixCell {
+    /// Environment name
+    pub environment: String,
+
+    /// Request label
+    pub request: String,
+
+    /// Response status (if a response arrived)
+    pub status: Option<u16>,
+
+    /// Network duration
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    pub duration: Duration,
+
+    /// Value extracted from the response body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// Error message (if the request failed to complete)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl MatrixCell {
+    /// Whether a response arrived with a non-error status
+    pub fn success(&self) -> bool {
+        self.error.is_none() && self.status.is_some_and(|s| s < 400)
+    }
+
+    /// Short text shown in the grid
+    fn label(&self) -> String {
+        match (&self.error, self.status) {
+            (Some(_), _) | (None, None) => "error".to_string(),
+            (None, Some(status)) => {
+                let mut label = format!("{} {}ms", status, self.duration.as_millis());
+                if let Some(value) = &self.value {
+                    label.push_str(&format!(" {}", value));
+                }
+                label
+            }
+        }
+    }
+}
+
+fn serialize_millis<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
+/// Grid of results, one row per environment and one column per request
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixResult {
+    /// Environment names, in row order
+    pub environments: Vec<String>,
+
+    /// Request labels, in column order
+    pub requests: Vec<String>,
+
+    /// Cells in row-major order
+    pub cells: Vec<MatrixCell>,
+}
+
+impl MatrixResult {
+    /// Whether every cell succeeded
+    pub fn success(&self) -> bool {
+        self.cells.iter().all(|c| c.success())
+    }
+
+    /// Get the cell for an environment and request
+    pub fn cell(&self, environment: &str, request: &str) -> Option<&MatrixCell> {
+        self.cells
+            .iter()
+            .find(|c| c.environment == environment && c.request == request)
+    }
+
+    /// Render the grid as an aligned table, failed cells in red
+    pub fn render_table(&self) -> String {
+        let env_width = self
+            .environments
+            .iter()
+            .map(|e| e.chars().count())
+            .chain(std::iter::once("ENVIRONMENT".len()))
+            .max()
+            .unwrap_or_default();
+
+        let widths: Vec<usize> = self
+            .requests
+            .iter()
+            .enumerate()
+            .map(|(col, request)| {
+                self.cells
+                    .iter()
+                    .skip(col)
+                    .step_by(self.requests.len())
+                    .map(|c| c.label().chars().count())
+                    .chain(std::iter::once(request.chars().count()))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let mut lines = Vec::new();
+        let mut header = format!("{:<width$}", "ENVIRONMENT", width = env_width);
+        for (request, width) in self.requests.iter().zip(&widths) {
+            header.push_str(&format!("  {:<width$}", request, width = width));
+        }
+        lines.push(header.trim_end().bold().to_string());
+
+        for (row, environment) in self.environments.iter().enumerate() {
+            let mut line = format!("{:<width$}", environment, width = env_width);
+            let cells = &self.cells[row * self.requests.len()..(row + 1) * self.requests.len()];
+            for (cell, width) in cells.iter().zip(&widths) {
+                // Pad before coloring so escape codes don't skew the alignment
+                let text = format!("{:<width$}", cell.label(), width = width);
+                let text = if cell.success() {
+                    text.green()
+                } else {
+                    text.red()
+                };
+                line.push_str(&format!("  {}", text));
+            }
+            lines.push(line);
+        }
+
+        for cell in self.cells.iter().filter(|c| c.error.is_some()) {
+            lines.push(format!(
+                "{} {} / {}: {}",
+                "✗".red(),
+                cell.environment,
+                cell.request,
+                cell.error.as_deref().unwrap_or_default()
+            ));
+        }
+
+        lines.join("\n")
+    }
+
+    /// Serialize the grid as pretty JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// A saved request to run in every environment
+#[derive(Debug, Clone)]
+pub struct MatrixRequest {
+    /// Column label
+    pub label: String,
+
+    /// The saved request
+    pub item: RequestItem,
+
+    /// Collection and folder variables, overridden by each environment
+    pub variables: HashMap<String, String>,
+}
+
+impl MatrixRequest {
+    /// Create a matrix request with no scoped variables
+    pub fn new(label: String, item: RequestItem) -> Self {
+        Self {
+            label,
+            item,
+            variables: HashMap::new(),
+        }
+    }
+
+    /// Set the collection and folder variables the request sees
+    pub fn with_variables(mut self, variables: HashMap<String, String>) -> Self {
+        self.variables = variables;
+        self
+    }
+}
+
+/// Runs every request against every environment at once
+pub struct MatrixRunner {
+    client: HttpClient,
+    extract: Option<String>,
+    profiles: Option<AuthProfileStore>,
+}
+
+impl MatrixRunner {
+    /// Create a new matrix runner
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            extract: None,
+            profiles: None,
+        }
+    }
+
+    /// Extract a value from each JSON response by path (e.g. `$.version`)
+    pub fn with_extract(mut self, path: String) -> Self {
+        self.extract = Some(path);
+        self
+    }
+
+    /// Resolve per-request auth profiles from a store
+    pub fn with_auth_profiles(mut self, profiles: AuthProfileStore) -> Self {
+        self.profiles = Some(profiles);
+        self
+    }
+
+    /// Run each labelled request against each environment concurrently
+    ///
+    /// A failing cell is recorded and never stops the others.
+    pub fn run(&self, requests: &[MatrixRequest], environments: &[Environment]) -> MatrixResult {
+        let cells = std::thread::scope(|scope| {
+            let handles: Vec<_> = environments
+                .iter()
+                .flat_map(|env| requests.iter().map(move |request| (env, request)))
+                .map(|(env, request)| scope.spawn(move || self.run_cell(env, request)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("matrix worker panicked"))
+                .collect()
+        });
+
+        MatrixResult {
+            environments: environments.iter().map(|e| e.name.clone()).collect(),
+            requests: requests.iter().map(|r| r.label.clone()).collect(),
+            cells,
+        }
+    }
+
+    fn run_cell(&self, environment: &Environment, request: &MatrixRequest) -> MatrixCell {
+        let mut cell = MatrixCell {
+            environment: environment.name.clone(),
+            request: request.label.clone(),
+            status: None,
+            duration: Duration::ZERO,
+            value: None,
+            error: None,
+        };
+
+        match self.execute(environment, request) {
+            Ok(response) => {
+                cell.status = Some(response.status.as_u16());
+                cell.duration = response.duration;
+                cell.value = self
+                    .extract
+                    .as_deref()
+                    .and_then(|path| extract_value(&response.body, path));
+            }
+            Err(e) => cell.error = Some(e.to_string()),
+        }
+
+        cell
+    }
+
+    fn execute(&self, environment: &Environment, request: &MatrixRequest) -> Result<HttpResponse> {
+        let mut variables: HashMap<&str, &str> = request
+            .variables
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        variables.extend(environment.enabled_variables());
+
+        let item = &request.item;
+        let mut request = item.to_request_builder_with(&variables);
+
+        if let (Some(profiles), Some(profile)) = (&self.profiles, item.auth_profile()) {
+            request = request.auth(profiles.resolve(profile)?);
+        }
+
+        self.client.execute(&request)
+    }
+}
+
+/// Look up `$.a.b[0]`-style paths in a JSON body, rendering strings unquoted
+fn extract_value(body: &str, path: &str) -> Option<String> {
+    let json: Value = serde_json::from_str(body).ok()?;
+    let pointer: String = path
+        .trim_start_matches('$')
+        .replace('[', ".")
+        .replace(']', "")
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| format!("/{}", segment))
+        .collect();
+
+    match json.pointer(&pointer)? {
+        Value::String(s) => Some(s.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::HttpMethod;
+
+    fn environment(name: &str, base_url: String) -> Environment {
+        let mut env = Environment::new(name.to_string());
+        env.set_variable("BASE_URL".to_string(), base_url);
+        env
+    }
+
+    #[test]
+    fn test_extract_value() {
+        let body = r#"{"version":"1.2.3","build":{"number":42},"tags":["a","b"]}"#;
+        assert_eq!(extract_value(body, "$.version").as_deref(), Some("1.2.3"));
+        assert_eq!(extract_value(body, "$.build.number").as_deref(), Some("42"));
+        assert_eq!(extract_value(body, "$.tags[1]").as_deref(), Some("b"));
+        assert_eq!(extract_value(body, "$.missing"), None);
+        assert_eq!(extract_value("not json", "$.version"), None);
+    }
+
+    #[test]
+    fn test_run_matrix_records_failures_without_aborting() {
+        let mut server = mockito::Server::new();
+        let _health = server
+            .mock("GET", "/dev/health")
+            .with_status(200)
+            .with_body(r#"{"version":"2.0"}"#)
+            .create();
+        let _broken = server
+            .mock("GET", "/prod/health")
+            .with_status(503)
+            .with_body(r#"{"version":"1.9"}"#)
+            .create();
+
+        let item = RequestItem::new(
+            "Health".to_string(),
+            HttpMethod::Get,
+            "{{BASE_URL}}{{HEALTH_PATH}}".to_string(),
+        );
+        // Collection variables fill the path; each environment overrides BASE_URL
+        let request =
+            MatrixRequest::new("Health".to_string(), item).with_variables(HashMap::from([
+                (
+                    "BASE_URL".to_string(),
+                    "http://collection.invalid".to_string(),
+                ),
+                ("HEALTH_PATH".to_string(), "/health".to_string()),
+            ]));
+        let environments = vec![
+            environment("dev", format!("{}/dev", server.url())),
+            environment("prod", format!("{}/prod", server.url())),
+            environment("offline", "http://127.0.0.1:1".to_string()),
+        ];
+
+        let result = MatrixRunner::new(HttpClient::new())
+            .with_extract("$.version".to_string())
+            .run(&[request], &environments);
+
+        assert_eq!(result.environments, vec!["dev", "prod", "offline"]);
+        assert!(!result.success());
+
+        let dev = result.cell("dev", "Health").unwrap();
+        assert!(dev.success());
+        assert_eq!(dev.status, Some(200));
+        assert_eq!(dev.value.as_deref(), Some("2.0"));
+
+        let prod = result.cell("prod", "Health").unwrap();
+        assert!(!prod.success());
+        assert_eq!(prod.status, Some(503));
+
+        let offline = result.cell("offline", "Health").unwrap();
+        assert!(offline.error.is_some());
+
+        let json: Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+        assert_eq!(json["cells"][0]["status"], 200);
+        assert_eq!(json["cells"][0]["value"], "2.0");
+
+        let table = result.render_table();
+        assert_eq!(table.lines().count(), 5);
+        assert!(table.contains("503"));
+        assert!(table.contains("offline / Health"));
+    }
+}
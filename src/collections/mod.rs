@@ -1,13 +1,19 @@
 //! Collections and workspaces for organizing requests
 
+pub mod cache;
 pub mod collection;
 pub mod folder;
+pub mod matrix;
 pub mod request_item;
+pub mod runner;
 pub mod storage;
 pub mod workspace;
 
+pub use cache::{CacheEntry, ResponseCache};
 pub use collection::{Collection, CollectionInfo};
 pub use folder::Folder;
+pub use matrix::{MatrixCell, MatrixRequest, MatrixResult, MatrixRunner};
 pub use request_item::RequestItem;
-pub use storage::CollectionStorage;
+pub use runner::{CollectionRunResult, CollectionRunner, RequestRunResult};
+pub use storage::{CollectionStorage, MergeReport, MergeStrategy};
 pub use workspace::{Workspace, WorkspaceStorage};
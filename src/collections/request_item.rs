@@ -1,11 +1,17 @@
 //! Request item data structure for collections
 
-use crate::http::HttpMethod;
+use crate::assertions::Assertion;
+use crate::auth::AuthScheme;
+use crate::env::VariableSubstitutor;
+use crate::http::{HttpMethod, RequestBuilder};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Metadata key holding the name of the auth profile a request uses
+pub const AUTH_PROFILE_KEY: &str = "auth_profile";
+
 /// A saved HTTP request in a collection
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RequestItem {
@@ -54,6 +60,14 @@ pub struct RequestItem {
     /// Custom metadata
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+
+    /// Authentication; secrets can be `{{VARIABLE}}` references filled at send time
+    #[serde(default)]
+    pub auth: AuthScheme,
+
+    /// Assertions to check against the response when the request is replayed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<Assertion>,
 }
 
 impl RequestItem {
@@ -74,6 +88,8 @@ impl RequestItem {
             updated_at: now,
             tags: Vec::new(),
             metadata: HashMap::new(),
+            auth: AuthScheme::None,
+            assertions: Vec::new(),
         }
     }
 
@@ -115,15 +131,41 @@ impl RequestItem {
         self
     }
 
+    /// Add an assertion to check when the request is replayed
+    pub fn with_assertion(mut self, assertion: Assertion) -> Self {
+        self.assertions.push(assertion);
+        self.updated_at = Utc::now();
+        self
+    }
+
+    /// Set authentication
+    pub fn with_auth(mut self, auth: AuthScheme) -> Self {
+        self.auth = auth;
+        self.updated_at = Utc::now();
+        self
+    }
+
+    /// Remember which auth profile this request needs
+    pub fn with_auth_profile(mut self, profile: String) -> Self {
+        self.metadata.insert(AUTH_PROFILE_KEY.to_string(), profile);
+        self.updated_at = Utc::now();
+        self
+    }
+
+    /// Get the auth profile this request needs
+    pub fn auth_profile(&self) -> Option<&str> {
+        self.metadata.get(AUTH_PROFILE_KEY).map(String::as_str)
+    }
+
     /// Update the modified timestamp
     pub fn touch(&mut self) {
         self.updated_at = Utc::now();
     }
 
     /// Convert to HTTP request builder
-    pub fn to_request_builder(&self) -> crate::http::RequestBuilder {
+    pub fn to_request_builder(&self) -> RequestBuilder {
         let method = HttpMethod::parse(&self.method).unwrap_or(HttpMethod::Get);
-        let mut builder = crate::http::RequestBuilder::new(method, self.url.clone());
+        let mut builder = RequestBuilder::new(method, self.url.clone()).auth(self.auth.clone());
 
         // Add headers
         for (key, value) in &self.headers {
@@ -142,11 +184,51 @@ impl RequestItem {
 
         builder
     }
+
+    /// Convert to HTTP request builder, filling {{variables}} from `variables`
+    pub fn to_request_builder_with(&self, variables: &HashMap<&str, &str>) -> RequestBuilder {
+        let substitutor = VariableSubstitutor::new();
+        let substitute = |text: &str| substitutor.substitute(text, variables);
+
+        let mut item = self.clone();
+        item.url = substitute(&self.url);
+        item.headers = self
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), substitute(v)))
+            .collect();
+        item.query_params = self
+            .query_params
+            .iter()
+            .map(|(k, v)| (k.clone(), substitute(v)))
+            .collect();
+        item.body = self.body.as_deref().map(substitute);
+        item.auth = self.auth.with_variables(variables);
+        item.to_request_builder()
+    }
+
+    /// Names of the {{variables}} referenced by the URL, header and query values, and body
+    pub fn variable_references(&self) -> Vec<String> {
+        let substitutor = VariableSubstitutor::new();
+        std::iter::once(self.url.as_str())
+            .chain(self.headers.values().map(String::as_str))
+            .chain(self.query_params.values().map(String::as_str))
+            .chain(self.body.as_deref())
+            .flat_map(|text| substitutor.find_variables(text))
+            .collect()
+    }
+
+    /// Convert to a request builder plus the assertions to run on its response
+    pub fn to_request_with_assertions(&self) -> (RequestBuilder, &[Assertion]) {
+        (self.to_request_builder(), &self.assertions)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::assertions::Matcher;
+    use crate::auth::BearerAuth;
 
     #[test]
     fn test_request_item_creation() {
@@ -178,6 +260,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_request_item_auth_profile() {
+        let item = RequestItem::new(
+            "Test".to_string(),
+            HttpMethod::Get,
+            "https://example.com".to_string(),
+        );
+        assert!(item.auth_profile().is_none());
+
+        let item = item.with_auth_profile("github".to_string());
+        assert_eq!(item.auth_profile(), Some("github"));
+        assert_eq!(
+            item.metadata.get("auth_profile"),
+            Some(&"github".to_string())
+        );
+    }
+
     #[test]
     fn test_request_item_with_body() {
         let item = RequestItem::new(
@@ -222,4 +321,112 @@ mod tests {
         assert_eq!(item.name, deserialized.name);
         assert_eq!(item.method, deserialized.method);
     }
+
+    #[test]
+    fn test_request_item_assertions_round_trip() {
+        let item = RequestItem::new(
+            "Health".to_string(),
+            HttpMethod::Get,
+            "https://example.com/health".to_string(),
+        )
+        .with_assertion(Assertion::status_code(Matcher::equals(200)));
+
+        let json = serde_json::to_string(&item).unwrap();
+        assert!(json.contains("\"assertions\""));
+        assert!(json.contains("StatusCode"));
+
+        let loaded: RequestItem = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, item);
+        assert_eq!(loaded.assertions.len(), 1);
+        assert_eq!(loaded.assertions[0].matcher.expected, "200");
+
+        let (request, assertions) = loaded.to_request_with_assertions();
+        assert_eq!(request.url, "https://example.com/health");
+        assert_eq!(assertions.len(), 1);
+    }
+
+    #[test]
+    fn test_request_item_without_assertions_field() {
+        let item = RequestItem::new(
+            "Legacy".to_string(),
+            HttpMethod::Get,
+            "https://example.com".to_string(),
+        );
+        let json = serde_json::to_string(&item).unwrap();
+        assert!(!json.contains("assertions"));
+
+        let loaded: RequestItem = serde_json::from_str(&json).unwrap();
+        assert!(loaded.assertions.is_empty());
+    }
+
+    #[test]
+    fn test_to_request_builder_with_variables() {
+        let item = RequestItem::new(
+            "Health".to_string(),
+            HttpMethod::Post,
+            "{{BASE_URL}}/health".to_string(),
+        )
+        .with_header("Authorization".to_string(), "Bearer {{TOKEN}}".to_string())
+        .with_query("region".to_string(), "{{REGION}}".to_string())
+        .with_body(r#"{"env":"{{ENV}}"}"#.to_string(), None);
+
+        let variables = HashMap::from([
+            ("BASE_URL", "https://staging.example.com"),
+            ("TOKEN", "abc"),
+            ("REGION", "eu"),
+        ]);
+        let request = item.to_request_builder_with(&variables);
+        assert_eq!(request.url, "https://staging.example.com/health");
+        assert_eq!(request.headers, vec!["Authorization:Bearer abc"]);
+        assert_eq!(request.query_params, vec!["region=eu"]);
+        assert_eq!(request.body.as_deref(), Some(r#"{"env":"{{ENV}}"}"#));
+    }
+
+    #[test]
+    fn test_saved_bearer_auth_reconstructs_authorization_header() {
+        let item = RequestItem::new(
+            "Me".to_string(),
+            HttpMethod::Get,
+            "https://api.example.com/me".to_string(),
+        )
+        .with_auth(AuthScheme::Bearer(BearerAuth::new("abc123".to_string())));
+
+        let saved = serde_json::to_string(&item).unwrap();
+        let loaded: RequestItem = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded, item);
+
+        let request = loaded.to_request_builder();
+        let (mut headers, mut query_params) = (request.headers.clone(), Vec::new());
+        request.apply_auth(&mut headers, &mut query_params);
+        assert_eq!(headers, vec!["Authorization:Bearer abc123"]);
+    }
+
+    #[test]
+    fn test_auth_secret_from_variable_reference() {
+        let item = RequestItem::new(
+            "Me".to_string(),
+            HttpMethod::Get,
+            "https://api.example.com/me".to_string(),
+        )
+        .with_auth(AuthScheme::Bearer(BearerAuth::new(
+            "{{API_TOKEN}}".to_string(),
+        )));
+        assert!(!serde_json::to_string(&item).unwrap().contains("s3cret"));
+
+        let request = item.to_request_builder_with(&HashMap::from([("API_TOKEN", "s3cret")]));
+        let (mut headers, mut query_params) = (Vec::new(), Vec::new());
+        request.apply_auth(&mut headers, &mut query_params);
+        assert_eq!(headers, vec!["Authorization:Bearer s3cret"]);
+    }
+
+    #[test]
+    fn test_request_without_auth_deserializes() {
+        let item: RequestItem = serde_json::from_str(
+            r#"{"id":"00000000-0000-0000-0000-000000000001","name":"Old","method":"GET",
+            "url":"https://example.com","created_at":"2024-01-01T00:00:00Z",
+            "updated_at":"2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(item.auth, AuthScheme::None);
+    }
 }
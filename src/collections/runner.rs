@@ -0,0 +1,615 @@
+//! Sequential execution of every request in a collection
+
+use crate::assertions::{ResponseValidator, ValidationReport};
+use crate::auth::{AuthProfileStore, AuthScheme};
+use crate::collections::{Collection, RequestItem, ResponseCache};
+use crate::error::Result;
+use crate::history::{HistoryLogger, HistoryStorage};
+use crate::http::{HttpClient, HttpMethod, HttpResponse};
+use crate::report::{JUnitTestCase, JUnitTestSuite};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Result of running one request from a collection
+#[derive(Debug, Clone)]
+pub struct RequestRunResult {
+    /// Request ID
+    pub request_id: Uuid,
+
+    /// Request name
+    pub name: String,
+
+    /// Response (for a 304, the cached response)
+    pub response: Option<HttpResponse>,
+
+    /// Whether the response came from the cache after a 304 Not Modified
+    pub cached: bool,
+
+    /// Error message (if the request failed)
+    pub error: Option<String>,
+
+    /// Network duration
+    pub duration: Duration,
+
+    /// Results of the request's saved assertions (if it has any)
+    pub validation: Option<ValidationReport>,
+}
+
+impl RequestRunResult {
+    /// Whether the request completed without error and its assertions passed
+    pub fn success(&self) -> bool {
+        self.error.is_none() && self.validation.as_ref().is_none_or(|v| v.success)
+    }
+
+    /// Get summary
+    pub fn summary(&self) -> String {
+        match (&self.response, &self.error) {
+            (_, Some(error)) => format!("✗ {} - {}", self.name, error),
+            (Some(response), None) => {
+                let cached = if self.cached {
+                    " (not modified, cached)"
+                } else {
+                    ""
+                };
+                let (marker, assertions) = match &self.validation {
+                    Some(report) if !report.success => (
+                        "✗",
+                        format!(", {} of {} assertions failed", report.failed, report.total),
+                    ),
+                    Some(report) => ("✓", format!(", {} assertions passed", report.total)),
+                    None => ("✓", String::new()),
+                };
+                format!(
+                    "{} {} - {}{} ({:?}{})",
+                    marker,
+                    self.name,
+                    response.status.as_u16(),
+                    cached,
+                    self.duration,
+                    assertions
+                )
+            }
+            (None, None) => format!("✓ {}", self.name),
+        }
+    }
+}
+
+/// Result of running a whole collection
+#[derive(Debug, Clone)]
+pub struct CollectionRunResult {
+    /// Collection name
+    pub collection_name: String,
+
+    /// Per-request results, in run order
+    pub results: Vec<RequestRunResult>,
+
+    /// Total duration
+    pub total_duration: Duration,
+
+    /// Whether the run was cut off by the client's --max-time deadline
+    pub deadline_exceeded: bool,
+}
+
+impl CollectionRunResult {
+    /// Whether every request completed without error
+    pub fn success(&self) -> bool {
+        self.results.iter().all(RequestRunResult::success)
+    }
+
+    /// Number of responses served from the cache
+    pub fn cached_count(&self) -> usize {
+        self.results.iter().filter(|r| r.cached).count()
+    }
+
+    /// Get summary
+    pub fn summary(&self) -> String {
+        let failed = self.results.iter().filter(|r| !r.success()).count();
+        let marker = if failed == 0 { "✓" } else { "✗" };
+        let mut summary = format!(
+            "{} Collection '{}': {} requests, {} failed, {} cached, {:?}",
+            marker,
+            self.collection_name,
+            self.results.len(),
+            failed,
+            self.cached_count(),
+            self.total_duration
+        );
+        if self.deadline_exceeded {
+            summary.push_str(" (stopped: --max-time exceeded)");
+        }
+        summary
+    }
+
+    /// Build a test suite named after the collection, with one test case per request
+    ///
+    /// A failed request's message is its error, or its failed assertions one per line.
+    pub fn test_suite(&self) -> JUnitTestSuite {
+        let mut suite = JUnitTestSuite::new(self.collection_name.clone());
+        for result in &self.results {
+            let case = JUnitTestCase::new(
+                result.name.clone(),
+                self.collection_name.clone(),
+                result.duration,
+            );
+            let failure = match (&result.error, &result.validation) {
+                (Some(error), _) => Some(error.clone()),
+                (None, Some(report)) if !report.success => Some(
+                    report
+                        .results
+                        .iter()
+                        .filter(|r| !r.passed)
+                        .map(|r| r.summary())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ),
+                _ => None,
+            };
+            suite.add_case(match failure {
+                Some(message) => case.with_failure(message),
+                None => case,
+            });
+        }
+        suite
+    }
+
+    /// Render as a JUnit XML test suite with one test case per request
+    pub fn to_junit_xml(&self) -> String {
+        self.test_suite().to_xml()
+    }
+
+    /// Render as a JSON report with one test case per request
+    pub fn to_json(&self) -> Result<String> {
+        self.test_suite().to_json()
+    }
+}
+
+/// Runs the requests of a collection one after another
+pub struct CollectionRunner {
+    client: HttpClient,
+    cache: Option<ResponseCache>,
+    profiles: Option<AuthProfileStore>,
+    default_auth: Option<AuthScheme>,
+    environment: HashMap<String, String>,
+    history: Option<HistoryStorage>,
+}
+
+impl CollectionRunner {
+    /// Create a new runner
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            cache: None,
+            profiles: None,
+            default_auth: None,
+            environment: HashMap::new(),
+            history: None,
+        }
+    }
+
+    /// Send conditional GETs and reuse cached bodies on 304 Not Modified
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Resolve per-request auth profiles from a store
+    pub fn with_auth_profiles(mut self, profiles: AuthProfileStore) -> Self {
+        self.profiles = Some(profiles);
+        self
+    }
+
+    /// Authenticate requests that have neither an auth profile nor auth of their own
+    pub fn with_default_auth(mut self, auth: AuthScheme) -> Self {
+        self.default_auth = Some(auth);
+        self
+    }
+
+    /// Fill {{variables}} from an environment, overriding collection and folder values
+    pub fn with_environment(mut self, variables: HashMap<String, String>) -> Self {
+        self.environment = variables;
+        self
+    }
+
+    /// Record every request and its outcome in history
+    pub fn with_history(mut self, storage: HistoryStorage) -> Self {
+        self.history = Some(storage);
+        self
+    }
+
+    /// Run every request in the collection
+    pub fn run(&self, collection: &Collection) -> CollectionRunResult {
+        let start = Instant::now();
+        let results: Vec<RequestRunResult> = collection
+            .list_all_requests()
+            .into_iter()
+            .map(|item| self.run_request_with(item, &collection.request_variables(&item.id)))
+            .collect();
+
+        // Requests past the deadline fail fast in the client and are reported as aborted
+        let deadline_exceeded = self.client.deadline().is_some_and(|d| d.is_expired())
+            && results.iter().any(|r| !r.success());
+
+        CollectionRunResult {
+            collection_name: collection.info.name.clone(),
+            results,
+            total_duration: start.elapsed(),
+            deadline_exceeded,
+        }
+    }
+
+    /// Run only the request at a folder path (e.g. `Auth/Login`)
+    pub fn run_path(&self, collection: &Collection, path: &str) -> Result<CollectionRunResult> {
+        let start = Instant::now();
+        let item = collection.request_at_path(path)?;
+        let result = self.run_request_with(item, &collection.request_variables(&item.id));
+        let deadline_exceeded =
+            self.client.deadline().is_some_and(|d| d.is_expired()) && !result.success();
+
+        Ok(CollectionRunResult {
+            collection_name: collection.info.name.clone(),
+            results: vec![result],
+            total_duration: start.elapsed(),
+            deadline_exceeded,
+        })
+    }
+
+    /// Run a single request
+    pub fn run_request(&self, item: &RequestItem) -> RequestRunResult {
+        self.run_request_with(item, &HashMap::new())
+    }
+
+    /// Run a single request, filling {{variables}} from its collection scope
+    pub fn run_request_with(
+        &self,
+        item: &RequestItem,
+        variables: &HashMap<String, String>,
+    ) -> RequestRunResult {
+        let mut result = RequestRunResult {
+            request_id: item.id,
+            name: item.name.clone(),
+            response: None,
+            cached: false,
+            error: None,
+            duration: Duration::ZERO,
+            validation: None,
+        };
+
+        match self.execute(item, variables) {
+            Ok((response, cached)) => {
+                if !item.assertions.is_empty() {
+                    result.validation =
+                        Some(ResponseValidator::new().validate(&response, &item.assertions));
+                }
+                result.duration = response.duration;
+                result.cached = cached;
+                result.response = Some(response);
+            }
+            Err(e) => result.error = Some(e.to_string()),
+        }
+
+        result
+    }
+
+    fn execute(
+        &self,
+        item: &RequestItem,
+        variables: &HashMap<String, String>,
+    ) -> Result<(HttpResponse, bool)> {
+        let variables = variables
+            .iter()
+            .chain(&self.environment)
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let mut request = item.to_request_builder_with(&variables);
+
+        match (item.auth_profile(), &self.default_auth) {
+            (Some(profile), _) => {
+                if let Some(profiles) = &self.profiles {
+                    request = request.auth(profiles.resolve(profile)?);
+                }
+            }
+            (None, Some(auth)) if !item.auth.is_configured() => {
+                request = request.auth(auth.clone());
+            }
+            _ => {}
+        }
+
+        // Only GETs are cached
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|_| request.method == HttpMethod::Get);
+        let entry = cache.and_then(|cache| cache.get(&item.id));
+        if let Some(entry) = &entry {
+            for header in entry.conditional_headers() {
+                request = request.header(header);
+            }
+        }
+
+        let result = self.client.execute(&request);
+        if let Some(storage) = &self.history {
+            let mut logger = HistoryLogger::new();
+            let id = logger.record(&request, &result);
+            // History is best-effort; failing to write it shouldn't fail the run
+            if let Some(entry) = logger.get_entry(&id) {
+                storage.save_entry(entry).ok();
+            }
+        }
+        let response = result?;
+
+        if let (Some(entry), StatusCode::NOT_MODIFIED) = (&entry, response.status) {
+            return Ok((entry.to_response(&response)?, true));
+        }
+        if let Some(cache) = cache {
+            cache.store(&item.id, &response)?;
+        }
+
+        Ok((response, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assertions::{validate_response, Assertion, Matcher};
+    use tempfile::TempDir;
+
+    fn collection_with(url: String) -> Collection {
+        let mut collection = Collection::new("Nightly".to_string());
+        collection.add_request(RequestItem::new("Data".to_string(), HttpMethod::Get, url));
+        collection
+    }
+
+    #[test]
+    fn test_run_without_cache() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/data")
+            .with_header("etag", "\"v1\"")
+            .with_body("full")
+            .expect(2)
+            .create();
+
+        let collection = collection_with(format!("{}/data", server.url()));
+        let runner = CollectionRunner::new(HttpClient::new());
+        for _ in 0..2 {
+            let result = runner.run(&collection);
+            assert!(result.success());
+            assert_eq!(result.cached_count(), 0);
+        }
+        mock.assert();
+    }
+
+    #[test]
+    fn test_run_fills_collection_variables() {
+        let mut server = mockito::Server::new();
+        let mock = server.mock("GET", "/v2/data").create();
+
+        let mut collection = collection_with("{{BASE_URL}}/data".to_string());
+        collection.set_variable("BASE_URL".to_string(), format!("{}/v2", server.url()));
+
+        let result = CollectionRunner::new(HttpClient::new()).run(&collection);
+        assert!(result.success());
+        mock.assert();
+    }
+
+    #[test]
+    fn test_run_environment_overrides_collection_variables() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/v2/data")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+            .create();
+
+        let mut collection = collection_with("{{BASE_URL}}/data?page={{PAGE}}".to_string());
+        collection.set_variable(
+            "BASE_URL".to_string(),
+            "http://collection.invalid".to_string(),
+        );
+        collection.set_variable("PAGE".to_string(), "1".to_string());
+
+        let environment = HashMap::from([("BASE_URL".to_string(), format!("{}/v2", server.url()))]);
+        let result = CollectionRunner::new(HttpClient::new())
+            .with_environment(environment)
+            .run(&collection);
+        assert!(result.success());
+        mock.assert();
+    }
+
+    #[test]
+    fn test_run_path_runs_only_that_request() {
+        let mut server = mockito::Server::new();
+        let login = server.mock("POST", "/login").create();
+        let data = server.mock("GET", "/data").expect(0).create();
+
+        let mut collection = collection_with(format!("{}/data", server.url()));
+        let mut auth = crate::collections::Folder::new("Auth".to_string());
+        auth.add_request(RequestItem::new(
+            "Login".to_string(),
+            HttpMethod::Post,
+            format!("{}/login", server.url()),
+        ));
+        collection.add_folder(auth);
+
+        let runner = CollectionRunner::new(HttpClient::new());
+        let result = runner.run_path(&collection, "Auth/Login").unwrap();
+        assert_eq!(result.results.len(), 1);
+        assert_eq!(result.results[0].name, "Login");
+        login.assert();
+        data.assert();
+
+        assert!(runner.run_path(&collection, "Admin/Login").is_err());
+    }
+
+    #[test]
+    fn test_run_uses_default_auth_for_requests_without_their_own() {
+        let mut server = mockito::Server::new();
+        let default = server
+            .mock("GET", "/data")
+            .match_header("authorization", "Bearer from-flag")
+            .create();
+        let own = server
+            .mock("GET", "/own")
+            .match_header("authorization", "Bearer from-item")
+            .create();
+
+        let mut collection = collection_with(format!("{}/data", server.url()));
+        let mut item = RequestItem::new(
+            "Own".to_string(),
+            HttpMethod::Get,
+            format!("{}/own", server.url()),
+        );
+        item.auth = AuthScheme::Bearer(crate::auth::BearerAuth::new("from-item".to_string()));
+        collection.add_request(item);
+
+        let result = CollectionRunner::new(HttpClient::new())
+            .with_default_auth(AuthScheme::Bearer(crate::auth::BearerAuth::new(
+                "from-flag".to_string(),
+            )))
+            .run(&collection);
+        assert!(result.success());
+        default.assert();
+        own.assert();
+    }
+
+    #[test]
+    fn test_run_records_history() {
+        let mut server = mockito::Server::new();
+        let _mock = server.mock("GET", "/data").with_body("ok").create();
+
+        let temp_dir = TempDir::new().unwrap();
+        let storage = HistoryStorage::new(temp_dir.path().to_path_buf()).unwrap();
+        let collection = collection_with(format!("{}/data", server.url()));
+        CollectionRunner::new(HttpClient::new())
+            .with_history(HistoryStorage::new(temp_dir.path().to_path_buf()).unwrap())
+            .run(&collection);
+
+        let entries = storage.load_all().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].response.as_ref().unwrap().status_code, 200);
+    }
+
+    #[test]
+    fn test_run_checks_saved_assertions() {
+        let mut server = mockito::Server::new();
+        let _mock = server.mock("GET", "/data").with_status(503).create();
+
+        let mut collection = Collection::new("Nightly".to_string());
+        collection.add_request(
+            RequestItem::new(
+                "Data".to_string(),
+                HttpMethod::Get,
+                format!("{}/data", server.url()),
+            )
+            .with_assertion(Assertion::status_code(Matcher::equals(200))),
+        );
+
+        let result = CollectionRunner::new(HttpClient::new()).run(&collection);
+        assert!(!result.success());
+        let request = &result.results[0];
+        assert!(request.error.is_none());
+        assert_eq!(request.validation.as_ref().unwrap().failed, 1);
+        assert!(request.summary().starts_with("✗ Data - 503"));
+        assert!(request.summary().contains("1 of 1 assertions failed"));
+
+        let xml = result.to_junit_xml();
+        crate::report::junit::assert_junit_schema(&xml);
+        assert!(xml.contains(r#"<testsuite name="Nightly" tests="1" failures="1""#));
+        let json: serde_json::Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+        assert_eq!(json["testcases"][0]["name"], "Data");
+        assert_eq!(
+            json["testcases"][0]["failure"],
+            "✗ Assertion: FAIL - Expected equals '200', got 503"
+        );
+    }
+
+    #[test]
+    fn test_run_deadline_aborts_remaining_requests() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let silent = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let mut held = Vec::new();
+            for stream in listener.incoming().flatten() {
+                held.push(stream);
+            }
+        });
+
+        let mut server = mockito::Server::new();
+        let fast = server.mock("GET", "/fast").expect(0).create();
+
+        let mut collection = collection_with(silent);
+        collection.add_request(RequestItem::new(
+            "Fast".to_string(),
+            HttpMethod::Get,
+            format!("{}/fast", server.url()),
+        ));
+
+        let client = HttpClient::new()
+            .with_deadline(crate::http::Deadline::after(Duration::from_millis(300)));
+        let started = Instant::now();
+        let result = CollectionRunner::new(client).run(&collection);
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        fast.assert();
+        assert!(result.deadline_exceeded);
+        assert_eq!(result.results.len(), 2);
+        assert!(result.results[1]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("--max-time"));
+        assert!(result.summary().contains("--max-time exceeded"));
+    }
+
+    #[test]
+    fn test_run_with_cache_reuses_body_on_304() {
+        let mut server = mockito::Server::new();
+        let full = server
+            .mock("GET", "/data")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("etag", "\"v1\"")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"version":1}"#)
+            .expect(1)
+            .create();
+        let not_modified = server
+            .mock("GET", "/data")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create();
+
+        let temp_dir = TempDir::new().unwrap();
+        let collection = collection_with(format!("{}/data", server.url()));
+        let runner = CollectionRunner::new(HttpClient::new())
+            .with_cache(ResponseCache::new(temp_dir.path().to_path_buf()));
+
+        let first = runner.run(&collection);
+        assert!(!first.results[0].cached);
+
+        let second = runner.run(&collection);
+        let result = &second.results[0];
+        assert!(result.cached);
+        assert!(result.summary().contains("not modified, cached"));
+
+        let response = result.response.as_ref().unwrap();
+        assert_eq!(response.status.as_u16(), 200);
+        assert_eq!(response.body, r#"{"version":1}"#);
+
+        // An assertion updated since the response was cached still fails
+        let report = validate_response(
+            response,
+            &[Assertion::json_path(
+                "$.version".to_string(),
+                Matcher::equals(2),
+            )],
+        )
+        .unwrap();
+        assert!(!report.success);
+
+        full.assert();
+        not_modified.assert();
+    }
+}
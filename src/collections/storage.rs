@@ -116,6 +116,55 @@ impl CollectionStorage {
             }
         }
     }
+
+    /// Import a collection and merge it into an existing one instead of duplicating requests
+    ///
+    /// Incoming requests are matched to existing ones by method and URL, wherever
+    /// they live; new requests keep their folder, which is created if needed.
+    pub fn import_merge(
+        &self,
+        path: &Path,
+        format: ImportFormat,
+        into: &mut Collection,
+        strategy: MergeStrategy,
+    ) -> crate::Result<MergeReport> {
+        let incoming = self.import(path, format)?;
+        Ok(into.merge(incoming, strategy))
+    }
+}
+
+/// What to do with an incoming request that matches an existing one by method and URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Keep the existing request untouched
+    #[default]
+    Skip,
+
+    /// Overwrite the existing request's details, keeping its ID
+    Update,
+}
+
+/// Counts from merging an import into a collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MergeReport {
+    /// Requests that were new
+    pub added: usize,
+
+    /// Duplicates overwritten in place
+    pub updated: usize,
+
+    /// Duplicates left alone
+    pub skipped: usize,
+}
+
+impl MergeReport {
+    /// One-line summary
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} updated, {} skipped",
+            self.added, self.updated, self.skipped
+        )
+    }
 }
 
 /// Export formats for collections
@@ -136,6 +185,8 @@ pub enum ImportFormat {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::collections::{Folder, RequestItem};
+    use crate::http::HttpMethod;
     use tempfile::TempDir;
 
     #[test]
@@ -189,4 +240,92 @@ mod tests {
         storage.delete(&id).unwrap();
         assert!(storage.load(&id).is_err());
     }
+
+    fn api_collection() -> Collection {
+        let mut collection = Collection::new("API".to_string());
+        collection.add_request(RequestItem::new(
+            "Health".to_string(),
+            HttpMethod::Get,
+            "https://api.example.com/health".to_string(),
+        ));
+        let mut users = Folder::new("Users".to_string());
+        users.add_request(RequestItem::new(
+            "List".to_string(),
+            HttpMethod::Get,
+            "https://api.example.com/users".to_string(),
+        ));
+        users.add_request(RequestItem::new(
+            "Create".to_string(),
+            HttpMethod::Post,
+            "https://api.example.com/users".to_string(),
+        ));
+        collection.add_folder(users);
+        collection
+    }
+
+    #[test]
+    fn test_import_merge_skips_duplicates() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = CollectionStorage::new(temp_dir.path().to_path_buf()).unwrap();
+        let path = temp_dir.path().join("export.json");
+        api_collection().save_to_file(&path).unwrap();
+
+        let mut target = Collection::new("Mine".to_string());
+        let first = storage
+            .import_merge(&path, ImportFormat::Json, &mut target, MergeStrategy::Skip)
+            .unwrap();
+        assert_eq!(first.added, 3);
+        assert_eq!(target.folders[0].name, "Users");
+
+        // The same export again, with fresh IDs from a second export
+        api_collection().save_to_file(&path).unwrap();
+        let second = storage
+            .import_merge(&path, ImportFormat::Json, &mut target, MergeStrategy::Skip)
+            .unwrap();
+        assert_eq!(
+            second,
+            MergeReport {
+                added: 0,
+                updated: 0,
+                skipped: 3
+            }
+        );
+        assert_eq!(target.total_requests(), 3);
+        assert_eq!(target.folders.len(), 1);
+    }
+
+    #[test]
+    fn test_import_merge_updates_in_place() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = CollectionStorage::new(temp_dir.path().to_path_buf()).unwrap();
+        let path = temp_dir.path().join("export.json");
+
+        let mut target = api_collection();
+        let health_id = target.requests[0].id;
+
+        let mut incoming = api_collection();
+        incoming.requests[0]
+            .headers
+            .insert("Accept".to_string(), "application/json".to_string());
+        incoming.add_request(RequestItem::new(
+            "Version".to_string(),
+            HttpMethod::Get,
+            "https://api.example.com/version".to_string(),
+        ));
+        incoming.save_to_file(&path).unwrap();
+
+        let report = storage
+            .import_merge(
+                &path,
+                ImportFormat::Json,
+                &mut target,
+                MergeStrategy::Update,
+            )
+            .unwrap();
+        assert_eq!(report.summary(), "1 added, 3 updated, 0 skipped");
+
+        let health = target.get_request(&health_id).unwrap();
+        assert_eq!(health.headers.get("Accept").unwrap(), "application/json");
+        assert_eq!(target.total_requests(), 4);
+    }
 }
@@ -1,5 +1,6 @@
 //! Workspace management for organizing collections
 
+use crate::collections::Collection;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -88,6 +89,20 @@ impl Workspace {
     pub fn get_variable(&self, key: &str) -> Option<&String> {
         self.variables.get(key)
     }
+
+    /// Resolve the variables visible inside a folder of a collection
+    ///
+    /// Workspace variables have the lowest precedence; see
+    /// [`Collection::resolve_variables`] for the rest.
+    pub fn resolve_variables(
+        &self,
+        collection: &Collection,
+        folder_path: &[&str],
+    ) -> HashMap<String, String> {
+        let mut variables = self.variables.clone();
+        variables.extend(collection.resolve_variables(folder_path));
+        variables
+    }
 }
 
 /// Storage for workspaces
@@ -212,4 +227,21 @@ mod tests {
             Some(&"secret123".to_string())
         );
     }
+
+    #[test]
+    fn test_workspace_variables_have_lowest_precedence() {
+        let mut workspace = Workspace::new("Test".to_string());
+        workspace.set_variable(
+            "API_URL".to_string(),
+            "https://workspace.example.com".to_string(),
+        );
+        workspace.set_variable("REGION".to_string(), "eu".to_string());
+
+        let mut collection = Collection::new("API".to_string());
+        collection.set_variable("API_URL".to_string(), "https://api.example.com".to_string());
+
+        let variables = workspace.resolve_variables(&collection, &[]);
+        assert_eq!(variables["API_URL"], "https://api.example.com");
+        assert_eq!(variables["REGION"], "eu");
+    }
 }
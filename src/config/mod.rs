@@ -0,0 +1,5 @@
+//! Global defaults from the config file
+
+pub mod settings;
+
+pub use settings::Config;
@@ -0,0 +1,291 @@
+//! Defaults loaded from `config.toml`, overridden by command-line flags
+//!
+//! ```toml
+//! timeout = "30s"
+//! follow_redirects = false
+//! insecure = true
+//! color = "never"
+//! user_agent = "team-cli/1.0"
+//!
+//! [headers]
+//! Accept = "application/json"
+//! ```
+
+use crate::error::{Error, Result};
+use crate::http::deadline::parse_duration;
+use crate::ui::ColorChoice;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Keys accepted by `config set`
+pub const CONFIG_KEYS: &[&str] = &[
+    "timeout",
+    "follow_redirects",
+    "insecure",
+    "color",
+    "user_agent",
+    "headers.<name>",
+];
+
+/// Request and output defaults
+///
+/// Unset fields fall back to the built-in defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Timeout for each request, e.g. "30s" or "500ms"
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_duration",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub timeout: Option<Duration>,
+
+    /// Whether to follow 3xx redirects (on by default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub follow_redirects: Option<bool>,
+
+    /// Skip TLS certificate verification
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insecure: Option<bool>,
+
+    /// When to use colors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorChoice>,
+
+    /// User-Agent to identify as (default `bazzounquester/<version>`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+
+    /// Headers sent with every request unless the request sets them
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Create an empty config
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get default config file path
+    pub fn default_path() -> Result<PathBuf> {
+        let dirs = directories::ProjectDirs::from("com", "bazzoun", "bazzounquester").ok_or_else(
+            || {
+                Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Could not determine config directory",
+                ))
+            },
+        )?;
+
+        Ok(dirs.config_dir().join("config.toml"))
+    }
+
+    /// Load a config file (a missing file is an empty config)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        Self::parse(&content).map_err(|problem| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Invalid config file '{}': {}", path.display(), problem),
+            ))
+        })
+    }
+
+    /// Parse config TOML
+    pub fn parse(content: &str) -> std::result::Result<Self, String> {
+        toml::from_str(content).map_err(|e| e.message().to_string())
+    }
+
+    /// Write the config file, creating its directory
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_toml())?;
+        Ok(())
+    }
+
+    /// Render as TOML
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("config always serializes")
+    }
+
+    /// Set a value by key, e.g. `timeout 30s` or `headers.Accept application/json`
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let invalid = |expected: &str| {
+            Error::InvalidCommand(format!(
+                "Invalid value '{}' for '{}': expected {}",
+                value, key, expected
+            ))
+        };
+        let parse_bool = |value: &str| match value.to_lowercase().as_str() {
+            "true" | "yes" | "on" => Ok(true),
+            "false" | "no" | "off" => Ok(false),
+            _ => Err(invalid("true or false")),
+        };
+
+        match key {
+            "timeout" => {
+                self.timeout = Some(parse_duration(value).map_err(|e| invalid(&e))?);
+            }
+            "follow_redirects" => self.follow_redirects = Some(parse_bool(value)?),
+            "insecure" => self.insecure = Some(parse_bool(value)?),
+            "color" => {
+                self.color = Some(
+                    <ColorChoice as clap::ValueEnum>::from_str(value, true)
+                        .map_err(|_| invalid("auto, always or never"))?,
+                );
+            }
+            "user_agent" => self.user_agent = Some(value.to_string()),
+            _ => match key.strip_prefix("headers.") {
+                Some(name) if !name.is_empty() => {
+                    self.headers.retain(|n, _| !n.eq_ignore_ascii_case(name));
+                    self.headers.insert(name.to_string(), value.to_string());
+                }
+                _ => {
+                    return Err(Error::InvalidCommand(format!(
+                        "Unknown config key '{}' (available: {})",
+                        key,
+                        CONFIG_KEYS.join(", ")
+                    )))
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Layer values given on the command line over this config
+    ///
+    /// Every value set in `flags` wins; headers are merged, with `flags`
+    /// replacing a header of the same name (ignoring case).
+    pub fn with_overrides(mut self, flags: Config) -> Self {
+        self.timeout = flags.timeout.or(self.timeout);
+        self.follow_redirects = flags.follow_redirects.or(self.follow_redirects);
+        self.insecure = flags.insecure.or(self.insecure);
+        self.color = flags.color.or(self.color);
+        self.user_agent = flags.user_agent.or(self.user_agent);
+        for (name, value) in flags.headers {
+            self.headers.retain(|n, _| !n.eq_ignore_ascii_case(&name));
+            self.headers.insert(name, value);
+        }
+        self
+    }
+
+    /// Default headers as `Name: value` lines
+    pub fn header_lines(&self) -> Vec<String> {
+        self.headers
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect()
+    }
+}
+
+/// Write a duration as whole seconds ("30s") or milliseconds ("500ms")
+fn serialize_duration<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) if duration.subsec_millis() == 0 => {
+            serializer.serialize_str(&format!("{}s", duration.as_secs()))
+        }
+        Some(duration) => serializer.serialize_str(&format!("{}ms", duration.as_millis())),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Read a duration like "30s", "500ms" or "2m"
+fn deserialize_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Duration>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_duration(&value)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "timeout = \"2s\"\nfollow_redirects = false\ncolor = \"never\"\n\n[headers]\nAccept = \"application/json\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.timeout, Some(Duration::from_secs(2)));
+        assert_eq!(config.follow_redirects, Some(false));
+        assert_eq!(config.insecure, None);
+        assert_eq!(config.color, Some(ColorChoice::Never));
+        assert_eq!(config.header_lines(), ["Accept: application/json"]);
+
+        assert_eq!(
+            Config::load(&dir.path().join("missing.toml")).unwrap(),
+            Config::new()
+        );
+
+        std::fs::write(&path, "timeot = \"2s\"\n").unwrap();
+        let error = Config::load(&path).unwrap_err().to_string();
+        assert!(error.contains("Invalid config file"));
+        assert!(error.contains("timeot"));
+    }
+
+    #[test]
+    fn test_flags_override_config() {
+        let mut config = Config::new();
+        config.set("timeout", "30s").unwrap();
+        config.set("insecure", "true").unwrap();
+        config.set("headers.Accept", "application/json").unwrap();
+        config.set("headers.User-Agent", "team-cli").unwrap();
+        config.set("user_agent", "team-cli/1.0").unwrap();
+
+        let mut flags = Config::new();
+        flags.timeout = Some(Duration::from_millis(500));
+        flags.user_agent = Some("ci-bot/2.0".to_string());
+        flags
+            .headers
+            .insert("accept".to_string(), "text/html".to_string());
+
+        let merged = config.with_overrides(flags);
+        assert_eq!(merged.timeout, Some(Duration::from_millis(500)));
+        assert_eq!(merged.insecure, Some(true));
+        assert_eq!(merged.follow_redirects, None);
+        assert_eq!(merged.user_agent.as_deref(), Some("ci-bot/2.0"));
+        assert_eq!(
+            merged.header_lines(),
+            ["User-Agent: team-cli", "accept: text/html"]
+        );
+    }
+
+    #[test]
+    fn test_set_and_save_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("config.toml");
+
+        let mut config = Config::new();
+        config.set("timeout", "1500ms").unwrap();
+        config.set("color", "Always").unwrap();
+        config.save(&path).unwrap();
+        assert_eq!(Config::load(&path).unwrap(), config);
+        assert!(config.to_toml().contains("timeout = \"1500ms\""));
+
+        assert!(config.set("insecure", "maybe").is_err());
+        assert!(config.set("timeout", "soon").is_err());
+        let error = config.set("proxy", "x").unwrap_err().to_string();
+        assert!(error.contains("Unknown config key 'proxy'"));
+    }
+}
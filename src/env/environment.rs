@@ -176,6 +176,22 @@ impl Environment {
             .collect()
     }
 
+    /// Copy this environment under a new name
+    ///
+    /// Variables keep their secret and enabled flags; the copy gets a fresh ID
+    /// and timestamps and starts inactive.
+    pub fn clone_as(&self, new_name: String) -> Environment {
+        let now = Utc::now();
+        Environment {
+            id: Uuid::new_v4(),
+            name: new_name,
+            created_at: now,
+            updated_at: now,
+            is_active: false,
+            ..self.clone()
+        }
+    }
+
     /// Activate this environment
     pub fn activate(&mut self) {
         self.is_active = true;
@@ -209,6 +225,113 @@ impl Environment {
         std::fs::write(path, yaml)?;
         Ok(())
     }
+
+    /// Export enabled variables as `KEY=value` lines of a `.env` file
+    ///
+    /// Values containing whitespace or shell-special characters are
+    /// double-quoted. Disabled variables are skipped.
+    pub fn export_dotenv(&self, path: &Path) -> crate::Result<()> {
+        let mut keys: Vec<&String> = self
+            .variables
+            .iter()
+            .filter(|(_, v)| v.enabled)
+            .map(|(k, _)| k)
+            .collect();
+        keys.sort();
+
+        let mut out = String::new();
+        for key in keys {
+            let value = &self.variables[key].value;
+            out.push_str(&format!("{}={}\n", key, quote_dotenv(value)));
+        }
+        std::fs::write(path, out)?;
+        Ok(())
+    }
+
+    /// Import variables from a `.env` file, returning how many were set
+    ///
+    /// Blank lines and `#` comments are ignored, an `export ` prefix is
+    /// accepted, and single- or double-quoted values are unquoted.
+    pub fn import_dotenv(&mut self, path: &Path) -> crate::Result<usize> {
+        let content = std::fs::read_to_string(path)?;
+        let mut count = 0;
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                crate::Error::InvalidCommand(format!(
+                    "{}:{}: expected KEY=value",
+                    path.display(),
+                    index + 1
+                ))
+            })?;
+            self.set_variable(key.trim().to_string(), unquote_dotenv(value.trim()));
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Double-quote a value if a shell would otherwise split or expand it
+fn quote_dotenv(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || "#\"'$\\=`".contains(c));
+    if !needs_quotes {
+        return value.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '$' => quoted.push_str("\\$"),
+            '`' => quoted.push_str("\\`"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Parse the value side of a `.env` line
+fn unquote_dotenv(raw: &str) -> String {
+    if let Some(inner) = raw.strip_prefix('\'') {
+        let end = inner.find('\'').unwrap_or(inner.len());
+        return inner[..end].to_string();
+    }
+
+    if let Some(inner) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some(other) => value.push(other),
+                    None => value.push('\\'),
+                },
+                _ => value.push(c),
+            }
+        }
+        return value;
+    }
+
+    // Unquoted: a ` #` starts a trailing comment
+    match raw.find(" #") {
+        Some(index) => raw[..index].trim_end().to_string(),
+        None => raw.to_string(),
+    }
 }
 
 #[cfg(test)]
@@ -308,4 +431,82 @@ mod tests {
         assert_eq!(enabled.get("VAR3"), Some(&"value3"));
         assert_eq!(enabled.get("VAR2"), None);
     }
+
+    #[test]
+    fn test_dotenv_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("dev.env");
+
+        let mut env = Environment::new("Dev".to_string());
+        env.set_variable(
+            "BASE_URL".to_string(),
+            "https://api.example.com".to_string(),
+        );
+        env.set_variable("GREETING".to_string(), "hello world".to_string());
+        env.set_variable(
+            "TRICKY".to_string(),
+            r#"say "hi" $HOME \ # not a comment"#.to_string(),
+        );
+        env.set_variable("MULTILINE".to_string(), "one\ntwo".to_string());
+        env.set_variable("DISABLED".to_string(), "skip me".to_string());
+        env.set_variable_enabled("DISABLED", false);
+        env.export_dotenv(&path).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("BASE_URL=https://api.example.com\n"));
+        assert!(content.contains("GREETING=\"hello world\"\n"));
+        assert!(!content.contains("DISABLED"));
+
+        let mut imported = Environment::new("Imported".to_string());
+        assert_eq!(imported.import_dotenv(&path).unwrap(), 4);
+        assert_eq!(imported.enabled_variables(), env.enabled_variables());
+    }
+
+    #[test]
+    fn test_import_dotenv_comments_and_quotes() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(".env");
+        std::fs::write(
+            &path,
+            "# shared settings\n\
+             \n\
+             export API_KEY=abc123\n\
+             NAME='single quoted # kept'\n\
+             TIMEOUT=30 # seconds\n\
+             EMPTY=\n",
+        )
+        .unwrap();
+
+        let mut env = Environment::new("Test".to_string());
+        assert_eq!(env.import_dotenv(&path).unwrap(), 4);
+        assert_eq!(env.get_variable("API_KEY"), Some("abc123"));
+        assert_eq!(env.get_variable("NAME"), Some("single quoted # kept"));
+        assert_eq!(env.get_variable("TIMEOUT"), Some("30"));
+        assert_eq!(env.get_variable("EMPTY"), Some(""));
+
+        std::fs::write(&path, "NOT A PAIR\n").unwrap();
+        assert!(env.import_dotenv(&path).is_err());
+    }
+
+    #[test]
+    fn test_clone_as() {
+        let mut prod = Environment::new("production".to_string());
+        prod.set_variable(
+            "BASE_URL".to_string(),
+            "https://api.example.com".to_string(),
+        );
+        prod.set_secret("API_KEY".to_string(), "secret123".to_string());
+        prod.set_variable("DEBUG".to_string(), "false".to_string());
+        prod.set_variable_enabled("DEBUG", false);
+        prod.activate();
+
+        let staging = prod.clone_as("staging".to_string());
+        assert_ne!(staging.id, prod.id);
+        assert_eq!(staging.name, "staging");
+        assert!(!staging.is_active);
+        assert!(staging.created_at >= prod.created_at);
+        assert_eq!(staging.variables, prod.variables);
+        assert!(staging.variables["API_KEY"].is_secret);
+        assert!(!staging.variables["DEBUG"].enabled);
+    }
 }
@@ -47,6 +47,16 @@ impl EnvironmentManager {
         self.environments.insert(id, environment);
     }
 
+    /// Copy an environment under a new name and register the copy
+    ///
+    /// Returns the new environment's ID, or `None` if `id` is unknown.
+    pub fn clone_environment(&mut self, id: &Uuid, new_name: String) -> Option<Uuid> {
+        let copy = self.environments.get(id)?.clone_as(new_name);
+        let new_id = copy.id;
+        self.add_environment(copy);
+        Some(new_id)
+    }
+
     /// Remove an environment
     pub fn remove_environment(&mut self, id: &Uuid) -> bool {
         if self.active_env_id == Some(*id) {
@@ -55,6 +65,30 @@ impl EnvironmentManager {
         self.environments.remove(id).is_some()
     }
 
+    /// Remove an environment by name
+    pub fn remove_environment_by_name(&mut self, name: &str) -> bool {
+        match self.get_environment_by_name(name).map(|env| env.id) {
+            Some(id) => self.remove_environment(&id),
+            None => false,
+        }
+    }
+
+    /// Remove every environment, in memory and on disk, returning how many were removed
+    ///
+    /// Environments that were never saved have no file, which is not an error.
+    pub fn clear_all(&mut self) -> crate::Result<usize> {
+        let ids: Vec<Uuid> = self.environments.keys().copied().collect();
+        for id in &ids {
+            match self.delete_environment_file(id) {
+                Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+                result => result?,
+            }
+            self.environments.remove(id);
+        }
+        self.active_env_id = None;
+        Ok(ids.len())
+    }
+
     /// Get an environment by ID
     pub fn get_environment(&self, id: &Uuid) -> Option<&Environment> {
         self.environments.get(id)
@@ -75,6 +109,17 @@ impl EnvironmentManager {
         self.environments.values().collect()
     }
 
+    /// Get environment names, sorted (e.g. for completion)
+    pub fn environment_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .environments
+            .values()
+            .map(|e| e.name.as_str())
+            .collect();
+        names.sort();
+        names
+    }
+
     /// Set active environment
     pub fn set_active(&mut self, id: &Uuid) -> bool {
         if self.environments.contains_key(id) {
@@ -148,10 +193,7 @@ impl EnvironmentManager {
             let path = self.storage_path.join(filename);
             env.save_to_file(&path)
         } else {
-            Err(crate::Error::InvalidCommand(format!(
-                "Environment {} not found",
-                id
-            )))
+            Err(crate::Error::EnvironmentNotFound(*id))
         }
     }
 
@@ -294,6 +336,47 @@ mod tests {
         assert_eq!(loaded.unwrap().name, "Test");
     }
 
+    #[test]
+    fn test_remove_environment_by_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = EnvironmentManager::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let staging = Environment::new("staging".to_string());
+        let staging_id = staging.id;
+        manager.add_environment(staging);
+        manager.add_environment(Environment::new("production".to_string()));
+        manager.set_active(&staging_id);
+
+        assert!(manager.remove_environment_by_name("staging"));
+        assert!(!manager.remove_environment_by_name("staging"));
+        assert_eq!(manager.environment_names(), vec!["production"]);
+        assert_eq!(manager.get_active_id(), None);
+    }
+
+    #[test]
+    fn test_clear_all() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = EnvironmentManager::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let dev = Environment::new("dev".to_string());
+        let dev_id = dev.id;
+        manager.add_environment(dev);
+        manager.add_environment(Environment::new("prod".to_string()));
+        manager.save_all().unwrap();
+        // Never saved, so it has no file
+        manager.add_environment(Environment::new("scratch".to_string()));
+        manager.set_active(&dev_id);
+
+        assert_eq!(manager.clear_all().unwrap(), 3);
+        assert!(manager.list_environments().is_empty());
+        assert_eq!(manager.get_active_id(), None);
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+        let mut reloaded = EnvironmentManager::new(temp_dir.path().to_path_buf()).unwrap();
+        reloaded.load_all().unwrap();
+        assert!(reloaded.list_environments().is_empty());
+    }
+
     #[test]
     fn test_get_by_name() {
         let temp_dir = TempDir::new().unwrap();
@@ -307,6 +390,16 @@ mod tests {
         assert_eq!(found.unwrap().name, "Production");
     }
 
+    #[test]
+    fn test_environment_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = EnvironmentManager::new(temp_dir.path().to_path_buf()).unwrap();
+        manager.add_environment(Environment::new("staging".to_string()));
+        manager.add_environment(Environment::new("dev".to_string()));
+
+        assert_eq!(manager.environment_names(), vec!["dev", "staging"]);
+    }
+
     #[test]
     fn test_create_quick_env() {
         let env = EnvironmentManager::create_quick_env("Dev", "https://dev.api.com");
@@ -349,4 +442,44 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), vec!["MISSING".to_string()]);
     }
+
+    #[test]
+    fn test_clone_environment() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = EnvironmentManager::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let prod = EnvironmentManager::create_quick_env("production", "https://api.example.com");
+        let prod_id = prod.id;
+        manager.add_environment(prod);
+        manager.set_active(&prod_id);
+
+        let staging_id = manager
+            .clone_environment(&prod_id, "staging".to_string())
+            .unwrap();
+        assert_ne!(staging_id, prod_id);
+        assert_eq!(manager.environment_names(), vec!["production", "staging"]);
+        assert_eq!(manager.get_active_id(), Some(prod_id));
+
+        let staging = manager.get_environment(&staging_id).unwrap();
+        assert_eq!(
+            staging.get_variable("BASE_URL"),
+            Some("https://api.example.com")
+        );
+
+        assert!(manager
+            .clone_environment(&Uuid::new_v4(), "nope".to_string())
+            .is_none());
+    }
+
+    #[test]
+    fn test_save_unknown_environment() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = EnvironmentManager::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let id = Uuid::new_v4();
+        match manager.save_environment(&id) {
+            Err(crate::Error::EnvironmentNotFound(missing)) => assert_eq!(missing, id),
+            other => panic!("expected EnvironmentNotFound, got {:?}", other),
+        }
+    }
 }
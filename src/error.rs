@@ -24,6 +24,7 @@ pub enum Error {
     Io(std::io::Error),
 
     /// Readline error
+    #[cfg(feature = "repl")]
     Readline(rustyline::error::ReadlineError),
 
     /// Invalid command
@@ -34,6 +35,36 @@ pub enum Error {
 
     /// Unsupported HTTP method
     UnsupportedMethod(String),
+
+    /// Malformed or unsupported request URL
+    InvalidUrl(String),
+
+    /// Authentication flow error
+    Auth(String),
+
+    /// Client or CA certificate could not be loaded
+    Certificate(String),
+
+    /// The invocation's --max-time budget ran out
+    DeadlineExceeded(std::time::Duration),
+
+    /// No environment with this id is loaded
+    EnvironmentNotFound(uuid::Uuid),
+
+    /// No session with this id is loaded
+    SessionNotFound(uuid::Uuid),
+
+    /// A pre- or post-request script failed to compile or run
+    ScriptExecution(String),
+
+    /// A request got no response within its timeout
+    Timeout(std::time::Duration),
+
+    /// Response assertions failed (the report summary)
+    AssertionFailed(String),
+
+    /// A request was redirected more times than allowed
+    TooManyRedirects(usize),
 }
 
 impl fmt::Display for Error {
@@ -44,10 +75,25 @@ impl fmt::Display for Error {
             Error::InvalidQuery(msg) => write!(f, "Invalid query parameter: {}", msg),
             Error::InvalidJson(e) => write!(f, "Invalid JSON: {}", e),
             Error::Io(e) => write!(f, "IO error: {}", e),
+            #[cfg(feature = "repl")]
             Error::Readline(e) => write!(f, "Readline error: {}", e),
             Error::InvalidCommand(cmd) => write!(f, "Invalid command: {}", cmd),
             Error::MissingArgument(arg) => write!(f, "Missing required argument: {}", arg),
             Error::UnsupportedMethod(method) => write!(f, "Unsupported HTTP method: {}", method),
+            Error::InvalidUrl(msg) => write!(f, "Invalid URL: {}", msg),
+            Error::Auth(msg) => write!(f, "Authentication failed: {}", msg),
+            Error::Certificate(msg) => write!(f, "Certificate error: {}", msg),
+            Error::DeadlineExceeded(budget) => {
+                write!(f, "Aborted: --max-time of {:?} exceeded", budget)
+            }
+            Error::EnvironmentNotFound(id) => write!(f, "Environment {} not found", id),
+            Error::SessionNotFound(id) => write!(f, "Session {} not found", id),
+            Error::ScriptExecution(msg) => write!(f, "Script execution error: {}", msg),
+            Error::Timeout(timeout) => write!(f, "Request timed out after {:?}", timeout),
+            Error::AssertionFailed(summary) => write!(f, "Assertions failed: {}", summary),
+            Error::TooManyRedirects(limit) => {
+                write!(f, "Too many redirects (more than {})", limit)
+            }
         }
     }
 }
@@ -72,6 +118,7 @@ impl From<std::io::Error> for Error {
     }
 }
 
+#[cfg(feature = "repl")]
 impl From<rustyline::error::ReadlineError> for Error {
     fn from(err: rustyline::error::ReadlineError) -> Self {
         Error::Readline(err)
@@ -0,0 +1,200 @@
+//! CSV export of history entries
+
+use crate::history::HistoryEntry;
+use clap::ValueEnum;
+use std::io::Write;
+
+/// A column in a CSV export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CsvField {
+    /// Entry ID
+    Id,
+
+    /// When the request was sent (RFC 3339, UTC)
+    Time,
+
+    /// Friendly name, if one was given
+    Name,
+
+    /// HTTP method
+    Method,
+
+    /// Request URL
+    Url,
+
+    /// Response status code; empty if the request failed or is pending
+    Status,
+
+    /// Response time in milliseconds
+    #[value(name = "duration_ms")]
+    DurationMs,
+
+    /// Response body size in bytes
+    Size,
+
+    /// Request body
+    #[value(name = "request_body")]
+    RequestBody,
+
+    /// Response body, as stored
+    #[value(name = "response_body")]
+    ResponseBody,
+
+    /// Transport error message
+    Error,
+}
+
+impl CsvField {
+    /// Columns used when none are chosen
+    pub const DEFAULT: &'static [CsvField] = &[
+        CsvField::Time,
+        CsvField::Method,
+        CsvField::Url,
+        CsvField::Status,
+        CsvField::DurationMs,
+        CsvField::Size,
+    ];
+
+    /// Column header
+    pub fn name(&self) -> &'static str {
+        match self {
+            CsvField::Id => "id",
+            CsvField::Time => "time",
+            CsvField::Name => "name",
+            CsvField::Method => "method",
+            CsvField::Url => "url",
+            CsvField::Status => "status",
+            CsvField::DurationMs => "duration_ms",
+            CsvField::Size => "size",
+            CsvField::RequestBody => "request_body",
+            CsvField::ResponseBody => "response_body",
+            CsvField::Error => "error",
+        }
+    }
+
+    /// This column's value for an entry
+    pub fn value(&self, entry: &HistoryEntry) -> String {
+        let response = entry.response.as_ref();
+        match self {
+            CsvField::Id => entry.id.to_string(),
+            CsvField::Time => entry.timestamp.to_rfc3339(),
+            CsvField::Name => entry.name().unwrap_or_default().to_string(),
+            CsvField::Method => entry.request.method.clone(),
+            CsvField::Url => entry.request.url.clone(),
+            CsvField::Status => response
+                .filter(|r| r.status_code != 0)
+                .map(|r| r.status_code.to_string())
+                .unwrap_or_default(),
+            CsvField::DurationMs => entry
+                .duration
+                .map(|d| d.as_millis().to_string())
+                .unwrap_or_default(),
+            CsvField::Size => response
+                .filter(|r| r.status_code != 0)
+                .map(|r| r.body_size.to_string())
+                .unwrap_or_default(),
+            CsvField::RequestBody => entry.request.body.clone().unwrap_or_default(),
+            CsvField::ResponseBody => response.and_then(|r| r.body.clone()).unwrap_or_default(),
+            CsvField::Error => response
+                .and_then(|r| r.error_message.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Write a header row and one row per entry
+pub fn write_csv<'a, W: Write>(
+    writer: &mut W,
+    entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    fields: &[CsvField],
+) -> crate::Result<usize> {
+    let header: Vec<String> = fields.iter().map(|f| f.name().to_string()).collect();
+    write_row(writer, &header)?;
+
+    let mut rows = 0;
+    for entry in entries {
+        let row: Vec<String> = fields.iter().map(|f| f.value(entry)).collect();
+        write_row(writer, &row)?;
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+fn write_row<W: Write>(writer: &mut W, cells: &[String]) -> crate::Result<()> {
+    let line: Vec<String> = cells.iter().map(|cell| escape(cell)).collect();
+    writer.write_all(line.join(",").as_bytes())?;
+    writer.write_all(b"\r\n")?;
+    Ok(())
+}
+
+/// Quote a cell if it contains a comma, quote, or line break (RFC 4180)
+pub fn escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{RequestLog, ResponseLog};
+    use std::time::Duration;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn test_write_csv_quotes_urls_and_bodies() {
+        let mut request = RequestLog::new(
+            "POST".to_string(),
+            "https://api.example.com/search?tags=a,b".to_string(),
+        );
+        request.body = Some(r#"{"q": "x"}"#.to_string());
+        let mut entry = HistoryEntry::new(request);
+        let mut response = ResponseLog::new(201, "Created".to_string());
+        response.set_body("ok".to_string());
+        entry.set_response(response, Duration::from_millis(42));
+
+        let mut out = Vec::new();
+        let rows = write_csv(
+            &mut out,
+            [&entry],
+            &[
+                CsvField::Method,
+                CsvField::Url,
+                CsvField::Status,
+                CsvField::DurationMs,
+                CsvField::Size,
+                CsvField::RequestBody,
+            ],
+        )
+        .unwrap();
+        assert_eq!(rows, 1);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "method,url,status,duration_ms,size,request_body\r\n\
+             POST,\"https://api.example.com/search?tags=a,b\",201,42,2,\"{\"\"q\"\": \"\"x\"\"}\"\r\n"
+        );
+    }
+
+    #[test]
+    fn test_failed_request_leaves_status_empty() {
+        let mut entry = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "http://localhost:1".to_string(),
+        ));
+        let mut response = ResponseLog::new(0, "Error".to_string());
+        response.set_error("connection refused".to_string());
+        entry.response = Some(response);
+
+        assert_eq!(CsvField::Status.value(&entry), "");
+        assert_eq!(CsvField::Error.value(&entry), "connection refused");
+    }
+}
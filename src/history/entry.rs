@@ -1,11 +1,27 @@
 //! History entry data structures
 
+use crate::history::NameTemplate;
+use crate::http::{
+    HttpMethod, HttpResponse, RequestBuilder, ResponseDiff, ResponseDiffer, ServerTimingEntry,
+};
+use crate::upload::FormData;
 use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 use uuid::Uuid;
 
+/// Metadata key holding a user-assigned entry name
+pub const NAME_KEY: &str = "name";
+
+/// Metadata key holding a free-form note
+pub const NOTE_KEY: &str = "note";
+
+/// Metadata key linking a replayed entry to the entry it was replayed from
+pub const REPLAY_OF_KEY: &str = "replay-of";
+
 /// A complete request/response entry in history
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HistoryEntry {
@@ -58,14 +74,18 @@ pub struct RequestLog {
     #[serde(default)]
     pub headers: HashMap<String, String>,
 
-    /// Query parameters
-    #[serde(default)]
-    pub query_params: HashMap<String, String>,
+    /// Query parameters, in order, repeated keys included
+    #[serde(default, deserialize_with = "deserialize_query_params")]
+    pub query_params: Vec<(String, String)>,
 
     /// Request body
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body: Option<String>,
 
+    /// Form fields (text values and file paths)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form_data: Option<FormData>,
+
     /// Request body size in bytes
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body_size: Option<usize>,
@@ -95,6 +115,10 @@ pub struct ResponseLog {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content_type: Option<String>,
 
+    /// Server-Timing metrics
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub server_timing: Vec<ServerTimingEntry>,
+
     /// Was the response successful (2xx)?
     pub is_success: bool,
 
@@ -141,6 +165,36 @@ impl HistoryEntry {
         self.response.as_ref().map(|r| r.is_error).unwrap_or(false)
     }
 
+    /// Rebuild the stored request so it can be sent again
+    ///
+    /// The stored URL already has its variables substituted, so it is used as is.
+    pub fn to_request_builder(&self) -> crate::Result<RequestBuilder> {
+        self.request.to_request_builder()
+    }
+
+    /// Rebuild the recorded response, unless the request failed or is pending
+    ///
+    /// Error statuses (4xx, 5xx) are responses too; only transport errors,
+    /// which carry an error message, have none.
+    pub fn recorded_response(&self) -> Option<HttpResponse> {
+        self.response
+            .as_ref()
+            .filter(|r| r.error_message.is_none())
+            .map(|r| r.to_response(self.duration.unwrap_or_default()))
+    }
+
+    /// Diff this entry's response against another's, ignoring noisy headers
+    ///
+    /// Returns `None` if either entry has no recorded response.
+    pub fn diff(&self, other: &HistoryEntry) -> Option<ResponseDiff> {
+        self.diff_with(other, &ResponseDiffer::new())
+    }
+
+    /// Diff this entry's response against another's with a configured differ
+    pub fn diff_with(&self, other: &HistoryEntry, differ: &ResponseDiffer) -> Option<ResponseDiff> {
+        Some(differ.diff(&self.recorded_response()?, &other.recorded_response()?))
+    }
+
     /// Add a tag
     pub fn add_tag(&mut self, tag: String) {
         if !self.tags.contains(&tag) {
@@ -148,11 +202,51 @@ impl HistoryEntry {
         }
     }
 
+    /// Remove a tag, returning whether it was present
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let before = self.tags.len();
+        self.tags.retain(|t| t != tag);
+        self.tags.len() != before
+    }
+
+    /// Check for a tag (case-insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
     /// Set metadata
     pub fn set_metadata(&mut self, key: String, value: String) {
         self.metadata.insert(key, value);
     }
 
+    /// Give this entry a friendly name
+    pub fn set_name(&mut self, name: String) {
+        self.metadata.insert(NAME_KEY.to_string(), name);
+    }
+
+    /// Get the user-assigned name, if any
+    pub fn name(&self) -> Option<&str> {
+        self.metadata.get(NAME_KEY).map(String::as_str)
+    }
+
+    /// Attach a note
+    pub fn set_note(&mut self, note: String) {
+        self.metadata.insert(NOTE_KEY.to_string(), note);
+    }
+
+    /// Get the note, if any
+    pub fn note(&self) -> Option<&str> {
+        self.metadata.get(NOTE_KEY).map(String::as_str)
+    }
+
+    /// Name to show in listings: the assigned name, or one rendered from the template
+    pub fn display_name(&self, template: &NameTemplate) -> String {
+        match self.name() {
+            Some(name) => name.to_string(),
+            None => template.render_log(&self.request),
+        }
+    }
+
     /// Get short summary
     pub fn summary(&self) -> String {
         let status = if let Some(resp) = &self.response {
@@ -173,8 +267,9 @@ impl RequestLog {
             url,
             original_url: None,
             headers: HashMap::new(),
-            query_params: HashMap::new(),
+            query_params: Vec::new(),
             body: None,
+            form_data: None,
             body_size: None,
         }
     }
@@ -185,6 +280,52 @@ impl RequestLog {
             self.body_size = Some(body.len());
         }
     }
+
+    /// Rebuild the request so it can be sent again
+    pub fn to_request_builder(&self) -> crate::Result<RequestBuilder> {
+        let mut builder = RequestBuilder::new(HttpMethod::parse(&self.method)?, self.url.clone());
+
+        let mut headers: Vec<_> = self.headers.iter().collect();
+        headers.sort();
+        for (key, value) in headers {
+            builder = builder.header(format!("{}:{}", key, value));
+        }
+
+        for (key, value) in &self.query_params {
+            builder = builder.query(format!("{}={}", key, value));
+        }
+
+        if let Some(body) = &self.body {
+            builder = builder.body(body.clone());
+        }
+
+        if let Some(form) = &self.form_data {
+            builder = builder.form(form.clone());
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Query parameters as written: a list of pairs, or a map in older entries
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WrittenQueryParams {
+    Pairs(Vec<(String, String)>),
+    Map(HashMap<String, String>),
+}
+
+fn deserialize_query_params<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<(String, String)>, D::Error> {
+    Ok(match WrittenQueryParams::deserialize(deserializer)? {
+        WrittenQueryParams::Pairs(pairs) => pairs,
+        WrittenQueryParams::Map(map) => {
+            let mut pairs: Vec<_> = map.into_iter().collect();
+            pairs.sort();
+            pairs
+        }
+    })
 }
 
 impl ResponseLog {
@@ -200,6 +341,7 @@ impl ResponseLog {
             body: None,
             body_size: 0,
             content_type: None,
+            server_timing: Vec::new(),
             is_success,
             is_error,
             error_message: None,
@@ -212,11 +354,57 @@ impl ResponseLog {
         self.body = Some(body);
     }
 
+    /// Cut the stored body to at most `max_bytes`, ending it with a marker
+    ///
+    /// `body_size` keeps the full size. Returns whether anything was cut.
+    pub fn truncate_body(&mut self, max_bytes: usize) -> bool {
+        let Some(body) = &mut self.body else {
+            return false;
+        };
+        if body.len() <= max_bytes {
+            return false;
+        }
+
+        let mut end = max_bytes;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str(&format!(
+            "\n… [truncated: {} of {} bytes stored]",
+            end, self.body_size
+        ));
+        true
+    }
+
     /// Set error
     pub fn set_error(&mut self, error: String) {
         self.is_error = true;
         self.error_message = Some(error);
     }
+
+    /// Rebuild a response for display; headers that are no longer valid are skipped
+    pub fn to_response(&self, duration: Duration) -> HttpResponse {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+
+        let body = self.body.clone().unwrap_or_default();
+        HttpResponse {
+            status: StatusCode::from_u16(self.status_code).unwrap_or(StatusCode::OK),
+            headers,
+            body_bytes: body.clone().into_bytes(),
+            body,
+            duration,
+            timing: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -281,6 +469,14 @@ mod tests {
 
         assert_eq!(entry.tags.len(), 2);
         assert!(entry.tags.contains(&"api".to_string()));
+        assert!(entry.has_tag("API"));
+
+        assert!(entry.remove_tag("api"));
+        assert!(!entry.remove_tag("api"));
+        assert_eq!(entry.tags, vec!["test"]);
+
+        entry.set_note("Flaky on staging".to_string());
+        assert_eq!(entry.note(), Some("Flaky on staging"));
     }
 
     #[test]
@@ -308,4 +504,212 @@ mod tests {
         assert_eq!(entry.id, deserialized.id);
         assert_eq!(entry.request.method, deserialized.request.method);
     }
+
+    #[test]
+    fn test_display_name() {
+        let mut entry = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://api.example.com/api/v2/users?page=3".to_string(),
+        ));
+        let template = NameTemplate::default();
+        assert_eq!(entry.display_name(&template), "GET /api/v2/users ?page=3");
+
+        entry.set_name("Third page of users".to_string());
+        assert_eq!(entry.name(), Some("Third page of users"));
+        assert_eq!(entry.display_name(&template), "Third page of users");
+    }
+
+    #[test]
+    fn test_request_log_to_request_builder() {
+        let mut log = RequestLog::new("POST".to_string(), "https://example.com/users".to_string());
+        log.headers
+            .insert("Content-Type".to_string(), "application/json".to_string());
+        log.query_params
+            .push(("notify".to_string(), "true".to_string()));
+        log.body = Some(r#"{"name":"Ada"}"#.to_string());
+
+        let request = log.to_request_builder().unwrap();
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.url, "https://example.com/users");
+        assert_eq!(request.headers, vec!["Content-Type:application/json"]);
+        assert_eq!(request.query_params, vec!["notify=true"]);
+        assert_eq!(request.body.as_deref(), Some(r#"{"name":"Ada"}"#));
+
+        let bad = RequestLog::new("BREW".to_string(), "https://example.com".to_string());
+        assert!(bad.to_request_builder().is_err());
+    }
+
+    #[test]
+    fn test_logged_post_replays_as_equivalent_request() {
+        let original = RequestBuilder::new(
+            HttpMethod::Post,
+            "https://api.example.com/users".to_string(),
+        )
+        .header("Content-Type: application/json".to_string())
+        .header("X-Trace: abc".to_string())
+        .query("notify=true".to_string())
+        .body(r#"{"name":"Ada"}"#.to_string());
+
+        let mut logger = crate::history::HistoryLogger::new();
+        let id = logger.log_request(&original);
+        let replayed = logger.get_entry(&id).unwrap().to_request_builder().unwrap();
+
+        assert_eq!(replayed.method, original.method);
+        assert_eq!(replayed.url, original.url);
+        assert_eq!(
+            replayed.parse_headers().unwrap(),
+            original.parse_headers().unwrap()
+        );
+        assert_eq!(
+            replayed.parse_query_params().unwrap(),
+            original.parse_query_params().unwrap()
+        );
+        assert_eq!(replayed.body, original.body);
+    }
+
+    #[test]
+    fn test_logged_form_post_replays_its_fields() {
+        let form = FormData::from_pairs(&["a=1".to_string(), "b=2".to_string()]).unwrap();
+        let original = RequestBuilder::new(
+            HttpMethod::Post,
+            "https://api.example.com/login".to_string(),
+        )
+        .form(form.clone());
+
+        let mut logger = crate::history::HistoryLogger::new();
+        let id = logger.log_request(&original);
+        let json = serde_json::to_string(logger.get_entry(&id).unwrap()).unwrap();
+        let entry: HistoryEntry = serde_json::from_str(&json).unwrap();
+        let replayed = entry.to_request_builder().unwrap();
+
+        assert_eq!(replayed.get_form_data(), Some(&form));
+        assert_eq!(replayed.body, None);
+    }
+
+    #[test]
+    fn test_logged_repeated_query_keys_survive_replay() {
+        let original =
+            RequestBuilder::new(HttpMethod::Get, "https://api.example.com/posts".to_string())
+                .query("tag=a".to_string())
+                .query("tag=b".to_string());
+
+        let mut logger = crate::history::HistoryLogger::new();
+        let id = logger.log_request(&original);
+        let json = serde_json::to_string(logger.get_entry(&id).unwrap()).unwrap();
+        let entry: HistoryEntry = serde_json::from_str(&json).unwrap();
+        let replayed = entry.to_request_builder().unwrap();
+
+        assert_eq!(replayed.query_params, vec!["tag=a", "tag=b"]);
+    }
+
+    #[test]
+    fn test_request_log_reads_query_params_map() {
+        let log: RequestLog = serde_json::from_str(
+            r#"{"method":"GET","url":"https://api.example.com","query_params":{"page":"2","limit":"10"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            log.query_params,
+            vec![
+                ("limit".to_string(), "10".to_string()),
+                ("page".to_string(), "2".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_request_log_without_form_data_deserializes() {
+        let log: RequestLog =
+            serde_json::from_str(r#"{"method":"POST","url":"https://api.example.com"}"#).unwrap();
+        assert_eq!(log.form_data, None);
+    }
+
+    fn entry_with_response(status: u16, headers: &[(&str, &str)], body: &str) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://api.example.com/users/1".to_string(),
+        ));
+        let mut response = ResponseLog::new(status, String::new());
+        for (name, value) in headers {
+            response.headers.insert(name.to_string(), value.to_string());
+        }
+        response.set_body(body.to_string());
+        entry.set_response(response, Duration::from_millis(20));
+        entry
+    }
+
+    #[test]
+    fn test_diff_status_change() {
+        let old = entry_with_response(200, &[("Date", "Mon, 01 Jan 2024 00:00:00 GMT")], "ok");
+        let new = entry_with_response(503, &[("Date", "Tue, 02 Jan 2024 00:00:00 GMT")], "ok");
+
+        let diff = old.diff(&new).unwrap();
+        assert_eq!(diff.status, Some((200, 503)));
+        assert!(diff.headers.is_empty());
+        assert_eq!(diff.body, crate::http::BodyDiff::Same);
+
+        // Volatile headers are compared only when asked for
+        let diff = old
+            .diff_with(&new, &ResponseDiffer::new().with_all_headers())
+            .unwrap();
+        assert_eq!(diff.headers.len(), 1);
+
+        let pending = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://api.example.com".to_string(),
+        ));
+        assert!(old.diff(&pending).is_none());
+    }
+
+    #[test]
+    fn test_diff_single_json_field() {
+        let old = entry_with_response(
+            200,
+            &[("Request-Id", "a1")],
+            r#"{"id":1,"name":"Ada","role":"admin"}"#,
+        );
+        let new = entry_with_response(
+            200,
+            &[("Request-Id", "b2")],
+            r#"{"id":1,"name":"Ada","role":"viewer"}"#,
+        );
+
+        let diff = old.diff(&new).unwrap();
+        assert!(diff.status.is_none());
+        assert!(diff.headers.is_empty());
+        let crate::http::BodyDiff::Json(changes) = &diff.body else {
+            panic!("expected a JSON diff");
+        };
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "$.role");
+        assert!(diff.render().contains(r#"$.role: "admin" -> "viewer""#));
+    }
+
+    #[test]
+    fn test_response_log_to_response() {
+        let mut log = ResponseLog::new(404, "Not Found".to_string());
+        log.headers
+            .insert("content-type".to_string(), "text/plain".to_string());
+        log.set_body("missing".to_string());
+
+        let response = log.to_response(Duration::from_millis(12));
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(response.headers["content-type"], "text/plain");
+        assert_eq!(response.body, "missing");
+        assert_eq!(response.body_bytes, b"missing");
+    }
+
+    #[test]
+    fn test_truncate_response_body() {
+        let mut response = ResponseLog::new(200, "OK".to_string());
+        response.set_body("héllo world".to_string());
+
+        assert!(!response.truncate_body(100));
+        assert!(response.truncate_body(2));
+        assert_eq!(
+            response.body.as_deref(),
+            Some("h\n… [truncated: 1 of 12 bytes stored]")
+        );
+        assert_eq!(response.body_size, 12);
+    }
 }
@@ -0,0 +1,209 @@
+//! Filters for browsing stored history
+
+use crate::history::HistoryEntry;
+use crate::http::normalize_url;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Criteria an entry must meet to be listed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryFilter {
+    /// HTTP method (case-insensitive)
+    pub method: Option<String>,
+
+    /// Response status code
+    pub status: Option<u16>,
+
+    /// Substring of the URL
+    pub url_contains: Option<String>,
+
+    /// Only entries sent at or after this time
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only entries sent before this time
+    pub until: Option<DateTime<Utc>>,
+
+    /// Only entries sent from this collection
+    pub collection_id: Option<Uuid>,
+
+    /// Only transport errors and non-2xx responses
+    pub failed: bool,
+
+    /// Only entries with this tag (case-insensitive)
+    pub tag: Option<String>,
+}
+
+impl HistoryFilter {
+    /// Create a filter that matches every entry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match this method
+    pub fn with_method(mut self, method: String) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// Only match this response status
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only match URLs containing this text
+    pub fn with_url_contains(mut self, pattern: String) -> Self {
+        self.url_contains = Some(pattern);
+        self
+    }
+
+    /// Only match entries sent at or after this time
+    pub fn with_since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Only match entries sent before this time
+    pub fn with_until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Only match entries sent from this collection
+    pub fn with_collection_id(mut self, id: Uuid) -> Self {
+        self.collection_id = Some(id);
+        self
+    }
+
+    /// Only match transport errors and non-2xx responses
+    pub fn with_failed(mut self) -> Self {
+        self.failed = true;
+        self
+    }
+
+    /// Only match entries with this tag
+    pub fn with_tag(mut self, tag: String) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Check whether an entry meets every criterion
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.method
+            .as_ref()
+            .is_none_or(|m| entry.request.method.eq_ignore_ascii_case(m))
+            && self.status.is_none_or(|status| {
+                entry
+                    .response
+                    .as_ref()
+                    .is_some_and(|r| r.status_code == status)
+            })
+            && self
+                .url_contains
+                .as_ref()
+                .is_none_or(|pattern| url_matches(&entry.request.url, pattern))
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp < until)
+            && self
+                .collection_id
+                .is_none_or(|id| entry.collection_id == Some(id))
+            && (!self.failed || (entry.response.is_some() && !entry.is_successful()))
+            && self.tag.as_ref().is_none_or(|tag| entry.has_tag(tag))
+    }
+
+    /// Keep the matching entries, in order
+    pub fn apply<'a>(
+        &self,
+        entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    ) -> Vec<&'a HistoryEntry> {
+        entries.into_iter().filter(|e| self.matches(e)).collect()
+    }
+}
+
+/// Check whether a URL contains a pattern, as written or once normalized
+///
+/// The normalized form lets `example.com:8080` find `http://EXAMPLE.com:8080/`
+/// and `[::1]` find IPv6 loopback requests.
+pub(crate) fn url_matches(url: &str, pattern: &str) -> bool {
+    url.contains(pattern)
+        || normalize_url(url).is_ok_and(|url| url.contains(&pattern.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{RequestLog, ResponseLog};
+    use std::time::Duration;
+
+    fn entry(method: &str, url: &str, status: Option<u16>) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(RequestLog::new(method.to_string(), url.to_string()));
+        if let Some(status) = status {
+            entry.set_response(
+                ResponseLog::new(status, String::new()),
+                Duration::from_millis(10),
+            );
+        }
+        entry
+    }
+
+    #[test]
+    fn test_history_filter() {
+        let mut old = entry("GET", "https://api.example.com/users", Some(200));
+        old.timestamp = Utc::now() - chrono::Duration::days(2);
+        let entries = [
+            old,
+            entry("POST", "https://api.example.com/users", Some(500)),
+            entry("GET", "http://[::1]:8080/health", None),
+        ];
+
+        assert_eq!(HistoryFilter::new().apply(&entries).len(), 3);
+        assert_eq!(
+            HistoryFilter::new()
+                .with_method("get".to_string())
+                .apply(&entries)
+                .len(),
+            2
+        );
+        assert_eq!(
+            HistoryFilter::new().with_status(500).apply(&entries)[0]
+                .request
+                .method,
+            "POST"
+        );
+        assert_eq!(
+            HistoryFilter::new()
+                .with_url_contains("API.EXAMPLE.COM".to_string())
+                .apply(&entries)
+                .len(),
+            2
+        );
+        assert_eq!(
+            HistoryFilter::new()
+                .with_since(Utc::now() - chrono::Duration::hours(2))
+                .apply(&entries)
+                .len(),
+            2
+        );
+        let mut tagged = entries.clone();
+        tagged[2].add_tag("Regression".to_string());
+        let found = HistoryFilter::new()
+            .with_tag("regression".to_string())
+            .apply(&tagged);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, tagged[2].id);
+
+        // Pending entries aren't failures
+        assert_eq!(
+            HistoryFilter::new().with_failed().apply(&entries)[0]
+                .request
+                .method,
+            "POST"
+        );
+        assert_eq!(HistoryFilter::new().with_failed().apply(&entries).len(), 1);
+        assert!(HistoryFilter::new()
+            .with_method("GET".to_string())
+            .with_status(500)
+            .apply(&entries)
+            .is_empty());
+    }
+}
@@ -0,0 +1,311 @@
+//! Append-only JSON Lines history log
+//!
+//! Every save appends the entry as one line of `history.jsonl`; deletes append a
+//! tombstone line. An index from entry ID to the offset of its latest line is
+//! built on first access, so lookups seek straight to one line and listing
+//! only parses live entries.
+
+use crate::history::HistoryEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// File name of the log inside the history directory
+pub const FILE_NAME: &str = "history.jsonl";
+
+/// Marks an entry as deleted
+#[derive(Serialize)]
+struct Tombstone {
+    deleted: Uuid,
+}
+
+/// The fields the index needs from a line; the rest of it is skipped
+#[derive(Deserialize)]
+struct RecordKey {
+    id: Option<Uuid>,
+    deleted: Option<Uuid>,
+}
+
+/// A JSON Lines history file with an in-memory offset index
+pub struct JsonLinesLog {
+    path: PathBuf,
+    index: Mutex<Option<HashMap<Uuid, u64>>>,
+}
+
+impl JsonLinesLog {
+    /// Open the log in a history directory (the file is created on first save)
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            path: dir.join(FILE_NAME),
+            index: Mutex::new(None),
+        }
+    }
+
+    /// Append entries, replacing earlier versions with the same ID
+    pub fn save(&self, entries: &[&HistoryEntry]) -> crate::Result<()> {
+        self.with_index(|index| {
+            let mut lines = String::new();
+            let mut offsets = Vec::with_capacity(entries.len());
+            for entry in entries {
+                offsets.push((entry.id, lines.len() as u64));
+                lines.push_str(&serde_json::to_string(entry)?);
+                lines.push('\n');
+            }
+
+            let start = self.append(&lines)?;
+            index.extend(offsets.into_iter().map(|(id, at)| (id, start + at)));
+            Ok(())
+        })
+    }
+
+    /// Load the latest version of an entry
+    pub fn load(&self, id: &Uuid) -> crate::Result<HistoryEntry> {
+        let offset = self
+            .with_index(|index| Ok(index.get(id).copied()))?
+            .ok_or_else(|| not_found(id))?;
+
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    /// Load every live entry, in the order they were first written
+    pub fn load_all(&self) -> crate::Result<Vec<HistoryEntry>> {
+        let mut live: Vec<u64> = self.with_index(|index| Ok(index.values().copied().collect()))?;
+        if live.is_empty() {
+            return Ok(Vec::new());
+        }
+        live.sort_unstable();
+
+        let content = std::fs::read_to_string(&self.path)?;
+        let mut entries = Vec::with_capacity(live.len());
+        for offset in live {
+            let line = content
+                .get(offset as usize..)
+                .and_then(|rest| rest.lines().next())
+                .unwrap_or_default();
+            if let Ok(entry) = serde_json::from_str(line) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// IDs of all live entries
+    pub fn ids(&self) -> crate::Result<Vec<Uuid>> {
+        self.with_index(|index| Ok(index.keys().copied().collect()))
+    }
+
+    /// Number of live entries
+    pub fn count(&self) -> crate::Result<usize> {
+        self.with_index(|index| Ok(index.len()))
+    }
+
+    /// Delete an entry by appending a tombstone
+    pub fn delete(&self, id: &Uuid) -> crate::Result<()> {
+        self.with_index(|index| {
+            if !index.contains_key(id) {
+                return Err(not_found(id));
+            }
+            let mut line = serde_json::to_string(&Tombstone { deleted: *id })?;
+            line.push('\n');
+            self.append(&line)?;
+            index.remove(id);
+            Ok(())
+        })
+    }
+
+    /// Rewrite the file with only the entries to keep, dropping old versions and tombstones
+    ///
+    /// Returns how many entries were removed.
+    pub fn compact(&self, keep: impl Fn(&HistoryEntry) -> bool) -> crate::Result<usize> {
+        let entries = self.load_all()?;
+        let total = entries.len();
+        let kept: Vec<&HistoryEntry> = entries.iter().filter(|e| keep(e)).collect();
+
+        let mut content = String::new();
+        for entry in &kept {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+
+        // Write beside the log and rename so a crash never leaves a partial file
+        let tmp = self.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, &self.path)?;
+        *self.lock() = None;
+
+        Ok(total - kept.len())
+    }
+
+    /// Remove the log file, returning how many entries it held
+    pub fn clear(&self) -> crate::Result<usize> {
+        let count = self.count()?;
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        *self.lock() = Some(HashMap::new());
+        Ok(count)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<HashMap<Uuid, u64>>> {
+        self.index.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run a closure against the index, building it from the file first if needed
+    fn with_index<T>(
+        &self,
+        f: impl FnOnce(&mut HashMap<Uuid, u64>) -> crate::Result<T>,
+    ) -> crate::Result<T> {
+        let mut guard = self.lock();
+        if guard.is_none() {
+            *guard = Some(build_index(&self.path)?);
+        }
+        f(guard.as_mut().expect("index was just built"))
+    }
+
+    /// Append text to the log, returning the offset it starts at
+    fn append(&self, text: &str) -> crate::Result<u64> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut offset = file.metadata()?.len();
+
+        // Never glue a new line onto one cut short by an interrupted write
+        if offset > 0 {
+            let mut last = [0u8];
+            file.seek(SeekFrom::Start(offset - 1))?;
+            std::io::Read::read_exact(&mut file, &mut last)?;
+            if last[0] != b'\n' {
+                file.write_all(b"\n")?;
+                offset += 1;
+            }
+        }
+
+        file.write_all(text.as_bytes())?;
+        Ok(offset)
+    }
+}
+
+/// Map each live entry ID to the offset of its latest line
+///
+/// Unreadable lines (e.g. from an interrupted write) are skipped.
+fn build_index(path: &Path) -> crate::Result<HashMap<Uuid, u64>> {
+    let mut index = HashMap::new();
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(index),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    let mut offset = 0;
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            break;
+        }
+        match serde_json::from_str::<RecordKey>(&line) {
+            Ok(RecordKey {
+                deleted: Some(id), ..
+            }) => {
+                index.remove(&id);
+            }
+            Ok(RecordKey { id: Some(id), .. }) if line.ends_with('\n') => {
+                index.insert(id, offset);
+            }
+            _ => {}
+        }
+        offset += read as u64;
+    }
+    Ok(index)
+}
+
+fn not_found(id: &Uuid) -> crate::Error {
+    crate::Error::Io(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("History entry {} not found", id),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::RequestLog;
+    use tempfile::TempDir;
+
+    fn entry(url: &str) -> HistoryEntry {
+        HistoryEntry::new(RequestLog::new("GET".to_string(), url.to_string()))
+    }
+
+    #[test]
+    fn test_resave_replaces_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = JsonLinesLog::new(temp_dir.path());
+
+        let mut first = entry("https://example.com/1");
+        log.save(&[&first, &entry("https://example.com/2")])
+            .unwrap();
+        first.add_tag("smoke".to_string());
+        log.save(&[&first]).unwrap();
+
+        // A fresh index sees only the latest version
+        let reopened = JsonLinesLog::new(temp_dir.path());
+        assert_eq!(reopened.count().unwrap(), 2);
+        assert_eq!(reopened.load(&first.id).unwrap().tags, vec!["smoke"]);
+        assert_eq!(reopened.load_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_tombstones_and_compaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = JsonLinesLog::new(temp_dir.path());
+
+        let keep = entry("https://example.com/keep");
+        let drop = entry("https://example.com/drop");
+        let gone = entry("https://example.com/gone");
+        log.save(&[&keep, &drop, &gone]).unwrap();
+
+        log.delete(&gone.id).unwrap();
+        assert!(log.delete(&gone.id).is_err());
+        assert!(JsonLinesLog::new(temp_dir.path()).load(&gone.id).is_err());
+
+        let removed = log.compact(|e| e.id != drop.id).unwrap();
+        assert_eq!(removed, 1);
+        let content = std::fs::read_to_string(temp_dir.path().join(FILE_NAME)).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        assert_eq!(log.load(&keep.id).unwrap().request.url, keep.request.url);
+    }
+
+    #[test]
+    fn test_skips_truncated_last_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = JsonLinesLog::new(temp_dir.path());
+        let saved = entry("https://example.com");
+        log.save(&[&saved]).unwrap();
+
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(temp_dir.path().join(FILE_NAME))
+            .unwrap();
+        file.write_all(br#"{"id":"6f1c"#).unwrap();
+
+        let reopened = JsonLinesLog::new(temp_dir.path());
+        assert_eq!(reopened.ids().unwrap(), vec![saved.id]);
+
+        // The next save starts on a fresh line
+        let next = entry("https://example.com/next");
+        reopened.save(&[&next]).unwrap();
+        assert_eq!(JsonLinesLog::new(temp_dir.path()).count().unwrap(), 2);
+    }
+}
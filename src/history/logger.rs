@@ -1,7 +1,12 @@
 //! History logger for capturing requests and responses
 
-use crate::history::{HistoryEntry, RequestLog, ResponseLog};
+use crate::history::filter::url_matches;
+use crate::history::{
+    GroupBy, HistoryEntry, HistoryFilter, HistoryStats, HistoryStorage, HistorySummary, RequestLog,
+    ResponseLog,
+};
 use crate::http::{HttpResponse, RequestBuilder};
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -11,6 +16,11 @@ pub struct HistoryLogger {
     max_entries: usize,
     current_collection_id: Option<Uuid>,
     current_environment_id: Option<Uuid>,
+    current_tags: Vec<String>,
+    current_metadata: HashMap<String, String>,
+    storage: Option<HistoryStorage>,
+    /// Entries whose latest state isn't on disk yet
+    pending: Vec<Uuid>,
 }
 
 impl HistoryLogger {
@@ -21,19 +31,35 @@ impl HistoryLogger {
             max_entries: 1000, // Default max
             current_collection_id: None,
             current_environment_id: None,
+            current_tags: Vec::new(),
+            current_metadata: HashMap::new(),
+            storage: None,
+            pending: Vec::new(),
         }
     }
 
     /// Create with custom max entries
     pub fn with_max_entries(max_entries: usize) -> Self {
         Self {
-            entries: Vec::new(),
             max_entries,
-            current_collection_id: None,
-            current_environment_id: None,
+            ..Self::new()
         }
     }
 
+    /// Write each entry to `storage` once its response or error is logged
+    ///
+    /// `max_entries` only caps the entries kept in memory; every entry stays
+    /// on disk.
+    pub fn with_storage(mut self, storage: HistoryStorage) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Get the storage entries are written to, if any
+    pub fn storage(&self) -> Option<&HistoryStorage> {
+        self.storage.as_ref()
+    }
+
     /// Set current collection ID
     pub fn set_collection_id(&mut self, id: Option<Uuid>) {
         self.current_collection_id = id;
@@ -44,6 +70,16 @@ impl HistoryLogger {
         self.current_environment_id = id;
     }
 
+    /// Tag every entry logged from now on
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        self.current_tags = tags;
+    }
+
+    /// Add a metadata key to every entry logged from now on
+    pub fn set_metadata(&mut self, key: String, value: String) {
+        self.current_metadata.insert(key, value);
+    }
+
     /// Log a request (before sending)
     pub fn log_request(&mut self, request: &RequestBuilder) -> Uuid {
         let mut request_log =
@@ -63,7 +99,7 @@ impl HistoryLogger {
             if let Some((key, value)) = param.split_once('=') {
                 request_log
                     .query_params
-                    .insert(key.to_string(), value.to_string());
+                    .push((key.to_string(), value.to_string()));
             }
         }
 
@@ -72,19 +108,34 @@ impl HistoryLogger {
             request_log.body = Some(body.clone());
             request_log.calculate_body_size();
         }
+        request_log.form_data = request.form_data.clone();
 
         let mut entry = HistoryEntry::new(request_log);
         entry.collection_id = self.current_collection_id;
         entry.environment_id = self.current_environment_id;
+        for tag in &self.current_tags {
+            entry.add_tag(tag.clone());
+        }
+        entry.metadata.extend(self.current_metadata.clone());
 
         let id = entry.id;
 
         // Add to history
         self.entries.push(entry);
+        if self.storage.is_some() {
+            self.pending.push(id);
+        }
 
-        // Trim if exceeding max
+        // Trim if exceeding max, writing out a dropped entry that isn't on disk yet
         if self.entries.len() > self.max_entries {
-            self.entries.remove(0);
+            let dropped = self.entries.remove(0);
+            if let Some(at) = self.pending.iter().position(|id| *id == dropped.id) {
+                self.pending.remove(at);
+                if let Some(storage) = &self.storage {
+                    // Best-effort: the entry has no response yet and can't be retried
+                    storage.save_entry(&dropped).ok();
+                }
+            }
         }
 
         id
@@ -120,8 +171,11 @@ impl HistoryLogger {
                 response_log.content_type = Some(ct.to_str().unwrap_or("").to_string());
             }
 
+            response_log.server_timing = response.server_timing();
+
             entry.set_response(response_log, response.duration);
         }
+        self.persist(entry_id);
     }
 
     /// Log an error
@@ -131,6 +185,59 @@ impl HistoryLogger {
             response_log.set_error(error);
             entry.response = Some(response_log);
         }
+        self.persist(entry_id);
+    }
+
+    /// Write a completed entry to storage, leaving it pending if that fails
+    fn persist(&mut self, entry_id: &Uuid) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        let Some(entry) = self.entries.iter().find(|e| e.id == *entry_id) else {
+            return;
+        };
+        if storage.save_entry(entry).is_ok() {
+            self.pending.retain(|id| id != entry_id);
+        }
+    }
+
+    /// Write every pending entry to storage
+    ///
+    /// Pending entries are requests still waiting for a response and entries
+    /// that failed to save. Returns how many were written.
+    pub fn flush(&mut self) -> crate::Result<usize> {
+        let Some(storage) = &self.storage else {
+            return Ok(0);
+        };
+
+        let mut written = 0;
+        while let Some(id) = self.pending.first().copied() {
+            if let Some(entry) = self.entries.iter().find(|e| e.id == id) {
+                storage.save_entry(entry)?;
+                written += 1;
+            }
+            self.pending.remove(0);
+        }
+        Ok(written)
+    }
+
+    /// Number of entries not yet written to storage
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Log a request together with the outcome of sending it
+    pub fn record(
+        &mut self,
+        request: &RequestBuilder,
+        result: &crate::Result<HttpResponse>,
+    ) -> Uuid {
+        let id = self.log_request(request);
+        match result {
+            Ok(response) => self.log_response(&id, response),
+            Err(e) => self.log_error(&id, e.to_string()),
+        }
+        id
     }
 
     /// Get all entries
@@ -179,17 +286,50 @@ impl HistoryLogger {
         self.entries.iter().filter(|e| e.has_error()).collect()
     }
 
+    /// Get entries sent from `start` (inclusive) to `end` (exclusive)
+    pub fn filter_by_time_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<&HistoryEntry> {
+        HistoryFilter::new()
+            .with_since(start)
+            .with_until(end)
+            .apply(&self.entries)
+    }
+
+    /// Get entries sent from a collection
+    pub fn filter_by_collection(&self, id: &Uuid) -> Vec<&HistoryEntry> {
+        HistoryFilter::new()
+            .with_collection_id(*id)
+            .apply(&self.entries)
+    }
+
     /// Search entries by URL pattern
+    ///
+    /// Also matches the normalized URL, so `example.com:8080` finds
+    /// `http://EXAMPLE.com:8080/` and `[::1]` finds IPv6 loopback requests.
     pub fn search_by_url(&self, pattern: &str) -> Vec<&HistoryEntry> {
         self.entries
             .iter()
-            .filter(|e| e.request.url.contains(pattern))
+            .filter(|e| url_matches(&e.request.url, pattern))
             .collect()
     }
 
-    /// Clear all entries
+    /// Compute latency and success statistics for the logged entries
+    pub fn stats(&self, group_by: GroupBy) -> HistoryStats {
+        HistoryStats::compute(&self.entries, group_by)
+    }
+
+    /// Summarize the logged entries: totals, status codes, latency and busiest hosts
+    pub fn summary(&self) -> HistorySummary {
+        HistorySummary::compute(&self.entries)
+    }
+
+    /// Clear all entries kept in memory (entries on disk are kept)
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.pending.clear();
     }
 
     /// Get total number of entries
@@ -234,6 +374,58 @@ mod tests {
         assert_eq!(entry.unwrap().request.method, "GET");
     }
 
+    #[test]
+    fn test_tags_and_metadata_applied() {
+        let mut logger = HistoryLogger::new();
+        logger.set_tags(vec![
+            "ci".to_string(),
+            "ci".to_string(),
+            "smoke".to_string(),
+        ]);
+        logger.set_metadata("build".to_string(), "42".to_string());
+
+        let id = logger.log_request(&RequestBuilder::new(
+            HttpMethod::Get,
+            "https://example.com".to_string(),
+        ));
+        let entry = logger.get_entry(&id).unwrap();
+        assert_eq!(entry.tags, vec!["ci", "smoke"]);
+        assert_eq!(entry.metadata["build"], "42");
+    }
+
+    #[test]
+    fn test_record() {
+        let mut logger = HistoryLogger::new();
+        let request = RequestBuilder::new(HttpMethod::Get, "http://127.0.0.1:1/".to_string());
+
+        let id = logger.record(
+            &request,
+            &Err(crate::Error::InvalidUrl("unreachable".to_string())),
+        );
+        let entry = logger.get_entry(&id).unwrap();
+        assert!(entry.has_error());
+        assert!(entry.response.as_ref().unwrap().error_message.is_some());
+    }
+
+    #[test]
+    fn test_stats_by_method() {
+        let mut logger = HistoryLogger::new();
+        for method in [HttpMethod::Get, HttpMethod::Get, HttpMethod::Post] {
+            logger.log_request(&RequestBuilder::new(
+                method,
+                "https://api.example.com".to_string(),
+            ));
+        }
+
+        let stats = logger.stats(GroupBy::Method);
+        let counts: Vec<(&str, usize)> = stats
+            .groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.count))
+            .collect();
+        assert_eq!(counts, vec![("GET", 2), ("POST", 1)]);
+    }
+
     #[test]
     fn test_max_entries() {
         let mut logger = HistoryLogger::with_max_entries(2);
@@ -276,6 +468,37 @@ mod tests {
         assert_eq!(post_requests.len(), 1);
     }
 
+    #[test]
+    fn test_filter_by_time_range_and_collection() {
+        let collection = Uuid::new_v4();
+        let mut logger = HistoryLogger::new();
+        let old = logger.log_request(&RequestBuilder::new(
+            HttpMethod::Get,
+            "https://example.com/old".to_string(),
+        ));
+        logger.set_collection_id(Some(collection));
+        logger.log_request(&RequestBuilder::new(
+            HttpMethod::Get,
+            "https://example.com/new".to_string(),
+        ));
+        logger.entries[0].timestamp = Utc::now() - chrono::Duration::days(2);
+
+        let now = Utc::now();
+        let recent = logger.filter_by_time_range(now - chrono::Duration::hours(1), now);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].request.url, "https://example.com/new");
+        let older = logger.filter_by_time_range(
+            now - chrono::Duration::days(3),
+            now - chrono::Duration::days(1),
+        );
+        assert_eq!(older[0].id, old);
+
+        let in_collection = logger.filter_by_collection(&collection);
+        assert_eq!(in_collection.len(), 1);
+        assert_eq!(in_collection[0].request.url, "https://example.com/new");
+        assert_eq!(logger.summary().total, 2);
+    }
+
     #[test]
     fn test_search_by_url() {
         let mut logger = HistoryLogger::new();
@@ -296,6 +519,85 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[test]
+    fn test_search_by_url_normalized() {
+        let mut logger = HistoryLogger::new();
+
+        logger.log_request(&RequestBuilder::new(
+            HttpMethod::Get,
+            "http://[::1]:8080/health".to_string(),
+        ));
+        logger.log_request(&RequestBuilder::new(
+            HttpMethod::Get,
+            "HTTPS://API.Example.com:443/users".to_string(),
+        ));
+
+        assert_eq!(logger.search_by_url("[::1]:8080").len(), 1);
+        assert_eq!(
+            logger.search_by_url("https://api.example.com/users").len(),
+            1
+        );
+        assert_eq!(logger.search_by_url("API.EXAMPLE.COM").len(), 1);
+    }
+
+    fn response(body: &str) -> HttpResponse {
+        use reqwest::{header::HeaderMap, StatusCode};
+        HttpResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: body.to_string(),
+            body_bytes: body.as_bytes().to_vec(),
+            duration: std::time::Duration::from_millis(10),
+            timing: None,
+        }
+    }
+
+    #[test]
+    fn test_with_storage_writes_completed_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = HistoryStorage::new(dir.path().to_path_buf()).unwrap();
+        let mut logger = HistoryLogger::with_max_entries(1).with_storage(storage);
+
+        let request = RequestBuilder::new(HttpMethod::Get, "https://example.com/1".to_string());
+        let first = logger.log_request(&request);
+        assert_eq!(logger.pending_count(), 1);
+        logger.log_response(&first, &response("one"));
+        assert_eq!(logger.pending_count(), 0);
+
+        let second = logger.record(
+            &RequestBuilder::new(HttpMethod::Post, "https://example.com/2".to_string()),
+            &Err(crate::Error::InvalidUrl("unreachable".to_string())),
+        );
+
+        // Only the newest entry stays in memory, but both are on disk
+        assert_eq!(logger.count(), 1);
+        let storage = HistoryStorage::new(dir.path().to_path_buf()).unwrap();
+        let saved = storage.load_entry(&first).unwrap();
+        assert_eq!(saved.request.url, "https://example.com/1");
+        assert_eq!(saved.response.unwrap().body.as_deref(), Some("one"));
+        assert!(storage.load_entry(&second).unwrap().has_error());
+    }
+
+    #[test]
+    fn test_flush_writes_pending_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = HistoryStorage::new(dir.path().to_path_buf()).unwrap();
+        let mut logger = HistoryLogger::new().with_storage(storage);
+
+        let id = logger.log_request(&RequestBuilder::new(
+            HttpMethod::Get,
+            "https://example.com".to_string(),
+        ));
+        assert!(logger.storage().unwrap().load_entry(&id).is_err());
+
+        assert_eq!(logger.flush().unwrap(), 1);
+        assert_eq!(logger.flush().unwrap(), 0);
+        let saved = logger.storage().unwrap().load_entry(&id).unwrap();
+        assert!(saved.response.is_none());
+
+        assert_eq!(HistoryLogger::new().flush().unwrap(), 0);
+    }
+
     #[test]
     fn test_clear() {
         let mut logger = HistoryLogger::new();
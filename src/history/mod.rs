@@ -1,9 +1,18 @@
 //! Request/response history and logging
 
+pub mod csv;
 pub mod entry;
+pub mod filter;
+pub mod jsonl;
 pub mod logger;
+pub mod naming;
+pub mod stats;
 pub mod storage;
 
-pub use entry::{HistoryEntry, RequestLog, ResponseLog};
+pub use csv::CsvField;
+pub use entry::{HistoryEntry, RequestLog, ResponseLog, NOTE_KEY, REPLAY_OF_KEY};
+pub use filter::HistoryFilter;
 pub use logger::HistoryLogger;
-pub use storage::HistoryStorage;
+pub use naming::{unique_name, NameTemplate};
+pub use stats::{GroupBy, GroupStats, HistoryStats, HistorySummary};
+pub use storage::{Backend, HistoryStorage, DEFAULT_MAX_BODY_SIZE};
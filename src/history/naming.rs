@@ -0,0 +1,189 @@
+//! Display names generated from request fields
+//!
+//! Templates use the same `{{name}}` placeholders as environment variables,
+//! filled with fields taken from the request: `method`, `scheme`, `host`,
+//! `path`, `query`, `query_summary`, and `url`.
+
+use crate::env::VariableSubstitutor;
+use crate::history::RequestLog;
+use std::collections::HashMap;
+use url::Url;
+
+/// Template used when none is configured, e.g. "GET /api/v2/users ?page=3"
+pub const DEFAULT_NAME_TEMPLATE: &str = "{{method}} {{path}} {{query_summary}}";
+
+/// Query parameters shown by `query_summary` before the rest are counted
+const QUERY_SUMMARY_PARAMS: usize = 2;
+
+/// A naming template for requests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameTemplate {
+    template: String,
+}
+
+impl NameTemplate {
+    /// Create a naming template
+    pub fn new(template: String) -> Self {
+        Self { template }
+    }
+
+    /// Get the template text
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Render a name for a request
+    ///
+    /// `extra_query` holds parameters kept apart from the URL (as in a
+    /// `RequestLog`); they are summarized after the URL's own.
+    pub fn render(&self, method: &str, url: &str, extra_query: &[(String, String)]) -> String {
+        let fields = request_fields(method, url, extra_query);
+        let variables: HashMap<&str, &str> = fields.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let rendered = VariableSubstitutor::new().substitute(&self.template, &variables);
+        rendered.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Render a name for a logged request
+    pub fn render_log(&self, request: &RequestLog) -> String {
+        self.render(&request.method, &request.url, &request.query_params)
+    }
+}
+
+impl Default for NameTemplate {
+    fn default() -> Self {
+        Self::new(DEFAULT_NAME_TEMPLATE.to_string())
+    }
+}
+
+/// Fields available to naming templates
+fn request_fields(
+    method: &str,
+    url: &str,
+    extra_query: &[(String, String)],
+) -> Vec<(&'static str, String)> {
+    let parsed = Url::parse(url).ok();
+    let raw_query = match &parsed {
+        Some(u) => u.query(),
+        None => url.split_once('?').map(|(_, query)| query),
+    };
+    let mut query: Vec<(String, String)> = raw_query
+        .map(|q| {
+            url::form_urlencoded::parse(q.as_bytes())
+                .into_owned()
+                .collect()
+        })
+        .unwrap_or_default();
+    query.extend(extra_query.iter().cloned());
+
+    let (scheme, host, path) = match &parsed {
+        Some(u) => (
+            u.scheme().to_string(),
+            u.host_str().unwrap_or_default().to_string(),
+            u.path().to_string(),
+        ),
+        // Unparseable URLs (e.g. with unresolved {{variables}}) keep the raw text as the path
+        None => (
+            String::new(),
+            String::new(),
+            url.split('?').next().unwrap_or(url).to_string(),
+        ),
+    };
+
+    let pairs: Vec<String> = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    let query_summary = if pairs.is_empty() {
+        String::new()
+    } else if pairs.len() <= QUERY_SUMMARY_PARAMS {
+        format!("?{}", pairs.join("&"))
+    } else {
+        format!(
+            "?{} (+{} more)",
+            pairs[..QUERY_SUMMARY_PARAMS].join("&"),
+            pairs.len() - QUERY_SUMMARY_PARAMS
+        )
+    };
+
+    vec![
+        ("method", method.to_uppercase()),
+        ("scheme", scheme),
+        ("host", host),
+        ("path", path),
+        ("query", pairs.join("&")),
+        ("query_summary", query_summary),
+        ("url", url.to_string()),
+    ]
+}
+
+/// Make a name unique among existing names by appending " (2)", " (3)", ...
+pub fn unique_name<'a>(base: &str, existing: impl IntoIterator<Item = &'a str>) -> String {
+    let existing: Vec<&str> = existing.into_iter().collect();
+    if !existing.contains(&base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{} ({})", base, n))
+        .find(|name| !existing.contains(&name.as_str()))
+        .expect("unbounded counter")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_template() {
+        let template = NameTemplate::default();
+        assert_eq!(
+            template.render("get", "https://api.example.com/api/v2/users?page=3", &[]),
+            "GET /api/v2/users ?page=3"
+        );
+        assert_eq!(
+            template.render("POST", "https://api.example.com/login", &[]),
+            "POST /login"
+        );
+        assert_eq!(
+            template.render(
+                "GET",
+                "https://example.com/search?q=rust",
+                &[
+                    ("page".to_string(), "2".to_string()),
+                    ("sort".to_string(), "new".to_string())
+                ]
+            ),
+            "GET /search ?q=rust&page=2 (+1 more)"
+        );
+    }
+
+    #[test]
+    fn test_custom_template_and_unresolved_url() {
+        let template = NameTemplate::new("{{host}}: {{method}} {{path}}".to_string());
+        assert_eq!(
+            template.render("DELETE", "http://[::1]:8080/items/7", &[]),
+            "[::1]: DELETE /items/7"
+        );
+
+        let template = NameTemplate::default();
+        assert_eq!(
+            template.render("GET", "{{BASE_URL}}/users?id=1", &[]),
+            "GET {{BASE_URL}}/users ?id=1"
+        );
+    }
+
+    #[test]
+    fn test_render_log_includes_query_params() {
+        let mut log = RequestLog::new("GET".to_string(), "https://example.com/users".to_string());
+        log.query_params.push(("page".to_string(), "3".to_string()));
+        assert_eq!(
+            NameTemplate::default().render_log(&log),
+            "GET /users ?page=3"
+        );
+    }
+
+    #[test]
+    fn test_unique_name() {
+        assert_eq!(unique_name("GET /users", ["POST /users"]), "GET /users");
+        assert_eq!(
+            unique_name("GET /users", ["GET /users", "GET /users (2)"]),
+            "GET /users (3)"
+        );
+    }
+}
@@ -0,0 +1,436 @@
+//! Latency and success statistics over recorded history
+
+use crate::history::HistoryEntry;
+use crate::http::bench::{percentile, serialize_millis};
+use crate::http::image::format_size;
+use crate::http::normalize_url;
+use crate::style::Colorize;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// Hosts listed in a summary's `top_hosts`
+pub const TOP_HOSTS: usize = 5;
+
+/// How entries are grouped in a stats report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// Host (and port)
+    Host,
+
+    /// Host plus path, with ID-like segments collapsed (`/users/123` → `/users/{id}`)
+    #[default]
+    Path,
+
+    /// HTTP method
+    Method,
+}
+
+impl GroupBy {
+    /// Group key for an entry
+    pub fn key(&self, entry: &HistoryEntry) -> String {
+        match self {
+            GroupBy::Method => entry.request.method.to_uppercase(),
+            GroupBy::Host => parse_url(&entry.request.url)
+                .map(|url| host_of(&url))
+                .unwrap_or_else(|| entry.request.url.clone()),
+            GroupBy::Path => parse_url(&entry.request.url)
+                .map(|url| format!("{}{}", host_of(&url), normalize_path(url.path())))
+                .unwrap_or_else(|| entry.request.url.clone()),
+        }
+    }
+}
+
+fn parse_url(url: &str) -> Option<url::Url> {
+    url::Url::parse(&normalize_url(url).ok()?).ok()
+}
+
+fn host_of(url: &url::Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+/// Replace numeric and UUID path segments with `{id}`
+pub fn normalize_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            let numeric = !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit());
+            if numeric || uuid::Uuid::parse_str(segment).is_ok() {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Aggregates for one group of entries
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupStats {
+    /// Group key (host, path pattern, or method)
+    pub key: String,
+
+    /// Number of entries
+    pub count: usize,
+
+    /// Share of entries with a 2xx response, from 0.0 to 1.0
+    pub success_rate: f64,
+
+    /// Median duration
+    #[serde(rename = "p50_ms", serialize_with = "serialize_millis")]
+    pub p50: Option<Duration>,
+
+    /// 90th percentile duration
+    #[serde(rename = "p90_ms", serialize_with = "serialize_millis")]
+    pub p90: Option<Duration>,
+
+    /// 99th percentile duration
+    #[serde(rename = "p99_ms", serialize_with = "serialize_millis")]
+    pub p99: Option<Duration>,
+
+    /// Fastest duration
+    #[serde(rename = "min_ms", serialize_with = "serialize_millis")]
+    pub min: Option<Duration>,
+
+    /// Slowest duration
+    #[serde(rename = "max_ms", serialize_with = "serialize_millis")]
+    pub max: Option<Duration>,
+
+    /// Response body bytes across the group
+    pub total_bytes: u64,
+}
+
+/// Per-group statistics, busiest group first
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryStats {
+    /// How entries were grouped
+    pub group_by: GroupBy,
+
+    /// One row per group
+    pub groups: Vec<GroupStats>,
+}
+
+impl HistoryStats {
+    /// Compute statistics for entries
+    ///
+    /// Entries without a recorded duration count towards totals but not latency.
+    pub fn compute<'a>(
+        entries: impl IntoIterator<Item = &'a HistoryEntry>,
+        group_by: GroupBy,
+    ) -> Self {
+        let mut grouped: HashMap<String, Vec<&HistoryEntry>> = HashMap::new();
+        for entry in entries {
+            grouped.entry(group_by.key(entry)).or_default().push(entry);
+        }
+
+        let mut groups: Vec<GroupStats> = grouped
+            .into_iter()
+            .map(|(key, entries)| group_stats(key, &entries))
+            .collect();
+        groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+
+        Self { group_by, groups }
+    }
+
+    /// Render an aligned table
+    pub fn render_table(&self) -> String {
+        let header = [
+            "COUNT", "SUCCESS", "P50", "P90", "P99", "MIN", "MAX", "BYTES",
+        ];
+        let rows: Vec<(String, Vec<String>)> = self
+            .groups
+            .iter()
+            .map(|g| {
+                let cells = vec![
+                    g.count.to_string(),
+                    format!("{:.1}%", g.success_rate * 100.0),
+                    format_millis(g.p50),
+                    format_millis(g.p90),
+                    format_millis(g.p99),
+                    format_millis(g.min),
+                    format_millis(g.max),
+                    format_size(g.total_bytes as usize),
+                ];
+                (g.key.clone(), cells)
+            })
+            .collect();
+
+        let key_label = match self.group_by {
+            GroupBy::Host => "HOST",
+            GroupBy::Path => "PATH",
+            GroupBy::Method => "METHOD",
+        };
+        let key_width = rows
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .chain(std::iter::once(key_label.len()))
+            .max()
+            .unwrap_or_default();
+        let widths: Vec<usize> = header
+            .iter()
+            .enumerate()
+            .map(|(col, label)| {
+                rows.iter()
+                    .map(|(_, cells)| cells[col].len())
+                    .chain(std::iter::once(label.len()))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let mut lines = Vec::new();
+        let mut line = format!("{:<width$}", key_label, width = key_width);
+        for (label, width) in header.iter().zip(&widths) {
+            line.push_str(&format!("  {:>width$}", label, width = width));
+        }
+        lines.push(line.bold().to_string());
+
+        for (key, cells) in rows {
+            let mut line = format!("{:<width$}", key, width = key_width);
+            for (cell, width) in cells.iter().zip(&widths) {
+                line.push_str(&format!("  {:>width$}", cell, width = width));
+            }
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+
+    /// Serialize as pretty JSON
+    pub fn to_json(&self) -> crate::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Totals across all entries, ungrouped
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HistorySummary {
+    /// Number of entries
+    pub total: usize,
+
+    /// Entries with a 2xx response
+    pub successes: usize,
+
+    /// Entries with a 4xx/5xx response or a transport error
+    pub errors: usize,
+
+    /// Entries per response status code (transport errors have none)
+    pub status_codes: BTreeMap<u16, usize>,
+
+    /// Mean duration
+    #[serde(rename = "average_ms", serialize_with = "serialize_millis")]
+    pub average: Option<Duration>,
+
+    /// 95th percentile duration
+    #[serde(rename = "p95_ms", serialize_with = "serialize_millis")]
+    pub p95: Option<Duration>,
+
+    /// Busiest hosts with their entry counts, most requests first
+    pub top_hosts: Vec<(String, usize)>,
+}
+
+impl HistorySummary {
+    /// Summarize entries
+    ///
+    /// Entries without a recorded duration count towards totals but not latency.
+    pub fn compute<'a>(entries: impl IntoIterator<Item = &'a HistoryEntry>) -> Self {
+        let mut summary = Self::default();
+        let mut durations = Vec::new();
+        let mut hosts: HashMap<String, usize> = HashMap::new();
+
+        for entry in entries {
+            summary.total += 1;
+            if entry.is_successful() {
+                summary.successes += 1;
+            }
+            if entry.has_error() {
+                summary.errors += 1;
+            }
+            if let Some(response) = entry.response.as_ref().filter(|r| r.status_code != 0) {
+                *summary
+                    .status_codes
+                    .entry(response.status_code)
+                    .or_default() += 1;
+            }
+            durations.extend(entry.duration);
+            *hosts.entry(GroupBy::Host.key(entry)).or_default() += 1;
+        }
+
+        durations.sort();
+        if !durations.is_empty() {
+            summary.average = Some(durations.iter().sum::<Duration>() / durations.len() as u32);
+        }
+        summary.p95 = percentile(&durations, 95.0);
+
+        let mut hosts: Vec<(String, usize)> = hosts.into_iter().collect();
+        hosts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        hosts.truncate(TOP_HOSTS);
+        summary.top_hosts = hosts;
+
+        summary
+    }
+}
+
+fn group_stats(key: String, entries: &[&HistoryEntry]) -> GroupStats {
+    let mut durations: Vec<Duration> = entries.iter().filter_map(|e| e.duration).collect();
+    durations.sort();
+
+    let successes = entries.iter().filter(|e| e.is_successful()).count();
+    let total_bytes = entries
+        .iter()
+        .filter_map(|e| e.response.as_ref())
+        .map(|r| r.body_size as u64)
+        .sum();
+
+    GroupStats {
+        key,
+        count: entries.len(),
+        success_rate: successes as f64 / entries.len() as f64,
+        p50: percentile(&durations, 50.0),
+        p90: percentile(&durations, 90.0),
+        p99: percentile(&durations, 99.0),
+        min: durations.first().copied(),
+        max: durations.last().copied(),
+        total_bytes,
+    }
+}
+
+fn format_millis(duration: Option<Duration>) -> String {
+    duration.map_or("-".to_string(), |d| format!("{}ms", d.as_millis()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{RequestLog, ResponseLog};
+
+    fn entry(method: &str, url: &str, status: u16, millis: u64) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(RequestLog::new(method.to_string(), url.to_string()));
+        let mut response = ResponseLog::new(status, String::new());
+        response.set_body("x".repeat(10));
+        entry.set_response(response, Duration::from_millis(millis));
+        entry
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/users/123"), "/users/{id}");
+        assert_eq!(
+            normalize_path("/orders/6f1c2b9e-8f3a-4d2a-9c41-5b7e0a1d2c3f/items"),
+            "/orders/{id}/items"
+        );
+        assert_eq!(normalize_path("/v2/users"), "/v2/users");
+        assert_eq!(normalize_path("/"), "/");
+    }
+
+    #[test]
+    fn test_group_keys() {
+        let entry = entry("get", "https://api.example.com:8443/users/42?x=1", 200, 1);
+        assert_eq!(GroupBy::Method.key(&entry), "GET");
+        assert_eq!(GroupBy::Host.key(&entry), "api.example.com:8443");
+        assert_eq!(GroupBy::Path.key(&entry), "api.example.com:8443/users/{id}");
+    }
+
+    #[test]
+    fn test_path_stats_aggregate_ids() {
+        let mut entries: Vec<HistoryEntry> = (1..=10)
+            .map(|i| {
+                let status = if i == 10 { 500 } else { 200 };
+                entry(
+                    "GET",
+                    &format!("https://api.example.com/users/{}", i),
+                    status,
+                    i * 10,
+                )
+            })
+            .collect();
+        entries.push(entry("POST", "https://api.example.com/login", 200, 5));
+
+        let stats = HistoryStats::compute(&entries, GroupBy::Path);
+        assert_eq!(stats.groups.len(), 2);
+
+        let users = &stats.groups[0];
+        assert_eq!(users.key, "api.example.com/users/{id}");
+        assert_eq!(users.count, 10);
+        assert!((users.success_rate - 0.9).abs() < f64::EPSILON);
+        assert_eq!(users.p50, Some(Duration::from_millis(50)));
+        assert_eq!(users.p90, Some(Duration::from_millis(90)));
+        assert_eq!(users.p99, Some(Duration::from_millis(100)));
+        assert_eq!(users.min, Some(Duration::from_millis(10)));
+        assert_eq!(users.max, Some(Duration::from_millis(100)));
+        assert_eq!(users.total_bytes, 100);
+
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json().unwrap()).unwrap();
+        assert_eq!(json["group_by"], "path");
+        assert_eq!(json["groups"][0]["p50_ms"], 50.0);
+
+        let table = stats.render_table();
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.contains("90.0%"));
+    }
+
+    #[test]
+    fn test_summary_distribution_and_percentiles() {
+        let mut entries: Vec<HistoryEntry> = (1..=20)
+            .map(|i| {
+                let status = match i {
+                    19 => 404,
+                    20 => 500,
+                    _ => 200,
+                };
+                entry("GET", "https://api.example.com/users", status, i * 10)
+            })
+            .collect();
+        entries.push(entry("GET", "https://cdn.example.com/logo.png", 201, 0));
+
+        let mut failed = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://down.example.com".to_string(),
+        ));
+        let mut response = ResponseLog::new(0, "Error".to_string());
+        response.set_error("connection refused".to_string());
+        failed.response = Some(response);
+        entries.push(failed);
+
+        let summary = HistorySummary::compute(&entries);
+        assert_eq!(summary.total, 22);
+        assert_eq!(summary.successes, 19);
+        assert_eq!(summary.errors, 3);
+        assert_eq!(
+            summary.status_codes,
+            BTreeMap::from([(200, 18), (201, 1), (404, 1), (500, 1)])
+        );
+        // (0 + 10 + 20 + ... + 200) / 21
+        assert_eq!(summary.average, Some(Duration::from_millis(100)));
+        assert_eq!(summary.p95, Some(Duration::from_millis(190)));
+        assert_eq!(
+            summary.top_hosts,
+            [
+                ("api.example.com".to_string(), 20),
+                ("cdn.example.com".to_string(), 1),
+                ("down.example.com".to_string(), 1)
+            ]
+        );
+
+        assert_eq!(HistorySummary::compute(&[]), HistorySummary::default());
+    }
+
+    #[test]
+    fn test_entries_without_duration() {
+        let entries = vec![HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://example.com".to_string(),
+        ))];
+        let stats = HistoryStats::compute(&entries, GroupBy::Method);
+        assert_eq!(stats.groups[0].count, 1);
+        assert_eq!(stats.groups[0].success_rate, 0.0);
+        assert_eq!(stats.groups[0].p50, None);
+        assert!(stats.render_table().contains("  -"));
+    }
+}
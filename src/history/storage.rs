@@ -1,20 +1,79 @@
 //! History storage and persistence
 
-use crate::history::HistoryEntry;
+use crate::history::csv::{self, CsvField};
+use crate::history::jsonl::{self, JsonLinesLog};
+use crate::history::{GroupBy, HistoryEntry, HistoryFilter, HistoryStats, HistorySummary};
 use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+/// Largest response body stored by default (256 KB); longer bodies are cut with a marker
+pub const DEFAULT_MAX_BODY_SIZE: usize = 256 * 1024;
+
+/// How history entries are laid out on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// One JSON file per entry
+    #[default]
+    Files,
+
+    /// A single append-only `history.jsonl` file, indexed in memory
+    JsonLines,
+}
+
+impl Backend {
+    /// The backend a history directory already uses (JSON Lines once `history.jsonl` exists)
+    pub fn detect(base_path: &Path) -> Self {
+        if base_path.join(jsonl::FILE_NAME).is_file() {
+            Backend::JsonLines
+        } else {
+            Backend::Files
+        }
+    }
+}
+
 /// Storage for history entries
 pub struct HistoryStorage {
     base_path: PathBuf,
+    max_body_size: Option<usize>,
+    log: Option<JsonLinesLog>,
 }
 
 impl HistoryStorage {
     /// Create a new history storage
     pub fn new(base_path: PathBuf) -> crate::Result<Self> {
         std::fs::create_dir_all(&base_path)?;
-        Ok(Self { base_path })
+        Ok(Self {
+            base_path,
+            max_body_size: Some(DEFAULT_MAX_BODY_SIZE),
+            log: None,
+        })
+    }
+
+    /// Choose how entries are stored
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.log = match backend {
+            Backend::Files => None,
+            Backend::JsonLines => Some(JsonLinesLog::new(&self.base_path)),
+        };
+        self
+    }
+
+    /// Get the active backend
+    pub fn backend(&self) -> Backend {
+        if self.log.is_some() {
+            Backend::JsonLines
+        } else {
+            Backend::Files
+        }
+    }
+
+    /// Cap stored response bodies at this many bytes (`None` stores them whole)
+    pub fn with_max_body_size(mut self, max_bytes: Option<usize>) -> Self {
+        self.max_body_size = max_bytes;
+        self
     }
 
     /// Get default storage path
@@ -34,23 +93,57 @@ impl HistoryStorage {
 
     /// Save a single entry
     pub fn save_entry(&self, entry: &HistoryEntry) -> crate::Result<()> {
+        let entry = self.capped(entry);
+        if let Some(log) = &self.log {
+            return log.save(&[&entry]);
+        }
+
         let filename = format!("{}.json", entry.id);
         let path = self.base_path.join(filename);
-        let json = serde_json::to_string_pretty(entry)?;
-        std::fs::write(path, json)?;
+        std::fs::write(path, serde_json::to_string_pretty(&entry)?)?;
         Ok(())
     }
 
     /// Save multiple entries
     pub fn save_entries(&self, entries: &[HistoryEntry]) -> crate::Result<()> {
+        if let Some(log) = &self.log {
+            let capped: Vec<_> = entries.iter().map(|e| self.capped(e)).collect();
+            return log.save(&capped.iter().map(|e| e.as_ref()).collect::<Vec<_>>());
+        }
+
         for entry in entries {
             self.save_entry(entry)?;
         }
         Ok(())
     }
 
+    /// The entry as it should be stored, with an oversized response body cut down
+    fn capped<'a>(&self, entry: &'a HistoryEntry) -> Cow<'a, HistoryEntry> {
+        let oversized = |max: usize| {
+            entry
+                .response
+                .as_ref()
+                .and_then(|r| r.body.as_ref())
+                .is_some_and(|body| body.len() > max)
+        };
+        match self.max_body_size.filter(|max| oversized(*max)) {
+            Some(max) => {
+                let mut entry = entry.clone();
+                if let Some(response) = &mut entry.response {
+                    response.truncate_body(max);
+                }
+                Cow::Owned(entry)
+            }
+            None => Cow::Borrowed(entry),
+        }
+    }
+
     /// Load an entry by ID
     pub fn load_entry(&self, id: &Uuid) -> crate::Result<HistoryEntry> {
+        if let Some(log) = &self.log {
+            return log.load(id);
+        }
+
         let filename = format!("{}.json", id);
         let path = self.base_path.join(filename);
         let content = std::fs::read_to_string(path)?;
@@ -58,8 +151,48 @@ impl HistoryStorage {
         Ok(entry)
     }
 
+    /// Load an entry by full ID or unique ID prefix (e.g. the first 8 characters)
+    pub fn find(&self, id: &str) -> crate::Result<HistoryEntry> {
+        if let Ok(id) = Uuid::parse_str(id) {
+            return self.load_entry(&id);
+        }
+
+        let prefix = id.to_lowercase();
+        let ids = match &self.log {
+            Some(log) => log.ids()?,
+            None => self.load_all()?.iter().map(|e| e.id).collect(),
+        };
+        let mut matches = ids
+            .into_iter()
+            .filter(|id| id.to_string().starts_with(&prefix));
+        match (matches.next(), matches.next()) {
+            (Some(id), None) if !prefix.is_empty() => self.load_entry(&id),
+            (Some(_), Some(_)) => Err(crate::Error::InvalidCommand(format!(
+                "History ID '{}' is ambiguous; use more characters",
+                id
+            ))),
+            _ => Err(crate::Error::InvalidCommand(format!(
+                "History entry '{}' not found",
+                id
+            ))),
+        }
+    }
+
     /// Load all entries
     pub fn load_all(&self) -> crate::Result<Vec<HistoryEntry>> {
+        let mut entries = match &self.log {
+            Some(log) => log.load_all()?,
+            None => self.load_files()?,
+        };
+
+        // Sort by timestamp (newest first)
+        entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+
+        Ok(entries)
+    }
+
+    /// Load every per-file entry in the directory, in no particular order
+    fn load_files(&self) -> crate::Result<Vec<HistoryEntry>> {
         let mut entries = Vec::new();
 
         for entry in std::fs::read_dir(&self.base_path)? {
@@ -73,9 +206,6 @@ impl HistoryStorage {
             }
         }
 
-        // Sort by timestamp (newest first)
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
         Ok(entries)
     }
 
@@ -86,8 +216,24 @@ impl HistoryStorage {
         Ok(entry)
     }
 
+    /// Compute latency and success statistics for the entries a filter matches
+    pub fn stats(&self, group_by: GroupBy, filter: &HistoryFilter) -> crate::Result<HistoryStats> {
+        let entries = self.load_all()?;
+        Ok(HistoryStats::compute(filter.apply(&entries), group_by))
+    }
+
+    /// Summarize the entries a filter matches
+    pub fn summary(&self, filter: &HistoryFilter) -> crate::Result<HistorySummary> {
+        let entries = self.load_all()?;
+        Ok(HistorySummary::compute(filter.apply(&entries)))
+    }
+
     /// Delete an entry
     pub fn delete_entry(&self, id: &Uuid) -> crate::Result<()> {
+        if let Some(log) = &self.log {
+            return log.delete(id);
+        }
+
         let filename = format!("{}.json", id);
         let path = self.base_path.join(filename);
         std::fs::remove_file(path)?;
@@ -96,6 +242,10 @@ impl HistoryStorage {
 
     /// Delete entries older than a certain date
     pub fn delete_older_than(&self, date: DateTime<Utc>) -> crate::Result<usize> {
+        if let Some(log) = &self.log {
+            return log.compact(|entry| entry.timestamp >= date);
+        }
+
         let entries = self.load_all()?;
         let mut deleted = 0;
 
@@ -111,6 +261,10 @@ impl HistoryStorage {
 
     /// Clear all history
     pub fn clear_all(&self) -> crate::Result<usize> {
+        if let Some(log) = &self.log {
+            return log.clear();
+        }
+
         let mut deleted = 0;
 
         for entry in std::fs::read_dir(&self.base_path)? {
@@ -134,6 +288,22 @@ impl HistoryStorage {
         Ok(())
     }
 
+    /// Export the entries a filter matches as CSV, newest first
+    ///
+    /// Returns the number of rows written.
+    pub fn export_csv(
+        &self,
+        path: &Path,
+        fields: &[CsvField],
+        filter: &HistoryFilter,
+    ) -> crate::Result<usize> {
+        let entries = self.load_all()?;
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let rows = csv::write_csv(&mut file, filter.apply(&entries), fields)?;
+        file.flush()?;
+        Ok(rows)
+    }
+
     /// Import history from a single file
     pub fn import_from_file(&self, path: &Path) -> crate::Result<usize> {
         let content = std::fs::read_to_string(path)?;
@@ -146,6 +316,10 @@ impl HistoryStorage {
 
     /// Get count of stored entries
     pub fn count(&self) -> crate::Result<usize> {
+        if let Some(log) = &self.log {
+            return log.count();
+        }
+
         let mut count = 0;
 
         for entry in std::fs::read_dir(&self.base_path)? {
@@ -158,6 +332,34 @@ impl HistoryStorage {
         Ok(count)
     }
 
+    /// Move per-file entries into the JSON Lines log, returning how many moved
+    ///
+    /// Files that can't be read are left in place.
+    pub fn migrate_files(&self) -> crate::Result<usize> {
+        let log = self.log.as_ref().ok_or_else(|| {
+            crate::Error::InvalidCommand(
+                "Migration needs the JSON Lines history backend".to_string(),
+            )
+        })?;
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.base_path)? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                if let Ok(hist_entry) = self.load_entry_from_path(&path) {
+                    files.push((path, hist_entry));
+                }
+            }
+        }
+
+        files.sort_by_key(|(_, entry)| entry.timestamp);
+        log.save(&files.iter().map(|(_, entry)| entry).collect::<Vec<_>>())?;
+        for (path, _) in &files {
+            std::fs::remove_file(path)?;
+        }
+        Ok(files.len())
+    }
+
     /// Get storage size in bytes
     pub fn storage_size(&self) -> crate::Result<u64> {
         let mut total_size = 0;
@@ -176,7 +378,7 @@ impl HistoryStorage {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::history::RequestLog;
+    use crate::history::{RequestLog, ResponseLog};
     use tempfile::TempDir;
 
     #[test]
@@ -202,6 +404,53 @@ mod tests {
         assert_eq!(loaded.request.method, "GET");
     }
 
+    #[test]
+    fn test_save_caps_response_body() {
+        use crate::history::ResponseLog;
+
+        let temp_dir = TempDir::new().unwrap();
+        let storage = HistoryStorage::new(temp_dir.path().to_path_buf())
+            .unwrap()
+            .with_max_body_size(Some(4));
+
+        let mut entry = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://example.com".to_string(),
+        ));
+        let mut response = ResponseLog::new(200, "OK".to_string());
+        response.set_body("0123456789".to_string());
+        entry.set_response(response, std::time::Duration::from_millis(5));
+        storage.save_entry(&entry).unwrap();
+
+        let loaded = storage.load_entry(&entry.id).unwrap().response.unwrap();
+        assert!(loaded.body.unwrap().starts_with("0123\n… [truncated"));
+        assert_eq!(loaded.body_size, 10);
+        // The caller's entry is untouched
+        assert_eq!(entry.response.unwrap().body.as_deref(), Some("0123456789"));
+    }
+
+    #[test]
+    fn test_find_by_prefix() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = HistoryStorage::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let entry = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://example.com".to_string(),
+        ));
+        storage.save_entry(&entry).unwrap();
+
+        let full = entry.id.to_string();
+        assert_eq!(storage.find(&full).unwrap().id, entry.id);
+        assert_eq!(storage.find(&full[..8]).unwrap().id, entry.id);
+        assert_eq!(
+            storage.find(&full[..8].to_uppercase()).unwrap().id,
+            entry.id
+        );
+        assert!(storage.find("zzzzzzzz").is_err());
+        assert!(storage.find("").is_err());
+    }
+
     #[test]
     fn test_load_all() {
         let temp_dir = TempDir::new().unwrap();
@@ -223,6 +472,31 @@ mod tests {
         assert_eq!(all.len(), 2);
     }
 
+    #[test]
+    fn test_stats_respects_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = HistoryStorage::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let mut old = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://example.com/users/1".to_string(),
+        ));
+        old.timestamp -= chrono::Duration::days(2);
+        let recent = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://example.com/users/2".to_string(),
+        ));
+        storage.save_entries(&[old, recent]).unwrap();
+
+        let all = storage.stats(GroupBy::Path, &HistoryFilter::new()).unwrap();
+        assert_eq!(all.groups[0].key, "example.com/users/{id}");
+        assert_eq!(all.groups[0].count, 2);
+
+        let filter = HistoryFilter::new().with_since(Utc::now() - chrono::Duration::days(1));
+        let recent = storage.stats(GroupBy::Path, &filter).unwrap();
+        assert_eq!(recent.groups[0].count, 1);
+    }
+
     #[test]
     fn test_delete_entry() {
         let temp_dir = TempDir::new().unwrap();
@@ -299,6 +573,37 @@ mod tests {
         assert_eq!(import_storage.count().unwrap(), 2);
     }
 
+    #[test]
+    fn test_export_csv_filtered() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = HistoryStorage::new(temp_dir.path().to_path_buf()).unwrap();
+        for (url, status) in [
+            ("https://example.com/ok", 200),
+            ("https://example.com/bad", 500),
+        ] {
+            let mut entry = HistoryEntry::new(RequestLog::new("GET".to_string(), url.to_string()));
+            entry.set_response(
+                ResponseLog::new(status, String::new()),
+                std::time::Duration::from_millis(5),
+            );
+            storage.save_entry(&entry).unwrap();
+        }
+
+        let path = temp_dir.path().join("failures.csv");
+        let rows = storage
+            .export_csv(
+                &path,
+                &[CsvField::Url, CsvField::Status],
+                &HistoryFilter::new().with_failed(),
+            )
+            .unwrap();
+        assert_eq!(rows, 1);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "url,status\r\nhttps://example.com/bad,500\r\n"
+        );
+    }
+
     #[test]
     fn test_storage_size() {
         let temp_dir = TempDir::new().unwrap();
@@ -314,4 +619,100 @@ mod tests {
         let size = storage.storage_size().unwrap();
         assert!(size > 0);
     }
+
+    fn json_lines_storage(temp_dir: &TempDir) -> HistoryStorage {
+        HistoryStorage::new(temp_dir.path().to_path_buf())
+            .unwrap()
+            .with_backend(Backend::JsonLines)
+    }
+
+    #[test]
+    fn test_json_lines_backend() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = json_lines_storage(&temp_dir);
+        assert_eq!(storage.backend(), Backend::JsonLines);
+
+        let old = HistoryEntry::new(RequestLog::new(
+            "GET".to_string(),
+            "https://example.com/old".to_string(),
+        ));
+        let mut new = HistoryEntry::new(RequestLog::new(
+            "POST".to_string(),
+            "https://example.com/new".to_string(),
+        ));
+        new.timestamp = old.timestamp + chrono::Duration::hours(1);
+        storage.save_entries(&[old.clone(), new.clone()]).unwrap();
+
+        assert_eq!(Backend::detect(temp_dir.path()), Backend::JsonLines);
+        assert_eq!(storage.count().unwrap(), 2);
+        assert_eq!(storage.load_all().unwrap()[0].id, new.id);
+        assert_eq!(
+            storage.find(&old.id.to_string()[..8]).unwrap().request.url,
+            "https://example.com/old"
+        );
+
+        storage.delete_entry(&new.id).unwrap();
+        assert!(storage.load_entry(&new.id).is_err());
+        assert_eq!(storage.count().unwrap(), 1);
+
+        storage.save_entry(&new).unwrap();
+        let removed = storage
+            .delete_older_than(old.timestamp + chrono::Duration::minutes(1))
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(storage.load_all().unwrap()[0].id, new.id);
+
+        assert_eq!(storage.clear_all().unwrap(), 1);
+        assert_eq!(storage.count().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_migrate_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = HistoryStorage::new(temp_dir.path().to_path_buf()).unwrap();
+        for i in 0..3 {
+            files
+                .save_entry(&HistoryEntry::new(RequestLog::new(
+                    "GET".to_string(),
+                    format!("https://example.com/{}", i),
+                )))
+                .unwrap();
+        }
+        assert_eq!(Backend::detect(temp_dir.path()), Backend::Files);
+        assert!(files.migrate_files().is_err());
+
+        let storage = json_lines_storage(&temp_dir);
+        assert_eq!(storage.migrate_files().unwrap(), 3);
+        assert_eq!(storage.count().unwrap(), 3);
+        assert_eq!(files.count().unwrap(), 0);
+        assert_eq!(Backend::detect(temp_dir.path()), Backend::JsonLines);
+    }
+
+    #[test]
+    fn test_json_lines_loads_10k_entries_quickly() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = json_lines_storage(&temp_dir);
+        let entries: Vec<HistoryEntry> = (0..10_000)
+            .map(|i| {
+                HistoryEntry::new(RequestLog::new(
+                    "GET".to_string(),
+                    format!("https://example.com/items/{}", i),
+                ))
+            })
+            .collect();
+        storage.save_entries(&entries).unwrap();
+
+        // Reopen so the index is built from the file too
+        let start = std::time::Instant::now();
+        let storage = json_lines_storage(&temp_dir);
+        let loaded = storage.load_all().unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(loaded.len(), 10_000);
+        assert!(
+            elapsed < std::time::Duration::from_secs(3),
+            "loading 10k entries took {:?}",
+            elapsed
+        );
+    }
 }
@@ -0,0 +1,160 @@
+//! Latency statistics for repeated requests
+
+use crate::style::Colorize;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Summary of sending the same request several times
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchStats {
+    /// Requests sent
+    pub requests: usize,
+
+    /// Requests that got a 2xx response
+    pub successes: usize,
+
+    /// Fastest response
+    #[serde(rename = "min_ms", serialize_with = "serialize_millis")]
+    pub min: Option<Duration>,
+
+    /// Slowest response
+    #[serde(rename = "max_ms", serialize_with = "serialize_millis")]
+    pub max: Option<Duration>,
+
+    /// Average response time
+    #[serde(rename = "mean_ms", serialize_with = "serialize_millis")]
+    pub mean: Option<Duration>,
+
+    /// Median response time
+    #[serde(rename = "p50_ms", serialize_with = "serialize_millis")]
+    pub p50: Option<Duration>,
+
+    /// 95th percentile response time
+    #[serde(rename = "p95_ms", serialize_with = "serialize_millis")]
+    pub p95: Option<Duration>,
+}
+
+impl BenchStats {
+    /// Compute statistics from the durations of completed responses
+    ///
+    /// Requests that failed before a response arrived count towards
+    /// `requests` but have no duration.
+    pub fn compute(requests: usize, successes: usize, durations: &[Duration]) -> Self {
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+
+        let mean =
+            (!sorted.is_empty()).then(|| sorted.iter().sum::<Duration>() / sorted.len() as u32);
+
+        Self {
+            requests,
+            successes,
+            min: sorted.first().copied(),
+            max: sorted.last().copied(),
+            mean,
+            p50: percentile(&sorted, 50.0),
+            p95: percentile(&sorted, 95.0),
+        }
+    }
+
+    /// Render a colored summary
+    pub fn render(&self) -> String {
+        let ratio = format!("{}/{} succeeded", self.successes, self.requests);
+        let mut out = if self.successes == self.requests {
+            format!("{} {}\n", "✓".green().bold(), ratio)
+        } else {
+            format!("{} {}\n", "✗".red().bold(), ratio)
+        };
+
+        for (label, value) in [
+            ("min", self.min),
+            ("max", self.max),
+            ("mean", self.mean),
+            ("p50", self.p50),
+            ("p95", self.p95),
+        ] {
+            out.push_str(&format!(
+                "  {:<5}{}\n",
+                label.cyan(),
+                format_duration(value)
+            ));
+        }
+        out
+    }
+
+    /// Serialize as pretty JSON
+    pub fn to_json(&self) -> crate::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Nearest-rank percentile of sorted durations
+pub fn percentile(sorted: &[Duration], pct: f64) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Serialize an optional duration as fractional milliseconds
+pub(crate) fn serialize_millis<S: serde::Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match duration {
+        Some(d) => serializer.serialize_f64(d.as_secs_f64() * 1000.0),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    duration.map_or("-".to_string(), |d| {
+        format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|ms| Duration::from_millis(*ms)).collect()
+    }
+
+    #[test]
+    fn test_compute() {
+        let durations = millis(&[30, 10, 20, 50, 40, 60, 70, 80, 90, 100]);
+        let stats = BenchStats::compute(10, 9, &durations);
+
+        assert_eq!(stats.min, Some(Duration::from_millis(10)));
+        assert_eq!(stats.max, Some(Duration::from_millis(100)));
+        assert_eq!(stats.mean, Some(Duration::from_millis(55)));
+        assert_eq!(stats.p50, Some(Duration::from_millis(50)));
+        assert_eq!(stats.p95, Some(Duration::from_millis(100)));
+
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json().unwrap()).unwrap();
+        assert_eq!(json["requests"], 10);
+        assert_eq!(json["successes"], 9);
+        assert_eq!(json["mean_ms"], 55.0);
+        assert!(stats.render().contains("9/10 succeeded"));
+    }
+
+    #[test]
+    fn test_compute_without_responses() {
+        let stats = BenchStats::compute(3, 0, &[]);
+        assert_eq!(stats.min, None);
+        assert_eq!(stats.mean, None);
+        assert_eq!(stats.p95, None);
+        assert!(stats.render().contains("0/3 succeeded"));
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let sorted = millis(&[1, 2, 3, 4]);
+        assert_eq!(percentile(&sorted, 50.0), Some(Duration::from_millis(2)));
+        assert_eq!(percentile(&sorted, 95.0), Some(Duration::from_millis(4)));
+        assert_eq!(percentile(&sorted, 0.0), Some(Duration::from_millis(1)));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+}
@@ -1,27 +1,281 @@
 //! HTTP client for executing requests
 
-use crate::error::Result;
+use crate::auth::AuthScheme;
+use crate::error::{Error, Result};
+use crate::http::deadline::Deadline;
+use crate::http::proxy::ProxyConfig;
 use crate::http::request::RequestBuilder;
 use crate::http::response::HttpResponse;
+use crate::session::CookieJar;
 use crate::upload::MultipartBuilder;
 use reqwest::blocking::Client;
-use std::time::Instant;
+use reqwest::blocking::{Request, Response};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_ENCODING, CONTENT_LENGTH,
+    CONTENT_TYPE, COOKIE, LOCATION, PROXY_AUTHORIZATION, SET_COOKIE,
+};
+use reqwest::{Certificate, Identity, Method, StatusCode, Url};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// User-Agent sent unless the client or request sets another
+pub const DEFAULT_USER_AGENT: &str = concat!("bazzounquester/", env!("CARGO_PKG_VERSION"));
+
+/// Most redirects followed for one request
+pub const MAX_REDIRECTS: usize = 10;
 
 /// HTTP client for making requests
 pub struct HttpClient {
-    client: Client,
+    /// The built client, or why it couldn't be built (reported on send)
+    client: std::result::Result<Client, String>,
+    accept_invalid_certs: bool,
+    identity: Option<Identity>,
+    root_certs: Vec<Certificate>,
+    proxy: Option<ProxyConfig>,
+    cookie_jar: Option<Arc<Mutex<CookieJar>>>,
+    deadline: Option<Deadline>,
+    timeout: Option<Duration>,
+    follow_redirects: bool,
+    default_headers: Vec<String>,
+    user_agent: String,
 }
 
 impl HttpClient {
     /// Create a new HTTP client
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
+        let mut client = Self {
+            client: Err(String::new()),
+            accept_invalid_certs: false,
+            identity: None,
+            root_certs: Vec::new(),
+            proxy: None,
+            cookie_jar: None,
+            deadline: None,
+            timeout: None,
+            follow_redirects: true,
+            default_headers: Vec::new(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+        };
+        client.rebuild();
+        client
+    }
+
+    /// Create a client that skips TLS certificate verification
+    pub fn insecure() -> Self {
+        Self::new().with_danger_accept_invalid_certs(true)
+    }
+
+    /// Enable or disable TLS certificate verification
+    pub fn with_danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self.rebuild();
+        self
+    }
+
+    /// Check if TLS certificate verification is disabled
+    pub fn is_insecure(&self) -> bool {
+        self.accept_invalid_certs
+    }
+
+    /// Authenticate with a PEM client certificate and PKCS#8 private key
+    pub fn with_client_cert(mut self, cert_path: &Path, key_path: &Path) -> Result<Self> {
+        let cert = read_cert_file(cert_path, "client certificate")?;
+        let key = read_cert_file(key_path, "private key")?;
+
+        let identity = Identity::from_pkcs8_pem(&cert, &key).map_err(|e| {
+            Error::Certificate(format!(
+                "Invalid PEM certificate '{}' or PKCS#8 key '{}': {}",
+                cert_path.display(),
+                key_path.display(),
+                e
+            ))
+        })?;
+
+        self.identity = Some(identity);
+        self.rebuild();
+        Ok(self)
+    }
+
+    /// Authenticate with a PKCS#12 (.p12/.pfx) client identity
+    pub fn with_pkcs12_cert(mut self, path: &Path, password: &str) -> Result<Self> {
+        let der = read_cert_file(path, "PKCS#12 identity")?;
+
+        let identity = Identity::from_pkcs12_der(&der, password).map_err(|e| {
+            Error::Certificate(format!(
+                "Invalid PKCS#12 identity '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        self.identity = Some(identity);
+        self.rebuild();
+        Ok(self)
+    }
+
+    /// Check if a client certificate is configured
+    pub fn has_client_cert(&self) -> bool {
+        self.identity.is_some()
+    }
+
+    /// Trust the CA certificates in a PEM bundle or DER file, on top of the system roots
+    pub fn with_ca_cert(mut self, path: &Path) -> Result<Self> {
+        let data = read_cert_file(path, "CA certificate")?;
+
+        let is_pem = data.windows(10).any(|w| w == b"-----BEGIN");
+        let certs = if is_pem {
+            Certificate::from_pem_bundle(&data)
+        } else {
+            Certificate::from_der(&data).map(|cert| vec![cert])
+        }
+        .map_err(|e| {
+            Error::Certificate(format!(
+                "Invalid CA certificate '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        if certs.is_empty() {
+            return Err(Error::Certificate(format!(
+                "No certificates found in '{}'",
+                path.display()
+            )));
+        }
+
+        self.root_certs.extend(certs);
+        self.rebuild();
+        Ok(self)
+    }
+
+    /// Number of extra trusted CA certificates
+    pub fn ca_cert_count(&self) -> usize {
+        self.root_certs.len()
+    }
+
+    /// Send requests through a proxy
+    ///
+    /// Without one, the `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and
+    /// `NO_PROXY` environment variables apply.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self.rebuild();
+        self
+    }
+
+    /// Get the explicit proxy, if any
+    pub fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
+    }
+
+    /// Time out requests that don't set their own timeout after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Get the default request timeout, if any
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Follow 3xx redirects (the default) or return them as the response
+    pub fn with_follow_redirects(mut self, follow: bool) -> Self {
+        self.follow_redirects = follow;
+        self.rebuild();
+        self
+    }
+
+    /// Check whether redirects are followed
+    pub fn follows_redirects(&self) -> bool {
+        self.follow_redirects
+    }
+
+    /// Identify as `user_agent` (a request's own `User-Agent` header still wins)
+    pub fn with_user_agent(mut self, user_agent: &str) -> Result<Self> {
+        HeaderValue::from_str(user_agent)
+            .map_err(|_| Error::InvalidHeader(format!("Invalid User-Agent: {:?}", user_agent)))?;
+        self.user_agent = user_agent.to_string();
+        self.rebuild();
+        Ok(self)
+    }
+
+    /// Get the User-Agent sent by default
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Send these `Key:Value` headers with every request that doesn't set
+    /// a header of the same name
+    pub fn with_default_headers(mut self, headers: Vec<String>) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Get the default headers
+    pub fn default_headers(&self) -> &[String] {
+        &self.default_headers
+    }
+
+    /// Send cookies from `jar` and store cookies set by responses in it
+    pub fn with_cookie_jar(mut self, jar: Arc<Mutex<CookieJar>>) -> Self {
+        self.cookie_jar = Some(jar);
+        self
+    }
+
+    /// Get the shared cookie jar, if cookie handling is enabled
+    pub fn cookie_jar(&self) -> Option<&Arc<Mutex<CookieJar>>> {
+        self.cookie_jar.as_ref()
+    }
+
+    /// Refuse to send after `deadline` and cap every request timeout by it
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Get the invocation deadline, if any
+    pub fn deadline(&self) -> Option<&Deadline> {
+        self.deadline.as_ref()
+    }
+
+    /// Rebuild the underlying client from the current settings
+    fn rebuild(&mut self) {
+        let mut builder = Client::builder()
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
+            .user_agent(&self.user_agent);
+        if let Some(identity) = &self.identity {
+            builder = builder.identity(identity.clone());
         }
+        for cert in &self.root_certs {
+            builder = builder.add_root_certificate(cert.clone());
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.to_reqwest());
+        }
+        // Redirects are followed in `execute`, so every hop's cookies are kept
+        builder = builder.redirect(reqwest::redirect::Policy::none());
+        self.client = builder.build().map_err(|e| e.to_string());
+    }
+
+    /// The underlying client, or the error that kept it from being built
+    fn client(&self) -> Result<&Client> {
+        self.client.as_ref().map_err(|e| {
+            Error::Io(std::io::Error::other(format!(
+                "Could not build HTTP client: {}",
+                e
+            )))
+        })
     }
 
     /// Execute a request and return the response
     pub fn execute(&self, request: &RequestBuilder) -> Result<HttpResponse> {
+        request.validate_url()?;
+        if let Some(deadline) = &self.deadline {
+            if deadline.is_expired() {
+                return Err(Error::DeadlineExceeded(deadline.budget()));
+            }
+        }
         let start = Instant::now();
 
         // Apply authentication first (modifies headers/query params)
@@ -34,54 +288,66 @@ impl HttpClient {
         auth_request.headers = headers;
         auth_request.query_params = query_params;
 
-        // Parse headers and query params
-        let header_map = auth_request.parse_headers()?;
-        let query_map = auth_request.parse_query_params()?;
+        // Parse headers over the defaults, and query params (keys and values encoded unless raw)
+        let header_map = auth_request.parse_headers_with_defaults(&self.default_headers)?;
+        auth_request.parse_query_params()?;
+        let url = request.url_with_query(&request.url, &auth_request.query_params);
 
         // Build request
+        let client = self.client()?;
         let mut req = match request.method {
-            crate::http::HttpMethod::Get => self.client.get(&request.url),
-            crate::http::HttpMethod::Post => self.client.post(&request.url),
-            crate::http::HttpMethod::Put => self.client.put(&request.url),
-            crate::http::HttpMethod::Delete => self.client.delete(&request.url),
-            crate::http::HttpMethod::Patch => self.client.patch(&request.url),
-            crate::http::HttpMethod::Head => self.client.head(&request.url),
-            crate::http::HttpMethod::Options => {
-                self.client.request(reqwest::Method::OPTIONS, &request.url)
-            }
+            crate::http::HttpMethod::Get => client.get(&url),
+            crate::http::HttpMethod::Post => client.post(&url),
+            crate::http::HttpMethod::Put => client.put(&url),
+            crate::http::HttpMethod::Delete => client.delete(&url),
+            crate::http::HttpMethod::Patch => client.patch(&url),
+            crate::http::HttpMethod::Head => client.head(&url),
+            crate::http::HttpMethod::Options => client.request(reqwest::Method::OPTIONS, &url),
         };
 
         // Add headers
+        let has_content_type = header_map.contains_key(CONTENT_TYPE);
         req = req.headers(header_map);
 
-        // Add query parameters
-        if !query_map.is_empty() {
-            req = req.query(&query_map);
+        // Per-request timeout, capped by what's left of the deadline
+        let request_timeout = request.timeout.or(self.timeout);
+        let timeout = match &self.deadline {
+            Some(deadline) => Some(deadline.cap(request_timeout)),
+            None => request_timeout,
+        };
+        if let Some(timeout) = timeout {
+            req = req.timeout(timeout);
         }
 
         // Add form data if present (takes precedence over body)
         if let Some(form_data) = request.get_form_data() {
             if form_data.has_files() {
-                // Use multipart/form-data for files
+                // Use multipart/form-data for files, streamed from disk unless
+                // the auth scheme has to sign the whole body
                 let multipart_builder = MultipartBuilder::from_form_data(form_data)?;
-                let multipart_body = multipart_builder.build()?;
-                let content_type = multipart_builder.content_type();
-
-                req = req
-                    .header(reqwest::header::CONTENT_TYPE, content_type)
-                    .body(multipart_body);
+                req = req.header(CONTENT_TYPE, multipart_builder.content_type());
+                req = if matches!(request.auth, AuthScheme::Hmac(_)) {
+                    req.body(multipart_builder.build()?)
+                } else {
+                    let reader = multipart_builder.build_streaming()?;
+                    let len = reader.content_length();
+                    req.body(reqwest::blocking::Body::sized(reader, len))
+                };
             } else {
                 // Use application/x-www-form-urlencoded for text-only forms
                 let encoded = form_data.to_urlencoded();
                 req = req
-                    .header(
-                        reqwest::header::CONTENT_TYPE,
-                        "application/x-www-form-urlencoded",
-                    )
+                    .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
                     .body(encoded);
             }
         } else if let Some(body_str) = request.get_raw_body() {
-            // Add body if present and no form data
+            // Add body if present and no form data, defaulting the content
+            // type from the body unless one was set
+            if !has_content_type {
+                if let Some(content_type) = request.detected_content_type() {
+                    req = req.header(CONTENT_TYPE, content_type);
+                }
+            }
             // Try to parse as JSON first
             match request.parse_body() {
                 Ok(Some(json_value)) => {
@@ -94,13 +360,182 @@ impl HttpClient {
             }
         }
 
-        // Send request and measure time
-        let response = req.send()?;
+        // Signing schemes run last, once the URL and body are final
+        let mut req = req.build()?;
+        let body = req.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+        let signing_headers =
+            request
+                .auth
+                .signing_headers(req.method().as_str(), req.url().as_str(), body)?;
+        for (name, value) in signing_headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| Error::InvalidHeader(format!("Invalid header name: {}", name)))?;
+            let value = HeaderValue::from_str(&value)
+                .map_err(|_| Error::InvalidHeader(format!("Invalid header value: {}", value)))?;
+            req.headers_mut().insert(name, value);
+        }
+
+        // Send request, following redirects hop by hop, and measure time
+        let response = self.send_following_redirects(req, timeout)?;
         let duration = start.elapsed();
 
         // Convert to our response type
         HttpResponse::from_reqwest(response, duration)
     }
+
+    /// Send a request and any redirects it leads to
+    ///
+    /// Each hop stores its `Set-Cookie` headers in the jar and sends the
+    /// jar's cookies, unless the user set a `Cookie` header for this host.
+    fn send_following_redirects(
+        &self,
+        mut req: Request,
+        timeout: Option<Duration>,
+    ) -> Result<Response> {
+        let mut explicit_cookie = req.headers().contains_key(COOKIE);
+        let mut redirects = 0;
+
+        loop {
+            if !explicit_cookie {
+                self.attach_cookies(&mut req);
+            }
+            let method = req.method().clone();
+            let headers = req.headers().clone();
+            let hop_timeout = req.timeout().copied();
+            // Only streamed bodies can't be copied
+            let body = req.try_clone().map(|mut copy| copy.body_mut().take());
+            let from = req.url().clone();
+
+            let response = self.send(req, timeout)?;
+            self.store_cookies(&response);
+
+            let Some(to) = self.redirect_target(&response) else {
+                return Ok(response);
+            };
+            // 307 and 308 resend the body, which a streamed upload can't do
+            let keeps_body = matches!(
+                response.status(),
+                StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT
+            );
+            if keeps_body && body.is_none() {
+                return Ok(response);
+            }
+            if redirects == MAX_REDIRECTS {
+                return Err(Error::TooManyRedirects(MAX_REDIRECTS));
+            }
+            redirects += 1;
+
+            let mut next = Request::new(method.clone(), to);
+            *next.headers_mut() = headers;
+            *next.timeout_mut() = hop_timeout;
+            if keeps_body {
+                *next.body_mut() = body.flatten();
+            } else {
+                // 301, 302 and 303 continue as a GET without the body
+                if method != Method::HEAD {
+                    *next.method_mut() = Method::GET;
+                }
+                strip_headers(
+                    next.headers_mut(),
+                    &[CONTENT_TYPE, CONTENT_LENGTH, CONTENT_ENCODING],
+                );
+            }
+            // Credentials only go to the host they were meant for
+            if next.url().origin() != from.origin() {
+                strip_headers(
+                    next.headers_mut(),
+                    &[AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE],
+                );
+                explicit_cookie = false;
+            }
+            req = next;
+        }
+    }
+
+    /// Send one request, mapping timeouts to the deadline or request timeout
+    fn send(&self, req: Request, timeout: Option<Duration>) -> Result<Response> {
+        self.client()?
+            .execute(req)
+            .map_err(|e| match (&self.deadline, timeout) {
+                (Some(deadline), _) if e.is_timeout() && deadline.is_expired() => {
+                    Error::DeadlineExceeded(deadline.budget())
+                }
+                (_, Some(timeout)) if e.is_timeout() => Error::Timeout(timeout),
+                _ => e.into(),
+            })
+    }
+
+    /// Where a response redirects to, if redirects are followed
+    fn redirect_target(&self, response: &Response) -> Option<Url> {
+        if !self.follow_redirects
+            || !matches!(
+                response.status(),
+                StatusCode::MOVED_PERMANENTLY
+                    | StatusCode::FOUND
+                    | StatusCode::SEE_OTHER
+                    | StatusCode::TEMPORARY_REDIRECT
+                    | StatusCode::PERMANENT_REDIRECT
+            )
+        {
+            return None;
+        }
+        let location = response.headers().get(LOCATION)?.to_str().ok()?;
+        response.url().join(location).ok()
+    }
+
+    /// Replace the request's `Cookie` header with the jar's cookies for its URL
+    fn attach_cookies(&self, req: &mut Request) {
+        let Some(jar) = &self.cookie_jar else {
+            return;
+        };
+        let url = req.url().to_string();
+        let cookie = jar.lock().expect("cookie jar poisoned").cookie_header(&url);
+        match cookie.and_then(|c| HeaderValue::from_str(&c).ok()) {
+            Some(value) => {
+                req.headers_mut().insert(COOKIE, value);
+            }
+            None => {
+                req.headers_mut().remove(COOKIE);
+            }
+        }
+    }
+
+    /// Store the cookies a response sets in the jar
+    fn store_cookies(&self, response: &Response) {
+        let Some(jar) = &self.cookie_jar else {
+            return;
+        };
+        let url = response.url().to_string();
+        let set_cookies: Vec<(String, String)> = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(|value| (SET_COOKIE.to_string(), value.to_string()))
+            .collect();
+        jar.lock()
+            .expect("cookie jar poisoned")
+            .add_from_headers(&url, &set_cookies);
+    }
+}
+
+/// Remove headers from a map
+fn strip_headers(headers: &mut HeaderMap, names: &[HeaderName]) {
+    for name in names {
+        headers.remove(name);
+    }
+}
+
+/// Read a certificate-related file with a descriptive error
+fn read_cert_file(path: &Path, what: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| {
+        Error::Certificate(format!(
+            "Could not read {} '{}': {}",
+            what,
+            path.display(),
+            e
+        ))
+    })
 }
 
 impl Default for HttpClient {
@@ -121,7 +556,518 @@ mod tests {
 
     #[test]
     fn test_default() {
-        let _client = HttpClient::default();
+        let client = HttpClient::default();
+        assert!(!client.is_insecure());
+    }
+
+    #[test]
+    fn test_insecure_client() {
+        let client = HttpClient::insecure();
+        assert!(client.is_insecure());
+
+        let client = client.with_danger_accept_invalid_certs(false);
+        assert!(!client.is_insecure());
+    }
+
+    #[test]
+    fn test_execute_through_proxy() {
+        let mut proxy = mockito::Server::new();
+        let mock = proxy
+            .mock("GET", mockito::Matcher::Any)
+            .match_header("proxy-authorization", "Basic dXNlcjpwYXNz")
+            .with_body("via proxy")
+            .create();
+
+        let config = crate::http::proxy::ProxyConfig::parse(&proxy.url())
+            .unwrap()
+            .with_auth("user", "pass");
+        let client = HttpClient::new().with_proxy(config);
+        assert!(client.proxy().is_some());
+
+        let request = RequestBuilder::new(
+            crate::http::HttpMethod::Get,
+            "http://api.example.invalid/users".to_string(),
+        );
+        let response = client.execute(&request).unwrap();
+        assert_eq!(response.body, "via proxy");
+        mock.assert();
+    }
+
+    #[test]
+    fn test_execute_adds_hmac_signature() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/orders")
+            .match_header(
+                "x-signature",
+                mockito::Matcher::Regex("^[0-9a-f]{64}$".to_string()),
+            )
+            .match_header(
+                "x-timestamp",
+                mockito::Matcher::Regex("^[0-9]+$".to_string()),
+            )
+            .with_status(201)
+            .create();
+
+        let request = RequestBuilder::new(
+            crate::http::HttpMethod::Post,
+            format!("{}/orders", server.url()),
+        )
+        .body(r#"{"id":42}"#.to_string())
+        .auth(crate::auth::AuthScheme::Hmac(crate::auth::HmacAuth::new(
+            "topsecret".to_string(),
+        )));
+
+        let response = HttpClient::new().execute(&request).unwrap();
+        assert_eq!(response.status.as_u16(), 201);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_execute_streams_multipart_upload() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let contents = "0123456789abcdef".repeat(128 * 1024); // 2 MB
+        file.write_all(contents.as_bytes()).unwrap();
+        file.write_all(b"END-OF-FILE").unwrap();
+
+        let form = crate::upload::FormData::new().with_file(
+            "upload".to_string(),
+            file.path().to_str().unwrap().to_string(),
+        );
+        let expected_len = crate::upload::MultipartBuilder::from_form_data(&form)
+            .unwrap()
+            .build()
+            .unwrap()
+            .len();
+
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/upload")
+            .match_header("content-length", expected_len.to_string().as_str())
+            .match_header(
+                "content-type",
+                mockito::Matcher::Regex("^multipart/form-data; boundary=".to_string()),
+            )
+            .match_body(mockito::Matcher::Regex(
+                "(?s)name=\"upload\".*0123456789abcdef.*END-OF-FILE\r\n--".to_string(),
+            ))
+            .with_status(201)
+            .create();
+
+        let request = RequestBuilder::new(
+            crate::http::HttpMethod::Post,
+            format!("{}/upload", server.url()),
+        )
+        .form(form);
+        let response = HttpClient::new().execute(&request).unwrap();
+        assert_eq!(response.status.as_u16(), 201);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_execute_cookie_jar_keeps_explicit_cookie_header() {
+        let mut server = mockito::Server::new();
+        let _login = server
+            .mock("GET", "/login")
+            .with_header("set-cookie", "session=abc123")
+            .create();
+        let explicit = server
+            .mock("GET", "/me")
+            .match_header("cookie", "session=mine")
+            .create();
+
+        let jar = Arc::new(Mutex::new(CookieJar::new()));
+        let client = HttpClient::new().with_cookie_jar(jar.clone());
+        let get = |path: &str| {
+            RequestBuilder::new(
+                crate::http::HttpMethod::Get,
+                format!("{}{}", server.url(), path),
+            )
+        };
+
+        client.execute(&get("/login")).unwrap();
+        assert_eq!(jar.lock().unwrap().get("session").unwrap().value, "abc123");
+
+        client
+            .execute(&get("/me").header("Cookie:session=mine".to_string()))
+            .unwrap();
+        explicit.assert();
+    }
+
+    #[test]
+    fn test_execute_detects_body_content_type() {
+        let mut server = mockito::Server::new();
+        let json = server
+            .mock("POST", "/json")
+            .match_header("content-type", "application/json")
+            .create();
+        let text = server
+            .mock("POST", "/text")
+            .match_header("content-type", "text/plain")
+            .create();
+        let explicit = server
+            .mock("POST", "/explicit")
+            .match_header("content-type", "application/vnd.api+json")
+            .create();
+
+        let client = HttpClient::new();
+        let post = |path: &str, body: &str| {
+            RequestBuilder::new(
+                crate::http::HttpMethod::Post,
+                format!("{}{}", server.url(), path),
+            )
+            .body(body.to_string())
+        };
+
+        client.execute(&post("/json", r#"{"a":1}"#)).unwrap();
+        client.execute(&post("/text", "hello")).unwrap();
+        client
+            .execute(
+                &post("/explicit", r#"{"a":1}"#)
+                    .header("content-type: application/vnd.api+json".to_string()),
+            )
+            .unwrap();
+        json.assert();
+        text.assert();
+        explicit.assert();
+    }
+
+    #[test]
+    fn test_execute_applies_default_headers() {
+        let mut server = mockito::Server::new();
+        let default = server
+            .mock("GET", "/default")
+            .match_header("accept", "application/json")
+            .create();
+        let overridden = server
+            .mock("GET", "/override")
+            .match_header("accept", "text/html")
+            .create();
+
+        let client =
+            HttpClient::new().with_default_headers(vec!["Accept: application/json".to_string()]);
+        let get = |path: &str| {
+            RequestBuilder::new(
+                crate::http::HttpMethod::Get,
+                format!("{}{}", server.url(), path),
+            )
+        };
+
+        client.execute(&get("/default")).unwrap();
+        client
+            .execute(&get("/override").header("ACCEPT: text/html".to_string()))
+            .unwrap();
+        default.assert();
+        overridden.assert();
+    }
+
+    #[test]
+    fn test_execute_sends_user_agent() {
+        let mut server = mockito::Server::new();
+        let default = server
+            .mock("GET", "/default")
+            .match_header("user-agent", DEFAULT_USER_AGENT)
+            .create();
+        let custom = server
+            .mock("GET", "/custom")
+            .match_header("user-agent", "team-cli/2.0")
+            .create();
+        let overridden = server
+            .mock("GET", "/override")
+            .match_header("user-agent", "curl/8.0")
+            .create();
+        let get = |path: &str| {
+            RequestBuilder::new(
+                crate::http::HttpMethod::Get,
+                format!("{}{}", server.url(), path),
+            )
+        };
+
+        assert!(DEFAULT_USER_AGENT.starts_with("bazzounquester/"));
+        HttpClient::new().execute(&get("/default")).unwrap();
+        let client = HttpClient::new().with_user_agent("team-cli/2.0").unwrap();
+        assert_eq!(client.user_agent(), "team-cli/2.0");
+        client.execute(&get("/custom")).unwrap();
+        client
+            .execute(&get("/override").header("User-Agent: curl/8.0".to_string()))
+            .unwrap();
+        default.assert();
+        custom.assert();
+        overridden.assert();
+
+        let error = HttpClient::new()
+            .with_user_agent("bad\x01ua")
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::InvalidHeader(_)), "{}", error);
+    }
+
+    #[test]
+    fn test_execute_sends_repeated_query_keys() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/items")
+            .match_query(mockito::Matcher::Exact("tag=a&tag=b&page=2".to_string()))
+            .create();
+
+        let request = RequestBuilder::new(
+            crate::http::HttpMethod::Get,
+            format!("{}/items", server.url()),
+        )
+        .query("tag=a".to_string())
+        .query("tag=b".to_string())
+        .query("page=2".to_string());
+        HttpClient::new().execute(&request).unwrap();
+        mock.assert();
+    }
+
+    #[test]
+    fn test_execute_encodes_query_values() {
+        let mut server = mockito::Server::new();
+        let encoded = server
+            .mock("GET", "/search")
+            .match_query(mockito::Matcher::Exact(
+                "q=rock+%26+roll&filter=a%3Db".to_string(),
+            ))
+            .create();
+        let raw = server
+            .mock("GET", "/raw")
+            .match_query(mockito::Matcher::Exact("q=rock%20roll".to_string()))
+            .create();
+
+        let client = HttpClient::new();
+        let get = |path: &str, param: &str| {
+            RequestBuilder::new(
+                crate::http::HttpMethod::Get,
+                format!("{}{}", server.url(), path),
+            )
+            .query(param.to_string())
+        };
+
+        client
+            .execute(&get("/search", "q=rock & roll").query("filter=a=b".to_string()))
+            .unwrap();
+        client
+            .execute(&get("/raw", "q=rock%20roll").raw_query(true))
+            .unwrap();
+        encoded.assert();
+        raw.assert();
+    }
+
+    #[test]
+    fn test_execute_without_following_redirects() {
+        let mut server = mockito::Server::new();
+        let _old = server
+            .mock("GET", "/old")
+            .with_status(301)
+            .with_header("location", "/new")
+            .create();
+        let new = server
+            .mock("GET", "/new")
+            .with_body("moved")
+            .expect(1)
+            .create();
+
+        let request = RequestBuilder::new(
+            crate::http::HttpMethod::Get,
+            format!("{}/old", server.url()),
+        );
+        let response = HttpClient::new()
+            .with_follow_redirects(false)
+            .execute(&request)
+            .unwrap();
+        assert_eq!(response.status.as_u16(), 301);
+
+        let response = HttpClient::new().execute(&request).unwrap();
+        assert_eq!(response.body, "moved");
+        new.assert();
+    }
+
+    #[test]
+    fn test_execute_keeps_cookies_set_by_redirects() {
+        let mut server = mockito::Server::new();
+        let _login = server
+            .mock("GET", "/login")
+            .with_status(302)
+            .with_header("set-cookie", "sid=abc; Path=/")
+            .with_header("location", "/me")
+            .create();
+        let me = server
+            .mock("GET", "/me")
+            .match_header("cookie", "sid=abc")
+            .with_body("ada")
+            .expect(2)
+            .create();
+
+        let jar = Arc::new(Mutex::new(CookieJar::new()));
+        let client = HttpClient::new().with_cookie_jar(jar.clone());
+        let get = |path: &str| {
+            RequestBuilder::new(
+                crate::http::HttpMethod::Get,
+                format!("{}{}", server.url(), path),
+            )
+        };
+
+        let response = client.execute(&get("/login")).unwrap();
+        assert_eq!(response.body, "ada");
+        assert_eq!(jar.lock().unwrap().count(), 1);
+
+        // Later requests send the cookie too
+        client.execute(&get("/me")).unwrap();
+        me.assert();
+    }
+
+    #[test]
+    fn test_execute_redirect_methods() {
+        let mut server = mockito::Server::new();
+        let _see_other = server
+            .mock("POST", "/orders")
+            .with_status(303)
+            .with_header("location", "/orders/1")
+            .create();
+        let order = server
+            .mock("GET", "/orders/1")
+            .match_body("")
+            .with_body("created")
+            .create();
+        let _temporary = server
+            .mock("POST", "/v1/orders")
+            .with_status(307)
+            .with_header("location", "/v2/orders")
+            .create();
+        let v2 = server
+            .mock("POST", "/v2/orders")
+            .match_body(r#"{"qty":2}"#)
+            .with_body("v2")
+            .create();
+        let _looping = server
+            .mock("GET", "/loop")
+            .with_status(302)
+            .with_header("location", "/loop")
+            .create();
+
+        let post = |path: &str| {
+            RequestBuilder::new(
+                crate::http::HttpMethod::Post,
+                format!("{}{}", server.url(), path),
+            )
+            .body(r#"{"qty":2}"#.to_string())
+        };
+        let client = HttpClient::new();
+        assert_eq!(client.execute(&post("/orders")).unwrap().body, "created");
+        assert_eq!(client.execute(&post("/v1/orders")).unwrap().body, "v2");
+        order.assert();
+        v2.assert();
+
+        let looping = RequestBuilder::new(
+            crate::http::HttpMethod::Get,
+            format!("{}/loop", server.url()),
+        );
+        assert!(matches!(
+            client.execute(&looping),
+            Err(Error::TooManyRedirects(MAX_REDIRECTS))
+        ));
+    }
+
+    /// Start a server that accepts connections but never answers
+    fn silent_server() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut held = Vec::new();
+            for stream in listener.incoming().flatten() {
+                held.push(stream);
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_execute_default_timeout() {
+        let client = HttpClient::new().with_timeout(std::time::Duration::from_millis(200));
+        let request = RequestBuilder::new(crate::http::HttpMethod::Get, silent_server());
+
+        let started = Instant::now();
+        assert!(matches!(client.execute(&request), Err(Error::Timeout(_))));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_execute_deadline_wins_over_request_timeout() {
+        let client =
+            HttpClient::new().with_deadline(Deadline::after(std::time::Duration::from_millis(300)));
+        let request = RequestBuilder::new(crate::http::HttpMethod::Get, silent_server())
+            .timeout(std::time::Duration::from_secs(30));
+
+        let started = Instant::now();
+        let err = client.execute(&request).unwrap_err();
+        assert!(matches!(err, Error::DeadlineExceeded(_)));
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+
+        // Nothing is sent once the deadline has passed
+        let err = client.execute(&request).unwrap_err();
+        assert!(err.to_string().contains("--max-time"));
+    }
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn test_client_cert_missing_files() {
+        let result = HttpClient::new().with_client_cert(
+            &fixture("does-not-exist.crt"),
+            &fixture("does-not-exist.key"),
+        );
+        let err = result.err().unwrap();
+        assert!(matches!(err, Error::Certificate(_)));
+        assert!(err.to_string().contains("does-not-exist.crt"));
+    }
+
+    #[test]
+    fn test_client_cert_malformed() {
+        // Swapping cert and key makes both unparsable in their roles
+        let result =
+            HttpClient::new().with_client_cert(&fixture("client.key"), &fixture("client.crt"));
+        assert!(matches!(result.err().unwrap(), Error::Certificate(_)));
+    }
+
+    #[test]
+    fn test_client_cert_valid_identity() {
+        let client = HttpClient::new()
+            .with_client_cert(&fixture("client.crt"), &fixture("client.key"))
+            .unwrap();
+        assert!(client.has_client_cert());
+
+        // Settings survive a rebuild
+        let client = client.with_danger_accept_invalid_certs(true);
+        assert!(client.has_client_cert());
+    }
+
+    #[test]
+    fn test_ca_cert() {
+        let client = HttpClient::new()
+            .with_ca_cert(&fixture("client.crt"))
+            .unwrap();
+        assert_eq!(client.ca_cert_count(), 1);
+
+        let err = HttpClient::new()
+            .with_ca_cert(&fixture("client.key"))
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Certificate(_)));
+        assert!(err.to_string().contains("client.key"));
+
+        let err = HttpClient::new()
+            .with_ca_cert(&fixture("missing-ca.pem"))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Could not read CA certificate"));
     }
 
     // Integration tests would go here with a mock server
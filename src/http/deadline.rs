@@ -0,0 +1,120 @@
+//! Wall-clock budget shared by every request of one invocation
+
+use std::time::{Duration, Instant};
+
+/// A fixed point in time after which no more requests are sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline {
+    start: Instant,
+    budget: Duration,
+}
+
+impl Deadline {
+    /// Create a deadline `budget` from now
+    pub fn after(budget: Duration) -> Self {
+        Self {
+            start: Instant::now(),
+            budget,
+        }
+    }
+
+    /// Get the total budget
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Time left before the deadline (zero once it has passed)
+    pub fn remaining(&self) -> Duration {
+        self.budget.saturating_sub(self.start.elapsed())
+    }
+
+    /// Check whether the deadline has passed
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Cap a timeout by the remaining time; the deadline always wins
+    pub fn cap(&self, timeout: Option<Duration>) -> Duration {
+        match timeout {
+            Some(timeout) => timeout.min(self.remaining()),
+            None => self.remaining(),
+        }
+    }
+}
+
+/// Parse a duration like `60s`, `500ms`, `2m`, `1h`, `30d`, or plain seconds
+pub fn parse_duration(input: &str) -> std::result::Result<Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}' (e.g. 30s, 500ms, 2m)", input))?;
+    let seconds = match unit.trim() {
+        "" | "s" => value,
+        "ms" => value / 1000.0,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        "d" => value * 86400.0,
+        other => {
+            return Err(format!(
+                "unknown duration unit '{}' in '{}' (use ms, s, m, h, or d)",
+                other, input
+            ))
+        }
+    };
+
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("duration out of range: {}", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("60s").unwrap(), Duration::from_secs(60));
+        assert_eq!(parse_duration("60").unwrap(), Duration::from_secs(60));
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+        assert_eq!(parse_duration("1.5h").unwrap(), Duration::from_secs(5400));
+        assert!(parse_duration("soon").is_err());
+        assert_eq!(
+            parse_duration("30d").unwrap(),
+            Duration::from_secs(30 * 86400)
+        );
+        assert!(parse_duration("5w")
+            .unwrap_err()
+            .contains("unknown duration unit"));
+        assert_eq!(
+            parse_duration("99999999999999999999999d").unwrap_err(),
+            "duration out of range: 99999999999999999999999d"
+        );
+    }
+
+    #[test]
+    fn test_deadline_remaining() {
+        let deadline = Deadline::after(Duration::from_secs(60));
+        assert!(!deadline.is_expired());
+        assert!(deadline.remaining() <= Duration::from_secs(60));
+        assert_eq!(deadline.budget(), Duration::from_secs(60));
+
+        let expired = Deadline::after(Duration::ZERO);
+        assert!(expired.is_expired());
+        assert_eq!(expired.remaining(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_deadline_cap() {
+        let deadline = Deadline::after(Duration::from_secs(60));
+        assert_eq!(
+            deadline.cap(Some(Duration::from_secs(5))),
+            Duration::from_secs(5)
+        );
+        assert!(deadline.cap(Some(Duration::from_secs(600))) <= Duration::from_secs(60));
+        assert!(deadline.cap(None) <= Duration::from_secs(60));
+    }
+}
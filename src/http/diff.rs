@@ -0,0 +1,390 @@
+//! Differences between two responses: status, headers, and body
+
+use crate::assertions::diff::{json_changes, show, text_diff, JsonChange};
+use crate::http::HttpResponse;
+use crate::style::Colorize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Headers that change on every response and are ignored by default
+pub const DEFAULT_IGNORED_HEADERS: &[&str] = &[
+    "date",
+    "age",
+    "expires",
+    "request-id",
+    "x-request-id",
+    "x-correlation-id",
+    "x-trace-id",
+    "x-amzn-requestid",
+    "x-amzn-trace-id",
+    "traceparent",
+    "server-timing",
+];
+
+/// A header that differs between two responses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderChange {
+    /// Only in the new response
+    Added { name: String, value: String },
+
+    /// Only in the old response
+    Removed { name: String, value: String },
+
+    /// In both, with different values
+    Changed {
+        name: String,
+        old: String,
+        new: String,
+    },
+}
+
+/// How two bodies differ
+#[derive(Debug, Clone, PartialEq)]
+pub enum BodyDiff {
+    /// Identical text, or equal JSON
+    Same,
+
+    /// Differing JSON values, by path
+    Json(Vec<JsonChange>),
+
+    /// Line diff with context, for bodies that aren't both JSON
+    Text(Vec<String>),
+}
+
+/// Everything that differs between an old and a new response
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseDiff {
+    /// Old and new status codes, if they differ
+    pub status: Option<(u16, u16)>,
+
+    /// Header changes, by name
+    pub headers: Vec<HeaderChange>,
+
+    /// Body changes
+    pub body: BodyDiff,
+}
+
+impl ResponseDiff {
+    /// Diff two responses, ignoring the default noisy headers
+    pub fn between(old: &HttpResponse, new: &HttpResponse) -> Self {
+        ResponseDiffer::new().diff(old, new)
+    }
+
+    /// Whether the responses are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.headers.is_empty() && self.body == BodyDiff::Same
+    }
+
+    /// Render the differences, one per line
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return format!("{}\n", "No differences".green());
+        }
+
+        let mut output = String::new();
+        if let Some((old, new)) = self.status {
+            output.push_str(&format!("{} {} -> {}\n", "Status:".bold(), old, new));
+        }
+
+        if !self.headers.is_empty() {
+            output.push_str(&format!("{}\n", "Headers:".bold()));
+            for change in &self.headers {
+                let line = match change {
+                    HeaderChange::Added { name, value } => format!("+ {}: {}", name, value).green(),
+                    HeaderChange::Removed { name, value } => format!("- {}: {}", name, value).red(),
+                    HeaderChange::Changed { name, old, new } => {
+                        format!("~ {}: {} -> {}", name, old, new).cyan()
+                    }
+                };
+                output.push_str(&format!("  {}\n", line));
+            }
+        }
+
+        match &self.body {
+            BodyDiff::Same => {}
+            BodyDiff::Json(changes) => {
+                output.push_str(&format!("{}\n", "Body:".bold()));
+                for change in changes {
+                    let line = match (&change.old, &change.new) {
+                        (Some(old), Some(new)) => {
+                            format!("~ {}: {} -> {}", change.path, show(old), show(new)).cyan()
+                        }
+                        (Some(old), None) => format!("- {}: {}", change.path, show(old)).red(),
+                        (None, new) => format!(
+                            "+ {}: {}",
+                            change.path,
+                            show(new.as_ref().unwrap_or(&Value::Null))
+                        )
+                        .green(),
+                    };
+                    output.push_str(&format!("  {}\n", line));
+                }
+            }
+            BodyDiff::Text(lines) => {
+                output.push_str(&format!("{}\n", "Body:".bold()));
+                for line in lines {
+                    let line = if line.starts_with('+') {
+                        line.green()
+                    } else if line.starts_with('-') {
+                        line.red()
+                    } else {
+                        line.dimmed()
+                    };
+                    output.push_str(&format!("  {}\n", line));
+                }
+            }
+        }
+        output
+    }
+}
+
+/// Compares responses, skipping headers on an ignore list
+#[derive(Debug, Clone)]
+pub struct ResponseDiffer {
+    ignored_headers: Vec<String>,
+}
+
+impl ResponseDiffer {
+    /// Create a differ that ignores `DEFAULT_IGNORED_HEADERS`
+    pub fn new() -> Self {
+        Self {
+            ignored_headers: DEFAULT_IGNORED_HEADERS
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+        }
+    }
+
+    /// Replace the ignored header names (case-insensitive)
+    pub fn with_ignored_headers(mut self, headers: Vec<String>) -> Self {
+        self.ignored_headers = headers.into_iter().map(|h| h.to_lowercase()).collect();
+        self
+    }
+
+    /// Compare every header, including the default noisy ones
+    pub fn with_all_headers(self) -> Self {
+        self.with_ignored_headers(Vec::new())
+    }
+
+    /// Ignore one more header
+    pub fn ignore_header(mut self, name: &str) -> Self {
+        self.ignored_headers.push(name.to_lowercase());
+        self
+    }
+
+    /// Diff an old response against a new one
+    pub fn diff(&self, old: &HttpResponse, new: &HttpResponse) -> ResponseDiff {
+        let status = (old.status != new.status).then(|| (old.status.as_u16(), new.status.as_u16()));
+
+        ResponseDiff {
+            status,
+            headers: self.diff_headers(old, new),
+            body: diff_bodies(&old.body, &new.body),
+        }
+    }
+
+    fn diff_headers(&self, old: &HttpResponse, new: &HttpResponse) -> Vec<HeaderChange> {
+        let old = self.header_values(old);
+        let new = self.header_values(new);
+
+        let names: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+        names
+            .into_iter()
+            .filter_map(|name| match (old.get(name), new.get(name)) {
+                (Some(o), Some(n)) if o != n => Some(HeaderChange::Changed {
+                    name: name.clone(),
+                    old: o.clone(),
+                    new: n.clone(),
+                }),
+                (Some(o), None) => Some(HeaderChange::Removed {
+                    name: name.clone(),
+                    value: o.clone(),
+                }),
+                (None, Some(n)) => Some(HeaderChange::Added {
+                    name: name.clone(),
+                    value: n.clone(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Header values by lowercase name, repeated headers joined with ", "
+    fn header_values(&self, response: &HttpResponse) -> BTreeMap<String, String> {
+        let mut values: BTreeMap<String, String> = BTreeMap::new();
+        for (name, value) in &response.headers {
+            let name = name.as_str().to_lowercase();
+            if self.ignored_headers.contains(&name) {
+                continue;
+            }
+            let value = value.to_str().unwrap_or_default();
+            values
+                .entry(name)
+                .and_modify(|v| {
+                    v.push_str(", ");
+                    v.push_str(value);
+                })
+                .or_insert_with(|| value.to_string());
+        }
+        values
+    }
+}
+
+impl Default for ResponseDiffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structural diff when both bodies are JSON, line diff otherwise
+fn diff_bodies(old: &str, new: &str) -> BodyDiff {
+    if let (Ok(old_json), Ok(new_json)) = (
+        serde_json::from_str::<Value>(old),
+        serde_json::from_str::<Value>(new),
+    ) {
+        let mut changes = Vec::new();
+        json_changes("$", &old_json, &new_json, &mut changes);
+        return if changes.is_empty() {
+            BodyDiff::Same
+        } else {
+            BodyDiff::Json(changes)
+        };
+    }
+
+    match text_diff(old, new) {
+        lines if lines.is_empty() => BodyDiff::Same,
+        lines => BodyDiff::Text(lines),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    fn response(status: u16, headers: &[(&'static str, &str)], body: &str) -> HttpResponse {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        HttpResponse {
+            status: StatusCode::from_u16(status).unwrap(),
+            headers: map,
+            body: body.to_string(),
+            body_bytes: body.as_bytes().to_vec(),
+            duration: Duration::ZERO,
+            timing: None,
+        }
+    }
+
+    #[test]
+    fn test_json_body_diff_by_path() {
+        let old = response(
+            200,
+            &[],
+            r#"{"items":[{"price":1},{"price":5},{"price":10}]}"#,
+        );
+        let new = response(
+            200,
+            &[],
+            r#"{"items":[{"price":1},{"price":5},{"price":12}]}"#,
+        );
+
+        let diff = ResponseDiff::between(&old, &new);
+        assert!(diff.status.is_none());
+        let BodyDiff::Json(changes) = &diff.body else {
+            panic!("expected a JSON diff");
+        };
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "$.items[2].price");
+        assert!(diff.render().contains("$.items[2].price: 10 -> 12"));
+    }
+
+    #[test]
+    fn test_headers_ignore_noise() {
+        let old = response(
+            200,
+            &[
+                ("date", "Mon, 01 Jan 2024 00:00:00 GMT"),
+                ("x-request-id", "a"),
+                ("content-type", "application/json"),
+                ("x-old", "1"),
+            ],
+            "",
+        );
+        let new = response(
+            500,
+            &[
+                ("date", "Tue, 02 Jan 2024 00:00:00 GMT"),
+                ("x-request-id", "b"),
+                ("content-type", "text/plain"),
+                ("x-new", "2"),
+            ],
+            "",
+        );
+
+        let diff = ResponseDiff::between(&old, &new);
+        assert_eq!(diff.status, Some((200, 500)));
+        assert_eq!(
+            diff.headers,
+            vec![
+                HeaderChange::Changed {
+                    name: "content-type".to_string(),
+                    old: "application/json".to_string(),
+                    new: "text/plain".to_string(),
+                },
+                HeaderChange::Added {
+                    name: "x-new".to_string(),
+                    value: "2".to_string(),
+                },
+                HeaderChange::Removed {
+                    name: "x-old".to_string(),
+                    value: "1".to_string(),
+                },
+            ]
+        );
+
+        // A custom ignore list replaces the defaults
+        let diff = ResponseDiffer::new()
+            .with_ignored_headers(vec!["Content-Type".to_string()])
+            .ignore_header("x-new")
+            .diff(&old, &new);
+        let names: Vec<&str> = diff
+            .headers
+            .iter()
+            .map(|c| match c {
+                HeaderChange::Added { name, .. }
+                | HeaderChange::Removed { name, .. }
+                | HeaderChange::Changed { name, .. } => name.as_str(),
+            })
+            .collect();
+        assert_eq!(names, vec!["date", "x-old", "x-request-id"]);
+    }
+
+    #[test]
+    fn test_text_body_and_equal_json() {
+        let diff = ResponseDiff::between(
+            &response(200, &[], "one\ntwo\nthree"),
+            &response(200, &[], "one\nTWO\nthree"),
+        );
+        assert_eq!(
+            diff.body,
+            BodyDiff::Text(vec![
+                "  one".to_string(),
+                "- two".to_string(),
+                "+ TWO".to_string(),
+                "  three".to_string(),
+            ])
+        );
+
+        // Formatting differences in JSON don't count
+        let same = ResponseDiff::between(
+            &response(200, &[], r#"{"a": 1}"#),
+            &response(200, &[], r#"{"a":1}"#),
+        );
+        assert!(same.is_empty());
+        assert!(same.render().contains("No differences"));
+    }
+}
@@ -0,0 +1,124 @@
+//! GraphQL over HTTP: request bodies and response errors
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// Build the conventional `{"query", "variables", "operationName"}` body
+///
+/// `variables` and `operationName` are left out when not given.
+pub fn request_body(query: &str, variables: Option<Value>, operation_name: Option<&str>) -> Value {
+    let mut body = Map::new();
+    body.insert("query".to_string(), json!(query));
+    if let Some(variables) = variables {
+        body.insert("variables".to_string(), variables);
+    }
+    if let Some(name) = operation_name {
+        body.insert("operationName".to_string(), json!(name));
+    }
+    Value::Object(body)
+}
+
+/// One entry of a GraphQL response's `errors` array
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GraphqlError {
+    /// Human-readable description
+    pub message: String,
+
+    /// Path to the failing field, e.g. `["user", "posts", 0]`
+    #[serde(default)]
+    pub path: Vec<Value>,
+
+    /// Server-specific details such as an error code
+    #[serde(default)]
+    pub extensions: Option<Value>,
+}
+
+impl fmt::Display for GraphqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if !self.path.is_empty() {
+            let path: Vec<String> = self
+                .path
+                .iter()
+                .map(|segment| match segment {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect();
+            write!(f, " (at {})", path.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse the `errors` array of a GraphQL response body
+///
+/// Returns an empty list when the body isn't JSON or has no errors. Entries
+/// without a `message` are kept, using their JSON as the message.
+pub fn parse_errors(body: &str) -> Vec<GraphqlError> {
+    let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(body) else {
+        return Vec::new();
+    };
+    let Some(Value::Array(errors)) = object.remove("errors") else {
+        return Vec::new();
+    };
+
+    errors
+        .into_iter()
+        .map(|error| {
+            serde_json::from_value(error.clone()).unwrap_or_else(|_| GraphqlError {
+                message: error.to_string(),
+                path: Vec::new(),
+                extensions: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_body_shape() {
+        let body = request_body(
+            "query User($id: ID!) { user(id: $id) { name } }",
+            Some(json!({"id": "42"})),
+            Some("User"),
+        );
+        assert_eq!(
+            body,
+            json!({
+                "query": "query User($id: ID!) { user(id: $id) { name } }",
+                "variables": {"id": "42"},
+                "operationName": "User"
+            })
+        );
+
+        assert_eq!(
+            request_body("{ me { id } }", None, None),
+            json!({"query": "{ me { id } }"})
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let body = r#"{
+            "data": {"user": null},
+            "errors": [
+                {"message": "Not authorized", "path": ["user", "posts", 0], "extensions": {"code": "FORBIDDEN"}},
+                {"unexpected": true}
+            ]
+        }"#;
+
+        let errors = parse_errors(body);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].to_string(), "Not authorized (at user.posts.0)");
+        assert_eq!(errors[0].extensions, Some(json!({"code": "FORBIDDEN"})));
+        assert_eq!(errors[1].message, r#"{"unexpected":true}"#);
+
+        assert!(parse_errors(r#"{"data": {"me": {"id": 1}}}"#).is_empty());
+        assert!(parse_errors("<html>").is_empty());
+    }
+}
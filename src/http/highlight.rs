@@ -0,0 +1,82 @@
+//! JSON syntax highlighting for terminal output
+
+use crate::style::Colorize;
+
+/// Color the tokens of JSON text: keys, strings, numbers, booleans and null
+///
+/// Punctuation and whitespace are left alone, so a truncated document still
+/// highlights cleanly. Whether colors are emitted follows the global color
+/// choice (`--color`, `NO_COLOR`).
+pub fn highlight_json(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let len = match c {
+            '"' => string_len(rest),
+            '-' | '0'..='9' => rest
+                .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+                .unwrap_or(rest.len()),
+            'a'..='z' => rest
+                .find(|c: char| !c.is_ascii_lowercase())
+                .unwrap_or(rest.len()),
+            _ => c.len_utf8(),
+        };
+        let (token, tail) = rest.split_at(len);
+
+        let colored = match c {
+            '"' if tail.trim_start().starts_with(':') => token.blue().bold().to_string(),
+            '"' => token.green().to_string(),
+            '-' | '0'..='9' => token.color("yellow").to_string(),
+            _ if matches!(token, "true" | "false" | "null") => token.color("magenta").to_string(),
+            _ => token.to_string(),
+        };
+        out.push_str(&colored);
+        rest = tail;
+    }
+
+    out
+}
+
+/// Length of the string literal at the start of `text`, quotes included
+///
+/// An unterminated string runs to the end of the text.
+fn string_len(text: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return i + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Remove ANSI escape sequences
+    fn strip_ansi(text: &str) -> String {
+        regex::Regex::new(r"\x1b\[[0-9;]*m")
+            .unwrap()
+            .replace_all(text, "")
+            .into_owned()
+    }
+
+    #[test]
+    fn test_highlight_preserves_text() {
+        let json = "{\n  \"name\": \"a \\\"quoted\\\" : value\",\n  \"n\": -1.5e3,\n  \"ok\": true,\n  \"none\": null,\n  \"list\": [1, \"é\"]\n}";
+        assert_eq!(strip_ansi(&highlight_json(json)), json);
+    }
+
+    #[test]
+    fn test_highlight_truncated_text() {
+        let truncated = "{\n  \"name\": \"unfinish";
+        assert_eq!(strip_ansi(&highlight_json(truncated)), truncated);
+        assert_eq!(string_len("\"abc"), 4);
+        assert_eq!(string_len("\"a\\\"b\" rest"), 6);
+    }
+}
@@ -0,0 +1,193 @@
+//! Host and port parsing shared by cookies, signing, and URL display
+
+use crate::error::{Error, Result};
+use std::net::IpAddr;
+use url::{Host, Url};
+
+/// The host part of a URL, normalized for comparisons
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostInfo {
+    /// Lowercase scheme
+    pub scheme: String,
+
+    /// Lowercase host name, or an IP address without brackets
+    pub host: String,
+
+    /// Explicit port, or the scheme's default
+    pub port: Option<u16>,
+
+    /// Whether the host is an IPv4 or IPv6 literal
+    pub is_ip: bool,
+}
+
+impl HostInfo {
+    /// Parse the host of a URL string
+    pub fn parse(url: &str) -> Result<Self> {
+        let parsed = Url::parse(url.trim())
+            .map_err(|e| Error::InvalidUrl(format!("'{}': {}", url.trim(), e)))?;
+        Self::from_url(&parsed)
+            .ok_or_else(|| Error::InvalidUrl(format!("'{}' has no host", url.trim())))
+    }
+
+    /// Get the host of a parsed URL
+    pub fn from_url(url: &Url) -> Option<Self> {
+        let (host, is_ip) = match url.host()? {
+            Host::Domain(domain) => (domain.to_lowercase(), false),
+            Host::Ipv4(ip) => (ip.to_string(), true),
+            Host::Ipv6(ip) => (ip.to_string(), true),
+        };
+
+        Some(Self {
+            scheme: url.scheme().to_string(),
+            host,
+            port: url.port_or_known_default(),
+            is_ip,
+        })
+    }
+
+    /// Whether the port is the scheme's default
+    fn has_default_port(&self) -> bool {
+        let default = match self.scheme.as_str() {
+            "http" => Some(80),
+            "https" => Some(443),
+            _ => None,
+        };
+        self.port == default
+    }
+
+    /// Host as written in a URL or Host header (IPv6 in brackets)
+    pub fn host_literal(&self) -> String {
+        if self.host.parse::<IpAddr>().is_ok_and(|ip| ip.is_ipv6()) {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        }
+    }
+
+    /// `host[:port]` as sent in the Host header; default ports are omitted
+    pub fn authority(&self) -> String {
+        match self.port {
+            Some(port) if !self.has_default_port() => format!("{}:{}", self.host_literal(), port),
+            _ => self.host_literal(),
+        }
+    }
+}
+
+/// Normalize a URL for display and comparison
+///
+/// Lowercases the scheme and host, drops default ports and userinfo, and
+/// keeps the path, query, and fragment as-is.
+pub fn normalize_url(url: &str) -> Result<String> {
+    let mut parsed = Url::parse(url.trim())
+        .map_err(|e| Error::InvalidUrl(format!("'{}': {}", url.trim(), e)))?;
+    // Both only fail for URLs that cannot have credentials, which have none to drop
+    parsed.set_username("").ok();
+    parsed.set_password(None).ok();
+    Ok(parsed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_info_matrix() {
+        // (url, host, port, is_ip, authority)
+        let cases = [
+            ("http://[::1]/health", "::1", Some(80), true, "[::1]"),
+            (
+                "http://[::1]:8080/health",
+                "::1",
+                Some(8080),
+                true,
+                "[::1]:8080",
+            ),
+            (
+                "https://[2001:DB8::1]:8443/",
+                "2001:db8::1",
+                Some(8443),
+                true,
+                "[2001:db8::1]:8443",
+            ),
+            (
+                "https://[2001:db8::1]:443/",
+                "2001:db8::1",
+                Some(443),
+                true,
+                "[2001:db8::1]",
+            ),
+            (
+                "http://127.0.0.1:3000",
+                "127.0.0.1",
+                Some(3000),
+                true,
+                "127.0.0.1:3000",
+            ),
+            (
+                "HTTPS://API.Example.COM/x",
+                "api.example.com",
+                Some(443),
+                false,
+                "api.example.com",
+            ),
+            (
+                "http://Example.com:8080/",
+                "example.com",
+                Some(8080),
+                false,
+                "example.com:8080",
+            ),
+            (
+                "https://user:pw@example.com/",
+                "example.com",
+                Some(443),
+                false,
+                "example.com",
+            ),
+        ];
+
+        for (url, host, port, is_ip, authority) in cases {
+            let info = HostInfo::parse(url).unwrap();
+            assert_eq!(info.host, host, "{}", url);
+            assert_eq!(info.port, port, "{}", url);
+            assert_eq!(info.is_ip, is_ip, "{}", url);
+            assert_eq!(info.authority(), authority, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_host_info_invalid() {
+        assert!(matches!(
+            HostInfo::parse("not a url"),
+            Err(Error::InvalidUrl(_))
+        ));
+        assert!(matches!(
+            HostInfo::parse("mailto:a@b.c"),
+            Err(Error::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_normalizes_host_and_port() {
+        let a = HostInfo::parse("http://[::1]:8080/a").unwrap();
+        let b = HostInfo::parse("http://[0:0:0:0:0:0:0:1]:8080/b").unwrap();
+        assert_eq!((a.host, a.port), (b.host, b.port));
+
+        let upper = HostInfo::parse("https://EXAMPLE.com/").unwrap();
+        let lower = HostInfo::parse("https://example.com:443/").unwrap();
+        assert_eq!((upper.host, upper.port), (lower.host, lower.port));
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("HTTPS://User:Pw@API.Example.com:443/Users?q=1").unwrap(),
+            "https://api.example.com/Users?q=1"
+        );
+        assert_eq!(
+            normalize_url("http://[::1]:8080/health").unwrap(),
+            "http://[::1]:8080/health"
+        );
+        assert_eq!(normalize_url("http://[::1]:80").unwrap(), "http://[::1]/");
+    }
+}
@@ -0,0 +1,376 @@
+//! Image response detection and terminal previews
+
+use base64::Engine as _;
+
+/// Column width used for inline image previews
+const PREVIEW_WIDTH_CELLS: u32 = 40;
+
+/// Supported image formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    WebP,
+}
+
+impl ImageFormat {
+    /// Get format name
+    pub fn as_str(&self) -> &str {
+        match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Gif => "GIF",
+            ImageFormat::WebP => "WebP",
+        }
+    }
+}
+
+/// Metadata parsed from image header bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageInfo {
+    /// Image format
+    pub format: ImageFormat,
+
+    /// Width in pixels
+    pub width: u32,
+
+    /// Height in pixels
+    pub height: u32,
+
+    /// Size of the encoded image in bytes
+    pub size: usize,
+}
+
+impl ImageInfo {
+    /// Parse image metadata from raw bytes
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let (format, width, height) = parse_png(bytes)
+            .map(|(w, h)| (ImageFormat::Png, w, h))
+            .or_else(|| parse_gif(bytes).map(|(w, h)| (ImageFormat::Gif, w, h)))
+            .or_else(|| parse_webp(bytes).map(|(w, h)| (ImageFormat::WebP, w, h)))
+            .or_else(|| parse_jpeg(bytes).map(|(w, h)| (ImageFormat::Jpeg, w, h)))?;
+
+        Some(Self {
+            format,
+            width,
+            height,
+            size: bytes.len(),
+        })
+    }
+
+    /// Format a one-line metadata summary
+    pub fn summary(&self) -> String {
+        format!(
+            "{} image, {}x{}, {}",
+            self.format.as_str(),
+            self.width,
+            self.height,
+            format_size(self.size)
+        )
+    }
+}
+
+/// Terminal inline image protocols
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlineProtocol {
+    /// iTerm2 (also WezTerm and others) OSC 1337
+    Iterm2,
+
+    /// Kitty graphics protocol
+    Kitty,
+}
+
+impl InlineProtocol {
+    /// Detect a supported protocol from the process environment
+    pub fn detect() -> Option<Self> {
+        Self::detect_with(|key| std::env::var(key).ok())
+    }
+
+    /// Detect a supported protocol using the given environment lookup
+    pub fn detect_with<F: Fn(&str) -> Option<String>>(get_env: F) -> Option<Self> {
+        if get_env("KITTY_WINDOW_ID").is_some()
+            || get_env("TERM").is_some_and(|t| t.contains("kitty"))
+        {
+            return Some(InlineProtocol::Kitty);
+        }
+
+        let term_program = get_env("TERM_PROGRAM").unwrap_or_default();
+        if term_program == "iTerm.app"
+            || term_program == "WezTerm"
+            || get_env("LC_TERMINAL").is_some_and(|t| t == "iTerm2")
+        {
+            return Some(InlineProtocol::Iterm2);
+        }
+
+        None
+    }
+
+    /// Check whether this protocol can display the given format
+    pub fn supports(&self, format: ImageFormat) -> bool {
+        match self {
+            InlineProtocol::Iterm2 => true,
+            // Kitty only accepts PNG without decoding on our side
+            InlineProtocol::Kitty => format == ImageFormat::Png,
+        }
+    }
+
+    /// Build the escape sequence that displays an image scaled to the preview width
+    pub fn render(&self, bytes: &[u8]) -> String {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+
+        match self {
+            InlineProtocol::Iterm2 => format!(
+                "\x1b]1337;File=inline=1;size={};width={};preserveAspectRatio=1:{}\x07\n",
+                bytes.len(),
+                PREVIEW_WIDTH_CELLS,
+                encoded
+            ),
+            InlineProtocol::Kitty => {
+                let mut output = String::new();
+                let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+                for (i, chunk) in chunks.iter().enumerate() {
+                    let more = if i + 1 < chunks.len() { 1 } else { 0 };
+                    let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+                    if i == 0 {
+                        output.push_str(&format!(
+                            "\x1b_Gf=100,a=T,c={},m={};{}\x1b\\",
+                            PREVIEW_WIDTH_CELLS, more, chunk
+                        ));
+                    } else {
+                        output.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+                    }
+                }
+                output.push('\n');
+                output
+            }
+        }
+    }
+}
+
+/// Check whether a Content-Type header denotes an image
+pub fn is_image_content_type(content_type: &str) -> bool {
+    content_type
+        .trim()
+        .to_ascii_lowercase()
+        .starts_with("image/")
+}
+
+/// Format a byte count for display
+pub fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+
+    let size = bytes as f64;
+    if size >= MB {
+        format!("{:.1} MB", size / MB)
+    } else if size >= KB {
+        format!("{:.1} KB", size / KB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+fn parse_png(b: &[u8]) -> Option<(u32, u32)> {
+    if b.len() < 24 || &b[..8] != b"\x89PNG\r\n\x1a\n" || &b[12..16] != b"IHDR" {
+        return None;
+    }
+    Some((be_u32(&b[16..20]), be_u32(&b[20..24])))
+}
+
+fn parse_gif(b: &[u8]) -> Option<(u32, u32)> {
+    if b.len() < 10 || (&b[..6] != b"GIF87a" && &b[..6] != b"GIF89a") {
+        return None;
+    }
+    Some((le_u16(&b[6..8]) as u32, le_u16(&b[8..10]) as u32))
+}
+
+fn parse_webp(b: &[u8]) -> Option<(u32, u32)> {
+    if b.len() < 30 || &b[..4] != b"RIFF" || &b[8..12] != b"WEBP" {
+        return None;
+    }
+
+    match &b[12..16] {
+        b"VP8 " => Some((
+            (le_u16(&b[26..28]) & 0x3fff) as u32,
+            (le_u16(&b[28..30]) & 0x3fff) as u32,
+        )),
+        b"VP8L" => {
+            let bits = [b[21] as u32, b[22] as u32, b[23] as u32, b[24] as u32];
+            let width = 1 + (((bits[1] & 0x3f) << 8) | bits[0]);
+            let height = 1 + (((bits[3] & 0x0f) << 10) | (bits[2] << 2) | ((bits[1] & 0xc0) >> 6));
+            Some((width, height))
+        }
+        b"VP8X" => Some((1 + le_u24(&b[24..27]), 1 + le_u24(&b[27..30]))),
+        _ => None,
+    }
+}
+
+fn parse_jpeg(b: &[u8]) -> Option<(u32, u32)> {
+    if b.len() < 4 || b[0] != 0xff || b[1] != 0xd8 {
+        return None;
+    }
+
+    let mut i = 2;
+    while i + 3 < b.len() {
+        if b[i] != 0xff {
+            return None;
+        }
+        let marker = b[i + 1];
+
+        // Fill bytes and standalone markers carry no length
+        if marker == 0xff {
+            i += 1;
+            continue;
+        }
+        if marker == 0x01 || (0xd0..=0xd9).contains(&marker) {
+            i += 2;
+            continue;
+        }
+
+        let length = be_u16(&b[i + 2..i + 4]) as usize;
+        let is_sof = (0xc0..=0xcf).contains(&marker) && ![0xc4, 0xc8, 0xcc].contains(&marker);
+        if is_sof {
+            if i + 9 > b.len() {
+                return None;
+            }
+            let height = be_u16(&b[i + 5..i + 7]) as u32;
+            let width = be_u16(&b[i + 7..i + 9]) as u32;
+            return Some((width, height));
+        }
+
+        i += 2 + length;
+    }
+
+    None
+}
+
+fn be_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn be_u16(b: &[u8]) -> u16 {
+    u16::from_be_bytes([b[0], b[1]])
+}
+
+fn le_u16(b: &[u8]) -> u16 {
+    u16::from_le_bytes([b[0], b[1]])
+}
+
+fn le_u24(b: &[u8]) -> u32 {
+    b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1x1 transparent PNG
+    const PNG_1X1: &str =
+        "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+
+    // 1x1 GIF
+    const GIF_1X1: &str = "R0lGODlhAQABAIAAAAAAAP///yH5BAEAAAAALAAAAAABAAEAAAIBRAA7";
+
+    fn decode(data: &str) -> Vec<u8> {
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_png() {
+        let info = ImageInfo::parse(&decode(PNG_1X1)).unwrap();
+        assert_eq!(info.format, ImageFormat::Png);
+        assert_eq!((info.width, info.height), (1, 1));
+        assert_eq!(info.size, 70);
+    }
+
+    #[test]
+    fn test_parse_gif() {
+        let info = ImageInfo::parse(&decode(GIF_1X1)).unwrap();
+        assert_eq!(info.format, ImageFormat::Gif);
+        assert_eq!((info.width, info.height), (1, 1));
+    }
+
+    #[test]
+    fn test_parse_jpeg() {
+        let mut bytes = vec![0xff, 0xd8];
+        // APP0 segment to skip
+        bytes.extend_from_slice(&[0xff, 0xe0, 0x00, 0x04, 0x00, 0x00]);
+        // SOF0: length, precision, height=480, width=640
+        bytes.extend_from_slice(&[0xff, 0xc0, 0x00, 0x11, 0x08, 0x01, 0xe0, 0x02, 0x80]);
+        bytes.extend_from_slice(&[0x00; 12]);
+
+        let info = ImageInfo::parse(&bytes).unwrap();
+        assert_eq!(info.format, ImageFormat::Jpeg);
+        assert_eq!((info.width, info.height), (640, 480));
+    }
+
+    #[test]
+    fn test_parse_webp_vp8x() {
+        let mut bytes = b"RIFF\x00\x00\x00\x00WEBPVP8X\x0a\x00\x00\x00\x00\x00\x00\x00".to_vec();
+        // canvas width-1 = 299, height-1 = 199 (24-bit little endian)
+        bytes.extend_from_slice(&[0x2b, 0x01, 0x00, 0xc7, 0x00, 0x00]);
+
+        let info = ImageInfo::parse(&bytes).unwrap();
+        assert_eq!(info.format, ImageFormat::WebP);
+        assert_eq!((info.width, info.height), (300, 200));
+    }
+
+    #[test]
+    fn test_parse_not_an_image() {
+        assert!(ImageInfo::parse(b"{\"key\":\"value\"}").is_none());
+        assert!(ImageInfo::parse(b"\x89PNG").is_none());
+    }
+
+    #[test]
+    fn test_is_image_content_type() {
+        assert!(is_image_content_type("image/png"));
+        assert!(is_image_content_type("Image/JPEG; charset=binary"));
+        assert!(!is_image_content_type("application/json"));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[test]
+    fn test_detect_protocol() {
+        let detect = |vars: &[(&str, &str)]| {
+            let vars: Vec<(String, String)> = vars
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            InlineProtocol::detect_with(|key| {
+                vars.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
+            })
+        };
+
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "iTerm.app")]),
+            Some(InlineProtocol::Iterm2)
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty")]),
+            Some(InlineProtocol::Kitty)
+        );
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), None);
+    }
+
+    #[test]
+    fn test_kitty_supports_png_only() {
+        assert!(InlineProtocol::Kitty.supports(ImageFormat::Png));
+        assert!(!InlineProtocol::Kitty.supports(ImageFormat::Jpeg));
+        assert!(InlineProtocol::Iterm2.supports(ImageFormat::Gif));
+    }
+
+    #[test]
+    fn test_render_iterm2() {
+        let output = InlineProtocol::Iterm2.render(&decode(PNG_1X1));
+        assert!(output.starts_with("\x1b]1337;File=inline=1;size=70;"));
+    }
+}
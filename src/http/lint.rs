@@ -0,0 +1,310 @@
+//! Pre-send checks for common request mistakes
+
+use crate::http::{HttpMethod, RequestBuilder};
+use std::fmt;
+
+/// A problem found in a request before sending it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    /// Short finding code (e.g. "L001")
+    pub code: &'static str,
+
+    /// What looks wrong
+    pub message: String,
+
+    /// How to fix it
+    pub suggestion: String,
+}
+
+impl LintFinding {
+    fn new(code: &'static str, message: String, suggestion: &str) -> Self {
+        Self {
+            code,
+            message,
+            suggestion: suggestion.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {} ({})", self.code, self.message, self.suggestion)
+    }
+}
+
+/// Check a composed request for common mistakes
+pub fn lint_request(request: &RequestBuilder) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    let headers = split_headers(&request.headers);
+
+    // L001: body on a method that shouldn't carry one
+    if request.body.is_some() && matches!(request.method, HttpMethod::Get | HttpMethod::Head) {
+        findings.push(LintFinding::new(
+            "L001",
+            format!("{} request has a body", request.method.as_str()),
+            "servers usually ignore it; use POST/PUT or move the data to query parameters",
+        ));
+    }
+
+    // L002: body without a content type that auto-detection sends as plain text
+    if request.detected_content_type() == Some("text/plain") {
+        let has_content_type = headers
+            .iter()
+            .any(|(name, _)| name.trim().eq_ignore_ascii_case("content-type"));
+        if !has_content_type {
+            findings.push(LintFinding::new(
+                "L002",
+                "Request has a body but no Content-Type header; it will be sent as text/plain"
+                    .to_string(),
+                "add -H 'Content-Type:<type>' with the body's actual type",
+            ));
+        }
+    }
+
+    // L003: the same header set twice with different values
+    for (i, (name, value)) in headers.iter().enumerate() {
+        let name = name.trim();
+        let conflict = headers[..i].iter().any(|(prev_name, prev_value)| {
+            prev_name.trim().eq_ignore_ascii_case(name) && prev_value.trim() != value.trim()
+        });
+        let already_reported = findings
+            .iter()
+            .any(|f| f.code == "L003" && f.message.contains(&format!("'{}'", name)));
+        if conflict && !already_reported {
+            findings.push(LintFinding::new(
+                "L003",
+                format!(
+                    "Header '{}' is set more than once with different values",
+                    name
+                ),
+                "keep a single value; only the last one is sent",
+            ));
+        }
+    }
+
+    // L004: template variables that were never substituted
+    let mut parts: Vec<&str> = vec![request.url.as_str()];
+    parts.extend(request.headers.iter().map(String::as_str));
+    parts.extend(request.query_params.iter().map(String::as_str));
+    if let Some(body) = &request.body {
+        parts.push(body);
+    }
+    for part in parts {
+        for name in unsubstituted_variables(part) {
+            findings.push(LintFinding::new(
+                "L004",
+                format!("Unsubstituted variable '{{{{{}}}}}'", name),
+                "define it in the active environment or replace it with a value",
+            ));
+        }
+    }
+
+    // L005: doubled scheme such as "https://https://"
+    let url = request.url.to_ascii_lowercase();
+    if let Some((_, rest)) = url.split_once("://") {
+        if rest.starts_with("http:") || rest.starts_with("https:") {
+            findings.push(LintFinding::new(
+                "L005",
+                format!("URL has a doubled scheme: {}", request.url),
+                "remove the repeated 'http://' / 'https://' prefix",
+            ));
+        }
+    }
+
+    // L006: whitespace in header names
+    for (name, _) in &headers {
+        if name.chars().any(char::is_whitespace) {
+            findings.push(LintFinding::new(
+                "L006",
+                format!("Header name '{}' contains whitespace", name),
+                "use 'Name:Value' with no spaces in or around the name",
+            ));
+        }
+    }
+
+    // L007: repeated auth scheme prefix
+    for (name, value) in &headers {
+        if name.trim().eq_ignore_ascii_case("authorization") {
+            let words: Vec<&str> = value.split_whitespace().collect();
+            if words.len() >= 2 && words[0].eq_ignore_ascii_case(words[1]) {
+                findings.push(LintFinding::new(
+                    "L007",
+                    format!("Authorization header repeats '{}'", words[0]),
+                    "pass only the token after the scheme name",
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+/// Split "Key:Value" headers without trimming the name
+fn split_headers(headers: &[String]) -> Vec<(&str, &str)> {
+    headers
+        .iter()
+        .filter_map(|header| header.split_once(':'))
+        .collect()
+}
+
+/// Find `{{name}}` placeholders left in a string
+fn unsubstituted_variables(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                names.push(after[..end].trim().to_string());
+                rest = &after[end + 2..];
+            }
+            None => break,
+        }
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(
+        method: HttpMethod,
+        url: &str,
+        headers: &[&str],
+        body: Option<&str>,
+    ) -> RequestBuilder {
+        let mut request = RequestBuilder::new(method, url.to_string())
+            .headers(headers.iter().map(|h| h.to_string()).collect());
+        if let Some(body) = body {
+            request = request.body(body.to_string());
+        }
+        request
+    }
+
+    #[test]
+    fn test_lint_findings_table() {
+        let json = "Content-Type:application/json";
+        let cases: Vec<(&str, RequestBuilder, Vec<&str>)> = vec![
+            (
+                "clean GET",
+                request(HttpMethod::Get, "https://example.com", &[], None),
+                vec![],
+            ),
+            (
+                "clean POST",
+                request(HttpMethod::Post, "https://example.com", &[json], Some("{}")),
+                vec![],
+            ),
+            (
+                "GET with body",
+                request(HttpMethod::Get, "https://example.com", &[json], Some("{}")),
+                vec!["L001"],
+            ),
+            (
+                "HEAD with body",
+                request(HttpMethod::Head, "https://example.com", &[json], Some("x")),
+                vec!["L001"],
+            ),
+            (
+                "JSON body without content type",
+                request(
+                    HttpMethod::Post,
+                    "https://example.com",
+                    &[],
+                    Some(r#"{"a":1}"#),
+                ),
+                vec![],
+            ),
+            (
+                "non-JSON body without content type",
+                request(
+                    HttpMethod::Post,
+                    "https://example.com",
+                    &[],
+                    Some("a=1&b=2"),
+                ),
+                vec!["L002"],
+            ),
+            (
+                "conflicting duplicate headers",
+                request(
+                    HttpMethod::Get,
+                    "https://example.com",
+                    &["Accept:text/html", "accept:application/json"],
+                    None,
+                ),
+                vec!["L003"],
+            ),
+            (
+                "identical duplicate headers",
+                request(
+                    HttpMethod::Get,
+                    "https://example.com",
+                    &["Accept:text/html", "Accept:text/html"],
+                    None,
+                ),
+                vec![],
+            ),
+            (
+                "unsubstituted variable",
+                request(HttpMethod::Get, "https://{{host}}/users", &[], None),
+                vec!["L004"],
+            ),
+            (
+                "double scheme",
+                request(HttpMethod::Get, "https://https://example.com", &[], None),
+                vec!["L005"],
+            ),
+            (
+                "whitespace in header name",
+                request(
+                    HttpMethod::Get,
+                    "https://example.com",
+                    &["X Custom:1"],
+                    None,
+                ),
+                vec!["L006"],
+            ),
+            (
+                "double bearer",
+                request(
+                    HttpMethod::Get,
+                    "https://example.com",
+                    &["Authorization:Bearer Bearer abc"],
+                    None,
+                ),
+                vec!["L007"],
+            ),
+        ];
+
+        for (name, request, expected) in cases {
+            let codes: Vec<&str> = lint_request(&request).iter().map(|f| f.code).collect();
+            assert_eq!(codes, expected, "case: {}", name);
+        }
+    }
+
+    #[test]
+    fn test_lint_finding_display() {
+        let findings = lint_request(&request(
+            HttpMethod::Get,
+            "https://example.com",
+            &[],
+            Some("data"),
+        ));
+        let text = findings[0].to_string();
+        assert!(text.starts_with("[L001]"));
+        assert!(text.contains("GET request has a body"));
+    }
+
+    #[test]
+    fn test_unsubstituted_variables() {
+        assert_eq!(
+            unsubstituted_variables("{{a}}/x/{{ b }}"),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert!(unsubstituted_variables("{{unclosed").is_empty());
+    }
+}
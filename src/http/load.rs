@@ -0,0 +1,376 @@
+//! Load testing: drive one request from several threads and aggregate results
+
+use crate::http::bench::{percentile, serialize_millis};
+use crate::http::{HttpClient, RequestBuilder};
+use crate::style::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Requests sent when neither a count nor a duration is given
+pub const DEFAULT_REQUESTS: usize = 100;
+
+/// Latencies and status codes collected by one worker
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadSamples {
+    /// Response times of completed requests
+    pub latencies: Vec<Duration>,
+
+    /// Responses per status code
+    pub status_counts: BTreeMap<u16, usize>,
+
+    /// Requests that failed before a response arrived
+    pub errors: usize,
+}
+
+impl LoadSamples {
+    /// Create an empty set of samples
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a completed response
+    pub fn record_response(&mut self, status: u16, latency: Duration) {
+        self.latencies.push(latency);
+        *self.status_counts.entry(status).or_default() += 1;
+    }
+
+    /// Record a transport error
+    pub fn record_error(&mut self) {
+        self.errors += 1;
+    }
+
+    /// Fold another worker's samples into these
+    pub fn merge(&mut self, other: LoadSamples) {
+        self.latencies.extend(other.latencies);
+        for (status, count) in other.status_counts {
+            *self.status_counts.entry(status).or_default() += count;
+        }
+        self.errors += other.errors;
+    }
+
+    /// Requests recorded, including errors
+    pub fn total(&self) -> usize {
+        self.latencies.len() + self.errors
+    }
+
+    /// Summarize samples collected over `elapsed`
+    pub fn report(&self, concurrency: usize, elapsed: Duration) -> LoadReport {
+        let mut sorted = self.latencies.clone();
+        sorted.sort();
+
+        let mean =
+            (!sorted.is_empty()).then(|| sorted.iter().sum::<Duration>() / sorted.len() as u32);
+        let throughput = if elapsed.is_zero() {
+            0.0
+        } else {
+            self.total() as f64 / elapsed.as_secs_f64()
+        };
+
+        LoadReport {
+            requests: self.total(),
+            errors: self.errors,
+            concurrency,
+            elapsed,
+            throughput,
+            min: sorted.first().copied(),
+            mean,
+            p50: percentile(&sorted, 50.0),
+            p90: percentile(&sorted, 90.0),
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+            max: sorted.last().copied(),
+            status_codes: self.status_counts.clone(),
+        }
+    }
+}
+
+/// Outcome of a load test
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoadReport {
+    /// Requests sent
+    pub requests: usize,
+
+    /// Requests that failed before a response arrived
+    pub errors: usize,
+
+    /// Worker threads used
+    pub concurrency: usize,
+
+    /// Wall-clock time of the whole test
+    #[serde(rename = "elapsed_ms", serialize_with = "serialize_elapsed")]
+    pub elapsed: Duration,
+
+    /// Requests per second
+    #[serde(rename = "requests_per_second")]
+    pub throughput: f64,
+
+    /// Fastest response
+    #[serde(rename = "min_ms", serialize_with = "serialize_millis")]
+    pub min: Option<Duration>,
+
+    /// Average response time
+    #[serde(rename = "mean_ms", serialize_with = "serialize_millis")]
+    pub mean: Option<Duration>,
+
+    /// Median response time
+    #[serde(rename = "p50_ms", serialize_with = "serialize_millis")]
+    pub p50: Option<Duration>,
+
+    /// 90th percentile response time
+    #[serde(rename = "p90_ms", serialize_with = "serialize_millis")]
+    pub p90: Option<Duration>,
+
+    /// 95th percentile response time
+    #[serde(rename = "p95_ms", serialize_with = "serialize_millis")]
+    pub p95: Option<Duration>,
+
+    /// 99th percentile response time
+    #[serde(rename = "p99_ms", serialize_with = "serialize_millis")]
+    pub p99: Option<Duration>,
+
+    /// Slowest response
+    #[serde(rename = "max_ms", serialize_with = "serialize_millis")]
+    pub max: Option<Duration>,
+
+    /// Responses per status code
+    pub status_codes: BTreeMap<u16, usize>,
+}
+
+fn serialize_elapsed<S: serde::Serializer>(
+    elapsed: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serialize_millis(&Some(*elapsed), serializer)
+}
+
+impl LoadReport {
+    /// Render a colored summary
+    pub fn render(&self) -> String {
+        let marker = if self.errors == 0 {
+            "✓".green().bold()
+        } else {
+            "✗".red().bold()
+        };
+        let mut out = format!(
+            "{} {} requests in {:.2}s with {} worker(s): {}\n",
+            marker,
+            self.requests,
+            self.elapsed.as_secs_f64(),
+            self.concurrency,
+            format!("{:.1} req/s", self.throughput).bold()
+        );
+
+        out.push_str(&format!("\n{}\n", "Latency".bold()));
+        for (label, value) in [
+            ("min", self.min),
+            ("mean", self.mean),
+            ("p50", self.p50),
+            ("p90", self.p90),
+            ("p95", self.p95),
+            ("p99", self.p99),
+            ("max", self.max),
+        ] {
+            let value = value.map_or("-".to_string(), |d| {
+                format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+            });
+            out.push_str(&format!("  {:<5}{}\n", label.cyan(), value));
+        }
+
+        out.push_str(&format!("\n{}\n", "Status codes".bold()));
+        for (status, count) in &self.status_codes {
+            // Same colors as a single response's status line
+            let color = match status {
+                200..=299 => "green",
+                400..=599 => "red",
+                _ => "yellow",
+            };
+            out.push_str(&format!(
+                "  {}  {}\n",
+                status.to_string().color(color),
+                count
+            ));
+        }
+        if self.errors > 0 {
+            out.push_str(&format!("  {}  {}\n", "errors".red(), self.errors));
+        }
+        out
+    }
+
+    /// Serialize as pretty JSON
+    pub fn to_json(&self) -> crate::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Sends one request repeatedly from a pool of threads sharing a client
+pub struct LoadTest<'a> {
+    client: &'a HttpClient,
+    request: &'a RequestBuilder,
+    concurrency: usize,
+    requests: Option<usize>,
+    duration: Option<Duration>,
+}
+
+impl<'a> LoadTest<'a> {
+    /// Create a load test with one worker and the default request count
+    pub fn new(client: &'a HttpClient, request: &'a RequestBuilder) -> Self {
+        Self {
+            client,
+            request,
+            concurrency: 1,
+            requests: None,
+            duration: None,
+        }
+    }
+
+    /// Set the number of worker threads
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Stop after this many requests in total
+    pub fn with_requests(mut self, requests: usize) -> Self {
+        self.requests = Some(requests);
+        self
+    }
+
+    /// Stop sending new requests after this long
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Run the test and summarize it
+    ///
+    /// With only a duration, requests are sent until it runs out. With
+    /// neither limit, [`DEFAULT_REQUESTS`] are sent.
+    pub fn run(&self) -> LoadReport {
+        let limit = match (self.requests, self.duration) {
+            (Some(requests), _) => requests,
+            (None, Some(_)) => usize::MAX,
+            (None, None) => DEFAULT_REQUESTS,
+        };
+        let issued = AtomicUsize::new(0);
+        let started = Instant::now();
+
+        let samples = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.concurrency)
+                .map(|_| {
+                    let issued = &issued;
+                    scope.spawn(move || {
+                        let mut samples = LoadSamples::new();
+                        loop {
+                            if self.duration.is_some_and(|d| started.elapsed() >= d)
+                                || issued.fetch_add(1, Ordering::Relaxed) >= limit
+                            {
+                                break;
+                            }
+                            match self.client.execute(self.request) {
+                                Ok(response) => samples
+                                    .record_response(response.status.as_u16(), response.duration),
+                                Err(_) => samples.record_error(),
+                            }
+                        }
+                        samples
+                    })
+                })
+                .collect();
+
+            let mut merged = LoadSamples::new();
+            for worker in workers {
+                merged.merge(worker.join().expect("load worker panicked"));
+            }
+            merged
+        });
+
+        samples.report(self.concurrency, started.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(statuses: &[(u16, u64)], errors: usize) -> LoadSamples {
+        let mut samples = LoadSamples::new();
+        for (status, millis) in statuses {
+            samples.record_response(*status, Duration::from_millis(*millis));
+        }
+        for _ in 0..errors {
+            samples.record_error();
+        }
+        samples
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut first = samples(&[(200, 10), (200, 30), (500, 90)], 1);
+        let second = samples(&[(200, 20), (404, 40)], 2);
+        first.merge(second);
+
+        assert_eq!(first.total(), 8);
+        assert_eq!(first.errors, 3);
+        assert_eq!(first.latencies.len(), 5);
+        assert_eq!(
+            first.status_counts,
+            BTreeMap::from([(200, 3), (404, 1), (500, 1)])
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let latencies: Vec<(u16, u64)> = (1..=100).map(|ms| (200, ms)).collect();
+        let report = samples(&latencies, 0).report(4, Duration::from_secs(2));
+
+        assert_eq!(report.requests, 100);
+        assert_eq!(report.throughput, 50.0);
+        assert_eq!(report.min, Some(Duration::from_millis(1)));
+        assert_eq!(report.p50, Some(Duration::from_millis(50)));
+        assert_eq!(report.p90, Some(Duration::from_millis(90)));
+        assert_eq!(report.p99, Some(Duration::from_millis(99)));
+        assert_eq!(report.max, Some(Duration::from_millis(100)));
+        assert_eq!(report.mean, Some(Duration::from_micros(50_500)));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["requests_per_second"], 50.0);
+        assert_eq!(json["elapsed_ms"], 2000.0);
+        assert_eq!(json["status_codes"]["200"], 100);
+        assert!(report.render().contains("50.0 req/s"));
+    }
+
+    #[test]
+    fn test_report_without_responses() {
+        let report = samples(&[], 3).report(1, Duration::ZERO);
+        assert_eq!(report.requests, 3);
+        assert_eq!(report.throughput, 0.0);
+        assert_eq!(report.p50, None);
+        assert!(report.status_codes.is_empty());
+    }
+
+    #[test]
+    fn test_run_against_mock_server() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/load")
+            .with_status(200)
+            .expect(12)
+            .create();
+
+        let client = HttpClient::new();
+        let request = RequestBuilder::new(
+            crate::http::HttpMethod::Get,
+            format!("{}/load", server.url()),
+        );
+        let report = LoadTest::new(&client, &request)
+            .with_concurrency(3)
+            .with_requests(12)
+            .run();
+
+        mock.assert();
+        assert_eq!(report.requests, 12);
+        assert_eq!(report.status_codes, BTreeMap::from([(200, 12)]));
+    }
+}
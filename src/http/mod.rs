@@ -1,9 +1,33 @@
 //! HTTP request and response handling
 
+pub mod bench;
 pub mod client;
+pub mod deadline;
+pub mod diff;
+pub mod graphql;
+pub mod highlight;
+pub mod host;
+pub mod image;
+pub mod lint;
+pub mod load;
+pub mod proxy;
+pub mod recording;
+pub mod redact;
 pub mod request;
 pub mod response;
+pub mod server_timing;
+pub mod timing;
 
-pub use client::HttpClient;
+pub use bench::BenchStats;
+pub use client::{HttpClient, DEFAULT_USER_AGENT};
+pub use deadline::Deadline;
+pub use diff::{BodyDiff, HeaderChange, ResponseDiff, ResponseDiffer, DEFAULT_IGNORED_HEADERS};
+pub use graphql::GraphqlError;
+pub use host::{normalize_url, HostInfo};
+pub use load::{LoadReport, LoadSamples, LoadTest};
+pub use proxy::{NoProxy, ProxyConfig, ProxyScheme};
+pub use recording::{RecordMode, Recording, RecordingClient};
 pub use request::{HttpMethod, RequestBuilder};
-pub use response::{HttpResponse, ResponseFormatter};
+pub use response::{BodyMode, HeaderMode, HttpResponse, ResponseFormatter};
+pub use server_timing::ServerTimingEntry;
+pub use timing::Timing;
@@ -0,0 +1,268 @@
+//! Record responses to disk and replay them without the network
+
+use crate::error::{Error, Result};
+use crate::http::{normalize_url, HttpClient, HttpResponse, RequestBuilder};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Whether a [`RecordingClient`] saves or serves responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RecordMode {
+    /// Send requests and save each response, replacing older recordings
+    Record,
+
+    /// Answer from saved responses without touching the network
+    Replay,
+}
+
+/// A saved response and the request it answers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Recording {
+    /// Request method
+    pub method: String,
+
+    /// Request URL, query parameters included
+    pub url: String,
+
+    /// Response status code
+    pub status: u16,
+
+    /// Response headers
+    pub headers: Vec<(String, String)>,
+
+    /// Base64-encoded response body
+    pub body: String,
+
+    /// Round-trip time when recorded
+    pub duration_ms: u64,
+
+    /// When the response was recorded
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl Recording {
+    /// Capture a response to a request
+    pub fn new(request: &RequestBuilder, response: &HttpResponse) -> Self {
+        let headers = response
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|v| (name.to_string(), v.to_string()))
+            })
+            .collect();
+
+        Self {
+            method: request.method.as_str().to_string(),
+            url: full_url(request),
+            status: response.status.as_u16(),
+            headers,
+            body: base64::engine::general_purpose::STANDARD.encode(&response.body_bytes),
+            duration_ms: response.duration.as_millis() as u64,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    /// Rebuild the response; its duration is the recorded one
+    pub fn to_response(&self) -> Result<HttpResponse> {
+        let body_bytes = base64::engine::general_purpose::STANDARD
+            .decode(&self.body)
+            .map_err(|e| Error::Io(std::io::Error::other(format!("Corrupt recording: {}", e))))?;
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+
+        Ok(HttpResponse {
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK),
+            headers,
+            body: String::from_utf8_lossy(&body_bytes).into_owned(),
+            body_bytes,
+            duration: Duration::from_millis(self.duration_ms),
+            timing: None,
+        })
+    }
+}
+
+/// The URL a request goes to, with its query parameters in order
+fn full_url(request: &RequestBuilder) -> String {
+    let url = normalize_url(&request.url).unwrap_or_else(|_| request.url.clone());
+    if request.query_params.is_empty() {
+        return url;
+    }
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{}{}{}", url, separator, request.query_params.join("&"))
+}
+
+/// Key identifying a request: method, URL, and a hash of its body
+///
+/// Headers are not part of the key, so a request replays even after its
+/// credentials change. Form fields are sorted before hashing.
+pub fn recording_key(request: &RequestBuilder) -> String {
+    let mut body = Sha256::new();
+    if let Some(text) = &request.body {
+        body.update(text.as_bytes());
+    }
+    if let Some(form) = &request.form_data {
+        let mut fields: Vec<String> = form
+            .text_fields()
+            .into_iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .chain(
+                form.file_fields()
+                    .into_iter()
+                    .map(|(name, path)| format!("{}=@{}", name, path)),
+            )
+            .collect();
+        fields.sort();
+        body.update(fields.join("&").as_bytes());
+    }
+
+    format!(
+        "{} {} {}",
+        request.method.as_str(),
+        full_url(request),
+        hex(&body.finalize())
+    )
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// An [`HttpClient`] that records responses to, or replays them from, a directory
+pub struct RecordingClient<'a> {
+    client: &'a HttpClient,
+    dir: PathBuf,
+    mode: RecordMode,
+}
+
+impl<'a> RecordingClient<'a> {
+    /// Wrap a client, keeping recordings in `dir`
+    pub fn new(client: &'a HttpClient, dir: PathBuf, mode: RecordMode) -> Self {
+        Self { client, dir, mode }
+    }
+
+    /// Get default recordings directory, inside the history store
+    pub fn default_path() -> Result<PathBuf> {
+        let dirs = directories::ProjectDirs::from("com", "bazzoun", "bazzounquester").ok_or_else(
+            || {
+                Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Could not determine data directory",
+                ))
+            },
+        )?;
+
+        let path = dirs.data_dir().join("history").join("recordings");
+        Ok(path)
+    }
+
+    /// The wrapped client
+    pub fn client(&self) -> &HttpClient {
+        self.client
+    }
+
+    /// Send a request (record mode) or answer it from disk (replay mode)
+    ///
+    /// Replaying a request that was never recorded is an error.
+    pub fn execute(&self, request: &RequestBuilder) -> Result<HttpResponse> {
+        let key = recording_key(request);
+        let path = self
+            .dir
+            .join(format!("{}.json", hex(&Sha256::digest(&key))));
+
+        match self.mode {
+            RecordMode::Record => {
+                let response = self.client.execute(request)?;
+                std::fs::create_dir_all(&self.dir)?;
+                let recording = Recording::new(request, &response);
+                std::fs::write(path, serde_json::to_string_pretty(&recording)?)?;
+                Ok(response)
+            }
+            RecordMode::Replay => {
+                let content = std::fs::read_to_string(&path).map_err(|_| {
+                    Error::InvalidCommand(format!(
+                        "No recording for {} {} (record it first with --record)",
+                        request.method.as_str(),
+                        full_url(request)
+                    ))
+                })?;
+                let recording: Recording = serde_json::from_str(&content)?;
+                recording.to_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::HttpMethod;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_recording_key() {
+        let get = RequestBuilder::new(HttpMethod::Get, "https://Example.com:443/items".to_string())
+            .query("page=2".to_string());
+        let key = recording_key(&get);
+        assert!(key.starts_with("GET https://example.com/items?page=2 "));
+
+        let post = |body: &str| {
+            RequestBuilder::new(HttpMethod::Post, "https://example.com/items".to_string())
+                .body(body.to_string())
+        };
+        assert_eq!(recording_key(&post("a")), recording_key(&post("a")));
+        assert_ne!(recording_key(&post("a")), recording_key(&post("b")));
+
+        let with_header = post("a").header("Authorization:Bearer new".to_string());
+        assert_eq!(recording_key(&with_header), recording_key(&post("a")));
+    }
+
+    #[test]
+    fn test_replay_without_server() {
+        let dir = TempDir::new().unwrap();
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/orders")
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":7}"#)
+            .expect(1)
+            .create();
+        let url = format!("{}/orders", server.url());
+        let request = RequestBuilder::new(HttpMethod::Post, url).body(r#"{"qty":1}"#.to_string());
+
+        let client = HttpClient::new();
+        let recorder = RecordingClient::new(&client, dir.path().to_path_buf(), RecordMode::Record);
+        recorder.execute(&request).unwrap();
+        mock.assert();
+        drop(server);
+
+        let replayer = RecordingClient::new(&client, dir.path().to_path_buf(), RecordMode::Replay);
+        let response = replayer.execute(&request).unwrap();
+        assert_eq!(response.status, StatusCode::CREATED);
+        assert_eq!(response.body, r#"{"id":7}"#);
+        assert!(response.is_json());
+
+        let other = RequestBuilder::new(HttpMethod::Post, request.url.clone())
+            .body(r#"{"qty":2}"#.to_string());
+        let err = replayer.execute(&other).unwrap_err();
+        assert!(err.to_string().contains("No recording for POST"));
+    }
+}
@@ -0,0 +1,122 @@
+//! Masking of secrets in displayed requests and responses
+
+use serde_json::Value;
+
+/// Replacement shown in place of a secret value
+pub const MASK: &str = "****";
+
+/// Header names that always carry credentials
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+];
+
+/// Name fragments that suggest a header carries a secret
+const SENSITIVE_FRAGMENTS: &[&str] = &["token", "secret", "password", "api-key", "apikey", "auth"];
+
+/// Check whether a header's value should be masked
+pub fn is_sensitive_header(name: &str) -> bool {
+    let name = name.trim().to_ascii_lowercase();
+    SENSITIVE_HEADERS.contains(&name.as_str())
+        || SENSITIVE_FRAGMENTS.iter().any(|f| name.contains(f))
+}
+
+/// Mask a header value, keeping an auth scheme like "Bearer" visible
+pub fn mask_header_value(name: &str, value: &str) -> String {
+    let is_authorization = name.trim().eq_ignore_ascii_case("authorization")
+        || name.trim().eq_ignore_ascii_case("proxy-authorization");
+
+    match value.trim().split_once(' ') {
+        Some((scheme, _)) if is_authorization => format!("{} {}", scheme, MASK),
+        _ => MASK.to_string(),
+    }
+}
+
+/// Mask the value of a header if it looks sensitive
+pub fn redact_header(name: &str, value: &str) -> String {
+    if is_sensitive_header(name) {
+        mask_header_value(name, value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// JSON fields whose values are masked in displayed response bodies by default
+pub const DEFAULT_SECRET_FIELDS: &[&str] = &["access_token", "refresh_token", "password", "secret"];
+
+/// Mask the values of the named fields (case-insensitive) anywhere in a JSON value
+///
+/// Returns how many values were masked.
+pub fn mask_json_fields(value: &mut Value, fields: &[String]) -> usize {
+    match value {
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(key, value)| {
+                if fields.iter().any(|f| f.eq_ignore_ascii_case(key)) {
+                    *value = Value::String(MASK.to_string());
+                    1
+                } else {
+                    mask_json_fields(value, fields)
+                }
+            })
+            .sum(),
+        Value::Array(items) => items.iter_mut().map(|v| mask_json_fields(v, fields)).sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_sensitive_header() {
+        for name in [
+            "Authorization",
+            "cookie",
+            "X-Api-Key",
+            "X-Auth-Token",
+            "X-Client-Secret",
+        ] {
+            assert!(is_sensitive_header(name), "{}", name);
+        }
+        for name in ["Content-Type", "Accept", "X-Request-Id"] {
+            assert!(!is_sensitive_header(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_redact_header() {
+        assert_eq!(redact_header("Authorization", "Bearer abc"), "Bearer ****");
+        assert_eq!(redact_header("Authorization", "abc"), "****");
+        assert_eq!(redact_header("X-Api-Key", "abc def"), "****");
+        assert_eq!(redact_header("Accept", "*/*"), "*/*");
+    }
+
+    #[test]
+    fn test_mask_json_fields_nested() {
+        let fields: Vec<String> = DEFAULT_SECRET_FIELDS
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        let mut value = serde_json::json!({
+            "Access_Token": "abc",
+            "user": {"name": "alice", "password": "hunter2"},
+            "sessions": [{"refresh_token": "r1"}, {"refresh_token": {"value": "r2"}}],
+            "secret_count": 3
+        });
+
+        assert_eq!(mask_json_fields(&mut value, &fields), 4);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "Access_Token": MASK,
+                "user": {"name": "alice", "password": MASK},
+                "sessions": [{"refresh_token": MASK}, {"refresh_token": MASK}],
+                "secret_count": 3
+            })
+        );
+    }
+}
@@ -1,22 +1,36 @@
 //! HTTP request building and configuration
 
 use crate::auth::AuthScheme;
+use crate::env::VariableSubstitutor;
 use crate::error::{Error, Result};
+use crate::http::graphql;
+use crate::http::host::HostInfo;
+use crate::http::ResponseFormatter;
+use crate::upload::form::urlencoding;
 use crate::upload::FormData;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
 
 /// HTTP methods supported by the client
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HttpMethod {
+    #[serde(alias = "GET", alias = "get")]
     Get,
+    #[serde(alias = "POST", alias = "post")]
     Post,
+    #[serde(alias = "PUT", alias = "put")]
     Put,
+    #[serde(alias = "DELETE", alias = "delete")]
     Delete,
+    #[serde(alias = "PATCH", alias = "patch")]
     Patch,
+    #[serde(alias = "HEAD", alias = "head")]
     Head,
+    #[serde(alias = "OPTIONS", alias = "options")]
     Options,
 }
 
@@ -67,6 +81,9 @@ pub struct RequestBuilder {
     pub body: Option<String>,
     pub form_data: Option<FormData>,
     pub auth: AuthScheme,
+    pub timeout: Option<Duration>,
+    /// Send query parameters as given instead of percent-encoding them
+    pub raw_query: bool,
 }
 
 impl RequestBuilder {
@@ -80,6 +97,8 @@ impl RequestBuilder {
             body: None,
             form_data: None,
             auth: AuthScheme::default(),
+            timeout: None,
+            raw_query: false,
         }
     }
 
@@ -113,6 +132,47 @@ impl RequestBuilder {
         self
     }
 
+    /// Set the body to the contents of a UTF-8 text file
+    pub fn body_from_file(self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let body = std::fs::read_to_string(path).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("Could not read body file '{}': {}", path.display(), e),
+            ))
+        })?;
+        Ok(self.body(body))
+    }
+
+    /// Set the body from a `--body` value: `@path` reads the file, anything else is literal
+    pub fn body_arg(self, value: &str) -> Result<Self> {
+        match value.strip_prefix('@') {
+            Some(path) => self.body_from_file(path),
+            None => Ok(self.body(value.to_string())),
+        }
+    }
+
+    /// Send a GraphQL operation as a JSON POST body
+    ///
+    /// Adds `Content-Type: application/json` unless a content type is already set.
+    pub fn graphql(
+        mut self,
+        query: &str,
+        variables: Option<Value>,
+        operation_name: Option<&str>,
+    ) -> Self {
+        self.body = Some(graphql::request_body(query, variables, operation_name).to_string());
+        let has_content_type = self.headers.iter().any(|h| {
+            h.split_once(':')
+                .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("content-type"))
+        });
+        if !has_content_type {
+            self.headers
+                .push("Content-Type: application/json".to_string());
+        }
+        self
+    }
+
     /// Set form data (for multipart/form-data or application/x-www-form-urlencoded)
     pub fn form(mut self, form_data: FormData) -> Self {
         self.form_data = Some(form_data);
@@ -130,6 +190,66 @@ impl RequestBuilder {
         self
     }
 
+    /// Set a timeout for this request
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send query parameters as given (already percent-encoded) instead of
+    /// encoding their keys and values
+    pub fn raw_query(mut self, raw: bool) -> Self {
+        self.raw_query = raw;
+        self
+    }
+
+    /// Append query parameters to a URL, percent-encoded unless `raw_query`
+    ///
+    /// The parameters join any query the URL already has, and a `#fragment`
+    /// stays at the end.
+    pub fn url_with_query(&self, url: &str, query_params: &[String]) -> String {
+        if query_params.is_empty() {
+            return url.to_string();
+        }
+
+        let query: Vec<String> = if self.raw_query {
+            query_params.to_vec()
+        } else {
+            query_params
+                .iter()
+                .map(|param| {
+                    let (key, value) = param.split_once('=').unwrap_or((param, ""));
+                    format!(
+                        "{}={}",
+                        urlencoding::encode(key),
+                        urlencoding::encode(value)
+                    )
+                })
+                .collect()
+        };
+        let query = query.join("&");
+
+        match url::Url::parse(url) {
+            Ok(mut parsed) => {
+                let query = match parsed.query() {
+                    Some(existing) if !existing.is_empty() => format!("{}&{}", existing, query),
+                    _ => query,
+                };
+                parsed.set_query(Some(&query));
+                parsed.to_string()
+            }
+            // Not a full URL (e.g. it still has placeholders); splice the query in by hand
+            Err(_) => {
+                let (base, fragment) = match url.split_once('#') {
+                    Some((base, fragment)) => (base, format!("#{}", fragment)),
+                    None => (url, String::new()),
+                };
+                let separator = if base.contains('?') { '&' } else { '?' };
+                format!("{}{}{}{}", base, separator, query, fragment)
+            }
+        }
+    }
+
     /// Apply authentication to headers and query params
     pub fn apply_auth(&self, headers: &mut Vec<String>, query_params: &mut Vec<String>) {
         self.auth.apply(headers, query_params);
@@ -137,39 +257,28 @@ impl RequestBuilder {
 
     /// Parse headers into HeaderMap
     pub fn parse_headers(&self) -> Result<HeaderMap> {
-        let mut header_map = HeaderMap::new();
-
-        for header in &self.headers {
-            if let Some((key, value)) = header.split_once(':') {
-                let key = key.trim();
-                let value = value.trim();
-
-                let header_name = HeaderName::from_bytes(key.as_bytes())
-                    .map_err(|_| Error::InvalidHeader(format!("Invalid header name: {}", key)))?;
-
-                let header_value = HeaderValue::from_str(value).map_err(|_| {
-                    Error::InvalidHeader(format!("Invalid header value: {}", value))
-                })?;
-
-                header_map.insert(header_name, header_value);
-            } else {
-                return Err(Error::InvalidHeader(format!(
-                    "Header must be in format 'Key:Value', got: {}",
-                    header
-                )));
-            }
-        }
+        parse_header_lines(&self.headers)
+    }
 
+    /// Parse headers over a set of defaults
+    ///
+    /// A default is only sent when the request doesn't set a header of the
+    /// same name (names compare case-insensitively).
+    pub fn parse_headers_with_defaults(&self, defaults: &[String]) -> Result<HeaderMap> {
+        let mut header_map = parse_header_lines(defaults)?;
+        header_map.extend(self.parse_headers()?);
         Ok(header_map)
     }
 
-    /// Parse query parameters into HashMap
-    pub fn parse_query_params(&self) -> Result<HashMap<String, String>> {
-        let mut query_map = HashMap::new();
+    /// Parse query parameters into key/value pairs, in order
+    ///
+    /// Repeated keys (`tag=a`, `tag=b`) are all kept.
+    pub fn parse_query_params(&self) -> Result<Vec<(String, String)>> {
+        let mut query_pairs = Vec::new();
 
         for param in &self.query_params {
             if let Some((key, value)) = param.split_once('=') {
-                query_map.insert(key.to_string(), value.to_string());
+                query_pairs.push((key.to_string(), value.to_string()));
             } else {
                 return Err(Error::InvalidQuery(format!(
                     "Query parameter must be in format 'key=value', got: {}",
@@ -178,7 +287,7 @@ impl RequestBuilder {
             }
         }
 
-        Ok(query_map)
+        Ok(query_pairs)
     }
 
     /// Parse body as JSON Value
@@ -195,12 +304,200 @@ impl RequestBuilder {
     pub fn get_raw_body(&self) -> Option<&str> {
         self.body.as_deref()
     }
+
+    /// Content type implied by the body, used when no `Content-Type` header is set
+    ///
+    /// `application/json` if the body parses as JSON, `text/plain` otherwise,
+    /// `None` without a body.
+    pub fn detected_content_type(&self) -> Option<&'static str> {
+        let body = self.body.as_deref()?;
+        if serde_json::from_str::<Value>(body).is_ok() {
+            Some("application/json")
+        } else {
+            Some("text/plain")
+        }
+    }
+
+    /// Check that the URL has a supported scheme and a host
+    pub fn validate_url(&self) -> Result<()> {
+        validate_url(&self.url)
+    }
+
+    /// Fill in `{{placeholders}}` in the URL, headers, query, and body
+    pub fn substitute_variables(mut self, variables: &HashMap<&str, &str>) -> Self {
+        let substitutor = VariableSubstitutor::new();
+        let substitute = |text: &String| substitutor.substitute(text, variables);
+
+        self.url = substitutor.substitute(&self.url, variables);
+        self.headers = self.headers.iter().map(substitute).collect();
+        self.query_params = self.query_params.iter().map(substitute).collect();
+        self.body = self.body.as_ref().map(substitute);
+        self
+    }
+
+    /// Show what would be sent, without sending it: method, URL with query
+    /// parameters, headers with auth applied, and body
+    ///
+    /// Credentials are masked unless `show_secrets` is set.
+    pub fn preview(&self, show_secrets: bool) -> String {
+        ResponseFormatter::format_request_with(self, !show_secrets)
+    }
+}
+
+/// Parse `Key:Value` lines into a HeaderMap; a later line replaces an
+/// earlier one of the same name
+fn parse_header_lines(headers: &[String]) -> Result<HeaderMap> {
+    let mut header_map = HeaderMap::new();
+
+    for header in headers {
+        if let Some((key, value)) = header.split_once(':') {
+            let key = key.trim();
+            let value = value.trim();
+
+            let header_name = HeaderName::from_bytes(key.as_bytes())
+                .map_err(|_| Error::InvalidHeader(format!("Invalid header name: {}", key)))?;
+
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|_| Error::InvalidHeader(format!("Invalid header value: {}", value)))?;
+
+            header_map.insert(header_name, header_value);
+        } else {
+            return Err(Error::InvalidHeader(format!(
+                "Header must be in format 'Key:Value', got: {}",
+                header
+            )));
+        }
+    }
+
+    Ok(header_map)
+}
+
+/// Check that a URL has an http(s) scheme and a host
+pub fn validate_url(raw: &str) -> Result<()> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err(Error::InvalidUrl("URL is empty".to_string()));
+    }
+
+    let parsed = match url::Url::parse(raw) {
+        Ok(parsed) => parsed,
+        Err(url::ParseError::RelativeUrlWithoutBase) => {
+            return Err(Error::InvalidUrl(format!(
+                "'{}' is missing a scheme; did you mean https://{}?",
+                raw, raw
+            )))
+        }
+        Err(e) => return Err(Error::InvalidUrl(format!("'{}': {}", raw, e))),
+    };
+
+    match parsed.scheme() {
+        "http" | "https" => {}
+        scheme => {
+            // Likely a typo of http/https (e.g. "htps", "htp", "ttps")
+            let hint =
+                if scheme.starts_with('h') || scheme.ends_with("tps") || scheme.ends_with("ttp") {
+                    "; did you mean https://?"
+                } else {
+                    "; only http:// and https:// are supported"
+                };
+            return Err(Error::InvalidUrl(format!(
+                "unsupported scheme '{}' in '{}'{}",
+                scheme, raw, hint
+            )));
+        }
+    }
+
+    if HostInfo::from_url(&parsed).is_none_or(|info| info.host.is_empty()) {
+        return Err(Error::InvalidUrl(format!("'{}' has no host", raw)));
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_body_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("user.json");
+        std::fs::write(&path, r#"{"name":"Ada"}"#).unwrap();
+
+        let request =
+            RequestBuilder::new(HttpMethod::Put, "https://api.example.com/users/1".into())
+                .body_arg(&format!("@{}", path.display()))
+                .unwrap();
+        assert_eq!(request.body.as_deref(), Some(r#"{"name":"Ada"}"#));
+
+        let literal = RequestBuilder::new(HttpMethod::Post, "https://api.example.com".into())
+            .body_arg("user@example.com")
+            .unwrap();
+        assert_eq!(literal.body.as_deref(), Some("user@example.com"));
+
+        let missing = RequestBuilder::new(HttpMethod::Post, "https://api.example.com".into())
+            .body_arg("@does-not-exist.json")
+            .err()
+            .unwrap();
+        assert!(missing
+            .to_string()
+            .contains("Could not read body file 'does-not-exist.json'"));
+    }
+
+    #[test]
+    fn test_detected_content_type() {
+        let request = |body: &str| {
+            RequestBuilder::new(HttpMethod::Post, "https://api.example.com".into())
+                .body(body.to_string())
+        };
+
+        assert_eq!(
+            request(r#"{"name":"Ada"}"#).detected_content_type(),
+            Some("application/json")
+        );
+        assert_eq!(
+            request("[1, 2]").detected_content_type(),
+            Some("application/json")
+        );
+        assert_eq!(request("hello").detected_content_type(), Some("text/plain"));
+        assert_eq!(
+            RequestBuilder::new(HttpMethod::Get, "https://api.example.com".into())
+                .detected_content_type(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_graphql_body_and_content_type() {
+        let request =
+            RequestBuilder::new(HttpMethod::Post, "https://api.example.com/graphql".into())
+                .graphql(
+                    "query Me { me { id } }",
+                    Some(serde_json::json!({"first": 10})),
+                    Some("Me"),
+                );
+
+        assert_eq!(
+            request.parse_body().unwrap().unwrap(),
+            serde_json::json!({
+                "query": "query Me { me { id } }",
+                "variables": {"first": 10},
+                "operationName": "Me"
+            })
+        );
+        assert_eq!(
+            request.parse_headers().unwrap()["content-type"],
+            "application/json"
+        );
+
+        // An explicit content type is kept
+        let request =
+            RequestBuilder::new(HttpMethod::Post, "https://api.example.com/graphql".into())
+                .header("content-type: application/graphql+json".to_string())
+                .graphql("{ me { id } }", None, None);
+        assert_eq!(request.headers.len(), 1);
+    }
+
     #[test]
     fn test_http_method_from_str() {
         use std::str::FromStr;
@@ -228,6 +525,37 @@ mod tests {
         assert_eq!(builder.query_params.len(), 1);
     }
 
+    #[test]
+    fn test_validate_url_valid() {
+        let builder = RequestBuilder::new(HttpMethod::Get, "https://example.com/a?b=1".to_string());
+        assert!(builder.validate_url().is_ok());
+        assert!(validate_url("http://localhost:8080").is_ok());
+        assert!(validate_url("http://[::1]:8080/health").is_ok());
+    }
+
+    #[test]
+    fn test_validate_url_missing_scheme() {
+        let err = validate_url("example.com/users").unwrap_err().to_string();
+        assert!(err.contains("missing a scheme"));
+        assert!(err.contains("did you mean https://example.com/users?"));
+    }
+
+    #[test]
+    fn test_validate_url_unsupported_scheme() {
+        let err = validate_url("htps://example.com").unwrap_err().to_string();
+        assert!(err.contains("unsupported scheme 'htps'"));
+        assert!(err.contains("did you mean https://?"));
+
+        let err = validate_url("ftp://example.com").unwrap_err().to_string();
+        assert!(err.contains("only http:// and https:// are supported"));
+    }
+
+    #[test]
+    fn test_validate_url_missing_host() {
+        assert!(matches!(validate_url("http://"), Err(Error::InvalidUrl(_))));
+        assert!(matches!(validate_url(""), Err(Error::InvalidUrl(_))));
+    }
+
     #[test]
     fn test_parse_headers_valid() {
         let builder = RequestBuilder::new(HttpMethod::Get, "https://example.com".to_string())
@@ -238,6 +566,24 @@ mod tests {
         assert_eq!(headers.len(), 2);
     }
 
+    #[test]
+    fn test_substitute_variables() {
+        let variables = HashMap::from([("BASE_URL", "https://prod.example.com"), ("id", "7")]);
+        let request = RequestBuilder::new(HttpMethod::Post, "{{BASE_URL}}/users".to_string())
+            .header("X-User: {{id}}".to_string())
+            .query("host={{BASE_URL}}".to_string())
+            .body(r#"{"id":"{{id}}","name":"{{name}}"}"#.to_string())
+            .substitute_variables(&variables);
+
+        assert_eq!(request.url, "https://prod.example.com/users");
+        assert_eq!(request.headers, ["X-User: 7"]);
+        assert_eq!(request.query_params, ["host=https://prod.example.com"]);
+        assert_eq!(
+            request.body.as_deref(),
+            Some(r#"{"id":"7","name":"{{name}}"}"#)
+        );
+    }
+
     #[test]
     fn test_parse_headers_invalid() {
         let builder = RequestBuilder::new(HttpMethod::Get, "https://example.com".to_string())
@@ -246,6 +592,25 @@ mod tests {
         assert!(builder.parse_headers().is_err());
     }
 
+    #[test]
+    fn test_parse_headers_with_defaults() {
+        let defaults = vec![
+            "Accept: application/json".to_string(),
+            "X-Team: api".to_string(),
+        ];
+
+        let builder = RequestBuilder::new(HttpMethod::Get, "https://example.com".to_string());
+        let headers = builder.parse_headers_with_defaults(&defaults).unwrap();
+        assert_eq!(headers["accept"], "application/json");
+        assert_eq!(headers["x-team"], "api");
+
+        let builder = builder.header("accept: text/html".to_string());
+        let headers = builder.parse_headers_with_defaults(&defaults).unwrap();
+        assert_eq!(headers.get_all("accept").iter().count(), 1);
+        assert_eq!(headers["accept"], "text/html");
+        assert_eq!(headers["x-team"], "api");
+    }
+
     #[test]
     fn test_parse_query_params_valid() {
         let builder = RequestBuilder::new(HttpMethod::Get, "https://example.com".to_string())
@@ -254,7 +619,48 @@ mod tests {
 
         let params = builder.parse_query_params().unwrap();
         assert_eq!(params.len(), 2);
-        assert_eq!(params.get("foo"), Some(&"bar".to_string()));
+        assert_eq!(params[0], ("foo".to_string(), "bar".to_string()));
+    }
+
+    #[test]
+    fn test_parse_query_params_repeated_key() {
+        let builder = RequestBuilder::new(HttpMethod::Get, "https://example.com".to_string())
+            .query("tag=a".to_string())
+            .query("tag=b".to_string());
+
+        let params = builder.parse_query_params().unwrap();
+        assert_eq!(
+            params,
+            vec![
+                ("tag".to_string(), "a".to_string()),
+                ("tag".to_string(), "b".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_url_with_query_keeps_fragment_last() {
+        let request = RequestBuilder::new(HttpMethod::Get, "http://h/p#x".to_string());
+        let params = ["q=1".to_string(), "name=a b".to_string()];
+        assert_eq!(
+            request.url_with_query("http://h/p#x", &params),
+            "http://h/p?q=1&name=a+b#x"
+        );
+        assert_eq!(
+            request.url_with_query("http://h/p?page=2#x", &params[..1]),
+            "http://h/p?page=2&q=1#x"
+        );
+        assert_eq!(
+            request
+                .clone()
+                .raw_query(true)
+                .url_with_query("http://h/p#x", &["q=rock%20roll".to_string()]),
+            "http://h/p?q=rock%20roll#x"
+        );
+        assert_eq!(
+            request.url_with_query("/relative#x", &params[..1]),
+            "/relative?q=1#x"
+        );
     }
 
     #[test]
@@ -265,6 +671,33 @@ mod tests {
         assert!(builder.parse_query_params().is_err());
     }
 
+    #[test]
+    fn test_preview_resolved_request() {
+        use crate::auth::BearerAuth;
+        use crate::env::VariableSubstitutor;
+
+        let variables =
+            std::collections::HashMap::from([("BASE", "https://api.example.com"), ("ID", "42")]);
+        let url = VariableSubstitutor::new().substitute("{{BASE}}/users/{{ID}}", &variables);
+        let request = RequestBuilder::new(HttpMethod::Delete, url)
+            .query("force=true".to_string())
+            .header("X-Trace:abc".to_string())
+            .body(r#"{"reason":"cleanup"}"#.to_string())
+            .auth(AuthScheme::Bearer(BearerAuth::new(
+                "s3cret-token".to_string(),
+            )));
+
+        let preview = request.preview(false);
+        assert!(preview.contains("DELETE https://api.example.com/users/42?force=true"));
+        assert!(preview.contains("X-Trace: abc"));
+        assert!(preview.contains("Authorization: Bearer ****"));
+        assert!(!preview.contains("s3cret-token"));
+        assert!(preview.contains(r#"{"reason":"cleanup"}"#));
+
+        let revealed = request.preview(true);
+        assert!(revealed.contains("Authorization: Bearer s3cret-token"));
+    }
+
     #[test]
     fn test_parse_body_valid_json() {
         let builder = RequestBuilder::new(HttpMethod::Post, "https://example.com".to_string())
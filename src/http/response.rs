@@ -1,9 +1,22 @@
 //! HTTP response handling and formatting
 
+use crate::auth::api_key::ApiKeyLocation;
+use crate::auth::AuthScheme;
 use crate::error::Result;
+use crate::http::graphql::{self, GraphqlError};
+use crate::http::highlight::highlight_json;
+use crate::http::image::{self, ImageInfo, InlineProtocol};
+use crate::http::redact::{self, MASK};
+use crate::http::server_timing::{parse_server_timing, ServerTimingEntry};
+use crate::http::timing::Timing;
+use crate::http::RequestBuilder;
+use crate::session::Cookie;
 use reqwest::header::HeaderMap;
 use reqwest::StatusCode;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Line printed between NDJSON records in pretty output
+pub const NDJSON_DIVIDER: &str = "────────";
 
 /// Represents an HTTP response
 #[derive(Debug, Clone)]
@@ -11,21 +24,33 @@ pub struct HttpResponse {
     pub status: StatusCode,
     pub headers: HeaderMap,
     pub body: String,
+    pub body_bytes: Vec<u8>,
     pub duration: Duration,
+    pub timing: Option<Timing>,
 }
 
 impl HttpResponse {
     /// Create a response from a reqwest response
+    ///
+    /// `duration` is the time until headers arrived; reading the body is
+    /// timed separately and recorded in `timing`.
     pub fn from_reqwest(response: reqwest::blocking::Response, duration: Duration) -> Result<Self> {
         let status = response.status();
         let headers = response.headers().clone();
-        let body = response.text()?;
+        let transfer_start = Instant::now();
+        let body_bytes = response.bytes()?.to_vec();
+        let body = String::from_utf8_lossy(&body_bytes).into_owned();
+        let timing = Timing::new()
+            .with_time_to_first_byte(duration)
+            .with_content_transfer(transfer_start.elapsed());
 
         Ok(Self {
             status,
             headers,
             body,
+            body_bytes,
             duration,
+            timing: Some(timing),
         })
     }
 
@@ -60,6 +85,9 @@ impl HttpResponse {
         if self.body.is_empty() {
             return String::new();
         }
+        if self.is_ndjson() {
+            return pretty_ndjson(&self.body, |_| {});
+        }
 
         // Try to parse as JSON
         if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&self.body) {
@@ -77,60 +105,524 @@ impl HttpResponse {
     pub fn is_json(&self) -> bool {
         serde_json::from_str::<serde_json::Value>(&self.body).is_ok()
     }
+
+    /// Check if body is newline-delimited JSON
+    ///
+    /// True for an NDJSON content type, or for a body of several lines that
+    /// are each a JSON document but don't parse as one document together.
+    pub fn is_ndjson(&self) -> bool {
+        if self.content_type().is_some_and(is_ndjson_content_type) {
+            return true;
+        }
+        let mut lines = self.body.lines().filter(|line| !line.trim().is_empty());
+        lines.clone().nth(1).is_some()
+            && !self.is_json()
+            && lines.all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok())
+    }
+
+    /// Parse each line of the body as a JSON document
+    ///
+    /// Blank lines and lines that aren't JSON are skipped.
+    pub fn ndjson_lines(&self) -> Vec<serde_json::Value> {
+        self.body
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// Get the Content-Type header value
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+    }
+
+    /// Parse all Server-Timing headers into entries
+    pub fn server_timing(&self) -> Vec<ServerTimingEntry> {
+        self.headers
+            .get_all("server-timing")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(parse_server_timing)
+            .collect()
+    }
+
+    /// Parse all Set-Cookie headers into cookies
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.headers
+            .get_all(reqwest::header::SET_COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .filter_map(Cookie::from_header)
+            .collect()
+    }
+
+    /// Errors reported in a GraphQL response body, if any
+    pub fn graphql_errors(&self) -> Vec<GraphqlError> {
+        graphql::parse_errors(&self.body)
+    }
+
+    /// Parse image metadata if the response is an image
+    pub fn image_info(&self) -> Option<ImageInfo> {
+        if !self
+            .content_type()
+            .is_some_and(image::is_image_content_type)
+        {
+            return None;
+        }
+        ImageInfo::parse(&self.body_bytes)
+    }
+}
+
+/// Which response headers to show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderMode {
+    /// All headers
+    All,
+
+    /// Only the named headers (case-insensitive)
+    Filtered(Vec<String>),
+
+    /// No headers
+    None,
+}
+
+/// How to show the response body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyMode {
+    /// Pretty-printed JSON (other bodies as-is), optionally truncated
+    Pretty { max_bytes: Option<usize> },
+
+    /// The body exactly as received, optionally truncated
+    Raw { max_bytes: Option<usize> },
+
+    /// No body
+    None,
+}
+
+impl BodyMode {
+    /// Most bytes of the body to show, if limited
+    pub fn max_bytes(&self) -> Option<usize> {
+        match self {
+            Self::Pretty { max_bytes } | Self::Raw { max_bytes } => *max_bytes,
+            Self::None => None,
+        }
+    }
 }
 
 /// Formatter for displaying HTTP responses
-pub struct ResponseFormatter;
+///
+/// `ResponseFormatter::new()` is the default preset (status, all headers,
+/// pretty body); each section can be switched independently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseFormatter {
+    status: bool,
+    headers: HeaderMode,
+    body: BodyMode,
+    timing: bool,
+    secret_fields: Vec<String>,
+}
+
+impl Default for ResponseFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl ResponseFormatter {
-    /// Format a response for terminal display
+    /// Create a formatter with the default sections
+    pub fn new() -> Self {
+        Self {
+            status: true,
+            headers: HeaderMode::All,
+            body: BodyMode::Pretty { max_bytes: None },
+            timing: false,
+            secret_fields: redact::DEFAULT_SECRET_FIELDS
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+        }
+    }
+
+    /// Show the status, time, and Server-Timing lines
+    pub fn status(mut self, show: bool) -> Self {
+        self.status = show;
+        self
+    }
+
+    /// Choose which headers to show
+    pub fn headers(mut self, mode: HeaderMode) -> Self {
+        self.headers = mode;
+        self
+    }
+
+    /// Choose how to show the body
+    pub fn body(mut self, mode: BodyMode) -> Self {
+        self.body = mode;
+        self
+    }
+
+    /// Show the per-phase timing breakdown
+    pub fn timing(mut self, show: bool) -> Self {
+        self.timing = show;
+        self
+    }
+
+    /// Choose which JSON fields are masked in pretty bodies (empty shows everything)
+    ///
+    /// Only the display is masked; the response itself is left untouched.
+    pub fn secret_fields(mut self, fields: Vec<String>) -> Self {
+        self.secret_fields = fields;
+        self
+    }
+
+    /// Render a response with the selected sections
+    pub fn build(&self, response: &HttpResponse) -> String {
+        let mut output = String::new();
+        output.push_str(&self.render_status(response));
+        output.push_str(&self.render_headers(response));
+        output.push_str(&self.render_body(response));
+        output
+    }
+
+    /// Format a response with the default preset
     pub fn format(response: &HttpResponse) -> String {
-        use colored::*;
+        Self::new().build(response)
+    }
+
+    /// Status, time, timing breakdown, and Server-Timing section
+    fn render_status(&self, response: &HttpResponse) -> String {
+        use crate::style::Colorize;
 
         let mut output = String::new();
+        if self.status {
+            output.push_str(&Self::format_status(response));
+            output.push('\n');
+            output.push_str(&format!("{} {:.2?}\n", "Time:".bold(), response.duration));
+        }
+        if self.timing {
+            if let Some(timing) = &response.timing {
+                output.push_str(&Self::format_timing(timing));
+            }
+        }
+        if self.status {
+            output.push_str(&Self::format_server_timing(&response.server_timing()));
+        }
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output
+    }
 
-        // Status line with better colors for both light and dark modes
-        let status_str = format!(
-            "{} {}",
-            response.status.as_str(),
-            response.status.canonical_reason().unwrap_or("")
+    /// Response headers section
+    fn render_headers(&self, response: &HttpResponse) -> String {
+        use crate::style::Colorize;
+
+        let shown: Vec<_> = response
+            .headers
+            .iter()
+            .filter(|(name, _)| match &self.headers {
+                HeaderMode::All => true,
+                HeaderMode::Filtered(names) => {
+                    names.iter().any(|n| n.eq_ignore_ascii_case(name.as_str()))
+                }
+                HeaderMode::None => false,
+            })
+            .collect();
+        if shown.is_empty() {
+            return String::new();
+        }
+
+        let mut output = format!("{}\n", "Response Headers:".bold());
+        for (name, value) in shown {
+            output.push_str(&format!(
+                "  {}: {}\n",
+                name.as_str().blue().bold(),
+                value.to_str().unwrap_or("<binary>")
+            ));
+        }
+        output.push('\n');
+        output
+    }
+
+    /// Response body section
+    fn render_body(&self, response: &HttpResponse) -> String {
+        use crate::style::Colorize;
+
+        let highlight = matches!(self.body, BodyMode::Pretty { .. })
+            && (response.is_json() || response.is_ndjson());
+        let (text, max_bytes) = match self.body {
+            BodyMode::None => return String::new(),
+            BodyMode::Pretty { max_bytes } => {
+                // Images get a preview or metadata instead of the raw bytes
+                if let Some(info) = response.image_info() {
+                    let image = Self::format_image(response, &info, InlineProtocol::detect());
+                    return format!("{}\n{}\n", "Response Body:".bold(), image);
+                }
+                (self.masked_pretty_body(response), max_bytes)
+            }
+            BodyMode::Raw { max_bytes } => ((response.body.clone(), 0), max_bytes),
+        };
+        let (text, masked) = text;
+        if text.is_empty() {
+            return String::new();
+        }
+
+        // Only pretty JSON is colored; raw bodies stay exactly as received
+        let mut output = format!(
+            "{}\n{}\n",
+            "Response Body:".bold(),
+            Self::truncate(&text, max_bytes, response.body_bytes.len(), highlight)
         );
+        if masked > 0 {
+            output.push_str(&format!(
+                "{}\n",
+                format!("({} value(s) masked, use --show-secrets)", masked).dimmed()
+            ));
+        }
+        output.push('\n');
+        output
+    }
+
+    /// Pretty body with secret fields masked, and how many were masked
+    fn masked_pretty_body(&self, response: &HttpResponse) -> (String, usize) {
+        if !self.secret_fields.is_empty() {
+            if response.is_ndjson() {
+                let mut masked = 0;
+                let text = pretty_ndjson(&response.body, |json| {
+                    masked += redact::mask_json_fields(json, &self.secret_fields);
+                });
+                return (text, masked);
+            }
+            if let Ok(mut json) = serde_json::from_str::<serde_json::Value>(&response.body) {
+                let masked = redact::mask_json_fields(&mut json, &self.secret_fields);
+                if masked > 0 {
+                    let text = serde_json::to_string_pretty(&json).unwrap_or_default();
+                    return (text, masked);
+                }
+            }
+        }
+        (response.pretty_body(), 0)
+    }
+
+    /// Cut the displayed text to at most `max_bytes` (on a char boundary)
+    /// with a marker, when the body itself (`total` bytes) is over the limit;
+    /// highlights the kept part as JSON if asked
+    fn truncate(text: &str, max_bytes: Option<usize>, total: usize, highlight: bool) -> String {
+        use crate::style::Colorize;
+
+        let render = |kept: &str| {
+            if highlight {
+                highlight_json(kept)
+            } else {
+                kept.to_string()
+            }
+        };
+        match max_bytes {
+            Some(max) if total > max => {
+                let mut end = max.min(text.len());
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                format!(
+                    "{}\n{}",
+                    render(&text[..end]),
+                    format!("… (truncated, total {} bytes)", total).dimmed()
+                )
+            }
+            _ => render(text),
+        }
+    }
+
+    /// Format a timing breakdown, one phase per line
+    pub fn format_timing(timing: &Timing) -> String {
+        use crate::style::Colorize;
+
+        let mut output = String::new();
+        for (label, duration) in timing.phases() {
+            let value = duration
+                .map(|d| format!("{:.2?}", d))
+                .unwrap_or_else(|| "n/a".to_string());
+            output.push_str(&format!(
+                "  {}\n",
+                format!("{:<18}  {}", label, value).dimmed()
+            ));
+        }
+        output
+    }
+
+    /// Format Server-Timing entries as an aligned table
+    pub fn format_server_timing(entries: &[ServerTimingEntry]) -> String {
+        use crate::style::Colorize;
+
+        if entries.is_empty() {
+            return String::new();
+        }
+
+        let name_width = entries.iter().map(|e| e.name.len()).max().unwrap_or(0);
+        let durations: Vec<String> = entries
+            .iter()
+            .map(|e| {
+                e.duration
+                    .map(|d| format!("{:.1}ms", d))
+                    .unwrap_or_else(|| "-".to_string())
+            })
+            .collect();
+        let duration_width = durations.iter().map(|d| d.len()).max().unwrap_or(0);
+
+        let mut output = String::new();
+        for (entry, duration) in entries.iter().zip(&durations) {
+            let line = format!(
+                "  {:<name_width$}  {:>duration_width$}  {}",
+                entry.name,
+                duration,
+                entry.description.as_deref().unwrap_or(""),
+            );
+            output.push_str(&format!("{}\n", line.trim_end().dimmed()));
+        }
+        output
+    }
+
+    /// Format an image body as an inline preview (when supported) plus metadata
+    pub fn format_image(
+        response: &HttpResponse,
+        info: &ImageInfo,
+        protocol: Option<InlineProtocol>,
+    ) -> String {
+        use crate::style::Colorize;
+
+        let mut output = String::new();
+        if let Some(protocol) = protocol.filter(|p| p.supports(info.format)) {
+            output.push_str(&protocol.render(&response.body_bytes));
+        }
 
         output.push_str(&format!(
             "{} {}\n",
-            "Status:".bold(),
-            status_str.color(response.status_color()).bold()
+            format!("[{}]", info.summary()).cyan(),
+            "(use --save-body <FILE> to write it to disk)".dimmed()
         ));
+        output
+    }
 
-        // Duration
-        output.push_str(&format!("{} {:.2?}\n\n", "Time:".bold(), response.duration));
-
-        // Headers
-        if !response.headers.is_empty() {
-            output.push_str(&format!("{}\n", "Response Headers:".bold()));
-            for (name, value) in response.headers.iter() {
-                output.push_str(&format!(
-                    "  {}: {}\n",
-                    name.as_str().blue().bold(),
-                    value.to_str().unwrap_or("<binary>")
-                ));
-            }
-            output.push('\n');
+    /// Format an outgoing request curl-style, with secrets masked
+    pub fn format_request(request: &RequestBuilder) -> String {
+        Self::format_request_with(request, true)
+    }
+
+    /// Format an outgoing request curl-style, masking secrets if `mask` is set
+    pub fn format_request_with(request: &RequestBuilder, mask: bool) -> String {
+        use crate::style::Colorize;
+
+        let mut headers = request.headers.clone();
+        let mut query_params = request.query_params.clone();
+        request.apply_auth(&mut headers, &mut query_params);
+
+        // A custom API key name isn't caught by the header heuristics
+        let api_key = match &request.auth {
+            AuthScheme::ApiKey(auth) => Some((auth.name.as_str(), &auth.location)),
+            _ => None,
+        };
+        let is_api_key = |name: &str, location: ApiKeyLocation| {
+            mask && api_key
+                .is_some_and(|(key, loc)| *loc == location && key.eq_ignore_ascii_case(name))
+        };
+
+        let query: Vec<(&str, &str)> = query_params
+            .iter()
+            .map(|param| {
+                let (name, value) = param.split_once('=').unwrap_or((param, ""));
+                if is_api_key(name, ApiKeyLocation::Query) {
+                    (name, MASK)
+                } else {
+                    (name, value)
+                }
+            })
+            .collect();
+
+        let mut output = String::new();
+        output.push_str(&format!(
+            "{} {} {}\n",
+            ">".dimmed(),
+            request.method.as_str().bold(),
+            Self::resolved_url(&request.url, &query, request.raw_query)
+        ));
+
+        for header in &headers {
+            let (name, value) = header.split_once(':').unwrap_or((header, ""));
+            let (name, value) = (name.trim(), value.trim());
+            let value = if !mask {
+                value.to_string()
+            } else if is_api_key(name, ApiKeyLocation::Header) {
+                redact::mask_header_value(name, value)
+            } else {
+                redact::redact_header(name, value)
+            };
+            output.push_str(&format!(
+                "{} {}: {}\n",
+                ">".dimmed(),
+                name.blue().bold(),
+                value
+            ));
         }
+        output.push_str(&format!("{}\n", ">".dimmed()));
 
-        // Body - no color for better readability in both modes
-        if !response.body.is_empty() {
-            output.push_str(&format!("{}\n", "Response Body:".bold()));
-            let body = response.pretty_body();
-            output.push_str(&format!("{}\n\n", body));
+        if let Some(form_data) = request.get_form_data() {
+            output.push_str(&format!(
+                "{}\n",
+                format!("[form data: {} field(s)]", form_data.count()).dimmed()
+            ));
+        } else if let Some(body) = request.get_raw_body() {
+            output.push_str(&format!("{}\n", body));
         }
 
         output
     }
 
-    /// Format just the status line
-    pub fn format_status(response: &HttpResponse) -> String {
-        use colored::*;
+    /// Append query parameters to a URL, encoding them unless `raw`
+    fn resolved_url(url: &str, query: &[(&str, &str)], raw: bool) -> String {
+        if query.is_empty() {
+            return url.to_string();
+        }
+        match reqwest::Url::parse(url) {
+            Ok(mut parsed) if !raw => {
+                parsed.query_pairs_mut().extend_pairs(query);
+                // Keep the mask readable instead of percent-encoding it
+                parsed.to_string().replace("%2A%2A%2A%2A", MASK)
+            }
+            _ => {
+                let pairs: Vec<String> =
+                    query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                let separator = if url.contains('?') { '&' } else { '?' };
+                format!("{}{}{}", url, separator, pairs.join("&"))
+            }
+        }
+    }
+
+    /// Format a response as a JSON object (status, headers, body, duration_ms)
+    pub fn format_json(response: &HttpResponse) -> String {
+        let mut headers = serde_json::Map::new();
+        for name in response.headers.keys() {
+            let values: Vec<&str> = response
+                .headers
+                .get_all(name)
+                .iter()
+                .map(|v| v.to_str().unwrap_or("<binary>"))
+                .collect();
+            headers.insert(name.to_string(), values.join(", ").into());
+        }
+
+        serde_json::json!({
+            "status": response.status.as_u16(),
+            "headers": headers,
+            "body": response.body,
+            "duration_ms": response.duration.as_millis() as u64,
+        })
+        .to_string()
+    }
+
+    /// Format a one-line summary: status, time, and body size
+    pub fn format_compact(response: &HttpResponse) -> String {
+        use crate::style::Colorize;
 
         let status_str = format!(
             "{} {}",
@@ -138,18 +630,133 @@ impl ResponseFormatter {
             response.status.canonical_reason().unwrap_or("")
         );
 
+        format!(
+            "{}  {}ms  {}",
+            status_str.color(response.status_color()).bold(),
+            response.duration.as_millis(),
+            image::format_size(response.body_bytes.len())
+        )
+    }
+
+    /// Format just the status line
+    pub fn format_status(response: &HttpResponse) -> String {
+        use crate::style::Colorize;
+
         format!(
             "{} {}",
             "Status:".bold(),
-            status_str.color(response.status_color()).bold()
+            Self::format_status_only(response)
         )
     }
+
+    /// Format the bare status, e.g. `200 OK`
+    pub fn format_status_only(response: &HttpResponse) -> String {
+        use crate::style::Colorize;
+
+        let status_str = format!(
+            "{} {}",
+            response.status.as_str(),
+            response.status.canonical_reason().unwrap_or("")
+        );
+        status_str
+            .trim_end()
+            .color(response.status_color())
+            .bold()
+            .to_string()
+    }
+
+    /// Format just the body: pretty and masked like the body section, without its label
+    pub fn format_body(&self, response: &HttpResponse) -> String {
+        let (text, _) = self.masked_pretty_body(response);
+        Self::truncate(
+            &text,
+            self.body.max_bytes(),
+            response.body_bytes.len(),
+            response.is_json() || response.is_ndjson(),
+        )
+    }
+}
+
+/// Check for an NDJSON / JSON Lines media type
+fn is_ndjson_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    [
+        "application/x-ndjson",
+        "application/ndjson",
+        "application/jsonl",
+        "application/x-jsonlines",
+    ]
+    .iter()
+    .any(|ndjson| mime.eq_ignore_ascii_case(ndjson))
+}
+
+/// Pretty-print each NDJSON record between dividers, passing parsed records
+/// through `edit` first; lines that aren't JSON are kept as they are
+fn pretty_ndjson(body: &str, mut edit: impl FnMut(&mut serde_json::Value)) -> String {
+    body.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(
+            |line| match serde_json::from_str::<serde_json::Value>(line) {
+                Ok(mut json) => {
+                    edit(&mut json);
+                    serde_json::to_string_pretty(&json).unwrap_or_else(|_| line.to_string())
+                }
+                Err(_) => line.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join(&format!("\n{}\n", NDJSON_DIVIDER))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_compact_ok() {
+        let response = create_mock_response(StatusCode::OK, &"x".repeat(1234));
+        assert_eq!(
+            ResponseFormatter::format_compact(&response),
+            "200 OK  100ms  1.2 KB"
+        );
+    }
+
+    #[test]
+    fn test_format_compact_not_found() {
+        let response = create_mock_response(StatusCode::NOT_FOUND, "missing");
+        assert_eq!(
+            ResponseFormatter::format_compact(&response),
+            "404 Not Found  100ms  7 B"
+        );
+    }
+
+    #[test]
+    fn test_format_status_only() {
+        let response = create_mock_response(StatusCode::OK, "test");
+        assert_eq!(ResponseFormatter::format_status_only(&response), "200 OK");
+
+        let response = create_mock_response(StatusCode::NOT_FOUND, "test");
+        assert_eq!(
+            ResponseFormatter::format_status_only(&response),
+            "404 Not Found"
+        );
+    }
+
+    #[test]
+    fn test_format_body() {
+        let response = create_mock_response(StatusCode::OK, r#"{"id":1,"password":"abc"}"#);
+        let body = ResponseFormatter::new().format_body(&response);
+        assert_eq!(body, "{\n  \"id\": 1,\n  \"password\": \"****\"\n}");
+
+        let revealed = ResponseFormatter::new()
+            .secret_fields(Vec::new())
+            .format_body(&response);
+        assert!(revealed.contains("abc"));
+
+        let text = create_mock_response(StatusCode::OK, "plain text");
+        assert_eq!(ResponseFormatter::new().format_body(&text), "plain text");
+    }
+
     #[test]
     fn test_status_color_success() {
         let response = create_mock_response(StatusCode::OK, "test");
@@ -177,6 +784,56 @@ mod tests {
         assert!(!response.is_json());
     }
 
+    const NDJSON: &str =
+        "{\"id\":1,\"state\":\"queued\"}\n{\"id\":2,\"password\":\"abc\"}\n\n{\"id\":3}\n";
+
+    #[test]
+    fn test_ndjson_lines() {
+        let response = create_mock_response(StatusCode::OK, NDJSON);
+        assert!(response.is_ndjson());
+        assert!(!response.is_json());
+
+        let lines = response.ndjson_lines();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["state"], "queued");
+        assert_eq!(lines[2]["id"], 3);
+
+        let single = create_mock_response(StatusCode::OK, "{\n  \"id\": 1\n}");
+        assert!(!single.is_ndjson());
+        assert!(!create_mock_response(StatusCode::OK, "one\ntwo").is_ndjson());
+
+        let mut declared = create_mock_response(StatusCode::OK, r#"{"id":1}"#);
+        declared.headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            "application/x-ndjson; charset=utf-8".parse().unwrap(),
+        );
+        assert!(declared.is_ndjson());
+    }
+
+    #[test]
+    fn test_pretty_body_ndjson() {
+        let response = create_mock_response(StatusCode::OK, NDJSON);
+        let divider = format!("\n{}\n", NDJSON_DIVIDER);
+        let records: Vec<String> = response
+            .pretty_body()
+            .split(&divider)
+            .map(str::to_string)
+            .collect();
+        assert_eq!(
+            records,
+            [
+                "{\n  \"id\": 1,\n  \"state\": \"queued\"\n}",
+                "{\n  \"id\": 2,\n  \"password\": \"abc\"\n}",
+                "{\n  \"id\": 3\n}"
+            ]
+        );
+
+        // Secret fields are masked in every record
+        let body = ResponseFormatter::new().format_body(&response);
+        assert!(body.contains("\"password\": \"****\""));
+        assert_eq!(body.matches(NDJSON_DIVIDER).count(), 2);
+    }
+
     #[test]
     fn test_pretty_body_json() {
         let response = create_mock_response(StatusCode::OK, r#"{"key":"value"}"#);
@@ -192,13 +849,304 @@ mod tests {
         assert_eq!(pretty, "plain text");
     }
 
+    #[test]
+    fn test_image_info_requires_image_content_type() {
+        let gif = b"GIF89a\x02\x00\x03\x00\x80\x00\x00";
+        let mut response = create_mock_response(StatusCode::OK, "");
+        response.body_bytes = gif.to_vec();
+        assert!(response.image_info().is_none());
+
+        response
+            .headers
+            .insert(reqwest::header::CONTENT_TYPE, "image/gif".parse().unwrap());
+        let info = response.image_info().unwrap();
+        assert_eq!((info.width, info.height), (2, 3));
+
+        let formatted = ResponseFormatter::format_image(&response, &info, None);
+        assert!(formatted.contains("GIF image, 2x3"));
+        assert!(formatted.contains("--save-body"));
+        assert!(!formatted.contains("\x1b]1337"));
+    }
+
+    #[test]
+    fn test_server_timing_from_headers() {
+        let mut response = create_mock_response(StatusCode::OK, "");
+        response
+            .headers
+            .append("server-timing", "db;dur=12.3".parse().unwrap());
+        response.headers.append(
+            "server-timing",
+            r#"cache;dur=0.4;desc="Cache Read""#.parse().unwrap(),
+        );
+
+        let entries = response.server_timing();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].description.as_deref(), Some("Cache Read"));
+
+        let table = ResponseFormatter::format_server_timing(&entries);
+        assert!(table.contains("db     12.3ms"));
+        assert!(table.contains("cache   0.4ms  Cache Read"));
+    }
+
+    #[test]
+    fn test_format_timing() {
+        let timing = Timing::new()
+            .with_time_to_first_byte(Duration::from_millis(120))
+            .with_content_transfer(Duration::from_millis(8));
+
+        let table = ResponseFormatter::format_timing(&timing);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].contains("DNS lookup          n/a"));
+        assert!(lines[2].contains("TLS handshake       n/a"));
+        assert!(lines[3].contains("Time to first byte  120.00ms"));
+        assert!(lines[4].contains("Content transfer    8.00ms"));
+    }
+
+    #[test]
+    fn test_render_status_section() {
+        let mut response = create_mock_response(StatusCode::NOT_FOUND, "ok");
+        response.timing = Some(Timing::new().with_time_to_first_byte(Duration::from_millis(5)));
+
+        let section = ResponseFormatter::new().render_status(&response);
+        assert!(section.contains("Status: 404 Not Found"));
+        assert!(section.contains("Time: 100.00ms"));
+        assert!(!section.contains("Time to first byte"));
+
+        let section = ResponseFormatter::new()
+            .timing(true)
+            .render_status(&response);
+        assert!(section.contains("Time to first byte"));
+
+        let section = ResponseFormatter::new()
+            .status(false)
+            .render_status(&response);
+        assert!(section.is_empty());
+    }
+
+    #[test]
+    fn test_render_headers_section() {
+        let mut response = create_mock_response(StatusCode::OK, "");
+        response
+            .headers
+            .insert("content-type", "text/plain".parse().unwrap());
+        response.headers.insert("x-trace", "abc".parse().unwrap());
+
+        let all = ResponseFormatter::new().render_headers(&response);
+        assert!(all.starts_with("Response Headers:\n"));
+        assert!(all.contains("  content-type: text/plain\n"));
+        assert!(all.contains("  x-trace: abc\n"));
+
+        let filtered = ResponseFormatter::new()
+            .headers(HeaderMode::Filtered(vec!["Content-Type".to_string()]))
+            .render_headers(&response);
+        assert!(filtered.contains("content-type"));
+        assert!(!filtered.contains("x-trace"));
+
+        let none = ResponseFormatter::new()
+            .headers(HeaderMode::None)
+            .render_headers(&response);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_render_body_section() {
+        let response = create_mock_response(StatusCode::OK, r#"{"a":1}"#);
+
+        let pretty = ResponseFormatter::new().render_body(&response);
+        assert_eq!(pretty, "Response Body:\n{\n  \"a\": 1\n}\n\n");
+
+        let raw = ResponseFormatter::new()
+            .body(BodyMode::Raw { max_bytes: None })
+            .render_body(&response);
+        assert_eq!(raw, "Response Body:\n{\"a\":1}\n\n");
+
+        let truncated = ResponseFormatter::new()
+            .body(BodyMode::Raw { max_bytes: Some(3) })
+            .render_body(&response);
+        assert!(truncated.contains("{\"a\n… (truncated, total 7 bytes)"));
+
+        let none = ResponseFormatter::new()
+            .body(BodyMode::None)
+            .render_body(&response);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_truncate_body() {
+        let below = ResponseFormatter::truncate("hello", Some(5), 5, false);
+        assert_eq!(below, "hello");
+
+        let above = ResponseFormatter::truncate("hello world", Some(5), 11, false);
+        assert_eq!(above, "hello\n… (truncated, total 11 bytes)");
+
+        // "é" is two bytes; a limit inside it keeps only the whole characters
+        let accented = ResponseFormatter::truncate("café au lait", Some(4), 13, false);
+        assert_eq!(accented, "caf\n… (truncated, total 13 bytes)");
+        let emoji = ResponseFormatter::truncate("🦀🦀", Some(6), 8, false);
+        assert_eq!(emoji, "🦀\n… (truncated, total 8 bytes)");
+
+        let response = create_mock_response(StatusCode::OK, "ünïcödé text body");
+        let formatter = ResponseFormatter::new().body(BodyMode::Pretty { max_bytes: Some(2) });
+        assert!(formatter.format_body(&response).starts_with("ü\n…"));
+        assert!(formatter.build(&response).contains("ü\n…"));
+
+        // The limit applies to the body as received, not its pretty-printed form
+        let minified = r#"{"id":1,"name":"Ada","tags":["a","b"]}"#;
+        let response = create_mock_response(StatusCode::OK, minified);
+        let pretty = response.pretty_body();
+        assert!(minified.len() <= 40 && pretty.len() > 40);
+        let formatter = ResponseFormatter::new().body(BodyMode::Pretty {
+            max_bytes: Some(40),
+        });
+        assert_eq!(formatter.format_body(&response), highlight_json(&pretty));
+
+        let formatter = ResponseFormatter::new().body(BodyMode::Pretty {
+            max_bytes: Some(10),
+        });
+        assert!(formatter
+            .format_body(&response)
+            .contains(&format!("… (truncated, total {} bytes)", minified.len())));
+    }
+
+    #[test]
+    fn test_render_body_masks_secrets_for_display_only() {
+        use crate::assertions::{Assertion, Matcher, ResponseValidator};
+
+        let body = r#"{"access_token":"tok-123","user":{"id":7,"password":"hunter2"}}"#;
+        let response = create_mock_response(StatusCode::OK, body);
+
+        let shown = ResponseFormatter::new().render_body(&response);
+        assert!(!shown.contains("tok-123"));
+        assert!(!shown.contains("hunter2"));
+        assert!(shown.contains("\"id\": 7"));
+        assert!(shown.contains("(2 value(s) masked, use --show-secrets)"));
+
+        // Assertions and extraction still see the real body
+        assert_eq!(response.body, body);
+        let report = ResponseValidator::new().validate(
+            &response,
+            &[Assertion::json_path(
+                "$.access_token".to_string(),
+                Matcher::equals_str("tok-123"),
+            )],
+        );
+        assert!(report.success);
+
+        let unmasked = ResponseFormatter::new()
+            .secret_fields(Vec::new())
+            .render_body(&response);
+        assert!(unmasked.contains("tok-123"));
+        assert!(!unmasked.contains("masked"));
+
+        let custom = ResponseFormatter::new()
+            .secret_fields(vec!["id".to_string()])
+            .render_body(&response);
+        assert!(custom.contains("tok-123"));
+        assert!(!custom.contains("\"id\": 7"));
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        assert_eq!(
+            ResponseFormatter::truncate("héllo", None, 6, false),
+            "héllo"
+        );
+        assert!(ResponseFormatter::truncate("héllo", Some(2), 6, false).starts_with("h\n"));
+    }
+
+    #[test]
+    fn test_default_preset_matches_sections() {
+        let response = create_mock_response(StatusCode::OK, "body");
+        let formatter = ResponseFormatter::new();
+        assert_eq!(
+            ResponseFormatter::format(&response),
+            format!(
+                "{}{}{}",
+                formatter.render_status(&response),
+                formatter.render_headers(&response),
+                formatter.render_body(&response)
+            )
+        );
+        assert_eq!(ResponseFormatter::default(), formatter);
+    }
+
+    #[test]
+    fn test_format_request() {
+        let request = RequestBuilder::new(
+            crate::http::HttpMethod::Post,
+            "https://api.example.com/users".to_string(),
+        )
+        .header("Content-Type: application/json".to_string())
+        .header("X-Auth-Token: abc123".to_string())
+        .query("page=2".to_string())
+        .body(r#"{"name":"x"}"#.to_string())
+        .auth(AuthScheme::Bearer(crate::auth::BearerAuth::new(
+            "secret-token".to_string(),
+        )));
+
+        let output = ResponseFormatter::format_request(&request);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "> POST https://api.example.com/users?page=2");
+        assert!(output.contains("> Content-Type: application/json\n"));
+        assert!(output.contains("> X-Auth-Token: ****\n"));
+        assert!(output.contains("> Authorization: Bearer ****\n"));
+        assert!(!output.contains("secret-token"));
+        assert!(!output.contains("abc123"));
+        assert!(output.ends_with(">\n{\"name\":\"x\"}\n"));
+    }
+
+    #[test]
+    fn test_format_request_masks_custom_api_key() {
+        let request = RequestBuilder::new(
+            crate::http::HttpMethod::Get,
+            "https://api.example.com".to_string(),
+        )
+        .auth(AuthScheme::ApiKey(crate::auth::ApiKeyAuth::query(
+            "key".to_string(),
+            "hunter2".to_string(),
+        )));
+
+        let output = ResponseFormatter::format_request(&request);
+        assert!(output.starts_with("> GET https://api.example.com/?key=****\n"));
+        assert!(!output.contains("hunter2"));
+
+        let request = request.auth(AuthScheme::ApiKey(crate::auth::ApiKeyAuth::header(
+            "Custom".to_string(),
+            "hunter2".to_string(),
+        )));
+        let output = ResponseFormatter::format_request(&request);
+        assert!(output.contains("> Custom: ****\n"));
+    }
+
+    #[test]
+    fn test_format_json() {
+        let mut response = create_mock_response(StatusCode::CREATED, r#"{"id":1}"#);
+        response
+            .headers
+            .append("set-cookie", "a=1".parse().unwrap());
+        response
+            .headers
+            .append("set-cookie", "b=2".parse().unwrap());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&ResponseFormatter::format_json(&response)).unwrap();
+        assert_eq!(json["status"], 201);
+        assert_eq!(json["headers"]["set-cookie"], "a=1, b=2");
+        assert_eq!(json["body"], r#"{"id":1}"#);
+        assert_eq!(json["duration_ms"], 100);
+    }
+
     // Helper function for tests
     fn create_mock_response(status: StatusCode, body: &str) -> HttpResponse {
         HttpResponse {
             status,
             headers: HeaderMap::new(),
             body: body.to_string(),
+            body_bytes: body.as_bytes().to_vec(),
             duration: Duration::from_millis(100),
+            timing: None,
         }
     }
 }
@@ -0,0 +1,181 @@
+//! Server-Timing header parsing
+
+use serde::{Deserialize, Serialize};
+
+/// A single metric from a Server-Timing header
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerTimingEntry {
+    /// Metric name
+    pub name: String,
+
+    /// Duration in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+
+    /// Human-readable description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Parse a Server-Timing header value, skipping malformed metrics
+pub fn parse_server_timing(value: &str) -> Vec<ServerTimingEntry> {
+    split_outside_quotes(value, ',')
+        .into_iter()
+        .filter_map(|metric| parse_metric(&metric))
+        .collect()
+}
+
+/// Parse one `name;param=value;...` metric
+fn parse_metric(metric: &str) -> Option<ServerTimingEntry> {
+    let mut parts = split_outside_quotes(metric, ';').into_iter();
+    let name = parts.next()?.trim().to_string();
+    if name.is_empty() || !name.chars().all(is_token_char) {
+        return None;
+    }
+
+    let mut entry = ServerTimingEntry {
+        name,
+        duration: None,
+        description: None,
+    };
+
+    for param in parts {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        let value = unquote(value.trim());
+
+        // Only the first occurrence of each parameter counts
+        match key.trim().to_ascii_lowercase().as_str() {
+            "dur" if entry.duration.is_none() => entry.duration = value.parse().ok(),
+            "desc" if entry.description.is_none() => entry.description = Some(value),
+            _ => {}
+        }
+    }
+
+    Some(entry)
+}
+
+/// Split on a separator, ignoring separators inside quoted strings
+fn split_outside_quotes(value: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for c in value.chars() {
+        if escaped {
+            current.push(c);
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if in_quotes => {
+                current.push(c);
+                escaped = true;
+            }
+            '"' => {
+                current.push(c);
+                in_quotes = !in_quotes;
+            }
+            c if c == separator && !in_quotes => parts.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    parts.push(current);
+
+    parts
+}
+
+/// Remove surrounding quotes and unescape a quoted-string
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        let mut result = String::new();
+        let mut chars = value[1..value.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                if let Some(next) = chars.next() {
+                    result.push(next);
+                }
+            } else {
+                result.push(c);
+            }
+        }
+        result
+    } else {
+        value.to_string()
+    }
+}
+
+/// Check for an RFC 7230 token character
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, duration: Option<f64>, description: Option<&str>) -> ServerTimingEntry {
+        ServerTimingEntry {
+            name: name.to_string(),
+            duration,
+            description: description.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_spec_examples() {
+        assert_eq!(
+            parse_server_timing("miss, db;dur=53, app;dur=47.2"),
+            vec![
+                entry("miss", None, None),
+                entry("db", Some(53.0), None),
+                entry("app", Some(47.2), None),
+            ]
+        );
+        assert_eq!(
+            parse_server_timing("customView, dc;desc=atl"),
+            vec![
+                entry("customView", None, None),
+                entry("dc", None, Some("atl"))
+            ]
+        );
+        assert_eq!(
+            parse_server_timing(r#"cache;desc="Cache Read";dur=23.2"#),
+            vec![entry("cache", Some(23.2), Some("Cache Read"))]
+        );
+        assert_eq!(
+            parse_server_timing("total;dur=123.4"),
+            vec![entry("total", Some(123.4), None)]
+        );
+    }
+
+    #[test]
+    fn test_quoted_separators() {
+        assert_eq!(
+            parse_server_timing(r#"db;desc="a, b; c";dur=1, cache;dur=0.4"#),
+            vec![
+                entry("db", Some(1.0), Some("a, b; c")),
+                entry("cache", Some(0.4), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_entries_skipped() {
+        assert_eq!(
+            parse_server_timing(r#", bad name;dur=1, db;dur=abc, "quoted";dur=2, ok;dur=3"#),
+            vec![entry("db", None, None), entry("ok", Some(3.0), None)]
+        );
+        assert!(parse_server_timing("").is_empty());
+    }
+
+    #[test]
+    fn test_first_param_wins() {
+        assert_eq!(
+            parse_server_timing("db;dur=1;dur=2;desc=x;desc=y"),
+            vec![entry("db", Some(1.0), Some("x"))]
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Request/response timing breakdown
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Per-phase timing of a request
+///
+/// Phases the HTTP client doesn't expose are left as `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    /// DNS lookup
+    pub dns: Option<Duration>,
+
+    /// TCP connect
+    pub connect: Option<Duration>,
+
+    /// TLS handshake
+    pub tls_handshake: Option<Duration>,
+
+    /// Time from sending the request until response headers arrived
+    pub time_to_first_byte: Option<Duration>,
+
+    /// Time spent reading the response body
+    pub content_transfer: Option<Duration>,
+}
+
+impl Timing {
+    /// Create an empty timing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the time to first byte
+    pub fn with_time_to_first_byte(mut self, duration: Duration) -> Self {
+        self.time_to_first_byte = Some(duration);
+        self
+    }
+
+    /// Set the content transfer time
+    pub fn with_content_transfer(mut self, duration: Duration) -> Self {
+        self.content_transfer = Some(duration);
+        self
+    }
+
+    /// All phases in order, with their display labels
+    pub fn phases(&self) -> [(&'static str, Option<Duration>); 5] {
+        [
+            ("DNS lookup", self.dns),
+            ("TCP connect", self.connect),
+            ("TLS handshake", self.tls_handshake),
+            ("Time to first byte", self.time_to_first_byte),
+            ("Content transfer", self.content_transfer),
+        ]
+    }
+
+    /// Sum of all measured phases
+    pub fn total(&self) -> Duration {
+        self.phases().iter().filter_map(|(_, d)| *d).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_total_skips_missing_phases() {
+        let timing = Timing::new()
+            .with_time_to_first_byte(Duration::from_millis(120))
+            .with_content_transfer(Duration::from_millis(30));
+
+        assert_eq!(timing.dns, None);
+        assert_eq!(timing.total(), Duration::from_millis(150));
+    }
+}
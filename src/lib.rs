@@ -2,20 +2,50 @@
 //!
 //! This library provides the core functionality for making HTTP requests,
 //! managing collections, and handling various API testing scenarios.
+//!
+//! The `http`, `assertions`, and `env` layers are always available. Everything
+//! else sits behind cargo features, all enabled by default:
+//!
+//! - `scripts`: Rhai scripts and the `workflow` engine
+//! - `repl`: the interactive REPL, command-line parsing, and the config file
+//! - `ui-color`: colored terminal output (plain text without it)
+//! - `collections`: saved collections and workspaces (implies `history`)
+//! - `history`: request history storage
+
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub mod assertions;
 pub mod auth;
+#[cfg(feature = "repl")]
+#[cfg_attr(docsrs, doc(cfg(feature = "repl")))]
 pub mod cli;
+#[cfg(feature = "collections")]
+#[cfg_attr(docsrs, doc(cfg(feature = "collections")))]
 pub mod collections;
+#[cfg(feature = "repl")]
+#[cfg_attr(docsrs, doc(cfg(feature = "repl")))]
+pub mod config;
 pub mod env;
 pub mod error;
+#[cfg(feature = "history")]
+#[cfg_attr(docsrs, doc(cfg(feature = "history")))]
 pub mod history;
 pub mod http;
+#[cfg(feature = "repl")]
+#[cfg_attr(docsrs, doc(cfg(feature = "repl")))]
 pub mod repl;
+pub mod report;
+#[cfg(feature = "scripts")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scripts")))]
 pub mod scripts;
 pub mod session;
+mod style;
+#[cfg(feature = "ui-color")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ui-color")))]
 pub mod ui;
 pub mod upload;
+#[cfg(feature = "scripts")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scripts")))]
 pub mod workflow;
 
 pub use error::{Error, Result};
@@ -3,59 +3,1641 @@
 //! License: MIT
 
 use bazzounquester::{
-    cli::{Cli, Commands},
-    http::{HttpClient, HttpMethod, RequestBuilder, ResponseFormatter},
-    repl::ReplMode,
+    assertions::{Assertion, ResponseValidator, SnapshotOutcome, SnapshotRules, SnapshotStore},
+    auth::{
+        flow, AuthProfile, AuthProfileStore, AuthScheme, AuthorizationCodeFlow, JwtConfig,
+        TokenCache,
+    },
+    cli::{
+        exit, AssertArgs, AuthCommands, Cli, CollectionCacheCommands, CollectionCommands, Commands,
+        ConfigCommands, ExportFormat, HistoryCommands, HistoryFilterArgs, OutputFormat,
+        SessionCommands, WorkflowCommands, WorkflowVariableArgs,
+    },
+    collections::{
+        Collection, CollectionRunner, CollectionStorage, MatrixRequest, MatrixRunner, ResponseCache,
+    },
+    config::Config,
+    env::EnvironmentManager,
+    history::{
+        csv::write_csv, Backend, CsvField, HistoryEntry, HistoryFilter, HistoryLogger,
+        HistoryStorage, NameTemplate, REPLAY_OF_KEY,
+    },
+    http::{
+        lint::lint_request, redact, BenchStats, BodyMode, Deadline, HttpClient, HttpMethod,
+        HttpResponse, LoadTest, NoProxy, ProxyConfig, RecordMode, RecordingClient, RequestBuilder,
+        ResponseDiffer, ResponseFormatter,
+    },
+    repl::{InputHistory, ReplCompleter, ReplMode},
+    report::{JUnitTestSuite, ReportFormat, ReportTarget},
+    session::{CookieJar, SessionManager},
+    ui,
+    upload::FormData,
+    workflow::{RequestChain, StreamRunner, WorkflowExecutor},
 };
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use colored::*;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Output options shared by all request commands
+#[derive(Clone)]
+struct RequestOptions {
+    auth: Option<String>,
+    insecure: bool,
+    timeout: Option<Duration>,
+    follow_redirects: bool,
+    user_agent: Option<String>,
+    default_headers: Vec<String>,
+    cert: Option<PathBuf>,
+    key: Option<PathBuf>,
+    cert_password: Option<String>,
+    cacert: Option<PathBuf>,
+    proxy: Option<String>,
+    raw_query: bool,
+    strict_lint: bool,
+    save_body: Option<PathBuf>,
+    max_body_bytes: Option<usize>,
+    no_cookies: bool,
+    deadline: Option<Deadline>,
+    verbose: bool,
+    quiet: bool,
+    body_only: bool,
+    status_only: bool,
+    output: OutputFormat,
+    fail: bool,
+    fail_with_body: bool,
+    secret_fields: Vec<String>,
+    show_secrets: bool,
+    dry_run: bool,
+    no_history: bool,
+    history_max_body: Option<usize>,
+    tags: Vec<String>,
+    history_metadata: Vec<(String, String)>,
+    repeat: u32,
+    assertions: Vec<Assertion>,
+    snapshot: Option<String>,
+    snapshot_rules: SnapshotRules,
+    update_snapshots: bool,
+    reports: Vec<ReportTarget>,
+    record_mode: Option<RecordMode>,
+}
 
 fn main() {
     let cli = Cli::parse();
+    let settings = load_config().with_overrides(Config {
+        timeout: cli.timeout,
+        follow_redirects: cli.follow_redirects,
+        insecure: cli.insecure.then_some(true),
+        color: cli.color,
+        user_agent: cli.user_agent,
+        ..Config::new()
+    });
+    ui::color::init(settings.color.unwrap_or_default());
+    let options = RequestOptions {
+        auth: cli.auth,
+        insecure: settings.insecure.unwrap_or(false),
+        timeout: settings.timeout,
+        follow_redirects: settings.follow_redirects.unwrap_or(true),
+        user_agent: settings.user_agent.clone(),
+        default_headers: settings.header_lines(),
+        cert: cli.cert,
+        key: cli.key,
+        cert_password: cli.cert_password,
+        cacert: cli.cacert,
+        proxy: cli.proxy,
+        raw_query: cli.raw_query,
+        strict_lint: cli.strict_lint,
+        save_body: cli.save_body,
+        max_body_bytes: cli.max_body_bytes,
+        no_cookies: cli.no_cookies,
+        deadline: cli.max_time.map(Deadline::after),
+        verbose: cli.verbose,
+        // Printing only part of the response is as quiet as the summary line
+        quiet: cli.quiet || cli.body_only || cli.status_only,
+        body_only: cli.body_only,
+        status_only: cli.status_only,
+        output: cli.output,
+        fail: cli.fail || cli.fail_with_body,
+        fail_with_body: cli.fail_with_body,
+        secret_fields: if cli.show_secrets {
+            Vec::new()
+        } else {
+            redact::DEFAULT_SECRET_FIELDS
+                .iter()
+                .map(|f| f.to_string())
+                .chain(cli.secret_fields)
+                .collect()
+        },
+        show_secrets: cli.show_secrets,
+        dry_run: cli.dry_run,
+        no_history: cli.no_history,
+        history_max_body: Some(cli.history_max_body).filter(|max| *max > 0),
+        tags: Vec::new(),
+        repeat: cli.repeat,
+        assertions: Vec::new(),
+        snapshot: None,
+        snapshot_rules: SnapshotRules::new(),
+        update_snapshots: cli.update_snapshots,
+        reports: cli.reports,
+        record_mode: if cli.record {
+            Some(RecordMode::Record)
+        } else if cli.replay {
+            Some(RecordMode::Replay)
+        } else {
+            None
+        },
+        history_metadata: Vec::new(),
+    };
 
     match cli.command {
         None | Some(Commands::Interactive) => {
-            if let Err(e) = run_interactive_mode() {
+            if let Err(e) = run_interactive_mode(&options) {
                 eprintln!("{} {}", "Error:".red().bold(), e);
                 std::process::exit(1);
             }
         }
-        Some(Commands::Get { url, header, query }) => {
-            execute_request(HttpMethod::Get, &url, header, None, query);
+        Some(Commands::Get {
+            url,
+            header,
+            query,
+            tags,
+            checks,
+        }) => {
+            let options = RequestOptions {
+                tags,
+                assertions: request_assertions(&checks),
+                snapshot: checks.snapshot.clone(),
+                snapshot_rules: checks.snapshot_rules(),
+                ..options
+            };
+            execute_request(
+                HttpMethod::Get,
+                &url,
+                header,
+                None,
+                Vec::new(),
+                query,
+                &options,
+            );
         }
         Some(Commands::Post {
             url,
             header,
             body,
+            form,
             query,
+            tags,
+            checks,
         }) => {
-            execute_request(HttpMethod::Post, &url, header, body, query);
+            let options = RequestOptions {
+                tags,
+                assertions: request_assertions(&checks),
+                snapshot: checks.snapshot.clone(),
+                snapshot_rules: checks.snapshot_rules(),
+                ..options
+            };
+            execute_request(HttpMethod::Post, &url, header, body, form, query, &options);
         }
         Some(Commands::Put {
             url,
             header,
             body,
+            form,
             query,
+            tags,
+            checks,
         }) => {
-            execute_request(HttpMethod::Put, &url, header, body, query);
+            let options = RequestOptions {
+                tags,
+                assertions: request_assertions(&checks),
+                snapshot: checks.snapshot.clone(),
+                snapshot_rules: checks.snapshot_rules(),
+                ..options
+            };
+            execute_request(HttpMethod::Put, &url, header, body, form, query, &options);
         }
-        Some(Commands::Delete { url, header, query }) => {
-            execute_request(HttpMethod::Delete, &url, header, None, query);
+        Some(Commands::Delete {
+            url,
+            header,
+            query,
+            tags,
+            checks,
+        }) => {
+            let options = RequestOptions {
+                tags,
+                assertions: request_assertions(&checks),
+                snapshot: checks.snapshot.clone(),
+                snapshot_rules: checks.snapshot_rules(),
+                ..options
+            };
+            execute_request(
+                HttpMethod::Delete,
+                &url,
+                header,
+                None,
+                Vec::new(),
+                query,
+                &options,
+            );
         }
         Some(Commands::Patch {
             url,
             header,
             body,
+            form,
+            query,
+            tags,
+            checks,
+        }) => {
+            let options = RequestOptions {
+                tags,
+                assertions: request_assertions(&checks),
+                snapshot: checks.snapshot.clone(),
+                snapshot_rules: checks.snapshot_rules(),
+                ..options
+            };
+            execute_request(HttpMethod::Patch, &url, header, body, form, query, &options);
+        }
+        Some(Commands::Graphql {
+            url,
+            query,
+            query_file,
+            variables,
+            operation_name,
+            header,
+            tags,
+            checks,
+        }) => match graphql_request(&url, query, query_file, variables, operation_name, header) {
+            Ok(request) => {
+                let options = RequestOptions {
+                    tags,
+                    assertions: request_assertions(&checks),
+                    snapshot: checks.snapshot.clone(),
+                    snapshot_rules: checks.snapshot_rules(),
+                    ..options
+                };
+                send_request(request, &options, history_storage(&options).as_ref(), true)
+            }
+            Err(e) => {
+                eprintln!("{} {}", "✗".red().bold(), e);
+                std::process::exit(1);
+            }
+        },
+        Some(Commands::ExecStream { concurrency }) => match exec_stream(concurrency, &options) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(failure_code(&options)),
+            Err(e) => {
+                eprintln!("{} {}", "✗".red().bold(), e);
+                std::process::exit(error_code(&e));
+            }
+        },
+        Some(Commands::Load {
+            url,
+            method,
+            header,
+            body,
             query,
+            concurrency,
+            requests,
+            duration,
         }) => {
-            execute_request(HttpMethod::Patch, &url, header, body, query);
+            let mut request = RequestBuilder::new(method, url)
+                .headers(header)
+                .queries(query);
+            if let Some(body) = body {
+                request = request.body_arg(&body).unwrap_or_else(|e| {
+                    eprintln!("{} {}", "✗".red().bold(), e);
+                    std::process::exit(1);
+                });
+            }
+            load_test(request, concurrency, requests, duration, &options);
+        }
+        Some(Commands::Auth { command }) => {
+            if let Err(e) = run_auth_command(command, &options) {
+                eprintln!();
+                eprintln!("{} {}", "✗".red().bold(), e);
+                eprintln!();
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Collection { command }) => match run_collection_command(command, &options) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(failure_code(&options)),
+            Err(e) => {
+                eprintln!();
+                eprintln!("{} {}", "✗".red().bold(), e);
+                eprintln!();
+                std::process::exit(error_code(&e));
+            }
+        },
+        Some(Commands::History { command }) => {
+            if let Err(e) = run_history_command(command, &options) {
+                eprintln!();
+                eprintln!("{} {}", "✗".red().bold(), e);
+                eprintln!();
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Config { command }) => {
+            if let Err(e) = run_config_command(command) {
+                eprintln!();
+                eprintln!("{} {}", "✗".red().bold(), e);
+                eprintln!();
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Session { command }) => {
+            if let Err(e) = run_session_command(command) {
+                eprintln!();
+                eprintln!("{} {}", "✗".red().bold(), e);
+                eprintln!();
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Matrix {
+            requests,
+            envs,
+            extract,
+            best_effort,
+        }) => match run_matrix(&requests, &envs, extract, &options) {
+            Ok(passed) if passed || best_effort => {}
+            Ok(_) => std::process::exit(failure_code(&options)),
+            Err(e) => {
+                eprintln!();
+                eprintln!("{} {}", "✗".red().bold(), e);
+                eprintln!();
+                std::process::exit(error_code(&e));
+            }
+        },
+        Some(Commands::Help { topic }) => show_help(topic.as_deref()),
+        Some(Commands::Examples) => ui::Help::show_examples(),
+        Some(Commands::Workflow { command }) => match run_workflow_command(command, &options) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(failure_code(&options)),
+            Err(e) => {
+                eprintln!();
+                eprintln!("{} {}", "✗".red().bold(), e);
+                eprintln!();
+                std::process::exit(error_code(&e));
+            }
+        },
+    }
+}
+
+fn show_help(topic: Option<&str>) {
+    match topic {
+        None => {
+            Cli::command().print_help().ok();
+            println!();
+            ui::Help::show_topics();
+        }
+        Some(name) => match ui::find_topic(name) {
+            Some(topic) => ui::Help::show_topic(topic),
+            None => {
+                eprintln!("{} Unknown help topic '{}'", "✗".red().bold(), name);
+                eprintln!();
+                ui::Help::show_topics();
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+fn run_auth_command(command: AuthCommands, options: &RequestOptions) -> bazzounquester::Result<()> {
+    match command {
+        AuthCommands::Add {
+            name,
+            bearer,
+            basic,
+            apikey,
+            location,
+            jwt,
+            alg,
+            iss,
+            sub,
+            aud,
+            ttl,
+            claim,
+        } => {
+            let profile = if jwt {
+                let key = options.key.as_ref().ok_or_else(|| {
+                    bazzounquester::Error::MissingArgument(
+                        "--key (signing key for --jwt)".to_string(),
+                    )
+                })?;
+                // Store an absolute key path so the profile works from any directory
+                let key = if key.is_file() {
+                    std::fs::canonicalize(key)?
+                } else {
+                    key.clone()
+                };
+                let mut config =
+                    JwtConfig::new(alg.parse()?, key.to_string_lossy().into_owned()).with_ttl(ttl);
+                if let Some(iss) = iss {
+                    config = config.with_issuer(iss);
+                }
+                if let Some(sub) = sub {
+                    config = config.with_subject(sub);
+                }
+                if let Some(aud) = aud {
+                    config = config.with_audience(aud);
+                }
+                for claim in claim {
+                    let (name, value) = claim.split_once('=').ok_or_else(|| {
+                        bazzounquester::Error::InvalidCommand(
+                            "JWT claims must be in format 'name=value'".to_string(),
+                        )
+                    })?;
+                    // Values that parse as JSON (numbers, booleans, objects) keep their type
+                    let value = serde_json::from_str(value)
+                        .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+                    config = config.with_claim(name.to_string(), value);
+                }
+                AuthProfile::jwt(name.clone(), config)
+            } else if let Some(token) = bearer {
+                AuthProfile::bearer(name.clone(), token)
+            } else if let Some(credentials) = basic {
+                AuthProfile::basic(name.clone(), &credentials)?
+            } else if let Some(key) = apikey {
+                AuthProfile::api_key(name.clone(), &key, location == "query")?
+            } else {
+                return Err(bazzounquester::Error::MissingArgument(
+                    "one of --bearer, --basic, --apikey or --jwt".to_string(),
+                ));
+            };
+
+            let kind = profile.kind();
+            let mut store = AuthProfileStore::load(AuthProfileStore::default_path()?)?;
+            store.add(profile);
+            store.save()?;
+            println!(
+                "{} Saved auth profile '{}' ({})",
+                "✓".green().bold(),
+                name,
+                kind
+            );
+            Ok(())
+        }
+        AuthCommands::List => {
+            let store = AuthProfileStore::load(AuthProfileStore::default_path()?)?;
+            let profiles = store.list();
+            if profiles.is_empty() {
+                println!(
+                    "No auth profiles saved. Add one with 'auth add <name> --bearer <token>'."
+                );
+            }
+            for profile in profiles {
+                println!("  {}  {}", profile.name.bold(), profile.kind().dimmed());
+            }
+            Ok(())
+        }
+        AuthCommands::Remove { name } => {
+            let mut store = AuthProfileStore::load(AuthProfileStore::default_path()?)?;
+            if !store.remove(&name) {
+                return Err(bazzounquester::Error::InvalidCommand(format!(
+                    "Auth profile '{}' not found",
+                    name
+                )));
+            }
+            store.save()?;
+            println!("{} Removed auth profile '{}'", "✓".green().bold(), name);
+            Ok(())
+        }
+        AuthCommands::Login {
+            name,
+            port,
+            no_browser,
+        } => auth_login(&name, port, no_browser),
+    }
+}
+
+fn auth_login(name: &str, port: u16, no_browser: bool) -> bazzounquester::Result<()> {
+    // An OAuth2 auth profile takes precedence over an environment of the same name
+    let mut profiles = AuthProfileStore::load(AuthProfileStore::default_path()?)?;
+    let profile_auth = match profiles.get(name).map(|p| &p.scheme) {
+        Some(AuthScheme::OAuth2(auth)) => Some(auth.clone()),
+        _ => None,
+    };
+
+    let mut manager = EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+    let mut env_id = None;
+    let auth = match profile_auth {
+        Some(auth) => auth,
+        None => {
+            manager.load_all()?;
+            let env = manager.get_environment_by_name(name).ok_or_else(|| {
+                bazzounquester::Error::InvalidCommand(format!(
+                    "No OAuth2 auth profile or environment named '{}'",
+                    name
+                ))
+            })?;
+            env_id = Some(env.id);
+            flow::oauth2_from_environment(env)?
+        }
+    };
+
+    let login = AuthorizationCodeFlow::new(auth.clone()).with_port(port);
+    let token = login.run(|url| {
+        println!();
+        if !no_browser && flow::open_browser(url) {
+            println!("{} Opened your browser to log in.", "→".blue().bold());
+            println!("If nothing happened, open this URL:");
+        } else {
+            println!("{} Open this URL to log in:", "→".blue().bold());
+        }
+        println!("  {}", url.cyan());
+        println!();
+        println!("Waiting for the redirect on {}...", login.redirect_uri());
+    })?;
+
+    let mut cache = TokenCache::load(TokenCache::default_path()?)?;
+    cache.insert(name.to_string(), token.clone());
+    cache.save()?;
+
+    match env_id {
+        Some(env_id) => {
+            if let Some(env) = manager.get_environment_mut(&env_id) {
+                env.set_secret(
+                    "OAUTH2_ACCESS_TOKEN".to_string(),
+                    token.access_token.clone(),
+                );
+                if let Some(refresh) = &token.refresh_token {
+                    env.set_secret("OAUTH2_REFRESH_TOKEN".to_string(), refresh.clone());
+                }
+            }
+            manager.save_environment(&env_id)?;
+        }
+        None => {
+            if let Some(profile) = profiles.get_mut(name) {
+                profile.scheme = AuthScheme::OAuth2(auth.with_token(token.clone()));
+            }
+            profiles.save()?;
+        }
+    }
+
+    println!();
+    println!("{} Logged in to '{}'", "✓".green().bold(), name);
+    if let Some(expires_at) = token.expires_at {
+        println!("  Token expires at {}", expires_at.to_rfc3339());
+    }
+    println!();
+    Ok(())
+}
+
+fn run_history_command(
+    command: HistoryCommands,
+    options: &RequestOptions,
+) -> bazzounquester::Result<()> {
+    let storage = open_history()?;
+
+    match command {
+        HistoryCommands::List { limit, filter } => {
+            let filter = history_filter(filter);
+            let entries = storage.load_all()?;
+            let entries: Vec<_> = filter.apply(&entries).into_iter().take(limit).collect();
+            if entries.is_empty() {
+                println!("No matching history entries.");
+                return Ok(());
+            }
+
+            for entry in entries {
+                let (status, status_color) = match &entry.response {
+                    Some(r) if r.status_code == 0 => ("ERR".to_string(), Color::Red),
+                    Some(r) if r.is_error => (r.status_code.to_string(), Color::Red),
+                    Some(r) => (r.status_code.to_string(), Color::Green),
+                    None => ("-".to_string(), Color::Yellow),
+                };
+                let duration = entry
+                    .duration
+                    .map_or("-".to_string(), |d| format!("{}ms", d.as_millis()));
+                let mut name = entry
+                    .name()
+                    .map(|name| format!("  {}", name.dimmed()))
+                    .unwrap_or_default();
+                if !entry.tags.is_empty() {
+                    name.push_str(&format!("  [{}]", entry.tags.join(", ")).cyan().to_string());
+                }
+                println!(
+                    "{}  {}  {:<7} {}  {}  {}{}",
+                    short_id(&entry.id).cyan(),
+                    entry
+                        .timestamp
+                        .with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S"),
+                    entry.request.method.bold(),
+                    entry.request.url,
+                    status.color(status_color),
+                    duration.dimmed(),
+                    name
+                );
+            }
+        }
+        HistoryCommands::Show { id } => {
+            let entry = storage.find(&id)?;
+            println!();
+            println!(
+                "{} {}  {}",
+                "Entry".bold(),
+                short_id(&entry.id).cyan(),
+                entry
+                    .timestamp
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+            );
+            println!("{}", entry.display_name(&NameTemplate::default()).dimmed());
+            if !entry.tags.is_empty() {
+                println!("{} {}", "Tags:".bold(), entry.tags.join(", ").cyan());
+            }
+            if let Some(note) = entry.note() {
+                println!("{} {}", "Note:".bold(), note);
+            }
+            if let Some(original) = entry.metadata.get(REPLAY_OF_KEY) {
+                println!("{} {}", "Replay of:".bold(), original);
+            }
+            println!();
+            print!(
+                "{}",
+                ResponseFormatter::format_request(&entry.request.to_request_builder()?)
+            );
+            println!();
+            match &entry.response {
+                Some(response) if response.status_code == 0 => println!(
+                    "{} {}",
+                    "✗".red().bold(),
+                    response
+                        .error_message
+                        .as_deref()
+                        .unwrap_or("Request failed")
+                ),
+                Some(response) => {
+                    let duration = entry.duration.unwrap_or_default();
+                    print!(
+                        "{}",
+                        ResponseFormatter::new()
+                            .secret_fields(options.secret_fields.clone())
+                            .build(&response.to_response(duration))
+                    );
+                }
+                None => println!("{}", "No response recorded".dimmed()),
+            }
+        }
+        HistoryCommands::Replay { id, resubstitute } => {
+            let entry = storage.find(&id)?;
+            let mut request = entry.to_request_builder()?;
+            if resubstitute {
+                let mut environments =
+                    EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+                environments.load_all()?;
+                if let Some(env) = environments.get_active_environment() {
+                    request = request.substitute_variables(&env.enabled_variables());
+                }
+            }
+            let options = RequestOptions {
+                history_metadata: vec![(REPLAY_OF_KEY.to_string(), entry.id.to_string())],
+                ..options.clone()
+            };
+            send_request(request, &options, history_storage(&options).as_ref(), false);
+        }
+        HistoryCommands::Tag { id, tag } => {
+            let mut entry = storage.find(&id)?;
+            entry.add_tag(tag.clone());
+            storage.save_entry(&entry)?;
+            println!(
+                "{} Tagged {} with '{}'",
+                "✓".green().bold(),
+                short_id(&entry.id),
+                tag
+            );
+        }
+        HistoryCommands::Untag { id, tag } => {
+            let mut entry = storage.find(&id)?;
+            if !entry.remove_tag(&tag) {
+                return Err(bazzounquester::Error::InvalidCommand(format!(
+                    "History entry {} has no tag '{}'",
+                    short_id(&entry.id),
+                    tag
+                )));
+            }
+            storage.save_entry(&entry)?;
+            println!(
+                "{} Removed tag '{}' from {}",
+                "✓".green().bold(),
+                tag,
+                short_id(&entry.id)
+            );
+        }
+        HistoryCommands::Note { id, message } => {
+            let mut entry = storage.find(&id)?;
+            entry.set_note(message);
+            storage.save_entry(&entry)?;
+            println!(
+                "{} Saved note on {}",
+                "✓".green().bold(),
+                short_id(&entry.id)
+            );
+        }
+        HistoryCommands::Delete { id } => {
+            let entry = storage.find(&id)?;
+            storage.delete_entry(&entry.id)?;
+            println!(
+                "{} Deleted history entry {}",
+                "✓".green().bold(),
+                short_id(&entry.id)
+            );
+        }
+        HistoryCommands::Clear { older_than } => {
+            let removed = match older_than {
+                Some(age) => {
+                    let age = chrono::Duration::from_std(age).unwrap_or(chrono::Duration::MAX);
+                    storage.delete_older_than(chrono::Utc::now() - age)?
+                }
+                None => storage.clear_all()?,
+            };
+            println!(
+                "{} Removed {} history entr{}",
+                "✓".green().bold(),
+                removed,
+                if removed == 1 { "y" } else { "ies" }
+            );
+        }
+        HistoryCommands::Name { id, name } => {
+            let mut entry = storage.find(&id)?;
+            let previous = entry.display_name(&NameTemplate::default());
+            entry.set_name(name.clone());
+            storage.save_entry(&entry)?;
+            println!(
+                "{} Renamed '{}' to '{}'",
+                "✓".green().bold(),
+                previous,
+                name
+            );
+        }
+        HistoryCommands::Diff {
+            id1,
+            id2,
+            ignore_headers,
+            all_headers,
+        } => {
+            let old = recorded_response(&storage.find(&id1)?)?;
+            let new = recorded_response(&storage.find(&id2)?)?;
+            let differ = if all_headers {
+                ResponseDiffer::new().with_all_headers()
+            } else {
+                ResponseDiffer::new()
+            };
+            let differ = ignore_headers
+                .iter()
+                .fold(differ, |differ, name| differ.ignore_header(name));
+            print!("{}", differ.diff(&old, &new).render());
+        }
+        HistoryCommands::Export {
+            file,
+            format,
+            fields,
+            filter,
+        } => {
+            let filter = history_filter(filter);
+            let fields = if fields.is_empty() {
+                CsvField::DEFAULT.to_vec()
+            } else {
+                fields
+            };
+
+            let count = match (format, &file) {
+                (ExportFormat::Csv, Some(path)) => storage.export_csv(path, &fields, &filter)?,
+                (ExportFormat::Csv, None) => {
+                    let entries = storage.load_all()?;
+                    write_csv(
+                        &mut std::io::stdout().lock(),
+                        filter.apply(&entries),
+                        &fields,
+                    )?
+                }
+                (ExportFormat::Json, _) => {
+                    let entries = storage.load_all()?;
+                    let entries = filter.apply(&entries);
+                    let json = serde_json::to_string_pretty(&entries)?;
+                    match &file {
+                        Some(path) => std::fs::write(path, json)?,
+                        None => println!("{}", json),
+                    }
+                    entries.len()
+                }
+            };
+            if let Some(path) = file {
+                println!(
+                    "{} Exported {} entr{} to {}",
+                    "✓".green().bold(),
+                    count,
+                    if count == 1 { "y" } else { "ies" },
+                    path.display()
+                );
+            }
+        }
+        HistoryCommands::Stats { group_by, since } => {
+            let filter = history_filter(HistoryFilterArgs {
+                since,
+                ..Default::default()
+            });
+
+            let stats = storage.stats(group_by, &filter)?;
+            if options.output == OutputFormat::Json {
+                println!("{}", stats.to_json()?);
+            } else if stats.groups.is_empty() {
+                println!("No matching history entries.");
+            } else {
+                println!("{}", stats.render_table());
+            }
+        }
+        HistoryCommands::Migrate => {
+            let storage = storage.with_backend(Backend::JsonLines);
+            let moved = storage.migrate_files()?;
+            println!(
+                "{} Moved {} history entr{} into history.jsonl",
+                "✓".green().bold(),
+                moved,
+                if moved == 1 { "y" } else { "ies" }
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Build a history filter from command-line flags
+fn history_filter(args: HistoryFilterArgs) -> HistoryFilter {
+    let mut filter = HistoryFilter::new();
+    if let Some(method) = args.method {
+        filter = filter.with_method(method);
+    }
+    if let Some(status) = args.status {
+        filter = filter.with_status(status);
+    }
+    if args.failed {
+        filter = filter.with_failed();
+    }
+    if let Some(pattern) = args.url_contains {
+        filter = filter.with_url_contains(pattern);
+    }
+    if let Some(tag) = args.tag {
+        filter = filter.with_tag(tag);
+    }
+    if let Some(since) = args.since {
+        let since = chrono::Duration::from_std(since).unwrap_or(chrono::Duration::MAX);
+        filter = filter.with_since(chrono::Utc::now() - since);
+    }
+    filter
+}
+
+/// The response recorded for an entry, or an error if there isn't one
+fn recorded_response(entry: &HistoryEntry) -> bazzounquester::Result<HttpResponse> {
+    entry.recorded_response().ok_or_else(|| {
+        bazzounquester::Error::InvalidCommand(format!(
+            "History entry {} has no response to compare",
+            short_id(&entry.id)
+        ))
+    })
+}
+
+/// Open the default history store with whichever backend it already uses
+fn open_history() -> bazzounquester::Result<HistoryStorage> {
+    let path = HistoryStorage::default_path()?;
+    let backend = Backend::detect(&path);
+    Ok(HistoryStorage::new(path)?.with_backend(backend))
+}
+
+/// First 8 characters of an ID, as accepted by the history commands
+fn short_id(id: &uuid::Uuid) -> String {
+    id.to_string()[..8].to_string()
+}
+
+/// Load the config file, warning and falling back to defaults if it's unreadable
+fn load_config() -> Config {
+    match Config::default_path().and_then(|path| Config::load(&path)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{} {}", "⚠".yellow().bold(), e);
+            Config::new()
+        }
+    }
+}
+
+fn run_config_command(command: ConfigCommands) -> bazzounquester::Result<()> {
+    let path = Config::default_path()?;
+    let mut config = Config::load(&path)?;
+
+    match command {
+        ConfigCommands::Show => {
+            println!("{}", format!("# {}", path.display()).dimmed());
+            print!("{}", config.to_toml());
+        }
+        ConfigCommands::Set { key, value } => {
+            config.set(&key, &value)?;
+            config.save(&path)?;
+            println!("{} Set {} = {}", "✓".green().bold(), key, value);
+        }
+    }
+    Ok(())
+}
+
+fn run_session_command(command: SessionCommands) -> bazzounquester::Result<()> {
+    let mut manager = SessionManager::new(SessionManager::default_path()?)?;
+    manager.load_all()?;
+
+    match command {
+        SessionCommands::Create { name } => {
+            if manager.get_session_by_name(&name).is_some() {
+                return Err(bazzounquester::Error::InvalidCommand(format!(
+                    "Session '{}' already exists",
+                    name
+                )));
+            }
+            manager.create_session(name.clone(), true);
+            manager.save_all()?;
+            println!(
+                "{} Created session '{}' and made it active",
+                "✓".green().bold(),
+                name
+            );
+        }
+        SessionCommands::List => {
+            let mut sessions = manager.list_sessions();
+            if sessions.is_empty() {
+                println!("No sessions saved. Create one with 'session create <name>'.");
+            }
+            sessions.sort_by(|a, b| a.name.cmp(&b.name));
+            for session in sessions {
+                let marker = if session.is_active { "*" } else { " " };
+                println!(
+                    "{} {}  {}",
+                    marker.green().bold(),
+                    session.name.bold(),
+                    format!(
+                        "{} cookie(s), last used {}",
+                        session.cookies.count(),
+                        session.last_used.format("%Y-%m-%d %H:%M")
+                    )
+                    .dimmed()
+                );
+            }
+        }
+        SessionCommands::Use { name } => {
+            let id = find_session(&manager, &name)?;
+            manager.set_active(&id);
+            manager.save_all()?;
+            println!("{} Using session '{}'", "✓".green().bold(), name);
+        }
+        SessionCommands::Delete { name } => {
+            let id = find_session(&manager, &name)?;
+            manager.delete_session_file(&id)?;
+            manager.remove_session(&id);
+            println!("{} Deleted session '{}'", "✓".green().bold(), name);
+        }
+        SessionCommands::Cookies => {
+            let session = active_session(&mut manager)?;
+            let mut cookies = session.cookies.all();
+            if cookies.is_empty() {
+                println!("Session '{}' has no cookies.", session.name);
+                return Ok(());
+            }
+            cookies
+                .sort_by(|a, b| (&a.domain, &a.path, &a.name).cmp(&(&b.domain, &b.path, &b.name)));
+
+            let rows: Vec<[String; 5]> = cookies
+                .iter()
+                .map(|c| {
+                    [
+                        c.name.clone(),
+                        c.value.clone(),
+                        c.domain.clone().unwrap_or_default(),
+                        c.path.clone().unwrap_or_else(|| "/".to_string()),
+                        c.expires.map_or("session".to_string(), |e| e.to_rfc3339()),
+                    ]
+                })
+                .collect();
+            let headings = ["NAME", "VALUE", "DOMAIN", "PATH", "EXPIRES"];
+            let mut widths = headings.map(str::len);
+            for row in &rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+
+            let line = |cells: [&str; 5]| {
+                cells
+                    .iter()
+                    .zip(widths)
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+                    .trim_end()
+                    .to_string()
+            };
+            println!("{}", line(headings).bold());
+            for row in &rows {
+                println!("{}", line(row.each_ref().map(String::as_str)));
+            }
+        }
+        SessionCommands::ClearCookies => {
+            let session = active_session(&mut manager)?;
+            let removed = session.cookies.count();
+            session.clear_cookies();
+            let (id, name) = (session.id, session.name.clone());
+            manager.save_session(&id)?;
+            println!(
+                "{} Removed {} cookie(s) from session '{}'",
+                "✓".green().bold(),
+                removed,
+                name
+            );
+        }
+        SessionCommands::ImportCookies { file } => {
+            let content = std::fs::read_to_string(&file)?;
+            let session = active_session(&mut manager)?;
+            let imported = session.cookies.import_netscape(&content)?;
+            session.touch();
+            let (id, name) = (session.id, session.name.clone());
+            manager.save_session(&id)?;
+            println!(
+                "{} Imported {} cookie(s) into session '{}'",
+                "✓".green().bold(),
+                imported,
+                name
+            );
+        }
+        SessionCommands::ExportCookies { file } => {
+            let session = active_session(&mut manager)?;
+            std::fs::write(&file, session.cookies.to_netscape())?;
+            println!(
+                "{} Wrote {} cookie(s) to {}",
+                "✓".green().bold(),
+                session.cookies.count(),
+                file.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Look up a session ID by name
+fn find_session(manager: &SessionManager, name: &str) -> bazzounquester::Result<uuid::Uuid> {
+    manager
+        .get_session_by_name(name)
+        .map(|session| session.id)
+        .ok_or_else(|| {
+            bazzounquester::Error::InvalidCommand(format!("Session '{}' not found", name))
+        })
+}
+
+/// Get the active session, or explain how to pick one
+fn active_session(
+    manager: &mut SessionManager,
+) -> bazzounquester::Result<&mut bazzounquester::session::Session> {
+    manager.get_active_session_mut().ok_or_else(|| {
+        bazzounquester::Error::InvalidCommand(
+            "No active session; run 'session create <name>' or 'session use <name>'".to_string(),
+        )
+    })
+}
+
+fn run_collection_command(
+    command: CollectionCommands,
+    options: &RequestOptions,
+) -> bazzounquester::Result<bool> {
+    match command {
+        CollectionCommands::Run {
+            collection,
+            request,
+            use_cache,
+            env,
+        } => {
+            let collection = load_collection(&collection)?;
+            let mut environments = EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+            environments.load_all()?;
+            let environment = match &env {
+                Some(name) => {
+                    Some(environments.get_environment_by_name(name).ok_or_else(|| {
+                        bazzounquester::Error::InvalidCommand(format!(
+                            "Environment '{}' not found",
+                            name
+                        ))
+                    })?)
+                }
+                None => environments.get_active_environment(),
+            };
+            let client = build_session_client(options)?;
+            let jar = client.cookie_jar().cloned();
+            let profiles = AuthProfileStore::load(AuthProfileStore::default_path()?)?;
+            let default_auth = options
+                .auth
+                .as_deref()
+                .map(|profile| profiles.resolve(profile))
+                .transpose()?;
+            let mut runner = CollectionRunner::new(client).with_auth_profiles(profiles);
+            if let Some(environment) = environment {
+                runner = runner.with_environment(
+                    environment
+                        .enabled_variables()
+                        .into_iter()
+                        .map(|(key, value)| (key.to_string(), value.to_string()))
+                        .collect(),
+                );
+            }
+            if let Some(auth) = default_auth {
+                runner = runner.with_default_auth(auth);
+            }
+            if use_cache {
+                runner = runner.with_cache(ResponseCache::new(ResponseCache::default_path()?));
+            }
+            if let Some(storage) = history_storage(options) {
+                runner = runner.with_history(storage);
+            }
+
+            let result = match request {
+                Some(path) => runner.run_path(&collection, &path)?,
+                None => runner.run(&collection),
+            };
+            save_session_cookies(jar.as_ref())?;
+
+            println!();
+            for (i, request_result) in result.results.iter().enumerate() {
+                println!("{}. {}", i + 1, request_result.summary());
+                let failures = request_result.validation.iter().flat_map(|v| &v.results);
+                for failure in failures.filter(|r| !r.passed) {
+                    println!("   {}", failure.summary());
+                }
+            }
+            println!();
+            println!("{}", result.summary());
+            write_reports(&options.reports, &result.test_suite(), false)?;
+
+            Ok(result.success())
+        }
+        CollectionCommands::Check { collection, env } => {
+            let collection = load_collection(&collection)?;
+            let mut manager = EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+            manager.load_all()?;
+            let environment = match &env {
+                Some(name) => manager.get_environment_by_name(name).ok_or_else(|| {
+                    bazzounquester::Error::InvalidCommand(format!(
+                        "Environment '{}' not found",
+                        name
+                    ))
+                })?,
+                None => manager.get_active_environment().ok_or_else(|| {
+                    bazzounquester::Error::MissingArgument(
+                        "--env (no environment is active)".to_string(),
+                    )
+                })?,
+            };
+
+            let missing = collection.undefined_variables(environment);
+            if missing.is_empty() {
+                println!(
+                    "{} Every variable in '{}' is defined by '{}'",
+                    "✓".green().bold(),
+                    collection.info.name,
+                    environment.name
+                );
+                return Ok(true);
+            }
+            println!(
+                "{} '{}' uses {} variable(s) that '{}' doesn't define:",
+                "✗".red().bold(),
+                collection.info.name,
+                missing.len(),
+                environment.name
+            );
+            for name in &missing {
+                println!("  {{{{{}}}}}", name);
+            }
+            Ok(false)
+        }
+        CollectionCommands::Search { query, collection } => {
+            let collections = match collection {
+                Some(name) => vec![load_collection(&name)?],
+                None => CollectionStorage::new(CollectionStorage::default_path()?)?.list_all()?,
+            };
+
+            let mut matches = Vec::new();
+            for collection in &collections {
+                for item in collection.search(&query) {
+                    let mut path = collection.folder_path(&item.id).unwrap_or_default();
+                    path.push(&item.name);
+                    matches.push((format!("{}/{}", collection.info.name, path.join("/")), item));
+                }
+            }
+
+            if options.output == OutputFormat::Json {
+                let json: Vec<_> = matches
+                    .iter()
+                    .map(|(spec, item)| {
+                        serde_json::json!({
+                            "request": spec,
+                            "method": item.method.as_str(),
+                            "url": item.url,
+                            "tags": item.tags,
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            } else if matches.is_empty() {
+                println!("No requests match '{}'.", query);
+            } else {
+                for (spec, item) in &matches {
+                    let tags = if item.tags.is_empty() {
+                        String::new()
+                    } else {
+                        format!("  [{}]", item.tags.join(", ")).dimmed().to_string()
+                    };
+                    println!(
+                        "{}  {:<7} {}{}",
+                        spec.cyan(),
+                        item.method.as_str().bold(),
+                        item.url,
+                        tags
+                    );
+                }
+            }
+            Ok(true)
+        }
+        CollectionCommands::Cache {
+            command: CollectionCacheCommands::Clear,
+        } => {
+            let removed = ResponseCache::new(ResponseCache::default_path()?).clear()?;
+            println!(
+                "{} Removed {} cached response(s)",
+                "✓".green().bold(),
+                removed
+            );
+            Ok(true)
+        }
+    }
+}
+
+/// Run requests across environments and print the grid
+fn run_matrix(
+    requests: &[String],
+    envs: &[String],
+    extract: Option<String>,
+    options: &RequestOptions,
+) -> bazzounquester::Result<bool> {
+    let requests = requests
+        .iter()
+        .map(|spec| find_collection_request(spec))
+        .collect::<bazzounquester::Result<Vec<_>>>()?;
+
+    let mut manager = EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+    manager.load_all()?;
+    let environments = envs
+        .iter()
+        .map(|name| {
+            manager
+                .get_environment_by_name(name)
+                .cloned()
+                .ok_or_else(|| {
+                    bazzounquester::Error::InvalidCommand(format!(
+                        "Environment '{}' not found",
+                        name
+                    ))
+                })
+        })
+        .collect::<bazzounquester::Result<Vec<_>>>()?;
+
+    let mut runner = MatrixRunner::new(build_client(options)?)
+        .with_auth_profiles(AuthProfileStore::load(AuthProfileStore::default_path()?)?);
+    if let Some(path) = extract {
+        runner = runner.with_extract(path);
+    }
+
+    let result = runner.run(&requests, &environments);
+    if options.output == OutputFormat::Json {
+        println!("{}", result.to_json()?);
+    } else {
+        println!("{}", result.render_table());
+    }
+
+    Ok(result.success())
+}
+
+/// Find a saved request given as "<collection>/<request name>"
+fn find_collection_request(spec: &str) -> bazzounquester::Result<MatrixRequest> {
+    let (collection, name) = spec.split_once('/').ok_or_else(|| {
+        bazzounquester::Error::InvalidCommand(format!(
+            "Expected '<collection>/<request name>', got '{}'",
+            spec
+        ))
+    })?;
+
+    let collection = load_collection(collection)?;
+    let item = match collection.find_request_by_name(name) {
+        Some(item) if !name.contains('/') => item,
+        _ => collection.request_at_path(name)?,
+    }
+    .clone();
+
+    let variables = collection.request_variables(&item.id);
+    Ok(MatrixRequest::new(spec.to_string(), item).with_variables(variables))
+}
+
+/// Load a collection from a file, or a saved collection by name or ID
+fn load_collection(name: &str) -> bazzounquester::Result<Collection> {
+    let path = std::path::Path::new(name);
+    if path.is_file() {
+        return Collection::load_from_file(path);
+    }
+
+    let storage = CollectionStorage::new(CollectionStorage::default_path()?)?;
+    storage
+        .list_all()?
+        .into_iter()
+        .find(|c| c.info.name == name || c.info.id.to_string() == name)
+        .ok_or_else(|| {
+            bazzounquester::Error::InvalidCommand(format!("Collection '{}' not found", name))
+        })
+}
+
+fn run_workflow_command(
+    command: WorkflowCommands,
+    options: &RequestOptions,
+) -> bazzounquester::Result<bool> {
+    match command {
+        WorkflowCommands::Run {
+            file,
+            junit,
+            data,
+            variables,
+        } => {
+            let mut chain = RequestChain::load_from_file(&file)?;
+            apply_workflow_variables(&mut chain, &variables)?;
+            if let Some(data) = data {
+                chain.config = chain.config.with_data_file(data);
+            }
+            let mut executor = WorkflowExecutor::new();
+            if let Some(profile) = &options.auth {
+                let store = AuthProfileStore::load(AuthProfileStore::default_path()?)?;
+                executor = executor.with_auth(store.resolve(profile)?);
+            }
+            if options.dry_run {
+                let steps = executor.dry_run(&chain)?;
+                for (index, (name, request)) in steps.iter().enumerate() {
+                    println!("{}", format!("Step {}: {}", index + 1, name).bold());
+                    print!("{}", request.preview(options.show_secrets));
+                }
+                return Ok(true);
+            }
+
+            let client = build_session_client(options)?;
+            let jar = client.cookie_jar().cloned();
+            let snapshots = SnapshotStore::open_default()?.with_update(options.update_snapshots);
+            let result = executor
+                .with_client(client)
+                .with_snapshots(snapshots)
+                .execute(&chain);
+            save_session_cookies(jar.as_ref())?;
+            let result = result?;
+
+            println!();
+            println!("{}", result.detailed_report());
+
+            let mut reports = options.reports.clone();
+            reports.extend(junit.map(|path| ReportTarget::new(ReportFormat::Junit, path)));
+            write_reports(&reports, &result.test_suite(), false)?;
+
+            Ok(result.success)
+        }
+        WorkflowCommands::Validate { file, variables } => {
+            let mut chain = RequestChain::load_from_file(&file)?;
+            apply_workflow_variables(&mut chain, &variables)?;
+
+            let problems = chain.validate(&HashSet::new());
+            if problems.is_empty() {
+                println!(
+                    "{} Workflow '{}' is valid ({} steps)",
+                    "✓".green().bold(),
+                    chain.name,
+                    chain.steps.len()
+                );
+                return Ok(true);
+            }
+
+            println!(
+                "{} Workflow '{}' has {} problem(s):",
+                "✗".red().bold(),
+                chain.name,
+                problems.len()
+            );
+            for problem in &problems {
+                println!("  {}", problem);
+            }
+            Ok(false)
         }
     }
 }
 
-fn run_interactive_mode() -> bazzounquester::Result<()> {
-    let mut repl = ReplMode::new()?;
-    repl.run()
+/// Write a test suite to each requested report file
+fn write_reports(
+    reports: &[ReportTarget],
+    suite: &JUnitTestSuite,
+    to_stderr: bool,
+) -> bazzounquester::Result<()> {
+    for report in reports {
+        report.write(suite)?;
+        let message = format!(
+            "{} {} report written to {}",
+            "✓".green().bold(),
+            report.format,
+            report.path.display()
+        );
+        if to_stderr {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    }
+    Ok(())
+}
+
+/// Layer environment and `--var` values over the chain's own variables
+fn apply_workflow_variables(
+    chain: &mut RequestChain,
+    args: &WorkflowVariableArgs,
+) -> bazzounquester::Result<()> {
+    if let Some(name) = &args.env {
+        let mut manager = EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+        manager.load_all()?;
+        let env = manager.get_environment_by_name(name).ok_or_else(|| {
+            bazzounquester::Error::InvalidCommand(format!("Environment '{}' not found", name))
+        })?;
+        for (key, value) in env.enabled_variables() {
+            chain.variables.insert(key.to_string(), value.to_string());
+        }
+    }
+    chain.variables.extend(args.vars.iter().cloned());
+    Ok(())
+}
+
+fn exec_stream(concurrency: usize, options: &RequestOptions) -> bazzounquester::Result<bool> {
+    let mut runner = StreamRunner::new(build_client(options)?).with_concurrency(concurrency);
+
+    if let Some(profile) = &options.auth {
+        let store = AuthProfileStore::load(AuthProfileStore::default_path()?)?;
+        runner = runner.with_auth(store.resolve(profile)?);
+    }
+
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    runner.run(stdin.lock(), stdout.lock())
+}
+
+fn run_interactive_mode(options: &RequestOptions) -> bazzounquester::Result<()> {
+    let client = build_session_client(options)?;
+    let mut repl = ReplMode::new()?
+        .with_client(client)
+        .with_formatter(
+            ResponseFormatter::new()
+                .body(BodyMode::Pretty {
+                    max_bytes: options.max_body_bytes,
+                })
+                .secret_fields(options.secret_fields.clone()),
+        )
+        .with_completer(repl_completer()?);
+    if let Some(storage) = history_storage(options) {
+        repl = repl.with_history(storage);
+    }
+    if !options.no_history {
+        repl = repl.with_input_history(InputHistory::new(InputHistory::default_path()?))?;
+    }
+    let mut environments = EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+    environments.load_all()?;
+    if let Some(env) = environments.get_active_environment() {
+        repl = repl.with_environment(env.clone());
+    }
+    let result = repl.run();
+    save_session_cookies(repl.cookie_jar())?;
+    result
+}
+
+/// Complete the saved requests and environments on disk in the REPL
+fn repl_completer() -> bazzounquester::Result<ReplCompleter> {
+    let requests = CollectionStorage::new(CollectionStorage::default_path()?)?
+        .list_all()?
+        .iter()
+        .flat_map(|c| c.request_specs())
+        .collect();
+
+    let mut manager = EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+    manager.load_all()?;
+    let environments = manager
+        .environment_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    Ok(ReplCompleter::new()
+        .with_requests(requests)
+        .with_environments(environments))
+}
+
+/// Build a client that shares one cookie jar across requests unless --no-cookies
+///
+/// The jar starts from the active session's cookies, if a session is active.
+fn build_session_client(options: &RequestOptions) -> bazzounquester::Result<HttpClient> {
+    let client = build_client(options)?;
+    if options.no_cookies {
+        return Ok(client);
+    }
+    let jar = load_sessions()?
+        .and_then(|manager| manager.active_cookie_jar())
+        .unwrap_or_else(|| Arc::new(Mutex::new(CookieJar::new())));
+    Ok(client.with_cookie_jar(jar))
+}
+
+/// Write a client's cookies back to the active session, if a session is active
+fn save_session_cookies(jar: Option<&Arc<Mutex<CookieJar>>>) -> bazzounquester::Result<()> {
+    let (Some(jar), Some(mut manager)) = (jar, load_sessions()?) else {
+        return Ok(());
+    };
+    let jar = jar.lock().expect("cookie jar poisoned").clone();
+    manager.save_active_cookies(&jar)?;
+    Ok(())
+}
+
+/// Load saved sessions; `None` until the first `session create`
+fn load_sessions() -> bazzounquester::Result<Option<SessionManager>> {
+    let path = SessionManager::default_path()?;
+    if !path.is_dir() {
+        return Ok(None);
+    }
+    let mut manager = SessionManager::new(path)?;
+    manager.load_all()?;
+    Ok(Some(manager))
+}
+
+/// Exit code for an error: the deadline code when --max-time ran out, 1 otherwise
+fn error_code(error: &bazzounquester::Error) -> i32 {
+    match error {
+        bazzounquester::Error::DeadlineExceeded(_) => exit::DEADLINE_EXCEEDED,
+        _ => exit::FAILURE,
+    }
+}
+
+/// Exit code for a failed run: the deadline code when --max-time ran out, 1 otherwise
+fn failure_code(options: &RequestOptions) -> i32 {
+    if options.deadline.is_some_and(|d| d.is_expired()) {
+        exit::DEADLINE_EXCEEDED
+    } else {
+        exit::FAILURE
+    }
+}
+
+fn build_client(options: &RequestOptions) -> bazzounquester::Result<HttpClient> {
+    let mut client = HttpClient::new()
+        .with_danger_accept_invalid_certs(options.insecure)
+        .with_follow_redirects(options.follow_redirects)
+        .with_default_headers(options.default_headers.clone());
+    if let Some(timeout) = options.timeout {
+        client = client.with_timeout(timeout);
+    }
+    if let Some(user_agent) = &options.user_agent {
+        client = client.with_user_agent(user_agent)?;
+    }
+    if let Some(deadline) = options.deadline {
+        client = client.with_deadline(deadline);
+    }
+
+    if let Some(cert) = &options.cert {
+        let is_pkcs12 = cert
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("p12") || ext.eq_ignore_ascii_case("pfx"));
+
+        client = if is_pkcs12 {
+            client.with_pkcs12_cert(cert, options.cert_password.as_deref().unwrap_or(""))?
+        } else {
+            let key = options.key.as_ref().ok_or_else(|| {
+                bazzounquester::Error::MissingArgument(
+                    "--key (required with a PEM --cert)".to_string(),
+                )
+            })?;
+            client.with_client_cert(cert, key)?
+        };
+    } else if options.key.is_some() {
+        return Err(bazzounquester::Error::MissingArgument(
+            "--cert (required with --key)".to_string(),
+        ));
+    }
+
+    if let Some(cacert) = &options.cacert {
+        client = client.with_ca_cert(cacert)?;
+    }
+
+    if let Some(proxy) = &options.proxy {
+        client = client.with_proxy(ProxyConfig::parse(proxy)?.with_no_proxy(NoProxy::from_env()));
+    }
+
+    Ok(client)
 }
 
 fn execute_request(
@@ -63,7 +1645,9 @@ fn execute_request(
     url: &str,
     headers: Vec<String>,
     body: Option<String>,
+    form: Vec<String>,
     query_params: Vec<String>,
+    options: &RequestOptions,
 ) {
     // Build request
     let mut request = RequestBuilder::new(method, url.to_string());
@@ -73,33 +1657,514 @@ fn execute_request(
     }
 
     if !query_params.is_empty() {
-        request = request.queries(query_params);
+        request = request.queries(query_params).raw_query(options.raw_query);
     }
 
     if let Some(b) = body {
-        request = request.body(b);
+        request = match request.body_arg(&b) {
+            Ok(request) => request,
+            Err(e) => {
+                eprintln!("{} {}", "✗".red().bold(), e);
+                std::process::exit(1);
+            }
+        };
     }
 
-    // Display request info
-    println!();
-    println!(
-        "{} {}",
-        "→".blue().bold(),
-        format!("{} {}", method.as_str(), url).bold()
-    );
-    println!();
+    if !form.is_empty() {
+        match FormData::from_pairs(&form) {
+            Ok(form) => request = request.form(form),
+            Err(e) => {
+                eprintln!("{} {}", "✗".red().bold(), e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if options.repeat > 1 && !options.dry_run {
+        benchmark_request(request, options);
+    } else {
+        send_request(request, options, history_storage(options).as_ref(), false);
+    }
+}
+
+/// Run the `load` subcommand and print its report
+fn load_test(
+    request: RequestBuilder,
+    concurrency: usize,
+    requests: Option<usize>,
+    duration: Option<Duration>,
+    options: &RequestOptions,
+) {
+    let request = with_auth_profile(request, options);
+    if options.dry_run {
+        print!("{}", request.preview(options.show_secrets));
+        return;
+    }
+    let client = match build_client(options) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{} {}", "✗".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let json_output = options.output == OutputFormat::Json;
+    if !json_output && !options.quiet {
+        println!();
+        println!(
+            "{} {}",
+            "→".blue().bold(),
+            format!("{} {}", request.method.as_str(), request.url).bold()
+        );
+        println!();
+    }
+
+    let mut test = LoadTest::new(&client, &request).with_concurrency(concurrency);
+    if let Some(requests) = requests {
+        test = test.with_requests(requests);
+    }
+    if let Some(duration) = duration {
+        test = test.with_duration(duration);
+    }
+    let report = test.run();
+
+    if json_output {
+        match report.to_json() {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("{} {}", "✗".red().bold(), e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        print!("{}", report.render());
+        println!();
+    }
+
+    if options.deadline.is_some_and(|d| d.is_expired()) {
+        std::process::exit(exit::DEADLINE_EXCEEDED);
+    }
+    let failed = report.errors > 0
+        || report
+            .status_codes
+            .keys()
+            .any(|status| !(200..300).contains(status));
+    if options.fail && failed {
+        std::process::exit(exit::FAILURE);
+    }
+}
+
+/// Snapshot diff lines, indented and colored by kind of change
+fn render_snapshot_changes(changes: &[String]) -> String {
+    changes
+        .iter()
+        .map(|line| {
+            let line = if line.starts_with('+') {
+                line.green()
+            } else if line.starts_with('-') {
+                line.red()
+            } else if line.starts_with('~') {
+                line.cyan()
+            } else {
+                line.dimmed()
+            };
+            format!("  {}", line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Assertions from a request command's `--assert-*` flags, exiting on a bad expression
+fn request_assertions(checks: &AssertArgs) -> Vec<Assertion> {
+    checks.assertions().unwrap_or_else(|e| {
+        eprintln!("{} {}", "✗".red().bold(), e);
+        std::process::exit(exit::FAILURE);
+    })
+}
+
+/// Send a request `--repeat` times and print latency statistics
+fn benchmark_request(request: RequestBuilder, options: &RequestOptions) {
+    let request = with_auth_profile(request, options);
+    let client = match build_session_client(options) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{} {}", "✗".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let json_output = options.output == OutputFormat::Json;
+    if !json_output && !options.quiet {
+        println!();
+        println!(
+            "{} {} {}",
+            "→".blue().bold(),
+            format!("{} {}", request.method.as_str(), request.url).bold(),
+            format!("(x{})", options.repeat).dimmed()
+        );
+        println!();
+    }
+
+    let mut durations = Vec::new();
+    let mut successes = 0;
+    let mut sent = 0;
+    for _ in 0..options.repeat {
+        if options.deadline.is_some_and(|d| d.is_expired()) {
+            break;
+        }
+        sent += 1;
+        match client.execute(&request) {
+            Ok(response) => {
+                durations.push(response.duration);
+                if response.is_success() {
+                    successes += 1;
+                }
+            }
+            Err(e) => {
+                if options.verbose {
+                    eprintln!("{} {}", "✗".red().bold(), e);
+                }
+            }
+        }
+    }
+
+    let stats = BenchStats::compute(sent, successes, &durations);
+    if json_output {
+        match stats.to_json() {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("{} {}", "✗".red().bold(), e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        print!("{}", stats.render());
+        println!();
+    }
+
+    if options.deadline.is_some_and(|d| d.is_expired()) {
+        std::process::exit(exit::DEADLINE_EXCEEDED);
+    }
+    if options.fail && successes < sent {
+        std::process::exit(1);
+    }
+}
+
+/// Build a GraphQL POST from the `graphql` subcommand's arguments
+fn graphql_request(
+    url: &str,
+    query: Option<String>,
+    query_file: Option<PathBuf>,
+    variables: Option<String>,
+    operation_name: Option<String>,
+    headers: Vec<String>,
+) -> bazzounquester::Result<RequestBuilder> {
+    let query = match (query, query_file) {
+        (Some(query), _) => query,
+        (None, Some(path)) => std::fs::read_to_string(&path).map_err(|e| {
+            bazzounquester::Error::InvalidCommand(format!(
+                "Could not read query file {}: {}",
+                path.display(),
+                e
+            ))
+        })?,
+        (None, None) => {
+            return Err(bazzounquester::Error::MissingArgument(
+                "--query or --query-file".to_string(),
+            ))
+        }
+    };
+    let variables = variables
+        .map(|json| serde_json::from_str(&json))
+        .transpose()?;
+
+    Ok(RequestBuilder::new(HttpMethod::Post, url.to_string())
+        .headers(headers)
+        .graphql(&query, variables, operation_name.as_deref()))
+}
+
+/// Open history storage for recording requests, unless --no-history
+///
+/// Problems opening it are reported as warnings so the request still goes out.
+fn history_storage(options: &RequestOptions) -> Option<HistoryStorage> {
+    if options.no_history {
+        return None;
+    }
+    match open_history() {
+        Ok(storage) => Some(storage.with_max_body_size(options.history_max_body)),
+        Err(e) => {
+            eprintln!("{} Could not open history: {}", "⚠".yellow().bold(), e);
+            None
+        }
+    }
+}
+
+/// Apply the `--auth` profile to a request, exiting if it can't be loaded
+fn with_auth_profile(request: RequestBuilder, options: &RequestOptions) -> RequestBuilder {
+    let Some(profile) = &options.auth else {
+        return request;
+    };
+    let scheme = AuthProfileStore::default_path()
+        .and_then(AuthProfileStore::load)
+        .and_then(|store| store.resolve(profile));
+    match scheme {
+        Ok(scheme) => request.auth(scheme),
+        Err(e) => {
+            eprintln!();
+            eprintln!("{} {}", "✗".red().bold(), e);
+            eprintln!();
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Send a request, print the response, and exit on failure
+///
+/// With `history`, the request and its outcome are saved as a new entry.
+/// With `graphql`, errors in the response body are listed separately and
+/// count as a failure under `--fail`.
+fn send_request(
+    mut request: RequestBuilder,
+    options: &RequestOptions,
+    history: Option<&HistoryStorage>,
+    graphql: bool,
+) {
+    request = with_auth_profile(request, options);
+
+    if options.dry_run {
+        print!("{}", request.preview(options.show_secrets));
+        for finding in lint_request(&request) {
+            eprintln!("{} {}", "⚠".yellow().bold(), finding);
+        }
+        return;
+    }
+
+    // Display request info (stdout is reserved for the JSON document in JSON mode)
+    let json_output = options.output == OutputFormat::Json;
+    if !json_output && !options.quiet {
+        println!();
+        println!(
+            "{} {}",
+            "→".blue().bold(),
+            format!("{} {}", request.method.as_str(), request.url).bold()
+        );
+        println!();
+    }
+
+    if options.verbose {
+        eprint!("{}", ResponseFormatter::format_request(&request));
+        eprintln!();
+    }
+
+    // Check for common mistakes before sending
+    let findings = lint_request(&request);
+    for finding in &findings {
+        eprintln!("{} {}", "⚠".yellow().bold(), finding);
+    }
+    if !findings.is_empty() {
+        if options.strict_lint {
+            eprintln!();
+            eprintln!(
+                "{} Request not sent: {} lint finding(s) (--strict-lint)",
+                "✗".red().bold(),
+                findings.len()
+            );
+            eprintln!();
+            std::process::exit(1);
+        }
+        eprintln!();
+    }
+
+    if options.insecure {
+        eprintln!(
+            "{} TLS certificate verification is disabled (--insecure)",
+            "⚠".yellow().bold()
+        );
+        eprintln!();
+    }
 
     // Execute request
-    let client = HttpClient::new();
-    match client.execute(&request) {
+    let client = match build_session_client(options) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{} {}", "✗".red().bold(), e);
+            eprintln!();
+            std::process::exit(1);
+        }
+    };
+    let result = match options.record_mode {
+        Some(mode) => RecordingClient::default_path()
+            .and_then(|dir| RecordingClient::new(&client, dir, mode).execute(&request)),
+        None => client.execute(&request),
+    };
+    if let Some(storage) = history {
+        let mut logger = HistoryLogger::new();
+        logger.set_tags(options.tags.clone());
+        for (key, value) in &options.history_metadata {
+            logger.set_metadata(key.clone(), value.clone());
+        }
+        let entry_id = logger.record(&request, &result);
+        let saved = logger
+            .get_entry(&entry_id)
+            .map_or(Ok(()), |entry| storage.save_entry(entry));
+        if let Err(e) = saved {
+            eprintln!("{} Could not save history: {}", "⚠".yellow().bold(), e);
+        }
+    }
+
+    match result {
         Ok(response) => {
-            print!("{}", ResponseFormatter::format(&response));
+            if let Err(e) = save_session_cookies(client.cookie_jar()) {
+                eprintln!(
+                    "{} Could not save session cookies: {}",
+                    "⚠".yellow().bold(),
+                    e
+                );
+            }
+
+            if exit::hides_body(response.status, options.fail, options.fail_with_body) {
+                eprintln!(
+                    "{} The server returned {} (--fail)",
+                    "✗".red().bold(),
+                    ResponseFormatter::format_status_only(&response)
+                );
+            } else if json_output {
+                println!("{}", ResponseFormatter::format_json(&response));
+                if options.verbose {
+                    if let Some(timing) = &response.timing {
+                        eprint!("{}", ResponseFormatter::format_timing(timing));
+                    }
+                }
+            } else if options.status_only {
+                println!("{}", ResponseFormatter::format_status_only(&response));
+            } else if options.body_only {
+                let formatter = ResponseFormatter::new()
+                    .body(BodyMode::Pretty {
+                        max_bytes: options.max_body_bytes,
+                    })
+                    .secret_fields(options.secret_fields.clone());
+                println!("{}", formatter.format_body(&response));
+            } else if options.quiet {
+                println!("{}", ResponseFormatter::format_compact(&response));
+            } else {
+                let formatter = ResponseFormatter::new()
+                    .body(BodyMode::Pretty {
+                        max_bytes: options.max_body_bytes,
+                    })
+                    .timing(options.verbose)
+                    .secret_fields(options.secret_fields.clone());
+                print!("{}", formatter.build(&response));
+            }
+
+            if let Some(path) = &options.save_body {
+                match std::fs::write(path, &response.body_bytes) {
+                    Ok(()) => {
+                        let message = format!(
+                            "{} Saved {} bytes to {}",
+                            "✓".green().bold(),
+                            response.body_bytes.len(),
+                            path.display()
+                        );
+                        if json_output || options.quiet {
+                            eprintln!("{}", message);
+                        } else {
+                            println!("{}", message);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("{} Could not save body: {}", "✗".red().bold(), e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            let mut assertions_passed = true;
+            if !options.assertions.is_empty() {
+                let report = ResponseValidator::new().validate(&response, &options.assertions);
+                let report_text = report.detailed_report();
+                if json_output || options.quiet {
+                    eprint!("{}", report_text);
+                } else {
+                    println!();
+                    print!("{}", report_text);
+                }
+                assertions_passed = report.success;
+
+                if let Err(e) = write_reports(
+                    &options.reports,
+                    &report.test_suite(),
+                    json_output || options.quiet,
+                ) {
+                    eprintln!("{} Could not write report: {}", "✗".red().bold(), e);
+                    std::process::exit(1);
+                }
+            }
+
+            if let Some(name) = &options.snapshot {
+                let outcome = SnapshotStore::open_default()
+                    .map(|store| store.with_update(options.update_snapshots))
+                    .and_then(|store| store.check(name, &response, &options.snapshot_rules));
+                let message = match &outcome {
+                    Ok(SnapshotOutcome::Created) => {
+                        format!("{} Snapshot '{}' saved", "✓".green().bold(), name)
+                    }
+                    Ok(SnapshotOutcome::Updated) => {
+                        format!("{} Snapshot '{}' updated", "✓".green().bold(), name)
+                    }
+                    Ok(SnapshotOutcome::Matched) => {
+                        format!("{} Snapshot '{}' matches", "✓".green().bold(), name)
+                    }
+                    Ok(SnapshotOutcome::Changed(changes)) => format!(
+                        "{} Snapshot '{}' changed (accept with --update-snapshots):\n{}",
+                        "✗".red().bold(),
+                        name,
+                        render_snapshot_changes(changes)
+                    ),
+                    Err(e) => format!("{} Snapshot '{}': {}", "✗".red().bold(), name, e),
+                };
+                if json_output || options.quiet {
+                    eprintln!("{}", message);
+                } else {
+                    println!();
+                    println!("{}", message);
+                }
+                assertions_passed &= outcome.is_ok_and(|outcome| outcome.passed());
+            }
+
+            // Listed before the --fail exit so a 4xx/5xx still shows its errors
+            let graphql_errors = if graphql {
+                response.graphql_errors()
+            } else {
+                Vec::new()
+            };
+            if !graphql_errors.is_empty() {
+                eprintln!(
+                    "{} GraphQL returned {} error(s):",
+                    "✗".red().bold(),
+                    graphql_errors.len()
+                );
+                for error in &graphql_errors {
+                    eprintln!("  - {}", error);
+                }
+            }
+
+            let code = exit::for_response(response.status, options.fail, assertions_passed);
+            if code != exit::SUCCESS {
+                std::process::exit(code);
+            }
+            if options.fail && !graphql_errors.is_empty() {
+                std::process::exit(exit::FAILURE);
+            }
         }
         Err(e) => {
             eprintln!();
             eprintln!("{} {}", "✗".red().bold(), e);
             eprintln!();
-            std::process::exit(1);
+            let code = error_code(&e);
+            if code == exit::FAILURE && !options.assertions.is_empty() {
+                std::process::exit(exit::TRANSPORT_ERROR);
+            }
+            std::process::exit(code);
         }
     }
 }
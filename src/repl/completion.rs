@@ -0,0 +1,284 @@
+//! Tab completion for the interactive REPL
+
+use crate::ui::TOPICS;
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+/// Commands understood by the REPL
+pub const REPL_COMMANDS: &[&str] = &[
+    "get", "post", "put", "delete", "patch", "head", "options", "run", "diff", "replay", "use",
+    "help", "version", "clear", "exit", "quit",
+];
+
+/// Header names suggested after `-H`/`--header`
+pub const COMMON_HEADERS: &[&str] = &[
+    "Accept",
+    "Accept-Encoding",
+    "Accept-Language",
+    "Authorization",
+    "Cache-Control",
+    "Content-Type",
+    "Cookie",
+    "If-Match",
+    "If-None-Match",
+    "Origin",
+    "User-Agent",
+    "X-API-Key",
+    "X-Request-ID",
+];
+
+/// Completes commands, help topics, saved requests, environment names, and
+/// header names
+///
+/// Matching ignores case.
+#[derive(Debug, Clone, Default)]
+pub struct ReplCompleter {
+    requests: Vec<String>,
+    environments: Vec<String>,
+}
+
+impl ReplCompleter {
+    /// Create a completer for commands and help topics only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Complete saved requests given as `<collection>/<request name>`
+    pub fn with_requests(mut self, requests: Vec<String>) -> Self {
+        self.requests = requests;
+        self
+    }
+
+    /// Complete environment names
+    pub fn with_environments(mut self, environments: Vec<String>) -> Self {
+        self.environments = environments;
+        self
+    }
+
+    /// Find completions for the text before the cursor
+    ///
+    /// Returns where the completed word starts and the candidates for it.
+    pub fn candidates(&self, line: &str) -> (usize, Vec<String>) {
+        let Some((command, rest)) = line.split_once(' ') else {
+            return (0, matching(REPL_COMMANDS.iter().copied(), line));
+        };
+        let start = command.len() + 1;
+
+        match command.to_lowercase().as_str() {
+            "help" | ":help" => (start, matching(TOPICS.iter().map(|t| t.name), rest)),
+            "get" | "post" | "put" | "delete" | "patch" | "head" | "options" => {
+                header_candidates(line)
+            }
+            "use" => match rest.split_once(' ') {
+                Some((kind, partial)) if kind.eq_ignore_ascii_case("env") => {
+                    let names = self.environments.iter().map(String::as_str);
+                    (line.len() - partial.len(), matching(names, partial))
+                }
+                Some(_) => (line.len(), Vec::new()),
+                None => (start, matching(["env", "session"].into_iter(), rest)),
+            },
+            "run" => {
+                // `--env <name>` may follow the request
+                if let Some(index) = rest.rfind("--env ") {
+                    let start = start + index + "--env ".len();
+                    let names = self.environments.iter().map(String::as_str);
+                    return (start, matching(names, &line[start..]));
+                }
+
+                // Request names can contain spaces, so the whole argument is one word
+                let partial = rest.trim_start_matches(['"', '\'']);
+                let candidates = matching(self.requests.iter().map(String::as_str), partial)
+                    .into_iter()
+                    .map(|spec| {
+                        if spec.contains(' ') {
+                            format!("\"{}\"", spec)
+                        } else {
+                            spec
+                        }
+                    })
+                    .collect();
+                (start, candidates)
+            }
+            _ => (line.len(), Vec::new()),
+        }
+    }
+}
+
+/// Header names for the word after `-H`/`--header`, completed as `Name:`
+fn header_candidates(line: &str) -> (usize, Vec<String>) {
+    let start = line.rfind(' ').map_or(0, |i| i + 1);
+    let previous = line[..start].split_whitespace().next_back();
+    if !matches!(previous, Some("-H" | "--header")) {
+        return (line.len(), Vec::new());
+    }
+
+    let word = &line[start..];
+    let quote = word.chars().next().filter(|c| *c == '"' || *c == '\'');
+    let partial = &word[quote.map_or(0, char::len_utf8)..];
+    let candidates = matching(COMMON_HEADERS.iter().copied(), partial)
+        .into_iter()
+        .map(|name| match quote {
+            Some(quote) => format!("{}{}: ", quote, name),
+            None => format!("{}:", name),
+        })
+        .collect();
+    (start, candidates)
+}
+
+/// Candidates starting with a prefix (ignoring case), sorted
+fn matching<'a>(candidates: impl Iterator<Item = &'a str>, prefix: &str) -> Vec<String> {
+    let prefix = prefix.to_lowercase();
+    let mut matches: Vec<String> = candidates
+        .filter(|c| c.to_lowercase().starts_with(&prefix))
+        .map(str::to_string)
+        .collect();
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+impl Completer for ReplCompleter {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, candidates) = self.candidates(&line[..pos]);
+        let pairs = candidates
+            .into_iter()
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: candidate,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ReplCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for ReplCompleter {}
+
+impl Validator for ReplCompleter {}
+
+impl Helper for ReplCompleter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completer() -> ReplCompleter {
+        ReplCompleter::new()
+            .with_requests(vec![
+                "API/Health".to_string(),
+                "API/List users".to_string(),
+                "Admin/Reset".to_string(),
+            ])
+            .with_environments(vec!["dev".to_string(), "staging".to_string()])
+    }
+
+    #[test]
+    fn test_complete_commands() {
+        let completer = completer();
+        assert_eq!(
+            completer.candidates("p"),
+            (
+                0,
+                vec!["patch".to_string(), "post".to_string(), "put".to_string()]
+            )
+        );
+        assert_eq!(
+            completer.candidates("ver"),
+            (0, vec!["version".to_string()])
+        );
+        assert!(completer.candidates("xyz").1.is_empty());
+    }
+
+    #[test]
+    fn test_complete_help_topics() {
+        assert_eq!(
+            completer().candidates("help as"),
+            (5, vec!["assertions".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_complete_saved_requests() {
+        let completer = completer();
+        assert_eq!(
+            completer.candidates("run API/"),
+            (
+                4,
+                vec!["API/Health".to_string(), "\"API/List users\"".to_string()]
+            )
+        );
+        assert_eq!(
+            completer.candidates("run \"API/L"),
+            (4, vec!["\"API/List users\"".to_string()])
+        );
+        assert_eq!(completer.candidates("run Ad").1, vec!["Admin/Reset"]);
+    }
+
+    #[test]
+    fn test_complete_environment_names() {
+        assert_eq!(
+            completer().candidates("run API/Health --env st"),
+            (21, vec!["staging".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_complete_use_targets() {
+        let completer = completer();
+        assert_eq!(
+            completer.candidates("use s"),
+            (4, vec!["session".to_string()])
+        );
+        assert_eq!(
+            completer.candidates("use env st"),
+            (8, vec!["staging".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_complete_ignores_case() {
+        let completer = completer();
+        assert_eq!(completer.candidates("PO").1, vec!["post"]);
+        assert_eq!(completer.candidates("RUN api/h").1, vec!["API/Health"]);
+        assert_eq!(
+            completer.candidates("run API/Health --env DE").1,
+            vec!["dev"]
+        );
+    }
+
+    #[test]
+    fn test_complete_header_names() {
+        let completer = completer();
+        assert_eq!(
+            completer.candidates("get https://example.com -H content"),
+            (27, vec!["Content-Type:".to_string()])
+        );
+        assert_eq!(
+            completer.candidates("post /users --header \"acc").1,
+            vec!["\"Accept: ", "\"Accept-Encoding: ", "\"Accept-Language: "]
+        );
+        assert!(completer
+            .candidates("get https://example.com -q acc")
+            .1
+            .is_empty());
+    }
+
+    #[test]
+    fn test_no_completion_for_urls() {
+        let line = "get https://exa";
+        assert_eq!(completer().candidates(line), (line.len(), Vec::new()));
+    }
+}
@@ -0,0 +1,233 @@
+//! Input history kept across REPL sessions
+//!
+//! Lines are stored one per line, oldest first. Lines that pass credentials
+//! (an `Authorization` header, a `token=` query parameter, ...) are never
+//! written to disk.
+
+use crate::cli::CommandParser;
+use crate::error::Result;
+use crate::http::redact::is_sensitive_header;
+use std::path::{Path, PathBuf};
+
+/// Flags whose value is a credential
+const SECRET_FLAGS: &[&str] = &[
+    "-u",
+    "--user",
+    "--password",
+    "--token",
+    "--bearer",
+    "--api-key",
+    "--client-secret",
+];
+
+/// Persistent REPL input history
+#[derive(Debug, Clone)]
+pub struct InputHistory {
+    /// History file
+    path: PathBuf,
+
+    /// Most lines kept in the file
+    max_entries: usize,
+
+    /// Lines loaded or recorded so far, oldest first
+    entries: Vec<String>,
+}
+
+impl InputHistory {
+    /// Lines kept by default
+    pub const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+    /// Create a history backed by a file (nothing is read until `load`)
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_entries: Self::DEFAULT_MAX_ENTRIES,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Keep at most this many lines
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Get default history file path
+    pub fn default_path() -> Result<PathBuf> {
+        let dirs = directories::ProjectDirs::from("com", "bazzoun", "bazzounquester").ok_or_else(
+            || {
+                crate::Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Could not determine data directory",
+                ))
+            },
+        )?;
+
+        Ok(dirs.data_dir().join("repl_history"))
+    }
+
+    /// History file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Most lines kept in the file
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    /// Lines recorded so far, oldest first
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Read the history file (a missing file is an empty history)
+    pub fn load(&mut self) -> Result<&[String]> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        self.entries = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::to_string)
+            .collect();
+        self.truncate();
+        Ok(&self.entries)
+    }
+
+    /// Record a line and rewrite the file
+    ///
+    /// Returns whether the line was kept; lines with secrets and repeats of
+    /// the previous line are skipped.
+    pub fn record(&mut self, line: &str) -> Result<bool> {
+        let line = line.trim();
+        if line.is_empty()
+            || contains_secret(line)
+            || self.entries.last().is_some_and(|last| last == line)
+        {
+            return Ok(false);
+        }
+
+        self.entries.push(line.to_string());
+        self.truncate();
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Write the recorded lines to the history file
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut content = self.entries.join("\n");
+        content.push('\n');
+        std::fs::write(&self.path, content)?;
+        Ok(())
+    }
+
+    /// Drop the oldest lines beyond the cap
+    fn truncate(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.max_entries);
+        self.entries.drain(..excess);
+    }
+}
+
+/// Check whether a REPL line passes a credential
+///
+/// Covers credential flags, sensitive `-H` headers and `-q` parameters
+/// named like secrets.
+pub fn contains_secret(line: &str) -> bool {
+    let Ok(args) = CommandParser::parse_line(line) else {
+        // Can't tell what an unparseable line holds, so don't keep it
+        return true;
+    };
+
+    let mut args = args.iter().map(String::as_str).peekable();
+    while let Some(arg) = args.next() {
+        let flag = arg.split_once('=').map_or(arg, |(flag, _)| flag);
+        if SECRET_FLAGS.contains(&flag) {
+            return true;
+        }
+
+        let (name, separator) = match arg {
+            "-H" | "--header" => (args.peek().copied(), ':'),
+            "-q" | "--query" => (args.peek().copied(), '='),
+            _ => continue,
+        };
+        let name = name.map(|value| value.split_once(separator).map_or(value, |(name, _)| name));
+        if name.is_some_and(is_sensitive_header) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("repl").join("history");
+
+        let mut history = InputHistory::new(&path);
+        assert!(history.load().unwrap().is_empty());
+        assert!(history.record("get https://api.example.com/users").unwrap());
+        assert!(!history.record("get https://api.example.com/users").unwrap());
+        assert!(history
+            .record(r#"post https://api.example.com/users -b '{"name":"ada"}'"#)
+            .unwrap());
+
+        let mut reloaded = InputHistory::new(&path);
+        assert_eq!(
+            reloaded.load().unwrap(),
+            [
+                "get https://api.example.com/users",
+                r#"post https://api.example.com/users -b '{"name":"ada"}'"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history");
+
+        let mut history = InputHistory::new(&path).with_max_entries(2);
+        for n in 1..=3 {
+            history.record(&format!("get /items/{}", n)).unwrap();
+        }
+        assert_eq!(history.entries(), ["get /items/2", "get /items/3"]);
+
+        std::fs::write(&path, "a\nb\nc\nd\n").unwrap();
+        let mut reloaded = InputHistory::new(&path).with_max_entries(3);
+        assert_eq!(reloaded.load().unwrap(), ["b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_secret_lines_are_not_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history");
+        let mut history = InputHistory::new(&path);
+
+        assert!(!history
+            .record("get https://api.example.com -H 'Authorization: Bearer abc'")
+            .unwrap());
+        assert!(!history
+            .record("get https://api.example.com -q access_token=abc")
+            .unwrap());
+        assert!(!history
+            .record("get https://api.example.com --token=abc")
+            .unwrap());
+        assert!(!history.record("get 'unterminated").unwrap());
+        assert!(history
+            .record("get https://api.example.com -H 'Accept: application/json'")
+            .unwrap());
+
+        assert_eq!(InputHistory::new(&path).load().unwrap().len(), 1);
+    }
+}
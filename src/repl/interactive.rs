@@ -1,26 +1,98 @@
 //! Interactive REPL implementation
 
-use crate::cli::CommandParser;
+use crate::cli::{CommandParser, UseTarget};
+use crate::collections::CollectionStorage;
+use crate::env::{Environment, EnvironmentManager};
 use crate::error::{Error, Result};
-use crate::http::HttpClient;
-use crate::ui::{Banner, Help};
+use crate::history::{HistoryLogger, HistoryStorage, REPLAY_OF_KEY};
+use crate::http::{HttpClient, HttpResponse, RequestBuilder, ResponseDiffer, ResponseFormatter};
+use crate::repl::{InputHistory, ReplCompleter};
+use crate::session::{CookieJar, SessionManager};
+use crate::ui::{find_topic, Banner, Help};
 use colored::*;
 use rustyline::error::ReadlineError;
-use rustyline::DefaultEditor;
+use rustyline::history::{DefaultHistory, History};
+use rustyline::Editor;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
 
 /// Interactive REPL mode handler
 pub struct ReplMode {
-    editor: DefaultEditor,
+    editor: Editor<ReplCompleter, DefaultHistory>,
     client: HttpClient,
+    formatter: ResponseFormatter,
+    history: Option<HistoryStorage>,
+    /// Input lines kept across sessions for up-arrow and Ctrl-R
+    input_history: Option<InputHistory>,
+    /// Environment whose variables fill in `{{placeholders}}`
+    environment: Option<Environment>,
+    /// The two most recent responses, oldest first, for `diff`
+    recent: Vec<HttpResponse>,
 }
 
 impl ReplMode {
-    /// Create a new REPL mode instance
+    /// Create a new REPL mode instance with a session cookie jar
     pub fn new() -> Result<Self> {
-        let editor = DefaultEditor::new()?;
-        let client = HttpClient::new();
+        let mut editor = Editor::new()?;
+        editor.set_helper(Some(ReplCompleter::new()));
+        let client = HttpClient::new().with_cookie_jar(Arc::new(Mutex::new(CookieJar::new())));
 
-        Ok(Self { editor, client })
+        Ok(Self {
+            editor,
+            client,
+            formatter: ResponseFormatter::new(),
+            history: None,
+            input_history: None,
+            environment: None,
+            recent: Vec::new(),
+        })
+    }
+
+    /// Use a preconfigured client (e.g. without a cookie jar)
+    pub fn with_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Choose how responses are displayed
+    pub fn with_formatter(mut self, formatter: ResponseFormatter) -> Self {
+        self.formatter = formatter;
+        self
+    }
+
+    /// Record every request and its outcome in history
+    pub fn with_history(mut self, storage: HistoryStorage) -> Self {
+        self.history = Some(storage);
+        self
+    }
+
+    /// Fill in request `{{placeholders}}` from this environment
+    pub fn with_environment(mut self, environment: Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// The client's cookie jar (the active session's, after `use session`)
+    pub fn cookie_jar(&self) -> Option<&Arc<Mutex<CookieJar>>> {
+        self.client.cookie_jar()
+    }
+
+    /// Recall input lines from earlier sessions and keep this session's
+    pub fn with_input_history(mut self, mut input_history: InputHistory) -> Result<Self> {
+        let history = self.editor.history_mut();
+        history.set_max_len(input_history.max_entries())?;
+        for line in input_history.load()? {
+            history.add(line)?;
+        }
+        self.input_history = Some(input_history);
+        Ok(self)
+    }
+
+    /// Complete saved requests and environment names as well as commands
+    pub fn with_completer(mut self, completer: ReplCompleter) -> Self {
+        self.editor.set_helper(Some(completer));
+        self
     }
 
     /// Run the interactive REPL
@@ -40,12 +112,24 @@ impl ReplMode {
                     }
 
                     self.editor.add_history_entry(line.as_str()).ok();
+                    if let Some(input_history) = &mut self.input_history {
+                        if let Err(e) = input_history.record(&line) {
+                            eprintln!(
+                                "{} Could not save input history: {}",
+                                "⚠".yellow().bold(),
+                                e
+                            );
+                        }
+                    }
 
                     let trimmed = line.trim();
 
                     // Handle built-in commands
-                    if self.handle_builtin_command(trimmed)? {
-                        continue;
+                    match self.handle_builtin_command(trimmed)? {
+                        Builtin::Handled => continue,
+                        // Return normally so the caller can save the session
+                        Builtin::Exit => break,
+                        Builtin::Http => {}
                     }
 
                     // Handle HTTP commands
@@ -81,35 +165,39 @@ impl ReplMode {
     }
 
     /// Handle built-in commands (help, version, exit, etc.)
-    /// Returns true if command was handled, false otherwise
-    fn handle_builtin_command(&self, command: &str) -> Result<bool> {
+    fn handle_builtin_command(&self, command: &str) -> Result<Builtin> {
         match command {
-            "exit" | "quit" => {
-                println!();
-                println!("{}", "Thank you for using Bazzounquester!".cyan().bold());
-                println!();
-                std::process::exit(0);
-            }
-            "help" => {
+            "exit" | "quit" => Ok(Builtin::Exit),
+            "help" | ":help" => {
                 Help::show_interactive();
-                Ok(true)
+                Help::show_topics();
+                Ok(Builtin::Handled)
+            }
+            _ if command.starts_with("help ") || command.starts_with(":help ") => {
+                let name = command.split_once(' ').map_or("", |(_, t)| t.trim());
+                match find_topic(name) {
+                    Some(topic) => Help::show_topic(topic),
+                    None => {
+                        eprintln!("{} Unknown help topic '{}'", "✗".red().bold(), name);
+                        Help::show_topics();
+                    }
+                }
+                Ok(Builtin::Handled)
             }
             "version" | "--version" | "-v" => {
                 Banner::show_version();
-                Ok(true)
+                Ok(Builtin::Handled)
             }
             "clear" | "cls" => {
                 print!("\x1B[2J\x1B[1;1H");
-                Ok(true)
+                Ok(Builtin::Handled)
             }
-            _ => Ok(false),
+            _ => Ok(Builtin::Http),
         }
     }
 
     /// Handle HTTP commands
-    fn handle_http_command(&self, input: &str) -> Result<()> {
-        use crate::http::ResponseFormatter;
-
+    fn handle_http_command(&mut self, input: &str) -> Result<()> {
         // Parse command line
         let args = CommandParser::parse_line(input)?;
 
@@ -120,36 +208,331 @@ impl ReplMode {
         let command = args[0].to_lowercase();
 
         // Check if it's a valid HTTP method
-        match command.as_str() {
+        let mut replay_of = None;
+        let request = match command.as_str() {
             "get" | "post" | "put" | "delete" | "patch" | "head" | "options" => {
-                // Parse HTTP command
                 let request = CommandParser::parse_http_command(&command, &args[1..])?;
+                match &self.environment {
+                    Some(env) => request.substitute_variables(&env.enabled_variables()),
+                    None => request,
+                }
+            }
+            "run" => saved_request(&args[1..], self.environment.as_ref())?,
+            "replay" => {
+                let (id, request) = self.replayed_request(&args[1..])?;
+                replay_of = Some(id);
+                request
+            }
+            "diff" | ":diff" => return self.show_diff(&args[1..]),
+            "use" => return self.switch(CommandParser::parse_use_command(&args[1..])?),
+            _ => {
+                return Err(Error::InvalidCommand(format!(
+                    "Unknown command: '{}'. Type 'help' for available commands.",
+                    command
+                )))
+            }
+        };
 
-                // Display request info
-                println!();
+        // Display request info
+        println!();
+        println!(
+            "{} {}",
+            "→".cyan().bold(),
+            format!("{} {}", request.method.as_str(), request.url)
+                .bright_white()
+                .bold()
+        );
+        println!();
+
+        // Execute request
+        let result = self.client.execute(&request);
+        if let Some(storage) = &self.history {
+            let mut logger = HistoryLogger::new();
+            if let Some(original) = replay_of {
+                logger.set_metadata(REPLAY_OF_KEY.to_string(), original.to_string());
+            }
+            let id = logger.record(&request, &result);
+            let saved = logger
+                .get_entry(&id)
+                .map_or(Ok(()), |entry| storage.save_entry(entry));
+            if let Err(e) = saved {
+                eprintln!("{} Could not save history: {}", "⚠".yellow().bold(), e);
+            }
+        }
+        let response = result?;
+
+        // Display response
+        print!("{}", self.formatter.build(&response));
+
+        if self.recent.len() == 2 {
+            self.recent.remove(0);
+        }
+        self.recent.push(response);
+
+        Ok(())
+    }
+
+    /// Make an environment or session active for the requests that follow
+    fn switch(&mut self, target: UseTarget) -> Result<()> {
+        match target {
+            UseTarget::Environment(name) => {
+                let mut manager = EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+                manager.load_all()?;
+                let env = activate_environment(&mut manager, &name)?;
                 println!(
-                    "{} {}",
-                    "→".cyan().bold(),
-                    format!("{} {}", request.method.as_str(), request.url)
-                        .bright_white()
-                        .bold()
+                    "{} Using environment '{}' ({} variable(s))",
+                    "✓".green().bold(),
+                    env.name,
+                    env.enabled_variables().len()
                 );
-                println!();
+                self.environment = Some(env);
+            }
+            UseTarget::Session(name) => {
+                let mut manager = SessionManager::new(SessionManager::default_path()?)?;
+                manager.load_all()?;
 
-                // Execute request
-                let response = self.client.execute(&request)?;
+                // The cookies gathered so far belong to the session being left
+                if let Some(jar) = self.client.cookie_jar() {
+                    let jar = jar.lock().expect("cookie jar poisoned").clone();
+                    manager.save_active_cookies(&jar)?;
+                }
+                let id = activate_session(&mut manager, &name)?;
 
-                // Display response
-                print!("{}", ResponseFormatter::format(&response));
+                let session = manager
+                    .get_session(&id)
+                    .expect("session was just activated");
+                match manager.active_cookie_jar() {
+                    Some(jar) if self.client.cookie_jar().is_some() => {
+                        self.client = std::mem::take(&mut self.client).with_cookie_jar(jar);
+                        println!(
+                            "{} Using session '{}' ({} cookie(s))",
+                            "✓".green().bold(),
+                            session.name,
+                            session.cookies.count()
+                        );
+                    }
+                    _ => println!(
+                        "{} Session '{}' is active, but cookies are off (--no-cookies)",
+                        "⚠".yellow().bold(),
+                        session.name
+                    ),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Diff two responses: `diff [<old> <new>] [--all-headers]`, each `last`,
+    /// `prev`, or a history ID
+    fn show_diff(&self, args: &[String]) -> Result<()> {
+        let all_headers = args.iter().any(|arg| arg == "--all-headers");
+        let args: Vec<&str> = args
+            .iter()
+            .map(String::as_str)
+            .filter(|arg| *arg != "--all-headers")
+            .collect();
+        let (old, new) =
+            match args[..] {
+                [] => ("prev", "last"),
+                [old, new] => (old, new),
+                _ => return Err(Error::MissingArgument(
+                    "Usage: diff [<last|prev|history id> <last|prev|history id>] [--all-headers]"
+                        .to_string(),
+                )),
+            };
 
-                Ok(())
+        let differ = if all_headers {
+            ResponseDiffer::new().with_all_headers()
+        } else {
+            ResponseDiffer::new()
+        };
+        let old = self.resolve_response(old)?;
+        let new = self.resolve_response(new)?;
+        println!();
+        print!("{}", differ.diff(&old, &new).render());
+        println!();
+        Ok(())
+    }
+
+    /// Rebuild a past request: `replay <history id> [--resubstitute]`
+    ///
+    /// The request is sent as stored, unless `--resubstitute` fills its
+    /// `{{placeholders}}` from the current environment again.
+    fn replayed_request(&self, args: &[String]) -> Result<(Uuid, RequestBuilder)> {
+        let (spec, resubstitute) = match args {
+            [spec] => (spec, false),
+            [spec, flag] | [flag, spec] if flag == "--resubstitute" => (spec, true),
+            _ => {
+                return Err(Error::MissingArgument(
+                    "Usage: replay <history id> [--resubstitute]".to_string(),
+                ))
             }
-            _ => Err(Error::InvalidCommand(format!(
-                "Unknown command: '{}'. Type 'help' for available commands.",
-                command
-            ))),
+        };
+        let storage = self
+            .history
+            .as_ref()
+            .ok_or_else(|| Error::InvalidCommand("History is disabled".to_string()))?;
+
+        let entry = storage.find(spec)?;
+        let request = entry.to_request_builder()?;
+        let request = match &self.environment {
+            Some(env) if resubstitute => request.substitute_variables(&env.enabled_variables()),
+            _ => request,
+        };
+        Ok((entry.id, request))
+    }
+
+    /// A response from this session (`last`, `prev`) or from history by ID
+    fn resolve_response(&self, spec: &str) -> Result<HttpResponse> {
+        let back = match spec {
+            "last" => Some(1),
+            "prev" => Some(2),
+            _ => None,
+        };
+        if let Some(back) = back {
+            return self
+                .recent
+                .len()
+                .checked_sub(back)
+                .map(|i| self.recent[i].clone())
+                .ok_or_else(|| {
+                    Error::InvalidCommand(format!("No '{}' response in this session yet", spec))
+                });
+        }
+
+        let storage = self.history.as_ref().ok_or_else(|| {
+            Error::InvalidCommand("History is disabled; use 'last' or 'prev'".to_string())
+        })?;
+        let entry = storage.find(spec)?;
+        entry.recorded_response().ok_or_else(|| {
+            Error::InvalidCommand(format!("History entry '{}' has no response", spec))
+        })
+    }
+}
+
+/// Make the named environment active and save the change
+fn activate_environment(manager: &mut EnvironmentManager, name: &str) -> Result<Environment> {
+    let candidates = manager
+        .list_environments()
+        .into_iter()
+        .map(|env| (env.id, env.name.as_str()))
+        .collect();
+    let id = find_by_name("Environment", candidates, name)?;
+    manager.set_active(&id);
+    manager.save_all()?;
+    Ok(manager
+        .get_environment(&id)
+        .cloned()
+        .expect("environment was just activated"))
+}
+
+/// Make the named session active and save the change
+fn activate_session(manager: &mut SessionManager, name: &str) -> Result<Uuid> {
+    let candidates = manager
+        .list_sessions()
+        .into_iter()
+        .map(|session| (session.id, session.name.as_str()))
+        .collect();
+    let id = find_by_name("Session", candidates, name)?;
+    manager.set_active(&id);
+    manager.save_all()?;
+    Ok(id)
+}
+
+/// Find the one candidate with a name, falling back to a case-insensitive match
+///
+/// The error lists the available names when none or several match.
+fn find_by_name(kind: &str, candidates: Vec<(Uuid, &str)>, name: &str) -> Result<Uuid> {
+    let exact: Vec<Uuid> = candidates
+        .iter()
+        .filter(|(_, n)| *n == name)
+        .map(|(id, _)| *id)
+        .collect();
+    let matches = if exact.is_empty() {
+        candidates
+            .iter()
+            .filter(|(_, n)| n.eq_ignore_ascii_case(name))
+            .map(|(id, _)| *id)
+            .collect()
+    } else {
+        exact
+    };
+
+    let mut names: Vec<&str> = candidates.iter().map(|(_, n)| *n).collect();
+    names.sort();
+    let available = if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    };
+    match matches.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(Error::InvalidCommand(format!(
+            "{} '{}' not found (available: {})",
+            kind, name, available
+        ))),
+        _ => Err(Error::InvalidCommand(format!(
+            "{} name '{}' is ambiguous (available: {})",
+            kind, name, available
+        ))),
+    }
+}
+
+/// Build a saved request from `run <collection>/<request> [--env <name>]`
+///
+/// Variables come from the request's collection and folders, overridden by
+/// the named environment, or the active one without `--env`.
+fn saved_request(args: &[String], active: Option<&Environment>) -> Result<RequestBuilder> {
+    let (spec, env_name) = match args {
+        [spec] => (spec, None),
+        [spec, flag, name] if flag == "--env" => (spec, Some(name)),
+        _ => {
+            return Err(Error::MissingArgument(
+                "Usage: run <collection>/<request> [--env <name>]".to_string(),
+            ))
         }
+    };
+    let (collection_name, request_name) = spec.split_once('/').ok_or_else(|| {
+        Error::InvalidCommand(format!(
+            "Expected '<collection>/<request name>', got '{}'",
+            spec
+        ))
+    })?;
+
+    let collection = CollectionStorage::new(CollectionStorage::default_path()?)?
+        .list_all()?
+        .into_iter()
+        .find(|c| c.info.name == collection_name)
+        .ok_or_else(|| {
+            Error::InvalidCommand(format!("Collection '{}' not found", collection_name))
+        })?;
+    let item = match collection.find_request_by_name(request_name) {
+        Some(item) if !request_name.contains('/') => item,
+        _ => collection.request_at_path(request_name)?,
+    };
+
+    let scoped = collection.request_variables(&item.id);
+    let mut variables: HashMap<&str, &str> = scoped
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+
+    let mut manager = EnvironmentManager::new(EnvironmentManager::default_path()?)?;
+    if let Some(name) = env_name {
+        manager.load_all()?;
+        let env = manager.get_environment_by_name(name).ok_or_else(|| {
+            Error::InvalidCommand(format!(
+                "Environment '{}' not found (available: {})",
+                name,
+                manager.environment_names().join(", ")
+            ))
+        })?;
+        variables.extend(env.enabled_variables());
+    } else if let Some(env) = active {
+        variables.extend(env.enabled_variables());
     }
+
+    Ok(item.to_request_builder_with(&variables))
 }
 
 impl Default for ReplMode {
@@ -158,6 +541,19 @@ impl Default for ReplMode {
     }
 }
 
+/// What a line turned out to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Builtin {
+    /// A built-in command that has run
+    Handled,
+
+    /// `exit` or `quit`
+    Exit,
+
+    /// Not a built-in, so an HTTP command
+    Http,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,5 +564,157 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_exit_returns_to_the_caller() {
+        let repl = ReplMode::new().unwrap();
+        assert_eq!(repl.handle_builtin_command("exit").unwrap(), Builtin::Exit);
+        assert_eq!(repl.handle_builtin_command("quit").unwrap(), Builtin::Exit);
+        assert_eq!(
+            repl.handle_builtin_command("version").unwrap(),
+            Builtin::Handled
+        );
+        assert_eq!(
+            repl.handle_builtin_command("get /health").unwrap(),
+            Builtin::Http
+        );
+    }
+
+    #[test]
+    fn test_run_requires_request_spec() {
+        assert!(matches!(
+            saved_request(&[], None),
+            Err(Error::MissingArgument(_))
+        ));
+        assert!(matches!(
+            saved_request(&["Health".to_string()], None),
+            Err(Error::InvalidCommand(_))
+        ));
+        assert!(matches!(
+            saved_request(&["API/Health".to_string(), "--env".to_string()], None),
+            Err(Error::MissingArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_input_history_is_loaded_into_editor() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("repl_history");
+        std::fs::write(&path, "get /a\nget /b\n").unwrap();
+
+        let repl = ReplMode::new()
+            .unwrap()
+            .with_input_history(InputHistory::new(&path))
+            .unwrap();
+        let lines: Vec<&String> = repl.editor.history().iter().collect();
+        assert_eq!(lines, ["get /a", "get /b"]);
+    }
+
+    #[test]
+    fn test_use_environment_switches_active() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = EnvironmentManager::new(dir.path().to_path_buf()).unwrap();
+        for name in ["dev", "prod"] {
+            let mut env = Environment::new(name.to_string());
+            env.set_variable(
+                "BASE_URL".to_string(),
+                format!("https://{}.example.com", name),
+            );
+            manager.add_environment(env);
+        }
+
+        let env = activate_environment(&mut manager, "PROD").unwrap();
+        assert_eq!(env.name, "prod");
+
+        let mut reloaded = EnvironmentManager::new(dir.path().to_path_buf()).unwrap();
+        reloaded.load_all().unwrap();
+        assert_eq!(reloaded.get_active_environment().unwrap().name, "prod");
+
+        let request = RequestBuilder::new(
+            crate::http::HttpMethod::Get,
+            "{{BASE_URL}}/users".to_string(),
+        )
+        .query("host={{BASE_URL}}".to_string())
+        .substitute_variables(&env.enabled_variables());
+        assert_eq!(request.url, "https://prod.example.com/users");
+        assert_eq!(request.query_params, ["host=https://prod.example.com"]);
+    }
+
+    #[test]
+    fn test_replay_from_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = HistoryStorage::new(dir.path().to_path_buf()).unwrap();
+        let mut logger = HistoryLogger::new();
+        let id = logger.log_request(
+            &RequestBuilder::new(
+                crate::http::HttpMethod::Post,
+                "{{BASE_URL}}/users".to_string(),
+            )
+            .body(r#"{"name":"ada"}"#.to_string()),
+        );
+        storage.save_entry(logger.get_entry(&id).unwrap()).unwrap();
+
+        let mut env = Environment::new("prod".to_string());
+        env.set_variable(
+            "BASE_URL".to_string(),
+            "https://prod.example.com".to_string(),
+        );
+        let repl = ReplMode::new()
+            .unwrap()
+            .with_history(HistoryStorage::new(dir.path().to_path_buf()).unwrap())
+            .with_environment(env);
+        let short = id.to_string()[..8].to_string();
+
+        let (original, request) = repl.replayed_request(std::slice::from_ref(&short)).unwrap();
+        assert_eq!(original, id);
+        assert_eq!(request.method, crate::http::HttpMethod::Post);
+        assert_eq!(request.url, "{{BASE_URL}}/users");
+        assert_eq!(request.body.as_deref(), Some(r#"{"name":"ada"}"#));
+
+        let (_, request) = repl
+            .replayed_request(&[short, "--resubstitute".to_string()])
+            .unwrap();
+        assert_eq!(request.url, "https://prod.example.com/users");
+
+        assert!(repl.replayed_request(&[]).is_err());
+        assert!(ReplMode::new()
+            .unwrap()
+            .replayed_request(&[id.to_string()])
+            .is_err());
+    }
+
+    #[test]
+    fn test_use_session_switches_active() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = SessionManager::new(dir.path().to_path_buf()).unwrap();
+        manager.create_session("work".to_string(), true);
+        let login = manager.create_session("my-login".to_string(), false);
+
+        assert_eq!(activate_session(&mut manager, "my-login").unwrap(), login);
+        assert_eq!(manager.get_active_session().unwrap().name, "my-login");
+    }
+
+    #[test]
+    fn test_use_unknown_or_ambiguous_name() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let error = find_by_name("Environment", vec![(a, "dev"), (b, "prod")], "qa")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Environment 'qa' not found (available: dev, prod)"));
+
+        let error = find_by_name("Session", vec![(a, "Work"), (b, "work")], "WORK")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("ambiguous"));
+        assert_eq!(
+            find_by_name("Session", vec![(a, "Work"), (b, "work")], "work").unwrap(),
+            b
+        );
+        assert!(find_by_name("Session", Vec::new(), "x")
+            .unwrap_err()
+            .to_string()
+            .contains("available: none"));
+    }
+
     // More integration tests would go here
 }
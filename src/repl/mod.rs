@@ -1,5 +1,9 @@
 //! REPL (Read-Eval-Print Loop) for interactive mode
 
+pub mod completion;
+pub mod history;
 pub mod interactive;
 
+pub use completion::ReplCompleter;
+pub use history::InputHistory;
 pub use interactive::ReplMode;
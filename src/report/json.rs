@@ -0,0 +1,109 @@
+//! JSON report generation
+//!
+//! The schema mirrors the JUnit report so CI tools can read either:
+//! `{"name", "tests", "failures", "time", "testcases": [{"name", "classname",
+//! "time", "status", "failure"}]}`, with times in seconds.
+
+use crate::error::Result;
+use crate::report::{JUnitTestCase, JUnitTestSuite};
+use serde::Serialize;
+use std::time::Duration;
+
+#[derive(Serialize)]
+struct JsonSuite<'a> {
+    name: &'a str,
+    tests: usize,
+    failures: usize,
+    time: f64,
+    testcases: Vec<JsonCase<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonCase<'a> {
+    name: &'a str,
+    classname: &'a str,
+    time: f64,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<&'a str>,
+}
+
+impl<'a> From<&'a JUnitTestCase> for JsonCase<'a> {
+    fn from(case: &'a JUnitTestCase) -> Self {
+        Self {
+            name: &case.name,
+            classname: &case.classname,
+            time: seconds(case.time),
+            status: if case.failure.is_some() {
+                "failed"
+            } else {
+                "passed"
+            },
+            failure: case.failure.as_deref(),
+        }
+    }
+}
+
+impl JUnitTestSuite {
+    /// Render as a JSON report
+    pub fn to_json(&self) -> Result<String> {
+        let suite = JsonSuite {
+            name: &self.name,
+            tests: self.cases.len(),
+            failures: self.failures(),
+            time: seconds(self.time()),
+            testcases: self.cases.iter().map(JsonCase::from).collect(),
+        };
+        Ok(serde_json::to_string_pretty(&suite)?)
+    }
+}
+
+/// Seconds with millisecond precision
+fn seconds(duration: Duration) -> f64 {
+    duration.as_millis() as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_suite_to_json() {
+        let mut suite = JUnitTestSuite::new("api".to_string());
+        suite.add_case(JUnitTestCase::new(
+            "login".to_string(),
+            "api".to_string(),
+            Duration::from_micros(1_500_400),
+        ));
+        suite.add_case(
+            JUnitTestCase::new(
+                "fetch".to_string(),
+                "api".to_string(),
+                Duration::from_millis(250),
+            )
+            .with_failure("Expected 200, got 500".to_string()),
+        );
+
+        let report: Value = serde_json::from_str(&suite.to_json().unwrap()).unwrap();
+        assert_eq!(
+            report,
+            json!({
+                "name": "api",
+                "tests": 2,
+                "failures": 1,
+                "time": 1.75,
+                "testcases": [
+                    {"name": "login", "classname": "api", "time": 1.5, "status": "passed"},
+                    {
+                        "name": "fetch",
+                        "classname": "api",
+                        "time": 0.25,
+                        "status": "failed",
+                        "failure": "Expected 200, got 500"
+                    }
+                ]
+            })
+        );
+    }
+}
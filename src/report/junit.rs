@@ -0,0 +1,246 @@
+//! JUnit XML report generation
+
+use std::time::Duration;
+
+/// A single JUnit test case
+#[derive(Debug, Clone, PartialEq)]
+pub struct JUnitTestCase {
+    /// Test case name
+    pub name: String,
+
+    /// Class name (usually the suite name)
+    pub classname: String,
+
+    /// Execution time
+    pub time: Duration,
+
+    /// Failure message (if failed)
+    pub failure: Option<String>,
+}
+
+impl JUnitTestCase {
+    /// Create a passing test case
+    pub fn new(name: String, classname: String, time: Duration) -> Self {
+        Self {
+            name,
+            classname,
+            time,
+            failure: None,
+        }
+    }
+
+    /// Mark the test case as failed
+    pub fn with_failure(mut self, message: String) -> Self {
+        self.failure = Some(message);
+        self
+    }
+}
+
+/// A JUnit test suite
+#[derive(Debug, Clone, PartialEq)]
+pub struct JUnitTestSuite {
+    /// Suite name
+    pub name: String,
+
+    /// Test cases
+    pub cases: Vec<JUnitTestCase>,
+}
+
+impl JUnitTestSuite {
+    /// Create an empty suite
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            cases: Vec::new(),
+        }
+    }
+
+    /// Add a test case
+    pub fn add_case(&mut self, case: JUnitTestCase) {
+        self.cases.push(case);
+    }
+
+    /// Number of failed test cases
+    pub fn failures(&self) -> usize {
+        self.cases.iter().filter(|c| c.failure.is_some()).count()
+    }
+
+    /// Total time of all test cases
+    pub fn time(&self) -> Duration {
+        self.cases.iter().map(|c| c.time).sum()
+    }
+
+    /// Render as a JUnit XML document
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\" time=\"{}\">\n",
+            escape(&self.name),
+            self.cases.len(),
+            self.failures(),
+            seconds(self.time())
+        ));
+
+        for case in &self.cases {
+            let attributes = format!(
+                "name=\"{}\" classname=\"{}\" time=\"{}\"",
+                escape(&case.name),
+                escape(&case.classname),
+                seconds(case.time)
+            );
+            match &case.failure {
+                Some(message) => {
+                    xml.push_str(&format!("  <testcase {}>\n", attributes));
+                    xml.push_str(&format!(
+                        "    <failure message=\"{}\">{}</failure>\n",
+                        escape(message),
+                        escape(message)
+                    ));
+                    xml.push_str("  </testcase>\n");
+                }
+                None => xml.push_str(&format!("  <testcase {}/>\n", attributes)),
+            }
+        }
+
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+}
+
+/// Format a duration as seconds with millisecond precision
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+/// Escape text for use in XML attributes and content
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            // Control characters are not allowed in XML 1.0
+            c if c.is_control() && c != '\t' && c != '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Check a report against the de-facto JUnit XML schema (as read by Jenkins,
+/// GitLab and surefire): a `testsuite` root with consistent counts, `testcase`
+/// children with a name, classname and time, and `failure` elements with a
+/// message. Times must be decimal seconds.
+#[cfg(test)]
+pub(crate) fn assert_junit_schema(xml: &str) {
+    let doc = roxmltree::Document::parse(xml).expect("report is not well-formed XML");
+    let root = doc.root_element();
+    assert_eq!(root.tag_name().name(), "testsuite");
+
+    let is_seconds = |time: &str| {
+        time.split_once('.').is_some_and(|(s, ms)| {
+            s.parse::<u64>().is_ok() && ms.len() == 3 && ms.parse::<u16>().is_ok()
+        })
+    };
+    let count = |name: &str| -> usize {
+        root.attribute(name)
+            .unwrap_or_else(|| panic!("testsuite is missing '{}'", name))
+            .parse()
+            .unwrap_or_else(|_| panic!("testsuite '{}' is not a count", name))
+    };
+    assert!(
+        root.attribute("name").is_some(),
+        "testsuite is missing 'name'"
+    );
+    assert!(is_seconds(root.attribute("time").unwrap_or_default()));
+
+    let mut cases = 0;
+    let mut failures = 0;
+    for case in root.children().filter(|n| n.is_element()) {
+        assert_eq!(case.tag_name().name(), "testcase");
+        for attribute in ["name", "classname"] {
+            assert!(
+                case.attribute(attribute).is_some(),
+                "testcase is missing '{}'",
+                attribute
+            );
+        }
+        assert!(is_seconds(case.attribute("time").unwrap_or_default()));
+        cases += 1;
+
+        for child in case.children().filter(|n| n.is_element()) {
+            assert!(
+                ["failure", "error", "skipped", "system-out", "system-err"]
+                    .contains(&child.tag_name().name()),
+                "unexpected <{}> in testcase",
+                child.tag_name().name()
+            );
+            if child.has_tag_name("failure") {
+                assert!(child.attribute("message").is_some());
+                failures += 1;
+            }
+        }
+    }
+    assert_eq!(count("tests"), cases);
+    assert_eq!(count("failures"), failures);
+    assert_eq!(count("errors"), 0);
+    assert_eq!(count("skipped"), 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suite_to_xml() {
+        let mut suite = JUnitTestSuite::new("api <smoke>".to_string());
+        suite.add_case(JUnitTestCase::new(
+            "login".to_string(),
+            "api".to_string(),
+            Duration::from_millis(1500),
+        ));
+        suite.add_case(
+            JUnitTestCase::new(
+                "fetch".to_string(),
+                "api".to_string(),
+                Duration::from_millis(250),
+            )
+            .with_failure("Expected \"200\" & got 500".to_string()),
+        );
+
+        let xml = suite.to_xml();
+        assert_junit_schema(&xml);
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let root = doc.root_element();
+
+        assert_eq!(root.tag_name().name(), "testsuite");
+        assert_eq!(root.attribute("name"), Some("api <smoke>"));
+        assert_eq!(root.attribute("tests"), Some("2"));
+        assert_eq!(root.attribute("failures"), Some("1"));
+        assert_eq!(root.attribute("time"), Some("1.750"));
+
+        let cases: Vec<_> = root
+            .children()
+            .filter(|n| n.has_tag_name("testcase"))
+            .collect();
+        assert_eq!(cases[0].attribute("time"), Some("1.500"));
+        let failure = cases[1]
+            .children()
+            .find(|n| n.has_tag_name("failure"))
+            .unwrap();
+        assert_eq!(
+            failure.attribute("message"),
+            Some("Expected \"200\" & got 500")
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a<b>&'\""), "a&lt;b&gt;&amp;&apos;&quot;");
+        assert_eq!(escape("bell\u{7}"), "bell");
+    }
+}
@@ -0,0 +1,8 @@
+//! Machine-readable test reports
+
+pub mod json;
+pub mod junit;
+pub mod target;
+
+pub use junit::{JUnitTestCase, JUnitTestSuite};
+pub use target::{ReportFormat, ReportTarget};
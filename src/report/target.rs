@@ -0,0 +1,114 @@
+//! Report destinations given as `--report <format>=<path>`
+
+use crate::error::Result;
+use crate::report::JUnitTestSuite;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Report file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// JUnit XML
+    Junit,
+
+    /// JSON with the same suite and test case fields
+    Json,
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportFormat::Junit => write!(f, "JUnit"),
+            ReportFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+/// A report to write, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportTarget {
+    /// File format
+    pub format: ReportFormat,
+
+    /// Output file
+    pub path: PathBuf,
+}
+
+impl ReportTarget {
+    /// Create a report target
+    pub fn new(format: ReportFormat, path: impl Into<PathBuf>) -> Self {
+        Self {
+            format,
+            path: path.into(),
+        }
+    }
+
+    /// Parse `junit=path.xml` or `json=path.json`
+    pub fn parse(input: &str) -> std::result::Result<Self, String> {
+        let (format, path) = input
+            .split_once('=')
+            .ok_or_else(|| format!("expected <format>=<path>, got '{}'", input))?;
+        let format = match format.trim().to_lowercase().as_str() {
+            "junit" | "xml" => ReportFormat::Junit,
+            "json" => ReportFormat::Json,
+            other => {
+                return Err(format!(
+                    "unknown report format '{}' (use junit or json)",
+                    other
+                ))
+            }
+        };
+        if path.trim().is_empty() {
+            return Err(format!("missing path in '{}'", input));
+        }
+        Ok(Self::new(format, path.trim()))
+    }
+
+    /// Render a suite in this target's format
+    pub fn render(&self, suite: &JUnitTestSuite) -> Result<String> {
+        match self.format {
+            ReportFormat::Junit => Ok(suite.to_xml()),
+            ReportFormat::Json => suite.to_json(),
+        }
+    }
+
+    /// Write a suite to this target's file
+    pub fn write(&self, suite: &JUnitTestSuite) -> Result<()> {
+        std::fs::write(&self.path, self.render(suite)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_report_target() {
+        assert_eq!(
+            ReportTarget::parse("junit=out/report.xml").unwrap(),
+            ReportTarget::new(ReportFormat::Junit, "out/report.xml")
+        );
+        assert_eq!(
+            ReportTarget::parse("JSON=report.json").unwrap().format,
+            ReportFormat::Json
+        );
+        assert!(ReportTarget::parse("report.xml").is_err());
+        assert!(ReportTarget::parse("html=report.html")
+            .unwrap_err()
+            .contains("unknown report format 'html'"));
+        assert!(ReportTarget::parse("junit=").is_err());
+    }
+
+    #[test]
+    fn test_write_report_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let suite = JUnitTestSuite::new("api".to_string());
+
+        let target = ReportTarget::new(ReportFormat::Json, dir.path().join("report.json"));
+        target.write(&suite).unwrap();
+
+        let written = std::fs::read_to_string(&target.path).unwrap();
+        assert!(written.contains("\"testcases\": []"));
+    }
+}
@@ -1,8 +1,9 @@
 //! Script execution engine
 
 use crate::error::{Error, Result};
+use crate::http::HttpResponse;
 use crate::scripts::{Script, ScriptContext};
-use rhai::{Dynamic, Engine, Map, Scope};
+use rhai::{Array, Dynamic, Engine, Map, Scope};
 use std::sync::{Arc, Mutex};
 
 /// Script execution engine
@@ -71,7 +72,7 @@ impl ScriptEngine {
         let _ = self
             .engine
             .eval_with_scope::<Dynamic>(&mut scope, &script.code)
-            .map_err(|e| Error::InvalidCommand(format!("Script execution error: {}", e)))?;
+            .map_err(|e| Error::ScriptExecution(e.to_string()))?;
 
         // Extract modified variables back to context
         // Clear existing variables
@@ -98,6 +99,67 @@ impl ScriptEngine {
 
         Ok(())
     }
+
+    /// Evaluate a boolean expression against a response
+    ///
+    /// In scope: `status`, `headers` (lower-case names), `body`, `json` (the
+    /// parsed body, or unit if it isn't JSON) and `duration_ms`. Script
+    /// errors and non-boolean results are an `Err` with the reason.
+    pub fn evaluate_response_expr(
+        &self,
+        expression: &str,
+        response: &HttpResponse,
+    ) -> std::result::Result<bool, String> {
+        let mut headers = Map::new();
+        for name in response.headers.keys() {
+            let values: Vec<&str> = response
+                .headers
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .collect();
+            headers.insert(name.as_str().into(), values.join(", ").into());
+        }
+        let json = serde_json::from_str(&response.body)
+            .map(|json| json_to_dynamic(&json))
+            .unwrap_or(Dynamic::UNIT);
+
+        let mut scope = Scope::new();
+        scope.push_constant("status", response.status.as_u16() as rhai::INT);
+        scope.push_constant("headers", headers);
+        scope.push_constant("body", response.body.clone());
+        scope.push_constant("json", json);
+        scope.push_constant("duration_ms", response.duration.as_millis() as rhai::INT);
+
+        let result = self
+            .engine
+            .eval_with_scope::<Dynamic>(&mut scope, expression)
+            .map_err(|e| format!("Script error: {}", e))?;
+        result
+            .as_bool()
+            .map_err(|type_name| format!("Expression returned {}, expected a boolean", type_name))
+    }
+}
+
+/// Convert JSON into the equivalent Rhai value (null becomes unit)
+fn json_to_dynamic(value: &serde_json::Value) -> Dynamic {
+    match value {
+        serde_json::Value::Null => Dynamic::UNIT,
+        serde_json::Value::Bool(b) => Dynamic::from(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Dynamic::from(i as rhai::INT),
+            None => Dynamic::from(n.as_f64().unwrap_or(f64::NAN) as rhai::FLOAT),
+        },
+        serde_json::Value::String(s) => Dynamic::from(s.clone()),
+        serde_json::Value::Array(items) => {
+            Dynamic::from_array(items.iter().map(json_to_dynamic).collect::<Array>())
+        }
+        serde_json::Value::Object(map) => Dynamic::from_map(
+            map.iter()
+                .map(|(key, value)| (key.as_str().into(), json_to_dynamic(value)))
+                .collect::<Map>(),
+        ),
+    }
 }
 
 impl Default for ScriptEngine {
@@ -229,6 +291,6 @@ mod tests {
         let mut context = ScriptContext::new();
 
         let result = engine.execute(&script, &mut context);
-        assert!(result.is_err());
+        assert!(matches!(result, Err(Error::ScriptExecution(_))));
     }
 }
@@ -22,12 +22,18 @@ pub struct Script {
     pub code: String,
 
     /// Script name/description (optional)
+    #[serde(default)]
     pub name: Option<String>,
 
     /// Whether script is enabled
+    #[serde(default = "default_enabled")]
     pub enabled: bool,
 }
 
+fn default_enabled() -> bool {
+    true
+}
+
 impl Script {
     /// Create a new script
     pub fn new(script_type: ScriptType, code: String) -> Self {
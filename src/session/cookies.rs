@@ -1,8 +1,10 @@
 //! Cookie handling and storage
 
-use chrono::{DateTime, Utc};
+use crate::http::host::HostInfo;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use url::Url;
 
 /// A simple cookie representation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -36,6 +38,10 @@ pub struct Cookie {
     /// Same site policy
     #[serde(skip_serializing_if = "Option::is_none")]
     pub same_site: Option<String>,
+
+    /// Only sent to the exact host that set it (no Domain attribute)
+    #[serde(default)]
+    pub host_only: bool,
 }
 
 impl Cookie {
@@ -50,6 +56,7 @@ impl Cookie {
             http_only: false,
             secure: false,
             same_site: None,
+            host_only: false,
         }
     }
 
@@ -81,51 +88,95 @@ impl Cookie {
     }
 
     /// Parse from Set-Cookie header
+    ///
+    /// `Max-Age` takes precedence over `Expires`; a leading dot on `Domain` is dropped.
     pub fn from_header(header: &str) -> Option<Self> {
-        let parts: Vec<&str> = header.split(';').collect();
-        if parts.is_empty() {
-            return None;
-        }
+        let mut parts = header.split(';');
 
-        // First part is name=value
-        let name_value: Vec<&str> = parts[0].split('=').collect();
-        if name_value.len() != 2 {
+        // First part is name=value (the value may itself contain '=')
+        let (name, value) = parts.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
             return None;
         }
 
-        let mut cookie = Cookie::new(
-            name_value[0].trim().to_string(),
-            name_value[1].trim().to_string(),
-        );
+        let mut cookie = Cookie::new(name.to_string(), value.trim().to_string());
+        let mut max_age = None;
 
         // Parse attributes
-        for part in &parts[1..] {
-            let attr: Vec<&str> = part.split('=').collect();
-            let attr_name = attr[0].trim().to_lowercase();
-
-            match attr_name.as_str() {
-                "domain" if attr.len() > 1 => {
-                    cookie.domain = Some(attr[1].trim().to_string());
+        for part in parts {
+            let (attr_name, attr_value) = match part.split_once('=') {
+                Some((name, value)) => (name, Some(value.trim())),
+                None => (part, None),
+            };
+
+            match (attr_name.trim().to_lowercase().as_str(), attr_value) {
+                ("domain", Some(domain)) => {
+                    let domain = domain.trim_start_matches('.').to_lowercase();
+                    if !domain.is_empty() {
+                        cookie.domain = Some(domain);
+                    }
+                }
+                ("path", Some(path)) if path.starts_with('/') => {
+                    cookie.path = Some(path.to_string());
                 }
-                "path" if attr.len() > 1 => {
-                    cookie.path = Some(attr[1].trim().to_string());
+                ("expires", Some(expires)) => {
+                    if let Some(expires) = parse_http_date(expires) {
+                        cookie.expires = Some(expires);
+                    }
                 }
-                "httponly" => {
+                ("max-age", Some(seconds)) => {
+                    if let Ok(seconds) = seconds.parse::<i64>() {
+                        max_age = Some(seconds);
+                    }
+                }
+                ("httponly", _) => {
                     cookie.http_only = true;
                 }
-                "secure" => {
+                ("secure", _) => {
                     cookie.secure = true;
                 }
-                "samesite" if attr.len() > 1 => {
-                    cookie.same_site = Some(attr[1].trim().to_string());
+                ("samesite", Some(same_site)) => {
+                    cookie.same_site = Some(same_site.to_string());
                 }
                 _ => {}
             }
         }
 
+        if let Some(seconds) = max_age {
+            // Zero or negative Max-Age expires the cookie immediately
+            cookie.expires = Some(if seconds <= 0 {
+                DateTime::<Utc>::UNIX_EPOCH
+            } else {
+                // A Max-Age too large for a date means "never", not a crash
+                TimeDelta::try_seconds(seconds)
+                    .and_then(|delta| Utc::now().checked_add_signed(delta))
+                    .unwrap_or_else(far_future)
+            });
+        }
+
         Some(cookie)
     }
 
+    /// Check whether this cookie should be sent to a request URL
+    ///
+    /// Cookies added without a domain (e.g. by hand) match every host. Like
+    /// browsers, matching ignores the port (RFC 6265 section 8.5).
+    pub fn matches_url(&self, url: &Url) -> bool {
+        let Some(HostInfo { host, .. }) = HostInfo::from_url(url) else {
+            return false;
+        };
+        let domain_ok = match &self.domain {
+            None => true,
+            Some(domain) if self.host_only => host == domain.to_lowercase(),
+            Some(domain) => domain_match(&host, domain),
+        };
+        let path_ok = path_match(url.path(), self.path.as_deref().unwrap_or("/"));
+        let scheme_ok = !self.secure || url.scheme() == "https";
+
+        domain_ok && path_ok && scheme_ok && !self.is_expired()
+    }
+
     /// Convert to Cookie header format
     pub fn to_header(&self) -> String {
         format!("{}={}", self.name, self.value)
@@ -133,6 +184,9 @@ impl Cookie {
 }
 
 /// Cookie jar for managing multiple cookies
+///
+/// Cookies are keyed by name, domain, and path, so the same name can be
+/// stored for different hosts.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CookieJar {
     cookies: HashMap<String, Cookie>,
@@ -146,19 +200,33 @@ impl CookieJar {
         }
     }
 
-    /// Add a cookie
+    fn key(cookie: &Cookie) -> String {
+        format!(
+            "{};{};{}",
+            cookie.name,
+            cookie.domain.as_deref().unwrap_or_default(),
+            cookie.path.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// Add a cookie, replacing one with the same name, domain, and path
     pub fn add(&mut self, cookie: Cookie) {
-        self.cookies.insert(cookie.name.clone(), cookie);
+        self.cookies.insert(Self::key(&cookie), cookie);
     }
 
     /// Get a cookie by name
     pub fn get(&self, name: &str) -> Option<&Cookie> {
-        self.cookies.get(name)
+        self.cookies.values().find(|c| c.name == name)
     }
 
     /// Remove a cookie
     pub fn remove(&mut self, name: &str) -> Option<Cookie> {
-        self.cookies.remove(name)
+        let key = self
+            .cookies
+            .iter()
+            .find(|(_, c)| c.name == name)
+            .map(|(key, _)| key.clone())?;
+        self.cookies.remove(&key)
     }
 
     /// Remove expired cookies
@@ -171,22 +239,39 @@ impl CookieJar {
         self.cookies.values().collect()
     }
 
-    /// Get cookies for a specific domain
+    /// Get cookies whose domain matches a host (ignores path and Secure)
     pub fn for_domain(&self, domain: &str) -> Vec<&Cookie> {
+        let host = domain.to_lowercase();
         self.cookies
             .values()
-            .filter(|c| {
-                c.domain
-                    .as_ref()
-                    .map(|d| domain.ends_with(d))
-                    .unwrap_or(true)
+            .filter(|c| match &c.domain {
+                None => true,
+                Some(d) if c.host_only => host == d.to_lowercase(),
+                Some(d) => domain_match(&host, d),
             })
             .collect()
     }
 
-    /// Get Cookie header value for request
-    pub fn cookie_header(&self, domain: &str) -> Option<String> {
-        let cookies = self.for_domain(domain);
+    /// Get unexpired cookies to send to a URL, longest path first
+    pub fn for_url(&self, url: &str) -> Vec<&Cookie> {
+        let Ok(url) = Url::parse(url) else {
+            return Vec::new();
+        };
+        let mut cookies: Vec<&Cookie> = self
+            .cookies
+            .values()
+            .filter(|c| c.matches_url(&url))
+            .collect();
+        cookies.sort_by(|a, b| {
+            let len = |c: &Cookie| c.path.as_deref().map_or(1, str::len);
+            len(b).cmp(&len(a)).then_with(|| a.name.cmp(&b.name))
+        });
+        cookies
+    }
+
+    /// Get Cookie header value for a request URL
+    pub fn cookie_header(&self, url: &str) -> Option<String> {
+        let cookies = self.for_url(url);
         if cookies.is_empty() {
             None
         } else {
@@ -200,15 +285,129 @@ impl CookieJar {
         }
     }
 
-    /// Parse Set-Cookie headers
-    pub fn add_from_headers(&mut self, headers: &[(String, String)]) {
+    /// Store cookies from the Set-Cookie headers of a response to `url`
+    ///
+    /// Cookies without a Domain become host-only, cookies whose Domain doesn't
+    /// cover the request host are rejected, and expired cookies delete any
+    /// stored cookie with the same name, domain, and path.
+    pub fn add_from_headers(&mut self, url: &str, headers: &[(String, String)]) {
+        let Ok(url) = Url::parse(url) else {
+            return;
+        };
+        let Some(HostInfo { host, .. }) = HostInfo::from_url(&url) else {
+            return;
+        };
+
         for (name, value) in headers {
-            if name.eq_ignore_ascii_case("set-cookie") {
-                if let Some(cookie) = Cookie::from_header(value) {
-                    self.add(cookie);
+            if !name.eq_ignore_ascii_case("set-cookie") {
+                continue;
+            }
+            let Some(mut cookie) = Cookie::from_header(value) else {
+                continue;
+            };
+
+            match &cookie.domain {
+                Some(domain) if !domain_match(&host, domain) => continue,
+                Some(_) => {}
+                None => {
+                    cookie.domain = Some(host.clone());
+                    cookie.host_only = true;
                 }
             }
+            if cookie.path.is_none() {
+                cookie.path = Some(default_path(url.path()));
+            }
+
+            if cookie.is_expired() {
+                self.cookies.remove(&Self::key(&cookie));
+            } else {
+                self.add(cookie);
+            }
+        }
+    }
+
+    /// Export cookies in the Netscape cookies.txt format (curl `-c`)
+    pub fn to_netscape(&self) -> String {
+        let mut cookies = self.all();
+        cookies.sort_by(|a, b| {
+            (a.domain.as_deref(), a.path.as_deref(), &a.name).cmp(&(
+                b.domain.as_deref(),
+                b.path.as_deref(),
+                &b.name,
+            ))
+        });
+
+        let mut out = String::from("# Netscape HTTP Cookie File\n");
+        for cookie in cookies {
+            let domain = cookie.domain.as_deref().unwrap_or_default();
+            let domain = if cookie.host_only || domain.is_empty() {
+                domain.to_string()
+            } else {
+                format!(".{}", domain)
+            };
+            let flag = |value: bool| if value { "TRUE" } else { "FALSE" };
+            out.push_str(&format!(
+                "{}{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                if cookie.http_only { "#HttpOnly_" } else { "" },
+                domain,
+                flag(!cookie.host_only),
+                cookie.path.as_deref().unwrap_or("/"),
+                flag(cookie.secure),
+                cookie.expires.map_or(0, |e| e.timestamp()),
+                cookie.name,
+                cookie.value
+            ));
         }
+        out
+    }
+
+    /// Import cookies from the Netscape cookies.txt format (curl `-b`/`-c`)
+    ///
+    /// Returns the number of cookies read; expired ones are skipped.
+    pub fn import_netscape(&mut self, content: &str) -> crate::Result<usize> {
+        let mut imported = 0;
+        for (number, line) in content.lines().enumerate() {
+            let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+                Some(rest) => (rest, true),
+                None => (line, false),
+            };
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.trim_end_matches(['\r', '\n']).split('\t').collect();
+            let [domain, include_subdomains, path, secure, expires, name, value] = fields[..]
+            else {
+                return Err(crate::Error::InvalidCommand(format!(
+                    "cookies.txt line {}: expected 7 tab-separated fields, found {}",
+                    number + 1,
+                    fields.len()
+                )));
+            };
+            let expires: i64 = expires.parse().map_err(|_| {
+                crate::Error::InvalidCommand(format!(
+                    "cookies.txt line {}: invalid expiry '{}'",
+                    number + 1,
+                    expires
+                ))
+            })?;
+
+            let mut cookie = Cookie::new(name.to_string(), value.to_string())
+                .with_domain(domain.trim_start_matches('.').to_lowercase())
+                .with_path(path.to_string());
+            cookie.host_only = include_subdomains.eq_ignore_ascii_case("FALSE");
+            cookie.secure = secure.eq_ignore_ascii_case("TRUE");
+            cookie.http_only = http_only;
+            if expires > 0 {
+                cookie.expires = DateTime::from_timestamp(expires, 0);
+            }
+
+            if !cookie.is_expired() {
+                self.add(cookie);
+                imported += 1;
+            }
+        }
+        Ok(imported)
     }
 
     /// Clear all cookies
@@ -222,6 +421,65 @@ impl CookieJar {
     }
 }
 
+/// RFC 6265 domain-match: the host equals the domain or is a subdomain of it
+///
+/// IP addresses only match exactly.
+fn domain_match(host: &str, domain: &str) -> bool {
+    let unbracket = |s: &str| {
+        s.trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_lowercase()
+    };
+    let host = unbracket(host.trim_end_matches('.'));
+    let domain = unbracket(domain.trim_start_matches('.'));
+    if host == domain {
+        return true;
+    }
+    let is_ip = host.parse::<std::net::IpAddr>().is_ok();
+    !is_ip && host.ends_with(&domain) && host[..host.len() - domain.len()].ends_with('.')
+}
+
+/// RFC 6265 path-match: equal, or a prefix ending at a '/' boundary
+fn path_match(request_path: &str, cookie_path: &str) -> bool {
+    let request_path = if request_path.is_empty() {
+        "/"
+    } else {
+        request_path
+    };
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/')
+                || request_path.as_bytes().get(cookie_path.len()) == Some(&b'/')))
+}
+
+/// RFC 6265 default-path: the request path up to (not including) its last '/'
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => request_path[..index].to_string(),
+    }
+}
+
+/// Parse an HTTP date (RFC 1123, or the legacy dashed form)
+fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    ["%a, %d-%b-%Y %H:%M:%S GMT", "%a, %d-%b-%y %H:%M:%S GMT"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|date| date.and_utc())
+}
+
+/// The latest expiry kept, for cookies that never expire in practice
+fn far_future() -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(9999, 12, 31)
+        .and_then(|date| date.and_hms_opt(23, 59, 59))
+        .map(|time| time.and_utc())
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,7 +556,7 @@ mod tests {
         jar.add(Cookie::new("session".to_string(), "abc123".to_string()));
         jar.add(Cookie::new("user".to_string(), "john".to_string()));
 
-        let header = jar.cookie_header("example.com").unwrap();
+        let header = jar.cookie_header("https://example.com/").unwrap();
         assert!(header.contains("session=abc123"));
         assert!(header.contains("user=john"));
     }
@@ -312,4 +570,293 @@ mod tests {
         jar.remove("test");
         assert_eq!(jar.count(), 0);
     }
+
+    #[test]
+    fn test_cookie_from_header_value_with_equals() {
+        let cookie = Cookie::from_header("token=abc==; Path=/api").unwrap();
+        assert_eq!(cookie.value, "abc==");
+        assert_eq!(cookie.path.as_deref(), Some("/api"));
+    }
+
+    #[test]
+    fn test_cookie_from_header_expiry() {
+        let cookie = Cookie::from_header("a=1; Max-Age=3600").unwrap();
+        assert!(!cookie.is_expired());
+        assert!(cookie.expires.unwrap() > Utc::now());
+
+        let cookie = Cookie::from_header("a=1; Max-Age=0").unwrap();
+        assert!(cookie.is_expired());
+
+        // Out-of-range Max-Age is clamped instead of overflowing
+        let cookie = Cookie::from_header("a=1; Max-Age=9223372036854775807").unwrap();
+        assert!(!cookie.is_expired());
+        assert_eq!(cookie.expires, Some(far_future()));
+
+        let cookie = Cookie::from_header("a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert!(cookie.is_expired());
+        assert_eq!(
+            cookie.expires.unwrap().to_rfc3339(),
+            "2015-10-21T07:28:00+00:00"
+        );
+
+        let cookie = Cookie::from_header("a=1; Expires=Wed, 21-Oct-2099 07:28:00 GMT").unwrap();
+        assert!(!cookie.is_expired());
+
+        // Max-Age wins over Expires
+        let cookie =
+            Cookie::from_header("a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Max-Age=60").unwrap();
+        assert!(!cookie.is_expired());
+    }
+
+    #[test]
+    fn test_domain_match() {
+        let cases = [
+            ("example.com", "example.com", true),
+            ("api.example.com", "example.com", true),
+            ("a.b.example.com", ".example.com", true),
+            ("EXAMPLE.com", "example.COM", true),
+            ("notexample.com", "example.com", false),
+            ("example.com.evil.com", "example.com", false),
+            ("example.com", "api.example.com", false),
+            ("127.0.0.1", "0.0.1", false),
+            ("127.0.0.1", "127.0.0.1", true),
+            ("::1", "[::1]", true),
+            ("API.Example.com", "example.com", true),
+        ];
+        for (host, domain, expected) in cases {
+            assert_eq!(
+                domain_match(host, domain),
+                expected,
+                "{} vs {}",
+                host,
+                domain
+            );
+        }
+    }
+
+    #[test]
+    fn test_path_match() {
+        let cases = [
+            ("/", "/", true),
+            ("/api", "/", true),
+            ("/api", "/api", true),
+            ("/api/users", "/api", true),
+            ("/api/users", "/api/", true),
+            ("/apiv2", "/api", false),
+            ("/ap", "/api", false),
+            ("/", "/api", false),
+            ("", "/", true),
+        ];
+        for (request, cookie, expected) in cases {
+            assert_eq!(
+                path_match(request, cookie),
+                expected,
+                "{} vs {}",
+                request,
+                cookie
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_path() {
+        assert_eq!(default_path(""), "/");
+        assert_eq!(default_path("/"), "/");
+        assert_eq!(default_path("/login"), "/");
+        assert_eq!(default_path("/api/login"), "/api");
+        assert_eq!(default_path("/api/v1/"), "/api/v1");
+    }
+
+    fn set_cookie(value: &str) -> Vec<(String, String)> {
+        vec![("Set-Cookie".to_string(), value.to_string())]
+    }
+
+    #[test]
+    fn test_cookie_jar_url_matching() {
+        // (Set-Cookie, response URL, request URL, sent?)
+        let cases = [
+            ("a=1", "https://example.com/", "https://example.com/x", true),
+            (
+                "a=1",
+                "https://example.com/",
+                "https://api.example.com/",
+                false,
+            ),
+            (
+                "a=1; Domain=example.com",
+                "https://example.com/",
+                "https://api.example.com/",
+                true,
+            ),
+            (
+                "a=1; Domain=.example.com",
+                "https://www.example.com/",
+                "https://example.com/",
+                true,
+            ),
+            (
+                "a=1; Domain=example.com",
+                "https://example.com/",
+                "https://notexample.com/",
+                false,
+            ),
+            (
+                "a=1; Domain=example.com",
+                "https://example.com/",
+                "https://example.com.evil.com/",
+                false,
+            ),
+            (
+                "a=1; Domain=evil.com",
+                "https://example.com/",
+                "https://evil.com/",
+                false,
+            ),
+            (
+                "a=1; Path=/api",
+                "https://example.com/",
+                "https://example.com/api/users",
+                true,
+            ),
+            (
+                "a=1; Path=/api",
+                "https://example.com/",
+                "https://example.com/apiv2",
+                false,
+            ),
+            (
+                "a=1",
+                "https://example.com/api/login",
+                "https://example.com/api/me",
+                true,
+            ),
+            (
+                "a=1",
+                "https://example.com/api/login",
+                "https://example.com/other",
+                false,
+            ),
+            (
+                "a=1; Secure",
+                "https://example.com/",
+                "https://example.com/",
+                true,
+            ),
+            (
+                "a=1; Secure",
+                "https://example.com/",
+                "http://example.com/",
+                false,
+            ),
+            (
+                "a=1; Max-Age=0",
+                "https://example.com/",
+                "https://example.com/",
+                false,
+            ),
+            (
+                "a=1",
+                "http://[::1]:8080/",
+                "http://[::1]:8080/health",
+                true,
+            ),
+            ("a=1", "http://[::1]:8080/", "http://[::1]:9090/", true),
+            ("a=1", "http://[::1]:8080/", "http://[::2]:8080/", false),
+            ("a=1", "http://[::1]/", "http://127.0.0.1/", false),
+            (
+                "a=1",
+                "http://Example.COM:8080/",
+                "http://example.com/",
+                true,
+            ),
+            (
+                "a=1; Domain=Example.com",
+                "http://API.example.com/",
+                "http://www.EXAMPLE.com/",
+                true,
+            ),
+        ];
+
+        for (header, origin, request, expected) in cases {
+            let mut jar = CookieJar::new();
+            jar.add_from_headers(origin, &set_cookie(header));
+            assert_eq!(
+                jar.cookie_header(request).is_some(),
+                expected,
+                "'{}' from {} sent to {}",
+                header,
+                origin,
+                request
+            );
+        }
+    }
+
+    #[test]
+    fn test_cookie_jar_expired_set_cookie_deletes() {
+        let mut jar = CookieJar::new();
+        jar.add_from_headers("https://example.com/", &set_cookie("session=abc"));
+        assert_eq!(jar.count(), 1);
+
+        jar.add_from_headers("https://example.com/", &set_cookie("session=; Max-Age=0"));
+        assert_eq!(jar.count(), 0);
+    }
+
+    #[test]
+    fn test_cookie_header_orders_longest_path_first() {
+        let mut jar = CookieJar::new();
+        jar.add_from_headers("https://example.com/", &set_cookie("a=root; Path=/"));
+        jar.add_from_headers("https://example.com/", &set_cookie("a=api; Path=/api"));
+
+        assert_eq!(jar.count(), 2);
+        assert_eq!(
+            jar.cookie_header("https://example.com/api/x").unwrap(),
+            "a=api; a=root"
+        );
+    }
+
+    #[test]
+    fn test_netscape_round_trip() {
+        let mut jar = CookieJar::new();
+        jar.add_from_headers(
+            "https://api.example.com/v1/users",
+            &set_cookie("session=abc; HttpOnly; Secure"),
+        );
+        jar.add_from_headers(
+            "https://api.example.com/",
+            &set_cookie("theme=dark; Domain=example.com; Path=/; Max-Age=3600"),
+        );
+
+        let exported = jar.to_netscape();
+        assert!(exported.starts_with("# Netscape HTTP Cookie File\n"));
+        assert!(exported.contains("#HttpOnly_api.example.com\tFALSE\t/v1\tTRUE\t0\tsession\tabc\n"));
+        assert!(exported.contains(".example.com\tTRUE\t/\tFALSE\t"));
+
+        let mut imported = CookieJar::new();
+        assert_eq!(imported.import_netscape(&exported).unwrap(), 2);
+        let session = imported.get("session").unwrap();
+        assert!(session.host_only && session.http_only && session.secure);
+        assert_eq!(session.domain.as_deref(), Some("api.example.com"));
+        let theme = imported.get("theme").unwrap();
+        assert!(!theme.host_only);
+        assert!(theme.expires.is_some());
+
+        assert_eq!(
+            imported.cookie_header("https://www.example.com/").unwrap(),
+            "theme=dark"
+        );
+    }
+
+    #[test]
+    fn test_netscape_import_skips_expired_and_rejects_malformed() {
+        let mut jar = CookieJar::new();
+        let content = "# comment\n\nexample.com\tFALSE\t/\tFALSE\t1\told\tx\n\
+                       example.com\tFALSE\t/\tFALSE\t0\tempty\t\n";
+        assert_eq!(jar.import_netscape(content).unwrap(), 1);
+        assert_eq!(jar.get("empty").unwrap().value, "");
+
+        assert!(jar.import_netscape("example.com\tFALSE\t/\n").is_err());
+        assert!(jar
+            .import_netscape("example.com\tFALSE\t/\tFALSE\tsoon\ta\tb\n")
+            .is_err());
+    }
 }
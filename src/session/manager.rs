@@ -1,8 +1,9 @@
 //! Session manager for handling multiple sessions
 
-use crate::session::Session;
+use crate::session::{CookieJar, Session};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
 /// Manager for multiple sessions
@@ -69,6 +70,11 @@ impl SessionManager {
         self.sessions.values().find(|s| s.name == name)
     }
 
+    /// Get mutable reference to a session by name
+    pub fn get_session_by_name_mut(&mut self, name: &str) -> Option<&mut Session> {
+        self.sessions.values_mut().find(|s| s.name == name)
+    }
+
     /// List all sessions
     pub fn list_sessions(&self) -> Vec<&Session> {
         self.sessions.values().collect()
@@ -105,6 +111,27 @@ impl SessionManager {
             .and_then(|id| self.sessions.get_mut(&id))
     }
 
+    /// Copy of the active session's cookies, shared so a client can update it
+    pub fn active_cookie_jar(&self) -> Option<Arc<Mutex<CookieJar>>> {
+        self.get_active_session()
+            .map(|session| Arc::new(Mutex::new(session.cookies.clone())))
+    }
+
+    /// Store cookies in the active session and save it
+    ///
+    /// Returns `false` when no session is active.
+    pub fn save_active_cookies(&mut self, jar: &CookieJar) -> crate::Result<bool> {
+        let Some(session) = self.get_active_session_mut() else {
+            return Ok(false);
+        };
+        session.cookies = jar.clone();
+        session.cookies.remove_expired();
+        session.touch();
+        let id = session.id;
+        self.save_session(&id)?;
+        Ok(true)
+    }
+
     /// Create a new session and optionally activate it
     pub fn create_session(&mut self, name: String, activate: bool) -> Uuid {
         let session = Session::new(name);
@@ -126,10 +153,7 @@ impl SessionManager {
             let path = self.storage_path.join(filename);
             session.save_to_file(&path)
         } else {
-            Err(crate::Error::InvalidCommand(format!(
-                "Session {} not found",
-                id
-            )))
+            Err(crate::Error::SessionNotFound(*id))
         }
     }
 
@@ -265,6 +289,30 @@ mod tests {
         assert_eq!(manager.count(), 0);
     }
 
+    #[test]
+    fn test_active_cookies_persist() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = SessionManager::new(temp_dir.path().to_path_buf()).unwrap();
+        assert!(manager.active_cookie_jar().is_none());
+        assert!(!manager.save_active_cookies(&CookieJar::new()).unwrap());
+
+        manager.create_session("staging".to_string(), true);
+        let jar = manager.active_cookie_jar().unwrap();
+        jar.lock().unwrap().add_from_headers(
+            "https://example.com/",
+            &[("Set-Cookie".to_string(), "session=abc".to_string())],
+        );
+        assert!(manager.save_active_cookies(&jar.lock().unwrap()).unwrap());
+
+        let mut reloaded = SessionManager::new(temp_dir.path().to_path_buf()).unwrap();
+        reloaded.load_all().unwrap();
+        let jar = reloaded.active_cookie_jar().unwrap();
+        assert_eq!(
+            jar.lock().unwrap().cookie_header("https://example.com/"),
+            Some("session=abc".to_string())
+        );
+    }
+
     #[test]
     fn test_get_by_name() {
         let temp_dir = TempDir::new().unwrap();
@@ -277,4 +325,16 @@ mod tests {
         assert!(found.is_some());
         assert_eq!(found.unwrap().name, "Session 1");
     }
+
+    #[test]
+    fn test_save_unknown_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = SessionManager::new(temp_dir.path().to_path_buf()).unwrap();
+
+        let id = Uuid::new_v4();
+        match manager.save_session(&id) {
+            Err(crate::Error::SessionNotFound(missing)) => assert_eq!(missing, id),
+            other => panic!("expected SessionNotFound, got {:?}", other),
+        }
+    }
 }
@@ -108,10 +108,11 @@ impl Session {
         Ok(())
     }
 
-    /// Load session from file
+    /// Load session from file, dropping cookies that expired since it was saved
     pub fn load_from_file(path: &Path) -> crate::Result<Self> {
         let content = std::fs::read_to_string(path)?;
-        let session = serde_json::from_str(&content)?;
+        let mut session: Self = serde_json::from_str(&content)?;
+        session.cookies.remove_expired();
         Ok(session)
     }
 
@@ -129,6 +130,7 @@ impl Session {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::session::Cookie;
     use tempfile::TempDir;
 
     #[test]
@@ -192,6 +194,26 @@ mod tests {
         assert_eq!(loaded.get_variable("key"), Some(&"value".to_string()));
     }
 
+    #[test]
+    fn test_load_purges_expired_cookies() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("session.json");
+
+        let mut session = Session::new("Test".to_string());
+        session
+            .cookies
+            .add(Cookie::new("live".to_string(), "1".to_string()));
+        session.cookies.add(
+            Cookie::new("stale".to_string(), "1".to_string())
+                .with_expires(Utc::now() - chrono::Duration::hours(1)),
+        );
+        session.save_to_file(&file_path).unwrap();
+
+        let loaded = Session::load_from_file(&file_path).unwrap();
+        assert_eq!(loaded.cookies.count(), 1);
+        assert!(loaded.cookies.get("live").is_some());
+    }
+
     #[test]
     fn test_clear_all() {
         let mut session = Session::new("Test".to_string());
@@ -0,0 +1,52 @@
+//! Terminal styling that falls back to plain text without the `ui-color` feature
+
+#[cfg(feature = "ui-color")]
+pub(crate) use colored::Colorize;
+
+#[cfg(not(feature = "ui-color"))]
+pub(crate) use plain::Colorize;
+
+#[cfg(not(feature = "ui-color"))]
+mod plain {
+    /// The subset of `colored::Colorize` used by library output, as no-ops
+    #[allow(dead_code)] // some methods are only used by optional modules
+    pub(crate) trait Colorize {
+        fn bold(&self) -> String;
+        fn dimmed(&self) -> String;
+        fn red(&self) -> String;
+        fn green(&self) -> String;
+        fn blue(&self) -> String;
+        fn cyan(&self) -> String;
+        fn color(&self, color: &str) -> String;
+    }
+
+    impl Colorize for str {
+        fn bold(&self) -> String {
+            self.to_string()
+        }
+
+        fn dimmed(&self) -> String {
+            self.to_string()
+        }
+
+        fn red(&self) -> String {
+            self.to_string()
+        }
+
+        fn green(&self) -> String {
+            self.to_string()
+        }
+
+        fn blue(&self) -> String {
+            self.to_string()
+        }
+
+        fn cyan(&self) -> String {
+            self.to_string()
+        }
+
+        fn color(&self, _color: &str) -> String {
+            self.to_string()
+        }
+    }
+}
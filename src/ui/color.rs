@@ -0,0 +1,63 @@
+//! Terminal color detection shared by all output
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+
+/// When to emit ANSI colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal (or `CLICOLOR_FORCE` is set) and
+    /// `NO_COLOR` is not set
+    #[default]
+    Auto,
+
+    /// Always color, even when redirected
+    Always,
+
+    /// Never color
+    Never,
+}
+
+/// Decide whether to color output
+///
+/// A non-empty `NO_COLOR` disables color in auto mode; an explicit choice wins.
+pub fn color_enabled(choice: ColorChoice, no_color: Option<&str>, is_terminal: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => no_color.is_none_or(str::is_empty) && is_terminal,
+    }
+}
+
+/// Apply a color choice to every module that prints colored text
+///
+/// `CLICOLOR_FORCE` (set, and not `0`) treats redirected output like a
+/// terminal; `NO_COLOR` still turns color off.
+pub fn init(choice: ColorChoice) {
+    let no_color = std::env::var("NO_COLOR").ok();
+    let force = std::env::var("CLICOLOR_FORCE").is_ok_and(|v| !v.is_empty() && v != "0");
+    let is_terminal = force || std::io::stdout().is_terminal();
+    let enabled = color_enabled(choice, no_color.as_deref(), is_terminal);
+    colored::control::set_override(enabled);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_enabled_auto() {
+        assert!(color_enabled(ColorChoice::Auto, None, true));
+        assert!(!color_enabled(ColorChoice::Auto, None, false));
+        assert!(!color_enabled(ColorChoice::Auto, Some("1"), true));
+        assert!(color_enabled(ColorChoice::Auto, Some(""), true));
+    }
+
+    #[test]
+    fn test_color_enabled_explicit_choice_wins() {
+        assert!(color_enabled(ColorChoice::Always, Some("1"), false));
+        assert!(!color_enabled(ColorChoice::Never, None, true));
+    }
+}
@@ -1,5 +1,6 @@
 //! Help text display
 
+use crate::ui::topics::{HelpTopic, EXAMPLES, TOPICS};
 use colored::*;
 
 /// Help text display utilities
@@ -64,6 +65,40 @@ impl Help {
             "-q".yellow()
         );
         println!();
+        println!("{}", "Saved Requests:".bright_white().bold());
+        println!();
+        println!(
+            "  {} <collection>/<request> [{} <environment>]",
+            "run".green().bold(),
+            "--env".yellow()
+        );
+        println!("    Press Tab to complete commands, saved requests, and environments");
+        println!();
+        println!("{}", "Environments & Sessions:".bright_white().bold());
+        println!();
+        println!("  {} <env|session> <name>", "use".green().bold());
+        println!("    Fill {{{{variables}}}} from an environment, or send a session's cookies");
+        println!();
+        println!("{}", "Comparing Responses:".bright_white().bold());
+        println!();
+        println!(
+            "  {} [<last|prev|history id> <last|prev|history id>] [--all-headers]",
+            "diff".green().bold()
+        );
+        println!("    Without arguments, compares the previous response with the last one");
+        println!("    Date, X-Request-Id and similar headers are skipped unless --all-headers");
+        println!();
+        println!("{}", "History:".bright_white().bold());
+        println!();
+        println!(
+            "  {} <history id> [{}]",
+            "replay".green().bold(),
+            "--resubstitute".yellow()
+        );
+        println!(
+            "    Send a past request again as stored, or with {{{{variables}}}} filled in again"
+        );
+        println!();
         println!("{}", "Built-in Commands:".bright_white().bold());
         println!("  {}      - Show this help message", "help".cyan());
         println!("  {}   - Show version and info", "version".cyan());
@@ -85,4 +120,47 @@ impl Help {
         );
         println!();
     }
+
+    /// Show a focused help topic
+    pub fn show_topic(topic: &HelpTopic) {
+        println!();
+        println!("{}", topic.title.bright_white().bold());
+        println!("{}", topic.summary.bright_black());
+        for section in topic.sections {
+            println!();
+            println!("{}", section.heading.cyan().bold());
+            println!("  {}", section.text);
+            for example in section.examples {
+                println!();
+                println!("  {}", example.description.bright_black());
+                println!("  {} {}", "→".bright_black(), example.command);
+            }
+        }
+        println!();
+    }
+
+    /// List the available help topics
+    pub fn show_topics() {
+        println!("{}", "Help topics:".bright_white().bold());
+        for topic in TOPICS {
+            println!("  {:<12} {}", topic.name.cyan(), topic.summary);
+        }
+        println!();
+    }
+
+    /// Show one example per command
+    pub fn show_examples() {
+        println!();
+        println!("{}", "Examples:".bright_white().bold());
+        for entry in EXAMPLES {
+            println!();
+            println!(
+                "  {:<24} {}",
+                entry.subcommand.cyan(),
+                entry.example.description.bright_black()
+            );
+            println!("  {} {}", "→".bright_black(), entry.example.command);
+        }
+        println!();
+    }
 }
@@ -1,7 +1,11 @@
 //! User interface components
 
 pub mod banner;
+pub mod color;
 pub mod help;
+pub mod topics;
 
 pub use banner::Banner;
+pub use color::ColorChoice;
 pub use help::Help;
+pub use topics::{find_topic, HelpTopic, TOPICS};
@@ -0,0 +1,562 @@
+//! Help topics and example commands as structured data
+//!
+//! The CLI `help <topic>`, the REPL `help <topic>`, and the `examples` command
+//! all render from these tables.
+
+/// A runnable example
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    /// What the example does
+    pub description: &'static str,
+
+    /// Command line to copy
+    pub command: &'static str,
+}
+
+/// A section of a help topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section {
+    /// Section heading
+    pub heading: &'static str,
+
+    /// Explanatory text
+    pub text: &'static str,
+
+    /// Examples for this section
+    pub examples: &'static [Example],
+}
+
+/// A focused help page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HelpTopic {
+    /// Name used in `help <name>`
+    pub name: &'static str,
+
+    /// Page title
+    pub title: &'static str,
+
+    /// One-line summary shown in topic lists
+    pub summary: &'static str,
+
+    /// Page sections
+    pub sections: &'static [Section],
+}
+
+/// An example for one CLI subcommand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandExample {
+    /// Subcommand path (e.g. "auth add")
+    pub subcommand: &'static str,
+
+    /// The example itself
+    pub example: Example,
+}
+
+/// All registered help topics
+pub const TOPICS: &[HelpTopic] = &[
+    HelpTopic {
+        name: "auth",
+        title: "Authentication",
+        summary: "Save auth profiles and apply them to requests",
+        sections: &[
+            Section {
+                heading: "Profiles",
+                text: "Credentials are saved once as named profiles and applied with --auth.",
+                examples: &[
+                    Example {
+                        description: "Save a bearer token",
+                        command: "bazzounquester auth add github --bearer ghp_xxx",
+                    },
+                    Example {
+                        description: "Save basic credentials",
+                        command: "bazzounquester auth add staging --basic admin:secret",
+                    },
+                    Example {
+                        description: "Save an API key sent as a query parameter",
+                        command: "bazzounquester auth add maps --apikey key:abc123 --in query",
+                    },
+                    Example {
+                        description: "Use a profile",
+                        command: "bazzounquester get https://api.github.com/user --auth github",
+                    },
+                ],
+            },
+            Section {
+                heading: "Signed tokens",
+                text: "JWT profiles sign a fresh token per request with the key given by --key.",
+                examples: &[Example {
+                    description: "Sign RS256 tokens for a service account",
+                    command: "bazzounquester auth add svc --jwt --key svc.pem --iss ci --aud api",
+                }],
+            },
+            Section {
+                heading: "OAuth 2.0",
+                text: "The authorization code flow opens a browser and caches the token.",
+                examples: &[Example {
+                    description: "Log in with an OAuth2 profile",
+                    command: "bazzounquester auth login google",
+                }],
+            },
+        ],
+    },
+    HelpTopic {
+        name: "assertions",
+        title: "Assertions",
+        summary: "Check status, headers, timing, and body values",
+        sections: &[
+            Section {
+                heading: "Expressions",
+                text:
+                    "An assertion is '<target> <operator> <value>'. Targets: status, time, body, \
+                       size, content_type, $.json.path, header <name>, cookie <name>, \
+                       server_timing <metric>. Operators: ==, !=, ~= (contains), !~ (does not \
+                       contain), >=, <=, >, <. Comparisons also order RFC 3339 timestamps; \
+                       'now' is the current time. 'valid json' and 'valid xml' check the body parses.",
+                examples: &[
+                    Example {
+                        description: "Status and JSON field",
+                        command: "status == 200, $.user.id >= 1",
+                    },
+                    Example {
+                        description: "Header and response time",
+                        command: "header content-type ~= json, time < 500",
+                    },
+                    Example {
+                        description: "Small, well-formed JSON",
+                        command: "content_type == application/json, size < 4096, valid json",
+                    },
+                    Example {
+                        description: "Token has not expired",
+                        command: "$.expires_at > now",
+                    },
+                ],
+            },
+            Section {
+                heading: "Running assertions",
+                text: "Request commands take --assert-* flags and exit 1 when a check fails \
+                       (2 when the request itself fails). exec-stream reads one JSON request \
+                       spec per line with an 'assert' list.",
+                examples: &[
+                    Example {
+                        description: "One-shot CI check",
+                        command: "bazzounquester get https://httpbin.org/json --assert-status 200 --assert-header 'content-type~=json' --assert-time-lt 800",
+                    },
+                    Example {
+                        description: "Check the body with a Rhai expression",
+                        command: "bazzounquester get https://httpbin.org/json --assert-expr 'json.slideshow.slides.len() > 1'",
+                    },
+                    Example {
+                        description: "Compare with a saved snapshot, ignoring volatile fields",
+                        command: "bazzounquester get https://httpbin.org/uuid --snapshot uuid --snapshot-ignore '$.uuid'",
+                    },
+                    Example {
+                        description: "Assert on a streamed request",
+                        command: r#"echo '{"url":"https://httpbin.org/get","assert":["status == 200"]}' | bazzounquester exec-stream"#,
+                    },
+                ],
+            },
+        ],
+    },
+    HelpTopic {
+        name: "variables",
+        title: "Variables",
+        summary: "Substitute {{NAME}} placeholders in workflows and collections",
+        sections: &[
+            Section {
+                heading: "Placeholders",
+                text: "URLs, headers, query parameters, and bodies may contain {{NAME}}. \
+                       Unknown names are left as-is.",
+                examples: &[Example {
+                    description: "A workflow step URL using a variable",
+                    command: "https://{{HOST}}/users/{{USER_ID}}",
+                }],
+            },
+            Section {
+                heading: "Workflow variables",
+                text: "A chain's 'variables' map seeds every step; 'extract_variables' stores \
+                       JSON path values from a response for later steps.",
+                examples: &[Example {
+                    description: "Run a workflow that logs in and reuses the token",
+                    command: "bazzounquester workflow run login.yaml",
+                }],
+            },
+        ],
+    },
+];
+
+/// Copy-pasteable one-liners, at least one per CLI subcommand
+pub const EXAMPLES: &[CommandExample] = &[
+    CommandExample {
+        subcommand: "interactive",
+        example: Example {
+            description: "Start the REPL",
+            command: "bazzounquester interactive",
+        },
+    },
+    CommandExample {
+        subcommand: "get",
+        example: Example {
+            description: "GET with a query parameter",
+            command: "bazzounquester get https://httpbin.org/get -q page=2",
+        },
+    },
+    CommandExample {
+        subcommand: "post",
+        example: Example {
+            description: "POST a JSON body",
+            command: r#"bazzounquester post https://httpbin.org/post -b '{"name":"Ada"}'"#,
+        },
+    },
+    CommandExample {
+        subcommand: "put",
+        example: Example {
+            description: "Replace a resource",
+            command: r#"bazzounquester put https://httpbin.org/put -b '{"status":"active"}'"#,
+        },
+    },
+    CommandExample {
+        subcommand: "load",
+        example: Example {
+            description: "Send 1000 requests, 20 at a time",
+            command: "bazzounquester load https://httpbin.org/get --requests 1000 --concurrency 20",
+        },
+    },
+    CommandExample {
+        subcommand: "graphql",
+        example: Example {
+            description: "Run a GraphQL query with variables",
+            command: r#"bazzounquester graphql https://api.example.com/graphql --query-file user.graphql --variables '{"id":"42"}'"#,
+        },
+    },
+    CommandExample {
+        subcommand: "patch",
+        example: Example {
+            description: "Update one field",
+            command: r#"bazzounquester patch https://httpbin.org/patch -b '{"email":"a@b.c"}'"#,
+        },
+    },
+    CommandExample {
+        subcommand: "delete",
+        example: Example {
+            description: "Delete with a bearer token",
+            command: r#"bazzounquester delete https://httpbin.org/delete -H "Authorization:Bearer TOKEN""#,
+        },
+    },
+    CommandExample {
+        subcommand: "exec-stream",
+        example: Example {
+            description: "Run request specs from a file, four at a time",
+            command: "bazzounquester exec-stream --concurrency 4 < requests.jsonl",
+        },
+    },
+    CommandExample {
+        subcommand: "auth add",
+        example: Example {
+            description: "Save a bearer token profile",
+            command: "bazzounquester auth add github --bearer ghp_xxx",
+        },
+    },
+    CommandExample {
+        subcommand: "auth list",
+        example: Example {
+            description: "List saved profiles",
+            command: "bazzounquester auth list",
+        },
+    },
+    CommandExample {
+        subcommand: "auth remove",
+        example: Example {
+            description: "Delete a profile",
+            command: "bazzounquester auth remove github",
+        },
+    },
+    CommandExample {
+        subcommand: "auth login",
+        example: Example {
+            description: "OAuth2 login without opening a browser",
+            command: "bazzounquester auth login google --no-browser",
+        },
+    },
+    CommandExample {
+        subcommand: "collection run",
+        example: Example {
+            description: "Run a saved collection with conditional GET caching",
+            command: "bazzounquester collection run nightly --use-cache",
+        },
+    },
+    CommandExample {
+        subcommand: "collection check",
+        example: Example {
+            description: "Catch variables the staging environment is missing",
+            command: "bazzounquester collection check api --env staging",
+        },
+    },
+    CommandExample {
+        subcommand: "collection search",
+        example: Example {
+            description: "Find saved requests by name, URL, or tag",
+            command: "bazzounquester collection search login",
+        },
+    },
+    CommandExample {
+        subcommand: "collection cache clear",
+        example: Example {
+            description: "Drop cached collection responses",
+            command: "bazzounquester collection cache clear",
+        },
+    },
+    CommandExample {
+        subcommand: "workflow run",
+        example: Example {
+            description: "Run a chain and write JUnit and JSON reports",
+            command: "bazzounquester workflow run smoke.yaml --report junit=report.xml --report json=report.json",
+        },
+    },
+    CommandExample {
+        subcommand: "workflow validate",
+        example: Example {
+            description: "Check a workflow's variables against an environment",
+            command: "bazzounquester workflow validate smoke.yaml --env dev --var password=secret",
+        },
+    },
+    CommandExample {
+        subcommand: "history list",
+        example: Example {
+            description: "Show today's failed POSTs",
+            command: "bazzounquester history list --method POST --status 500 --since 24h",
+        },
+    },
+    CommandExample {
+        subcommand: "history show",
+        example: Example {
+            description: "Show a past request and its response",
+            command: "bazzounquester history show 3f2a9c1e",
+        },
+    },
+    CommandExample {
+        subcommand: "history replay",
+        example: Example {
+            description: "Send a past request again",
+            command: "bazzounquester history replay 3f2a9c1e",
+        },
+    },
+    CommandExample {
+        subcommand: "history delete",
+        example: Example {
+            description: "Delete one entry",
+            command: "bazzounquester history delete 3f2a9c1e",
+        },
+    },
+    CommandExample {
+        subcommand: "history clear",
+        example: Example {
+            description: "Delete entries older than a month",
+            command: "bazzounquester history clear --older-than 30d",
+        },
+    },
+    CommandExample {
+        subcommand: "history tag",
+        example: Example {
+            description: "Mark an entry for follow-up",
+            command: "bazzounquester history tag 3f2a9c1e regression",
+        },
+    },
+    CommandExample {
+        subcommand: "history untag",
+        example: Example {
+            description: "Remove a tag from an entry",
+            command: "bazzounquester history untag 3f2a9c1e regression",
+        },
+    },
+    CommandExample {
+        subcommand: "history note",
+        example: Example {
+            description: "Record why an entry matters",
+            command: r#"bazzounquester history note 3f2a9c1e "500 only with an empty cart""#,
+        },
+    },
+    CommandExample {
+        subcommand: "history name",
+        example: Example {
+            description: "Label a history entry by its short ID",
+            command: r#"bazzounquester history name 3f2a9c1e "Create test user""#,
+        },
+    },
+    CommandExample {
+        subcommand: "history export",
+        example: Example {
+            description: "Export the last day's failures for a spreadsheet",
+            command: "bazzounquester history export failures.csv --format csv --failed --since 24h",
+        },
+    },
+    CommandExample {
+        subcommand: "history diff",
+        example: Example {
+            description: "See what changed between two responses",
+            command: "bazzounquester history diff 3f2a9c1e 7b41d0a2",
+        },
+    },
+    CommandExample {
+        subcommand: "history stats",
+        example: Example {
+            description: "Latency percentiles per endpoint over the last day",
+            command: "bazzounquester history stats --group-by path --since 24h",
+        },
+    },
+    CommandExample {
+        subcommand: "history migrate",
+        example: Example {
+            description: "Switch history to the faster single-file format",
+            command: "bazzounquester history migrate",
+        },
+    },
+    CommandExample {
+        subcommand: "session create",
+        example: Example {
+            description: "Start a session that keeps cookies between commands",
+            command: "bazzounquester session create staging",
+        },
+    },
+    CommandExample {
+        subcommand: "session list",
+        example: Example {
+            description: "List sessions and their cookie counts",
+            command: "bazzounquester session list",
+        },
+    },
+    CommandExample {
+        subcommand: "session use",
+        example: Example {
+            description: "Switch to another session",
+            command: "bazzounquester session use production",
+        },
+    },
+    CommandExample {
+        subcommand: "session delete",
+        example: Example {
+            description: "Delete a session",
+            command: "bazzounquester session delete staging",
+        },
+    },
+    CommandExample {
+        subcommand: "session cookies",
+        example: Example {
+            description: "Show the active session's cookies",
+            command: "bazzounquester session cookies",
+        },
+    },
+    CommandExample {
+        subcommand: "session clear-cookies",
+        example: Example {
+            description: "Log out by dropping all cookies",
+            command: "bazzounquester session clear-cookies",
+        },
+    },
+    CommandExample {
+        subcommand: "session import-cookies",
+        example: Example {
+            description: "Reuse cookies saved by curl -c",
+            command: "bazzounquester session import-cookies cookies.txt",
+        },
+    },
+    CommandExample {
+        subcommand: "session export-cookies",
+        example: Example {
+            description: "Write cookies for curl -b",
+            command: "bazzounquester session export-cookies cookies.txt",
+        },
+    },
+    CommandExample {
+        subcommand: "matrix",
+        example: Example {
+            description: "Check a health endpoint in every environment",
+            command: "bazzounquester matrix --request \"API/Health\" --envs dev,staging,prod --extract '$.version'",
+        },
+    },
+    CommandExample {
+        subcommand: "config show",
+        example: Example {
+            description: "Show the configured defaults",
+            command: "bazzounquester config show",
+        },
+    },
+    CommandExample {
+        subcommand: "config set",
+        example: Example {
+            description: "Time out every request after 30 seconds unless --timeout says otherwise",
+            command: "bazzounquester config set timeout 30s",
+        },
+    },
+    CommandExample {
+        subcommand: "help",
+        example: Example {
+            description: "Show the authentication topic",
+            command: "bazzounquester help auth",
+        },
+    },
+    CommandExample {
+        subcommand: "examples",
+        example: Example {
+            description: "List example commands",
+            command: "bazzounquester examples",
+        },
+    },
+];
+
+/// Find a help topic by name (case-insensitive)
+pub fn find_topic(name: &str) -> Option<&'static HelpTopic> {
+    TOPICS.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::CommandFactory;
+
+    /// Collect the paths of all leaf subcommands (e.g. "auth add")
+    fn leaf_subcommands(command: &clap::Command, prefix: &str, out: &mut Vec<String>) {
+        for sub in command.get_subcommands() {
+            let path = if prefix.is_empty() {
+                sub.get_name().to_string()
+            } else {
+                format!("{} {}", prefix, sub.get_name())
+            };
+            if sub.has_subcommands() {
+                leaf_subcommands(sub, &path, out);
+            } else {
+                out.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_subcommand_has_an_example() {
+        let mut paths = Vec::new();
+        leaf_subcommands(&Cli::command(), "", &mut paths);
+        assert!(!paths.is_empty());
+
+        for path in paths {
+            assert!(
+                EXAMPLES.iter().any(|e| e.subcommand == path),
+                "no example for '{}'",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_topic() {
+        assert_eq!(find_topic("auth").unwrap().title, "Authentication");
+        assert_eq!(find_topic("Assertions").unwrap().name, "assertions");
+        assert!(find_topic("variables").is_some());
+        assert!(find_topic("nope").is_none());
+    }
+
+    #[test]
+    fn test_topics_have_examples() {
+        for topic in TOPICS {
+            assert!(topic.sections.iter().any(|s| !s.examples.is_empty()));
+        }
+    }
+}
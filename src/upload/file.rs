@@ -2,8 +2,12 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
+/// How many leading bytes are read when sniffing a file's type
+const SNIFF_LEN: usize = 512;
+
 /// Represents a file to be uploaded
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileUpload {
@@ -51,10 +55,11 @@ impl FileUpload {
         let metadata = fs::metadata(&path_buf)?;
         let size = Some(metadata.len());
 
-        // Detect MIME type
+        // Detect MIME type from the extension, then from the content
         let mime_type = mime_guess::from_path(&path_buf)
             .first()
-            .map(|m| m.to_string());
+            .map(|m| m.to_string())
+            .or_else(|| sniff_file(&path_buf).map(str::to_string));
 
         Ok(Self {
             path: path_buf,
@@ -71,12 +76,17 @@ impl FileUpload {
         self
     }
 
-    /// Set MIME type
-    pub fn with_mime_type(mut self, mime_type: String) -> Self {
-        self.mime_type = Some(mime_type);
+    /// Override the detected MIME type
+    pub fn with_mime(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
         self
     }
 
+    /// Set MIME type
+    pub fn with_mime_type(self, mime_type: String) -> Self {
+        self.with_mime(mime_type)
+    }
+
     /// Get the filename to use for upload
     pub fn filename(&self) -> String {
         if let Some(ref custom) = self.custom_filename {
@@ -119,6 +129,58 @@ impl FileUpload {
     }
 }
 
+/// Guess a MIME type from a file's leading bytes
+///
+/// Recognizes PNG, JPEG, GIF, PDF, JSON, and CSV. Returns `None` for
+/// anything else.
+pub fn sniff_mime(bytes: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+    ];
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(sig, _)| bytes.starts_with(sig)) {
+        return Some(mime);
+    }
+
+    // The sample may end mid-character, so only the valid prefix counts
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => std::str::from_utf8(&bytes[..e.valid_up_to()]).ok()?,
+    };
+    if text.contains('\0') {
+        return None;
+    }
+
+    let trimmed = text.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        return Some("application/json");
+    }
+
+    // CSV: at least two complete lines with the same number of commas
+    let mut lines = text.lines().filter(|l| !l.is_empty());
+    let columns = lines.next()?.matches(',').count();
+    let second = lines.next()?;
+    if columns > 0 && second.matches(',').count() == columns {
+        return Some("text/csv");
+    }
+
+    None
+}
+
+/// Sniff the MIME type of a file on disk
+fn sniff_file(path: &Path) -> Option<&'static str> {
+    let mut buf = Vec::with_capacity(SNIFF_LEN);
+    fs::File::open(path)
+        .ok()?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut buf)
+        .ok()?;
+    sniff_mime(&buf)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +228,43 @@ mod tests {
         assert!(upload.mime().contains("text"));
     }
 
+    #[test]
+    fn test_mime_from_extension() {
+        let temp_file = NamedTempFile::with_suffix(".png").unwrap();
+        let upload = FileUpload::new(temp_file.path(), "file".to_string()).unwrap();
+        assert_eq!(upload.mime(), "image/png");
+
+        let upload = upload.with_mime("image/x-custom");
+        assert_eq!(upload.mime(), "image/x-custom");
+    }
+
+    #[test]
+    fn test_mime_sniffed_for_unknown_extension() {
+        let mut png = NamedTempFile::with_suffix(".upload").unwrap();
+        png.write_all(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let upload = FileUpload::new(png.path(), "file".to_string()).unwrap();
+        assert_eq!(upload.mime(), "image/png");
+
+        let mut csv = NamedTempFile::with_suffix(".upload").unwrap();
+        csv.write_all(b"id,name\n1,alice\n2,bob\n").unwrap();
+        let upload = FileUpload::new(csv.path(), "file".to_string()).unwrap();
+        assert_eq!(upload.mime(), "text/csv");
+
+        let mut unknown = NamedTempFile::with_suffix(".upload").unwrap();
+        unknown.write_all(b"\x00\x01\x02 opaque").unwrap();
+        let upload = FileUpload::new(unknown.path(), "file".to_string()).unwrap();
+        assert_eq!(upload.mime(), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_sniff_mime() {
+        assert_eq!(sniff_mime(b"\xff\xd8\xff\xe0"), Some("image/jpeg"));
+        assert_eq!(sniff_mime(b"%PDF-1.7\n"), Some("application/pdf"));
+        assert_eq!(sniff_mime(b"  {\"a\": 1}"), Some("application/json"));
+        assert_eq!(sniff_mime(b"just some prose"), None);
+        assert_eq!(sniff_mime(b""), None);
+    }
+
     #[test]
     fn test_read_contents() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -1,17 +1,18 @@
 //! Form data handling
 
+use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Represents form data (application/x-www-form-urlencoded or multipart/form-data)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct FormData {
     /// Form fields
     fields: HashMap<String, FormField>,
 }
 
 /// A single form field
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FormField {
     /// Text field
     Text(String),
@@ -28,6 +29,21 @@ impl FormData {
         }
     }
 
+    /// Build a text-only form from `key=value` pairs
+    pub fn from_pairs(pairs: &[String]) -> Result<Self> {
+        let mut form = Self::new();
+        for pair in pairs {
+            let (name, value) = pair.split_once('=').ok_or_else(|| {
+                Error::InvalidCommand(format!(
+                    "Form field must be in format 'key=value', got: {}",
+                    pair
+                ))
+            })?;
+            form.add_text(name.to_string(), value.to_string());
+        }
+        Ok(form)
+    }
+
     /// Add a text field
     pub fn add_text(&mut self, name: String, value: String) {
         self.fields.insert(name, FormField::Text(value));
@@ -127,16 +143,20 @@ impl FormData {
     }
 }
 
-// Need urlencoding crate for proper URL encoding
-mod urlencoding {
+pub(crate) mod urlencoding {
+    /// Percent-encode each UTF-8 byte outside the unreserved set; spaces become `+`
     pub fn encode(s: &str) -> String {
-        s.chars()
-            .map(|c| match c {
-                'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
-                ' ' => "+".to_string(),
-                _ => format!("%{:02X}", c as u8),
-            })
-            .collect()
+        let mut encoded = String::with_capacity(s.len());
+        for byte in s.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    encoded.push(byte as char)
+                }
+                b' ' => encoded.push('+'),
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
     }
 }
 
@@ -144,6 +164,17 @@ mod urlencoding {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_urlencode_reserved_and_non_ascii() {
+        assert_eq!(urlencoding::encode("a&b=c"), "a%26b%3Dc");
+        assert_eq!(urlencoding::encode("café"), "caf%C3%A9");
+        assert_eq!(urlencoding::encode("hello world+1"), "hello+world%2B1");
+
+        let form = FormData::from_pairs(&["q=crème brûlée".to_string()]).unwrap();
+        assert_eq!(form.to_urlencoded(), "q=cr%C3%A8me+br%C3%BBl%C3%A9e");
+        assert!(FormData::from_pairs(&["novalue".to_string()]).is_err());
+    }
+
     #[test]
     fn test_form_data_creation() {
         let form = FormData::new();
@@ -6,4 +6,4 @@ pub mod multipart;
 
 pub use file::FileUpload;
 pub use form::{FormData, FormField};
-pub use multipart::MultipartBuilder;
+pub use multipart::{MultipartBuilder, MultipartReader};
@@ -1,7 +1,16 @@
 //! Multipart form data builder
 
 use crate::upload::{FileUpload, FormData, FormField};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+
+/// A piece of the body: generated bytes, or a file's contents
+enum Segment<'a> {
+    Bytes(Vec<u8>),
+    File(&'a Path),
+}
 
 /// Builder for multipart/form-data requests
 pub struct MultipartBuilder {
@@ -57,52 +66,81 @@ impl MultipartBuilder {
         format!("multipart/form-data; boundary={}", self.boundary)
     }
 
-    /// Build the multipart body
+    /// Build the multipart body in memory
+    ///
+    /// Fine for small forms; use `build_streaming` for large files.
     pub fn build(&self) -> crate::Result<Vec<u8>> {
         let mut body = Vec::new();
+        for segment in self.segments()? {
+            match segment {
+                Segment::Bytes(bytes) => body.extend_from_slice(&bytes),
+                Segment::File(path) => body.extend_from_slice(&std::fs::read(path)?),
+            }
+        }
+        Ok(body)
+    }
+
+    /// Build a reader that produces the body, reading files as it goes
+    ///
+    /// Only part headers are held in memory, so uploads of any size use a
+    /// small, fixed amount of memory.
+    pub fn build_streaming(&self) -> crate::Result<MultipartReader> {
+        let mut parts: VecDeque<Box<dyn Read + Send>> = VecDeque::new();
+        let mut len = 0;
+        for segment in self.segments()? {
+            match segment {
+                Segment::Bytes(bytes) => {
+                    len += bytes.len() as u64;
+                    parts.push_back(Box::new(Cursor::new(bytes)));
+                }
+                Segment::File(path) => {
+                    let file = File::open(path)?;
+                    len += file.metadata()?.len();
+                    parts.push_back(Box::new(file));
+                }
+            }
+        }
+        Ok(MultipartReader { parts, len })
+    }
+
+    /// Lay out the body: part headers and text as bytes, file contents by path
+    fn segments(&self) -> crate::Result<Vec<Segment<'_>>> {
+        let mut segments = Vec::new();
         let boundary = format!("--{}", self.boundary);
-        let crlf = b"\r\n";
 
         // Add text fields
         for (name, value) in &self.text_fields {
-            body.extend_from_slice(boundary.as_bytes());
-            body.extend_from_slice(crlf);
-
-            body.extend_from_slice(
-                format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n", name).as_bytes(),
-            );
-
-            body.extend_from_slice(value.as_bytes());
-            body.extend_from_slice(crlf);
+            segments.push(Segment::Bytes(
+                format!(
+                    "{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                    boundary, name, value
+                )
+                .into_bytes(),
+            ));
         }
 
         // Add file uploads
         for file in &self.file_uploads {
             file.validate()?;
 
-            body.extend_from_slice(boundary.as_bytes());
-            body.extend_from_slice(crlf);
-
-            body.extend_from_slice(
+            segments.push(Segment::Bytes(
                 format!(
-                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n",
+                    "{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                    boundary,
                     file.field_name,
-                    file.filename()
+                    file.filename(),
+                    file.mime()
                 )
-                .as_bytes(),
-            );
-
-            body.extend_from_slice(format!("Content-Type: {}\r\n\r\n", file.mime()).as_bytes());
-
-            let file_contents = file.read_contents()?;
-            body.extend_from_slice(&file_contents);
-            body.extend_from_slice(crlf);
+                .into_bytes(),
+            ));
+            segments.push(Segment::File(&file.path));
+            segments.push(Segment::Bytes(b"\r\n".to_vec()));
         }
 
         // Final boundary
-        body.extend_from_slice(format!("{}--\r\n", boundary).as_bytes());
+        segments.push(Segment::Bytes(format!("{}--\r\n", boundary).into_bytes()));
 
-        Ok(body)
+        Ok(segments)
     }
 
     /// Create from FormData
@@ -143,6 +181,32 @@ impl Default for MultipartBuilder {
     }
 }
 
+/// A multipart body read piece by piece, from `MultipartBuilder::build_streaming`
+pub struct MultipartReader {
+    parts: VecDeque<Box<dyn Read + Send>>,
+    len: u64,
+}
+
+impl MultipartReader {
+    /// Total body size in bytes, for the Content-Length header
+    pub fn content_length(&self) -> u64 {
+        self.len
+    }
+}
+
+impl Read for MultipartReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while let Some(part) = self.parts.front_mut() {
+            let read = part.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            self.parts.pop_front();
+        }
+        Ok(0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,6 +264,39 @@ mod tests {
         assert!(body_str.contains("test file content"));
     }
 
+    #[test]
+    fn test_build_streaming_large_file() {
+        // 8 MB of non-repeating-looking data
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let chunk: Vec<u8> = (0..=255u8).cycle().take(64 * 1024).collect();
+        for _ in 0..128 {
+            temp_file.write_all(&chunk).unwrap();
+        }
+
+        let builder = MultipartBuilder::new()
+            .with_text("name".to_string(), "value".to_string())
+            .with_file(FileUpload::new(temp_file.path(), "file".to_string()).unwrap());
+
+        let mut reader = builder.build_streaming().unwrap();
+        let expected_len = reader.content_length();
+
+        // Read in small chunks, as an HTTP client would
+        let mut streamed = Vec::new();
+        let mut buf = [0u8; 8192];
+        loop {
+            let read = reader.read(&mut buf).unwrap();
+            if read == 0 {
+                break;
+            }
+            streamed.extend_from_slice(&buf[..read]);
+        }
+
+        assert_eq!(streamed.len() as u64, expected_len);
+        assert!(expected_len > 8 * 1024 * 1024);
+        assert_eq!(streamed, builder.build().unwrap());
+        assert!(streamed.ends_with(format!("--{}--\r\n", builder.boundary()).as_bytes()));
+    }
+
     #[test]
     fn test_from_form_data() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -1,23 +1,62 @@
 //! Request chain configuration
-
-use crate::workflow::WorkflowStep;
+//!
+//! Chains are saved as YAML (`.yaml`/`.yml`) or JSON (any other extension):
+//!
+//! ```yaml
+//! name: Create user
+//! variables:
+//!   base_url: https://api.example.com
+//! config:
+//!   max_duration: 30s
+//! steps:
+//!   - name: Login
+//!     method: POST
+//!     url: "{{base_url}}/login"
+//!     headers: ["Content-Type: application/json"]
+//!     body: '{"user":"ada","password":"{{password}}"}'
+//!     assertions: ["status == 200"]
+//!     extract:
+//!       token: $.token
+//!   - name: Profile
+//!     method: GET
+//!     url: "{{base_url}}/me"
+//!     headers: ["Authorization: Bearer {{token}}"]
+//!     timeout: 2s
+//! ```
+
+use crate::env::VariableSubstitutor;
+use crate::error::{Error, Result};
+use crate::workflow::{load_data_file, WorkflowStep};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// Configuration for chain execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ChainConfig {
     /// Stop execution on first failure
     pub stop_on_failure: bool,
 
     /// Delay between requests
+    #[serde(with = "crate::workflow::duration::option")]
     pub delay_between_requests: Option<Duration>,
 
     /// Maximum total duration
+    #[serde(with = "crate::workflow::duration::option")]
     pub max_duration: Option<Duration>,
 
     /// Number of iterations
     pub iterations: usize,
+
+    /// Start each iteration from the initial variables instead of the previous iteration's
+    pub iteration_isolation: bool,
+
+    /// CSV or JSON file with one iteration per row (overrides `iterations`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_file: Option<PathBuf>,
 }
 
 impl ChainConfig {
@@ -28,6 +67,8 @@ impl ChainConfig {
             delay_between_requests: None,
             max_duration: None,
             iterations: 1,
+            iteration_isolation: false,
+            data_file: None,
         }
     }
 
@@ -54,6 +95,19 @@ impl ChainConfig {
         self.iterations = iterations;
         self
     }
+
+    /// Set iteration isolation
+    pub fn with_iteration_isolation(mut self, isolated: bool) -> Self {
+        self.iteration_isolation = isolated;
+        self
+    }
+
+    /// Run one iteration per row of a CSV or JSON data file, with the row's
+    /// fields as variables
+    pub fn with_data_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.data_file = Some(path.into());
+        self
+    }
 }
 
 impl Default for ChainConfig {
@@ -69,12 +123,18 @@ pub struct RequestChain {
     pub name: String,
 
     /// Description
+    #[serde(default)]
     pub description: Option<String>,
 
     /// Steps in the chain
     pub steps: Vec<WorkflowStep>,
 
+    /// Initial variables available to every step
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+
     /// Execution configuration
+    #[serde(default)]
     pub config: ChainConfig,
 }
 
@@ -85,6 +145,7 @@ impl RequestChain {
             name,
             description: None,
             steps: Vec::new(),
+            variables: HashMap::new(),
             config: ChainConfig::default(),
         }
     }
@@ -101,6 +162,12 @@ impl RequestChain {
         self
     }
 
+    /// Set an initial variable
+    pub fn with_variable(mut self, name: String, value: String) -> Self {
+        self.variables.insert(name, value);
+        self
+    }
+
     /// Set config
     pub fn with_config(mut self, config: ChainConfig) -> Self {
         self.config = config;
@@ -111,6 +178,118 @@ impl RequestChain {
     pub fn step_count(&self) -> usize {
         self.steps.len()
     }
+
+    /// Load a chain from a YAML or JSON file, chosen by extension
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("Could not read workflow '{}': {}", path.display(), e),
+            ))
+        })?;
+        let parsed = if is_yaml(path) {
+            serde_yaml::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        };
+        parsed.map_err(|problem| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Invalid workflow '{}': {}", path.display(), problem),
+            ))
+        })
+    }
+
+    /// Save the chain as YAML or JSON, chosen by extension
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        let content = if is_yaml(path) {
+            serde_yaml::to_string(self)
+                .map_err(|e| Error::Io(std::io::Error::other(e.to_string())))?
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Check the chain without running it
+    ///
+    /// Reports variables a step uses before anything defines them. Variables
+    /// are defined by the chain, by `known` (environment or command line), by
+    /// the data file's columns, by earlier steps' extractions, and by names a
+    /// script declares with `let`.
+    pub fn validate(&self, known: &HashSet<String>) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.steps.is_empty() {
+            problems.push("Workflow has no steps".to_string());
+        }
+
+        let mut defined: HashSet<String> = known.clone();
+        defined.extend(self.variables.keys().cloned());
+        if let Some(path) = &self.config.data_file {
+            match load_data_file(path) {
+                Ok(rows) => {
+                    defined.extend(
+                        rows.iter()
+                            .flat_map(|row| row.values.iter().map(|(name, _)| name.clone())),
+                    );
+                }
+                Err(e) => problems.push(e.to_string()),
+            }
+        }
+
+        let substitutor = VariableSubstitutor::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            let label = format!("Step {} '{}'", index + 1, step.name);
+            if let Some(script) = &step.pre_request_script {
+                defined.extend(script_variables(&script.code));
+            }
+
+            let texts = std::iter::once(&step.url)
+                .chain(&step.headers)
+                .chain(&step.query_params)
+                .chain(&step.body);
+            let mut missing: Vec<String> = texts
+                .flat_map(|text| substitutor.find_variables(text))
+                .filter(|name| !defined.contains(name))
+                .collect();
+            missing.sort();
+            missing.dedup();
+            for name in missing {
+                problems.push(format!(
+                    "{} uses {{{{{}}}}} before it is defined",
+                    label, name
+                ));
+            }
+
+            if step.polling.as_ref().is_some_and(|p| p.max_attempts == 0) {
+                problems.push(format!("{} polls with max_attempts 0", label));
+            }
+
+            defined.extend(step.extract_variables.keys().cloned());
+            if let Some(script) = &step.post_response_script {
+                defined.extend(script_variables(&script.code));
+            }
+        }
+
+        problems
+    }
+}
+
+/// Whether a path names a YAML file
+fn is_yaml(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"))
+}
+
+/// Names a script declares with `let`, which become chain variables
+fn script_variables(code: &str) -> Vec<String> {
+    let pattern = Regex::new(r"\blet\s+([A-Za-z_][A-Za-z0-9_]*)").unwrap();
+    pattern
+        .captures_iter(code)
+        .map(|caps| caps[1].to_string())
+        .collect()
 }
 
 #[cfg(test)]
@@ -137,6 +316,15 @@ mod tests {
         assert_eq!(config.iterations, 5);
     }
 
+    #[test]
+    fn test_chain_config_with_iteration_isolation() {
+        let config = ChainConfig::new();
+        assert!(!config.iteration_isolation);
+
+        let config = config.with_iteration_isolation(true);
+        assert!(config.iteration_isolation);
+    }
+
     #[test]
     fn test_request_chain_new() {
         let chain = RequestChain::new("Test Chain".to_string());
@@ -206,4 +394,100 @@ mod tests {
         assert_eq!(chain.name, deserialized.name);
         assert_eq!(chain.description, deserialized.description);
     }
+
+    fn example() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("workflow.yaml")
+    }
+
+    #[test]
+    fn test_load_example_workflow() {
+        let chain = RequestChain::load_from_file(&example()).unwrap();
+        assert_eq!(chain.name, "Create user");
+        assert_eq!(chain.variables["base_url"], "https://api.example.com");
+        assert_eq!(
+            chain.config.delay_between_requests,
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(chain.config.max_duration, Some(Duration::from_secs(60)));
+        assert_eq!(chain.config.iterations, 1);
+        assert_eq!(chain.step_count(), 3);
+
+        let login = &chain.steps[0];
+        assert_eq!(login.method, HttpMethod::Post);
+        assert_eq!(login.timeout, Some(Duration::from_secs(2)));
+        assert_eq!(login.assertions.len(), 2);
+        assert_eq!(login.extract_variables["token"], "$.token");
+        assert!(chain.steps[1].pre_request_script.as_ref().unwrap().enabled);
+
+        let polling = chain.steps[2].polling.as_ref().unwrap();
+        assert_eq!(polling.interval, Duration::from_millis(500));
+        assert_eq!(polling.max_attempts, 10);
+
+        // `password` is the only variable nothing defines
+        assert_eq!(
+            chain.validate(&HashSet::new()),
+            ["Step 1 'Login' uses {{password}} before it is defined"]
+        );
+        assert!(chain
+            .validate(&HashSet::from(["password".to_string()]))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_yaml_and_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain = RequestChain::load_from_file(&example()).unwrap();
+
+        for name in ["chain.yml", "chain.json"] {
+            let path = dir.path().join(name);
+            chain.save_to_file(&path).unwrap();
+            let reloaded = RequestChain::load_from_file(&path).unwrap();
+            assert_eq!(reloaded.steps.len(), 3);
+            assert_eq!(reloaded.steps[0].timeout, Some(Duration::from_secs(2)));
+            assert_eq!(reloaded.steps[0].assertions, chain.steps[0].assertions);
+        }
+        let yaml = std::fs::read_to_string(dir.path().join("chain.yml")).unwrap();
+        assert!(yaml.contains("max_duration: 1m"));
+        assert!(yaml.contains("timeout: 2s"));
+    }
+
+    #[test]
+    fn test_validate_reports_order_and_bad_files() {
+        let chain = RequestChain::new("Order".to_string())
+            .add_step(WorkflowStep::new(
+                "Use".to_string(),
+                HttpMethod::Get,
+                "https://example.com/{{id}}".to_string(),
+            ))
+            .add_step(
+                WorkflowStep::new(
+                    "Define".to_string(),
+                    HttpMethod::Get,
+                    "https://example.com".to_string(),
+                )
+                .extract_variable("id".to_string(), "$.id".to_string()),
+            );
+        assert_eq!(
+            chain.validate(&HashSet::new()),
+            ["Step 1 'Use' uses {{id}} before it is defined"]
+        );
+        assert_eq!(
+            RequestChain::new("Empty".to_string()).validate(&HashSet::new()),
+            ["Workflow has no steps"]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.yaml");
+        std::fs::write(
+            &path,
+            "name: Bad\nsteps:\n  - name: Brew\n    method: BREW\n    url: https://example.com\n",
+        )
+        .unwrap();
+        let error = RequestChain::load_from_file(&path).unwrap_err().to_string();
+        assert!(error.contains("Invalid workflow"));
+        assert!(error.contains("BREW"));
+    }
 }
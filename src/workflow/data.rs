@@ -0,0 +1,247 @@
+//! Data files that drive one workflow iteration per row
+//!
+//! CSV files name their variables in the header row; JSON files hold an
+//! array of objects. Non-string JSON values are used as their JSON text.
+
+use crate::error::{Error, Result};
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+
+/// One row of a data file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataRow {
+    /// Position in the file (1-based, not counting a CSV header)
+    pub index: usize,
+
+    /// Variable names and values (CSV columns in file order, JSON keys sorted)
+    pub values: Vec<(String, String)>,
+}
+
+impl DataRow {
+    /// Create a row
+    pub fn new(index: usize, values: Vec<(String, String)>) -> Self {
+        Self { index, values }
+    }
+
+    /// Value of a variable
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl fmt::Display for DataRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values: Vec<String> = self
+            .values
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        write!(f, "row {} ({})", self.index, values.join(", "))
+    }
+}
+
+/// Load the rows of a CSV or JSON data file
+///
+/// `.json` files (or files starting with `[`) are read as JSON, anything
+/// else as CSV. A file without rows is an error, since it would run nothing.
+pub fn load_data_file(path: &Path) -> Result<Vec<DataRow>> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        Error::Io(std::io::Error::new(
+            e.kind(),
+            format!("Could not read data file '{}': {}", path.display(), e),
+        ))
+    })?;
+    let is_json = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        || content.trim_start().starts_with('[');
+
+    let rows = if is_json {
+        parse_json_rows(&content)
+    } else {
+        parse_csv_rows(&content)
+    };
+    let rows = rows.and_then(|rows| {
+        if rows.is_empty() {
+            Err("no rows".to_string())
+        } else {
+            Ok(rows)
+        }
+    });
+    rows.map_err(|problem| {
+        Error::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Invalid data file '{}': {}", path.display(), problem),
+        ))
+    })
+}
+
+/// Rows of a JSON array of objects
+pub fn parse_json_rows(content: &str) -> std::result::Result<Vec<DataRow>, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let Value::Array(items) = value else {
+        return Err("expected a JSON array of objects".to_string());
+    };
+
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| match item {
+            Value::Object(map) => Ok(DataRow::new(
+                i + 1,
+                map.into_iter()
+                    .map(|(name, value)| {
+                        let value = match value {
+                            Value::String(s) => s,
+                            Value::Null => String::new(),
+                            other => other.to_string(),
+                        };
+                        (name, value)
+                    })
+                    .collect(),
+            )),
+            _ => Err(format!("item {} is not an object", i + 1)),
+        })
+        .collect()
+}
+
+/// Rows of a CSV file whose header row names the variables
+///
+/// Fields may be quoted (RFC 4180); blank lines are skipped.
+pub fn parse_csv_rows(content: &str) -> std::result::Result<Vec<DataRow>, String> {
+    let mut records = parse_csv(content.trim_start_matches('\u{feff}'))?.into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Vec<String> = header.into_iter().map(|h| h.trim().to_string()).collect();
+    if header.iter().any(String::is_empty) {
+        return Err("the header row has an empty column name".to_string());
+    }
+
+    records
+        .enumerate()
+        .map(|(i, record)| {
+            if record.len() != header.len() {
+                return Err(format!(
+                    "row {} has {} fields, expected {}",
+                    i + 1,
+                    record.len(),
+                    header.len()
+                ));
+            }
+            Ok(DataRow::new(
+                i + 1,
+                header.iter().cloned().zip(record).collect(),
+            ))
+        })
+        .collect()
+}
+
+/// Split CSV text into records of fields
+fn parse_csv(content: &str) -> std::result::Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    // A blank line is a single empty field
+    records.retain(|r| !(r.len() == 1 && r[0].is_empty()));
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_rows() {
+        let rows =
+            parse_csv_rows("name,email\r\nada,ada@example.com\n\n\"Lovelace, A\",\"a\"\"b\"\n")
+                .unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].get("email"), Some("ada@example.com"));
+        assert_eq!(rows[1].index, 2);
+        assert_eq!(rows[1].get("name"), Some("Lovelace, A"));
+        assert_eq!(rows[1].get("email"), Some("a\"b"));
+        assert_eq!(
+            rows[0].to_string(),
+            "row 1 (name=ada, email=ada@example.com)"
+        );
+
+        assert!(parse_csv_rows("a,b\n1\n")
+            .unwrap_err()
+            .contains("row 1 has 1 fields, expected 2"));
+        assert!(parse_csv_rows("a\n\"open\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_rows() {
+        let rows =
+            parse_json_rows(r#"[{"id": 1, "name": "ada", "admin": true, "note": null}]"#).unwrap();
+
+        assert_eq!(rows[0].get("id"), Some("1"));
+        assert_eq!(rows[0].get("name"), Some("ada"));
+        assert_eq!(rows[0].get("admin"), Some("true"));
+        assert_eq!(rows[0].get("note"), Some(""));
+
+        assert!(parse_json_rows(r#"{"id": 1}"#).is_err());
+        assert!(parse_json_rows("[1]").is_err());
+    }
+
+    #[test]
+    fn test_load_data_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv = dir.path().join("users.csv");
+        std::fs::write(&csv, "id\n1\n2\n").unwrap();
+        assert_eq!(load_data_file(&csv).unwrap().len(), 2);
+
+        let json = dir.path().join("users.json");
+        std::fs::write(&json, r#"[{"id": 1}]"#).unwrap();
+        assert_eq!(load_data_file(&json).unwrap()[0].get("id"), Some("1"));
+
+        let missing = load_data_file(&dir.path().join("nope.csv")).unwrap_err();
+        assert!(missing.to_string().contains("Could not read data file"));
+
+        let header_only = dir.path().join("empty.csv");
+        std::fs::write(&header_only, "id\n").unwrap();
+        let empty_json = dir.path().join("empty.json");
+        std::fs::write(&empty_json, "[]").unwrap();
+        for path in [header_only, empty_json] {
+            let error = load_data_file(&path).unwrap_err();
+            assert!(error.to_string().contains("no rows"), "{}", error);
+        }
+    }
+}
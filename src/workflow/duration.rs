@@ -0,0 +1,122 @@
+//! Durations in workflow files, written as "500ms", "2s" or "1m"
+//!
+//! Plain numbers are read as seconds, and the `{ secs, nanos }` form written
+//! by earlier versions is still accepted.
+
+use crate::http::deadline::parse_duration;
+use serde::{Deserialize, Deserializer, Serializer};
+use std::time::Duration;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Repr {
+    Text(String),
+    Seconds(f64),
+    Parts { secs: u64, nanos: u32 },
+}
+
+/// Format a duration as whole minutes, seconds or milliseconds
+pub fn format(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if !duration.subsec_nanos().is_multiple_of(1_000_000) {
+        format!("{}s", duration.as_secs_f64())
+    } else if millis.is_multiple_of(60_000) && millis > 0 {
+        format!("{}m", millis / 60_000)
+    } else if millis.is_multiple_of(1000) {
+        format!("{}s", millis / 1000)
+    } else {
+        format!("{}ms", millis)
+    }
+}
+
+/// Write a duration as text
+pub fn serialize<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format(*duration))
+}
+
+/// Read a duration from text, seconds, or `{ secs, nanos }`
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Duration, D::Error> {
+    match Repr::deserialize(deserializer)? {
+        Repr::Text(text) => parse_duration(&text).map_err(serde::de::Error::custom),
+        Repr::Seconds(seconds) => {
+            Duration::try_from_secs_f64(seconds).map_err(serde::de::Error::custom)
+        }
+        Repr::Parts { secs, nanos } => Ok(Duration::new(secs, nanos)),
+    }
+}
+
+/// The same, for optional durations
+pub mod option {
+    use super::*;
+
+    /// Write an optional duration as text
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => super::serialize(duration, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Read an optional duration
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Option<Duration>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapped(#[serde(deserialize_with = "super::deserialize")] Duration);
+
+        Ok(Option::<Wrapped>::deserialize(deserializer)?.map(|Wrapped(duration)| duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Timings {
+        #[serde(with = "super")]
+        interval: Duration,
+        #[serde(default, with = "option")]
+        timeout: Option<Duration>,
+    }
+
+    #[test]
+    fn test_duration_round_trip() {
+        for (duration, text) in [
+            (Duration::from_millis(500), "500ms"),
+            (Duration::from_secs(2), "2s"),
+            (Duration::from_secs(120), "2m"),
+            (Duration::from_millis(1500), "1500ms"),
+            (Duration::ZERO, "0s"),
+        ] {
+            assert_eq!(format(duration), text);
+            assert_eq!(parse_duration(text).unwrap(), duration);
+        }
+
+        let timings: Timings = serde_yaml::from_str("interval: 250ms\ntimeout: 1.5\n").unwrap();
+        assert_eq!(timings.interval, Duration::from_millis(250));
+        assert_eq!(timings.timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(
+            serde_json::to_string(&timings).unwrap(),
+            r#"{"interval":"250ms","timeout":"1500ms"}"#
+        );
+
+        let legacy: Timings =
+            serde_json::from_str(r#"{"interval":{"secs":1,"nanos":0},"timeout":null}"#).unwrap();
+        assert_eq!(legacy.interval, Duration::from_secs(1));
+        assert_eq!(legacy.timeout, None);
+
+        let missing: Timings = serde_yaml::from_str("interval: 1s\n").unwrap();
+        assert_eq!(missing.timeout, None);
+        assert!(serde_yaml::from_str::<Timings>("interval: soon\n").is_err());
+    }
+}
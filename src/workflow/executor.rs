@@ -1,14 +1,77 @@
 //! Workflow execution engine
 
-use crate::assertions::validate_response;
+use crate::assertions::{validate_response, SnapshotOutcome, SnapshotStore};
+use crate::auth::AuthScheme;
 use crate::env::VariableSubstitutor;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::http::{HttpClient, RequestBuilder};
+use crate::report::{JUnitTestCase, JUnitTestSuite};
 use crate::scripts::{execute_post_response, execute_pre_request, ScriptContext};
-use crate::workflow::{RequestChain, StepResult, WorkflowStep};
+use crate::session::CookieJar;
+use crate::workflow::{load_data_file, DataRow, RequestChain, StepResult, WorkflowStep};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+/// Result of a single iteration of a workflow
+#[derive(Debug, Clone)]
+pub struct IterationResult {
+    /// Iteration number (1-based)
+    pub iteration: usize,
+
+    /// Step results for this iteration
+    pub step_results: Vec<StepResult>,
+
+    /// Whether every step in this iteration succeeded
+    pub success: bool,
+
+    /// Iteration duration
+    pub duration: Duration,
+
+    /// Variables at end of this iteration
+    pub variables: HashMap<String, String>,
+
+    /// Data file row that seeded this iteration's variables
+    pub data_row: Option<DataRow>,
+}
+
+impl IterationResult {
+    /// Create a new iteration result
+    pub fn new(iteration: usize) -> Self {
+        Self {
+            iteration,
+            step_results: Vec::new(),
+            success: true,
+            duration: Duration::ZERO,
+            variables: HashMap::new(),
+            data_row: None,
+        }
+    }
+
+    /// Attach the data file row this iteration ran with
+    pub fn with_data_row(mut self, row: DataRow) -> Self {
+        self.data_row = Some(row);
+        self
+    }
+
+    /// Label used in reports, e.g. "iteration 2" or "row 2"
+    pub fn label(&self) -> String {
+        match &self.data_row {
+            Some(row) => format!("row {}", row.index),
+            None => format!("iteration {}", self.iteration),
+        }
+    }
+
+    /// Add step result
+    pub fn add_step_result(&mut self, result: StepResult) {
+        if !result.success {
+            self.success = false;
+        }
+        self.duration += result.duration;
+        self.step_results.push(result);
+    }
+}
+
 /// Result of executing a workflow
 #[derive(Debug, Clone)]
 pub struct ExecutionResult {
@@ -26,6 +89,15 @@ pub struct ExecutionResult {
 
     /// Variables at end of execution
     pub final_variables: HashMap<String, String>,
+
+    /// Per-iteration results
+    pub iterations: Vec<IterationResult>,
+
+    /// Whether execution was cut off by the chain's max duration
+    pub max_duration_exceeded: bool,
+
+    /// Whether execution was cut off by the client's --max-time deadline
+    pub deadline_exceeded: bool,
 }
 
 impl ExecutionResult {
@@ -37,6 +109,9 @@ impl ExecutionResult {
             success: true,
             total_duration: Duration::ZERO,
             final_variables: HashMap::new(),
+            iterations: Vec::new(),
+            max_duration_exceeded: false,
+            deadline_exceeded: false,
         }
     }
 
@@ -49,12 +124,30 @@ impl ExecutionResult {
         self.step_results.push(result);
     }
 
+    /// Add iteration result (its steps are added to the flat step list too)
+    pub fn add_iteration_result(&mut self, iteration: IterationResult) {
+        for step_result in &iteration.step_results {
+            self.add_step_result(step_result.clone());
+        }
+        self.iterations.push(iteration);
+    }
+
+    /// Get iterations that had at least one failed step
+    pub fn failed_iterations(&self) -> Vec<&IterationResult> {
+        self.iterations.iter().filter(|i| !i.success).collect()
+    }
+
+    /// Whether results should be reported per iteration rather than as a flat list
+    fn is_grouped(&self) -> bool {
+        self.iterations.len() > 1 || self.iterations.iter().any(|i| i.data_row.is_some())
+    }
+
     /// Get summary
     pub fn summary(&self) -> String {
         let passed = self.step_results.iter().filter(|r| r.success).count();
         let failed = self.step_results.len() - passed;
 
-        if self.success {
+        let mut summary = if self.success {
             format!(
                 "✓ Chain '{}' completed successfully: {} steps, {:?}",
                 self.chain_name,
@@ -66,7 +159,16 @@ impl ExecutionResult {
                 "✗ Chain '{}' failed: {} passed, {} failed, {:?}",
                 self.chain_name, passed, failed, self.total_duration
             )
+        };
+
+        if self.max_duration_exceeded {
+            summary.push_str(" (stopped: max duration exceeded)");
         }
+        if self.deadline_exceeded {
+            summary.push_str(" (stopped: --max-time exceeded)");
+        }
+
+        summary
     }
 
     /// Get detailed report
@@ -74,12 +176,86 @@ impl ExecutionResult {
         let mut report = self.summary();
         report.push_str("\n\n");
 
+        if self.is_grouped() {
+            for iteration in &self.iterations {
+                let marker = if iteration.success { "✓" } else { "✗" };
+                match &iteration.data_row {
+                    Some(row) => report.push_str(&format!(
+                        "{} Iteration {}, {} ({:?})\n",
+                        marker, iteration.iteration, row, iteration.duration
+                    )),
+                    None => report.push_str(&format!(
+                        "{} Iteration {} ({:?})\n",
+                        marker, iteration.iteration, iteration.duration
+                    )),
+                }
+                for (i, result) in iteration.step_results.iter().enumerate() {
+                    report.push_str(&format!("  {}. {}\n", i + 1, result.summary()));
+                }
+            }
+
+            let failed_rows: Vec<String> = self
+                .failed_iterations()
+                .iter()
+                .filter_map(|i| i.data_row.as_ref().map(|row| row.index.to_string()))
+                .collect();
+            if !failed_rows.is_empty() {
+                report.push_str(&format!("\nFailed rows: {}\n", failed_rows.join(", ")));
+            }
+            return report;
+        }
+
         for (i, result) in self.step_results.iter().enumerate() {
             report.push_str(&format!("{}. {}\n", i + 1, result.summary()));
         }
 
         report
     }
+
+    /// Build a test suite named after the chain, with one test case per step
+    pub fn test_suite(&self) -> JUnitTestSuite {
+        let mut suite = JUnitTestSuite::new(self.chain_name.clone());
+
+        if self.is_grouped() {
+            for iteration in &self.iterations {
+                for step_result in &iteration.step_results {
+                    let name = format!("{} ({})", step_result.step_name, iteration.label());
+                    suite.add_case(self.junit_case(name, step_result));
+                }
+            }
+        } else {
+            for step_result in &self.step_results {
+                suite.add_case(self.junit_case(step_result.step_name.clone(), step_result));
+            }
+        }
+
+        suite
+    }
+
+    /// Render as a JUnit XML test suite with one test case per step
+    pub fn to_junit_xml(&self) -> String {
+        self.test_suite().to_xml()
+    }
+
+    /// Render as a JSON report with one test case per step
+    pub fn to_json(&self) -> Result<String> {
+        self.test_suite().to_json()
+    }
+
+    /// Build a JUnit test case for a step result
+    fn junit_case(&self, name: String, step_result: &StepResult) -> JUnitTestCase {
+        let case = JUnitTestCase::new(name, self.chain_name.clone(), step_result.duration);
+        if step_result.success {
+            case
+        } else {
+            case.with_failure(
+                step_result
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| "Unknown error".to_string()),
+            )
+        }
+    }
 }
 
 /// Workflow executor
@@ -89,90 +265,363 @@ pub struct WorkflowExecutor {
 
     /// Variable substitutor
     substitutor: VariableSubstitutor,
+
+    /// Where step snapshots live (the default data directory if unset)
+    snapshots: Option<SnapshotStore>,
+
+    /// Authentication applied to every step
+    auth: Option<AuthScheme>,
 }
 
 impl WorkflowExecutor {
-    /// Create a new executor
+    /// Create a new executor with its own cookie jar
     pub fn new() -> Self {
         Self {
-            client: HttpClient::new(),
+            client: HttpClient::new().with_cookie_jar(Arc::new(Mutex::new(CookieJar::new()))),
             substitutor: VariableSubstitutor::new(),
+            snapshots: None,
+            auth: None,
         }
     }
 
+    /// Use a preconfigured client (e.g. without a cookie jar)
+    pub fn with_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Keep step snapshots in this store
+    pub fn with_snapshots(mut self, store: SnapshotStore) -> Self {
+        self.snapshots = Some(store);
+        self
+    }
+
+    /// Authenticate every step's request, e.g. from an `--auth` profile
+    pub fn with_auth(mut self, auth: AuthScheme) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
     /// Execute a request chain
     pub fn execute(&self, chain: &RequestChain) -> Result<ExecutionResult> {
         let mut result = ExecutionResult::new(chain.name.clone());
-        let mut context = ScriptContext::new();
+        let chain_start = Instant::now();
 
-        // Run for configured iterations
-        for iteration in 0..chain.config.iterations {
+        let mut initial_context = ScriptContext::new();
+        for (name, value) in &chain.variables {
+            initial_context.set_variable(name.clone(), value.clone());
+        }
+        let mut context = initial_context.clone();
+
+        // A data file runs one iteration per row instead of the configured count
+        let rows = match &chain.config.data_file {
+            Some(path) => Some(load_data_file(path)?),
+            None => None,
+        };
+        let iterations = rows.as_ref().map_or(chain.config.iterations, Vec::len);
+
+        for iteration in 0..iterations {
             if iteration > 0 {
                 // Apply delay between iterations
                 if let Some(delay) = chain.config.delay_between_requests {
                     std::thread::sleep(delay);
                 }
+
+                // Start from a clean slate so extracted values don't leak across iterations
+                if chain.config.iteration_isolation {
+                    context = initial_context.clone();
+                }
+            }
+
+            let mut iteration_result = IterationResult::new(iteration + 1);
+
+            // Seed the row's fields as variables before any step runs
+            if let Some(row) = rows.as_ref().map(|rows| &rows[iteration]) {
+                for (name, value) in &row.values {
+                    context.set_variable(name.clone(), value.clone());
+                }
+                iteration_result = iteration_result.with_data_row(row.clone());
             }
 
             // Execute each step
-            for step in &chain.steps {
+            for (index, step) in chain.steps.iter().enumerate() {
                 let step_start = Instant::now();
 
-                match self.execute_step(step, &mut context) {
-                    Ok(step_result) => {
-                        result.add_step_result(step_result.clone());
+                // Remaining time before the chain's max duration is reached
+                let budget = chain
+                    .config
+                    .max_duration
+                    .map(|max| max.saturating_sub(chain_start.elapsed()));
 
-                        // Check if we should stop on failure
-                        if !step_result.success
-                            && chain.config.stop_on_failure
-                            && !step.continue_on_error
-                        {
-                            break;
-                        }
-                    }
+                let step_result = match self.execute_step(step, &mut context, budget) {
+                    Ok(step_result) => step_result,
                     Err(e) => {
-                        let step_result = StepResult::failure(
-                            step.name.clone(),
-                            e.to_string(),
-                            step_start.elapsed(),
-                        );
-                        result.add_step_result(step_result);
-
-                        if chain.config.stop_on_failure && !step.continue_on_error {
-                            break;
+                        if let Error::DeadlineExceeded(_) = e {
+                            result.deadline_exceeded = true;
                         }
+                        StepResult::failure(step.name.clone(), e.to_string(), step_start.elapsed())
+                    }
+                };
+                let failed = !step_result.success;
+                iteration_result.add_step_result(step_result);
+
+                // Record the steps that never ran so reports show them as aborted
+                if result.deadline_exceeded {
+                    let budget = self
+                        .client
+                        .deadline()
+                        .map(|d| d.budget())
+                        .unwrap_or_default();
+                    for skipped in &chain.steps[index + 1..] {
+                        iteration_result.add_step_result(StepResult::failure(
+                            skipped.name.clone(),
+                            Error::DeadlineExceeded(budget).to_string(),
+                            Duration::ZERO,
+                        ));
                     }
+                    break;
                 }
-            }
 
-            // Check max duration
-            if let Some(max_duration) = chain.config.max_duration {
-                if result.total_duration >= max_duration {
+                if Self::max_duration_exceeded(chain, chain_start) {
+                    result.max_duration_exceeded = true;
+                    break;
+                }
+
+                // Check if we should stop on failure
+                if failed && chain.config.stop_on_failure && !step.continue_on_error {
                     break;
                 }
             }
-        }
 
-        // Extract final variables
-        for (name, var) in context.variables() {
-            result
-                .final_variables
-                .insert(name.clone(), var.value.clone());
+            iteration_result.variables = Self::snapshot_variables(&context);
+            result.add_iteration_result(iteration_result);
+
+            if result.deadline_exceeded {
+                break;
+            }
+            if result.max_duration_exceeded || Self::max_duration_exceeded(chain, chain_start) {
+                result.max_duration_exceeded = true;
+                break;
+            }
         }
 
+        result.final_variables = Self::snapshot_variables(&context);
+
         Ok(result)
     }
 
+    /// Resolve every step's request without sending anything
+    ///
+    /// Chain variables, the first data file row and pre-request scripts
+    /// apply; values a step would extract from a response aren't known, so
+    /// their placeholders stay.
+    pub fn dry_run(&self, chain: &RequestChain) -> Result<Vec<(String, RequestBuilder)>> {
+        let mut context = ScriptContext::new();
+        for (name, value) in &chain.variables {
+            context.set_variable(name.clone(), value.clone());
+        }
+        if let Some(path) = &chain.config.data_file {
+            if let Some(row) = load_data_file(path)?.first() {
+                for (name, value) in &row.values {
+                    context.set_variable(name.clone(), value.clone());
+                }
+            }
+        }
+
+        chain
+            .steps
+            .iter()
+            .map(|step| Ok((step.name.clone(), self.prepare_request(step, &mut context)?)))
+            .collect()
+    }
+
+    /// Check whether the chain has run past its max duration
+    fn max_duration_exceeded(chain: &RequestChain, chain_start: Instant) -> bool {
+        chain
+            .config
+            .max_duration
+            .is_some_and(|max| chain_start.elapsed() >= max)
+    }
+
+    /// Copy the current variable values out of a context
+    fn snapshot_variables(context: &ScriptContext) -> HashMap<String, String> {
+        context
+            .variables()
+            .iter()
+            .map(|(name, var)| (name.clone(), var.value.clone()))
+            .collect()
+    }
+
     /// Execute a single step
-    fn execute_step(&self, step: &WorkflowStep, context: &mut ScriptContext) -> Result<StepResult> {
+    fn execute_step(
+        &self,
+        step: &WorkflowStep,
+        context: &mut ScriptContext,
+        budget: Option<Duration>,
+    ) -> Result<StepResult> {
         let step_start = Instant::now();
+        let request = self.prepare_request(step, context)?;
 
-        // Execute pre-request script
+        // Send the request, re-sending while the step polls
+        let mut attempts = 0;
+        let response = loop {
+            attempts += 1;
+
+            // Apply the step timeout, capped by what's left of the chain's max duration
+            let remaining = budget.map(|budget| budget.saturating_sub(step_start.elapsed()));
+            let timeout = match (step.timeout, remaining) {
+                (Some(step_timeout), Some(remaining)) => Some(step_timeout.min(remaining)),
+                (timeout, remaining) => timeout.or(remaining),
+            };
+            let attempt = match timeout {
+                Some(timeout) => request.clone().timeout(timeout),
+                None => request.clone(),
+            };
+
+            let response = match self.client.execute(&attempt) {
+                Ok(response) => response,
+                Err(Error::Timeout(_)) => {
+                    let reason = if step.timeout == timeout {
+                        "step timeout"
+                    } else {
+                        "chain max duration"
+                    };
+                    return Ok(StepResult::failure(
+                        step.name.clone(),
+                        format!("Timed out after {:?} ({})", step_start.elapsed(), reason),
+                        step_start.elapsed(),
+                    )
+                    .with_attempts(attempts));
+                }
+                Err(e) => return Err(e),
+            };
+
+            let Some(polling) = &step.polling else {
+                break response;
+            };
+            let report = validate_response(&response, std::slice::from_ref(&polling.until))?;
+            let Err(unmet) = report.ensure_success() else {
+                break response;
+            };
+
+            // Give up once attempts run out or the next wait would overrun the chain
+            let stopped_by = if attempts >= polling.max_attempts {
+                Some("attempts exhausted")
+            } else if remaining.is_some_and(|remaining| {
+                remaining.saturating_sub(response.duration) <= polling.interval
+            }) {
+                Some("chain max duration")
+            } else {
+                None
+            };
+            if let Some(reason) = stopped_by {
+                return Ok(StepResult::failure(
+                    step.name.clone(),
+                    format!(
+                        "Polling stopped after {} attempt(s) ({}), last status {}: {}",
+                        attempts,
+                        reason,
+                        response.status.as_u16(),
+                        unmet
+                    ),
+                    step_start.elapsed(),
+                )
+                .with_attempts(attempts)
+                .with_response(response));
+            }
+            std::thread::sleep(polling.interval);
+        };
+
+        // Store response data in context
+        context.set_response_data("status".to_string(), response.status.as_u16().to_string());
+        context.set_response_data("body".to_string(), response.body.clone());
+
+        // Execute post-response script
+        if let Some(ref script) = step.post_response_script {
+            execute_post_response(script, context)?;
+        }
+
+        // Validate assertions
+        if !step.assertions.is_empty() {
+            let validation_report = validate_response(&response, &step.assertions)?;
+            if let Err(e) = validation_report.ensure_success() {
+                return Ok(StepResult::failure(
+                    step.name.clone(),
+                    e.to_string(),
+                    step_start.elapsed(),
+                )
+                .with_attempts(attempts));
+            }
+        }
+
+        // Compare with the step's snapshot
+        if let Some(name) = &step.snapshot {
+            let store = match &self.snapshots {
+                Some(store) => store.clone(),
+                None => SnapshotStore::open_default()?,
+            };
+            if let SnapshotOutcome::Changed(changes) =
+                store.check(name, &response, &step.snapshot_rules)?
+            {
+                return Ok(StepResult::failure(
+                    step.name.clone(),
+                    format!("Snapshot '{}' changed:\n  {}", name, changes.join("\n  ")),
+                    step_start.elapsed(),
+                )
+                .with_attempts(attempts));
+            }
+        }
+
+        // Extract variables from response
+        let mut extracted = HashMap::new();
+        for (var_name, json_path) in &step.extract_variables {
+            let value = if let Some(metric) = json_path.strip_prefix("server-timing:") {
+                response
+                    .server_timing()
+                    .into_iter()
+                    .find(|entry| entry.name.eq_ignore_ascii_case(metric))
+                    .and_then(|entry| entry.duration)
+                    .map(|duration| duration.to_string())
+            } else {
+                serde_json::from_str::<serde_json::Value>(&response.body)
+                    .ok()
+                    .map(|json| self.extract_json_value(&json, json_path))
+            };
+
+            if let Some(value) = value {
+                context.set_variable(var_name.clone(), value.clone());
+                extracted.insert(var_name.clone(), value);
+            }
+        }
+
+        Ok(
+            StepResult::success(step.name.clone(), response, extracted, step_start.elapsed())
+                .with_attempts(attempts),
+        )
+    }
+
+    /// Run a step's pre-request script and build its request
+    fn prepare_request(
+        &self,
+        step: &WorkflowStep,
+        context: &mut ScriptContext,
+    ) -> Result<RequestBuilder> {
+        // Execute pre-request script, letting it see the request as it stands
         if let Some(ref script) = step.pre_request_script {
+            let request = self.build_request(step, context);
+            context.set_request_data("method".to_string(), request.method.as_str().to_string());
+            context.set_request_data("url".to_string(), request.url);
+            context.set_request_data("headers".to_string(), request.headers.join("\n"));
+            context.set_request_data("body".to_string(), request.body.unwrap_or_default());
             execute_pre_request(script, context)?;
         }
 
-        // Build request with variable substitution
+        // Build the request with whatever the script changed
+        Ok(self.build_request(step, context))
+    }
+
+    /// Build a step's request, substituting the context's variables
+    fn build_request(&self, step: &WorkflowStep, context: &ScriptContext) -> RequestBuilder {
         let mut variables = HashMap::new();
         for (name, var) in context.variables() {
             variables.insert(name.as_str(), var.value.as_str());
@@ -199,46 +648,11 @@ impl WorkflowExecutor {
             request = request.body(substituted);
         }
 
-        // Execute request
-        let response = self.client.execute(&request)?;
-
-        // Store response data in context
-        context.set_response_data("status".to_string(), response.status.as_u16().to_string());
-        context.set_response_data("body".to_string(), response.body.clone());
-
-        // Execute post-response script
-        if let Some(ref script) = step.post_response_script {
-            execute_post_response(script, context)?;
-        }
-
-        // Validate assertions
-        if !step.assertions.is_empty() {
-            let validation_report = validate_response(&response, &step.assertions)?;
-            if !validation_report.success {
-                return Ok(StepResult::failure(
-                    step.name.clone(),
-                    format!("Assertions failed: {}", validation_report.summary()),
-                    step_start.elapsed(),
-                ));
-            }
-        }
-
-        // Extract variables from response
-        let mut extracted = HashMap::new();
-        for (var_name, json_path) in &step.extract_variables {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&response.body) {
-                let value = self.extract_json_value(&json, json_path);
-                context.set_variable(var_name.clone(), value.clone());
-                extracted.insert(var_name.clone(), value);
-            }
+        if let Some(auth) = &self.auth {
+            request = request.auth(auth.clone());
         }
 
-        Ok(StepResult::success(
-            step.name.clone(),
-            response,
-            extracted,
-            step_start.elapsed(),
-        ))
+        request
     }
 
     /// Extract value from JSON using simplified path
@@ -297,7 +711,9 @@ mod tests {
             status: StatusCode::OK,
             headers: HeaderMap::new(),
             body: "ok".to_string(),
+            body_bytes: b"ok".to_vec(),
             duration: Duration::from_millis(100),
+            timing: None,
         };
 
         let step_result = StepResult::success(
@@ -329,6 +745,639 @@ mod tests {
         assert_eq!(result.step_results.len(), 1);
     }
 
+    #[test]
+    fn test_execution_result_add_iteration() {
+        let mut result = ExecutionResult::new("Test".to_string());
+
+        let mut first = IterationResult::new(1);
+        first.add_step_result(StepResult::failure(
+            "Step1".to_string(),
+            "Failed".to_string(),
+            Duration::from_millis(50),
+        ));
+        result.add_iteration_result(first);
+        result.add_iteration_result(IterationResult::new(2));
+
+        assert!(!result.success);
+        assert_eq!(result.step_results.len(), 1);
+        assert_eq!(result.iterations.len(), 2);
+        assert_eq!(result.failed_iterations()[0].iteration, 1);
+        assert!(result.detailed_report().contains("✗ Iteration 1"));
+    }
+
+    fn junit_cases(xml: &str) -> (usize, Vec<(String, Option<String>)>) {
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let suite = doc.root_element();
+        let failures = suite.attribute("failures").unwrap().parse().unwrap();
+        let cases = suite
+            .children()
+            .filter(|n| n.has_tag_name("testcase"))
+            .map(|case| {
+                let failure = case
+                    .children()
+                    .find(|n| n.has_tag_name("failure"))
+                    .and_then(|f| f.attribute("message").map(str::to_string));
+                (case.attribute("name").unwrap().to_string(), failure)
+            })
+            .collect();
+        (failures, cases)
+    }
+
+    #[test]
+    fn test_execution_result_to_junit_xml() {
+        let mut result = ExecutionResult::new("Smoke".to_string());
+        result.add_step_result(StepResult::failure(
+            "Login".to_string(),
+            "Timed out".to_string(),
+            Duration::from_millis(1234),
+        ));
+
+        let xml = result.to_junit_xml();
+        crate::report::junit::assert_junit_schema(&xml);
+        let (failures, cases) = junit_cases(&xml);
+        assert_eq!(failures, 1);
+        assert_eq!(
+            cases,
+            vec![("Login".to_string(), Some("Timed out".to_string()))]
+        );
+        assert!(xml.contains(r#"name="Smoke""#));
+        assert!(xml.contains(r#"time="1.234""#));
+
+        let json: serde_json::Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+        assert_eq!(json["name"], "Smoke");
+        assert_eq!(json["time"], 1.234);
+        assert_eq!(json["testcases"][0]["failure"], "Timed out");
+    }
+
+    #[test]
+    fn test_execution_result_to_junit_xml_iterations() {
+        let mut result = ExecutionResult::new("Smoke".to_string());
+        let mut first = IterationResult::new(1);
+        first.add_step_result(StepResult::failure(
+            "Ping".to_string(),
+            "Failed".to_string(),
+            Duration::from_millis(5),
+        ));
+        let mut second = IterationResult::new(2);
+        second.add_step_result(StepResult::failure(
+            "Ping".to_string(),
+            "Failed".to_string(),
+            Duration::from_millis(5),
+        ));
+        result.add_iteration_result(first);
+        result.add_iteration_result(second);
+
+        let (failures, cases) = junit_cases(&result.to_junit_xml());
+        assert_eq!(failures, 2);
+        assert_eq!(cases[0].0, "Ping (iteration 1)");
+        assert_eq!(cases[1].0, "Ping (iteration 2)");
+    }
+
+    fn counting_chain(url: String, isolated: bool) -> RequestChain {
+        let step = WorkflowStep::new("Ping".to_string(), crate::http::HttpMethod::Get, url)
+            .with_pre_request_script(crate::scripts::Script::pre_request(
+                r#"seen = seen + "x";"#.to_string(),
+            ));
+
+        RequestChain::new("Counting".to_string())
+            .with_variable("seen".to_string(), String::new())
+            .add_step(step)
+            .with_config(
+                crate::workflow::ChainConfig::new()
+                    .with_iterations(3)
+                    .with_iteration_isolation(isolated),
+            )
+    }
+
+    #[test]
+    fn test_execute_iterations_shared_context() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/ping")
+            .with_body("pong")
+            .expect(3)
+            .create();
+
+        let chain = counting_chain(format!("{}/ping", server.url()), false);
+        let result = WorkflowExecutor::new().execute(&chain).unwrap();
+
+        mock.assert();
+        assert!(result.success);
+        assert_eq!(result.iterations.len(), 3);
+        assert_eq!(result.iterations[0].variables["seen"], "x");
+        assert_eq!(result.iterations[2].variables["seen"], "xxx");
+        assert_eq!(result.final_variables["seen"], "xxx");
+    }
+
+    #[test]
+    fn test_execute_iterations_isolated_context() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/ping")
+            .with_body("pong")
+            .expect(3)
+            .create();
+
+        let chain = counting_chain(format!("{}/ping", server.url()), true);
+        let result = WorkflowExecutor::new().execute(&chain).unwrap();
+
+        mock.assert();
+        assert!(result.success);
+        for iteration in &result.iterations {
+            assert_eq!(iteration.variables["seen"], "x");
+        }
+        assert_eq!(result.final_variables["seen"], "x");
+    }
+
+    #[test]
+    fn test_execute_empty_data_file_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("users.csv");
+        std::fs::write(&data, "name\n").unwrap();
+
+        let step = WorkflowStep::new(
+            "User".to_string(),
+            crate::http::HttpMethod::Get,
+            "http://127.0.0.1:9/users/{{name}}".to_string(),
+        );
+        let chain = RequestChain::new("Users".to_string())
+            .add_step(step)
+            .with_config(crate::workflow::ChainConfig::new().with_data_file(&data));
+
+        let error = WorkflowExecutor::new().execute(&chain).unwrap_err();
+        assert!(error.to_string().contains("no rows"), "{}", error);
+    }
+
+    #[test]
+    fn test_execute_data_file_rows() {
+        let mut server = mockito::Server::new();
+        let ok = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex("^/users/(ada|grace)$".to_string()),
+            )
+            .with_body(r#"{"ok":true}"#)
+            .expect(2)
+            .create();
+        let missing = server
+            .mock("GET", "/users/alan")
+            .with_status(404)
+            .expect(1)
+            .create();
+
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("users.csv");
+        std::fs::write(&data, "name,role\nada,admin\nalan,user\ngrace,admin\n").unwrap();
+
+        let step = WorkflowStep::new(
+            "User".to_string(),
+            crate::http::HttpMethod::Get,
+            format!("{}/users/{{{{name}}}}", server.url()),
+        )
+        .with_assertion(crate::assertions::Assertion::status_code(
+            crate::assertions::Matcher::equals(200),
+        ));
+        let chain = RequestChain::new("Users".to_string())
+            .add_step(step)
+            .with_config(crate::workflow::ChainConfig::new().with_data_file(&data));
+
+        let result = WorkflowExecutor::new().execute(&chain).unwrap();
+
+        ok.assert();
+        missing.assert();
+        assert!(!result.success);
+        assert_eq!(result.iterations.len(), 3);
+        assert_eq!(result.iterations[2].variables["name"], "grace");
+
+        let failed = result.failed_iterations();
+        assert_eq!(failed.len(), 1);
+        let row = failed[0].data_row.as_ref().unwrap();
+        assert_eq!(row.index, 2);
+        assert_eq!(row.get("name"), Some("alan"));
+
+        let report = result.detailed_report();
+        assert!(report.contains("✗ Iteration 2, row 2 (name=alan, role=user)"));
+        assert!(report.contains("Failed rows: 2"));
+        let (_, cases) = junit_cases(&result.to_junit_xml());
+        assert_eq!(cases[1].0, "User (row 2)");
+    }
+
+    #[test]
+    fn test_execute_polling_step_until_complete() {
+        let mut server = mockito::Server::new();
+        let pending = server
+            .mock("GET", "/jobs/1")
+            .with_body(r#"{"state":"pending"}"#)
+            .expect(2)
+            .create();
+        let complete = server
+            .mock("GET", "/jobs/1")
+            .with_body(r#"{"state":"complete"}"#)
+            .create();
+
+        let until = crate::assertions::Assertion::json_path(
+            "$.state".to_string(),
+            crate::assertions::Matcher::equals_str("complete"),
+        );
+        let step = WorkflowStep::new(
+            "Job".to_string(),
+            crate::http::HttpMethod::Get,
+            format!("{}/jobs/1", server.url()),
+        )
+        .with_polling(crate::workflow::PollingConfig::new(
+            Duration::from_millis(10),
+            5,
+            until.clone(),
+        ));
+        let chain = RequestChain::new("Poll".to_string()).add_step(step);
+
+        let result = WorkflowExecutor::new().execute(&chain).unwrap();
+
+        pending.assert();
+        complete.assert();
+        assert!(result.success);
+        let step_result = &result.step_results[0];
+        assert_eq!(step_result.attempts, 3);
+        assert_eq!(step_result.last_status(), Some(200));
+        assert!(step_result.summary().contains("3 attempts"));
+
+        // Running out of attempts fails the step with the last response attached
+        let step = WorkflowStep::new(
+            "Job".to_string(),
+            crate::http::HttpMethod::Get,
+            format!("{}/jobs/2", server.url()),
+        )
+        .with_polling(crate::workflow::PollingConfig::new(
+            Duration::from_millis(10),
+            2,
+            until,
+        ));
+        let stuck = server
+            .mock("GET", "/jobs/2")
+            .with_body(r#"{"state":"pending"}"#)
+            .expect(2)
+            .create();
+        let result = WorkflowExecutor::new()
+            .execute(&RequestChain::new("Stuck".to_string()).add_step(step))
+            .unwrap();
+
+        stuck.assert();
+        assert!(!result.success);
+        let step_result = &result.step_results[0];
+        assert_eq!(step_result.attempts, 2);
+        assert_eq!(
+            step_result.response.as_ref().unwrap().body,
+            r#"{"state":"pending"}"#
+        );
+        let error = step_result.error.as_ref().unwrap();
+        assert!(error.contains("Polling stopped after 2 attempt(s) (attempts exhausted)"));
+        assert!(error.contains("last status 200"));
+    }
+
+    /// Start a server that accepts connections but never answers
+    fn silent_server() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut held = Vec::new();
+            for stream in listener.incoming().flatten() {
+                held.push(stream);
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_execute_step_timeout() {
+        let url = silent_server();
+        let step = WorkflowStep::new("Slow".to_string(), crate::http::HttpMethod::Get, url)
+            .with_timeout(Duration::from_millis(200));
+        let chain = RequestChain::new("Timeout".to_string()).add_step(step);
+
+        let started = Instant::now();
+        let result = WorkflowExecutor::new().execute(&chain).unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!result.success);
+        let error = result.step_results[0].error.as_ref().unwrap();
+        assert!(error.contains("Timed out"));
+        assert!(error.contains("step timeout"));
+    }
+
+    #[test]
+    fn test_execute_max_duration_cuts_off_mid_chain() {
+        let mut server = mockito::Server::new();
+        let fast = server
+            .mock("GET", "/fast")
+            .with_body("ok")
+            .expect(0)
+            .create();
+
+        let chain = RequestChain::new("Budget".to_string())
+            .add_step(WorkflowStep::new(
+                "Slow".to_string(),
+                crate::http::HttpMethod::Get,
+                silent_server(),
+            ))
+            .add_step(WorkflowStep::new(
+                "Fast".to_string(),
+                crate::http::HttpMethod::Get,
+                format!("{}/fast", server.url()),
+            ))
+            .with_config(
+                crate::workflow::ChainConfig::new()
+                    .with_stop_on_failure(false)
+                    .with_max_duration(Duration::from_millis(300)),
+            );
+
+        let result = WorkflowExecutor::new().execute(&chain).unwrap();
+
+        fast.assert();
+        assert!(result.max_duration_exceeded);
+        assert_eq!(result.step_results.len(), 1);
+        assert!(result.step_results[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("chain max duration"));
+        assert!(result.summary().contains("max duration exceeded"));
+    }
+
+    #[test]
+    fn test_execute_deadline_aborts_remaining_steps() {
+        let mut server = mockito::Server::new();
+        let fast = server
+            .mock("GET", "/fast")
+            .with_body("ok")
+            .expect(0)
+            .create();
+
+        let chain = RequestChain::new("Deadline".to_string())
+            .add_step(
+                WorkflowStep::new(
+                    "Slow".to_string(),
+                    crate::http::HttpMethod::Get,
+                    silent_server(),
+                )
+                .with_timeout(Duration::from_secs(30)),
+            )
+            .add_step(WorkflowStep::new(
+                "Fast".to_string(),
+                crate::http::HttpMethod::Get,
+                format!("{}/fast", server.url()),
+            ))
+            .with_config(crate::workflow::ChainConfig::new().with_iterations(2));
+
+        let client = HttpClient::new()
+            .with_deadline(crate::http::Deadline::after(Duration::from_millis(300)));
+        let started = Instant::now();
+        let result = WorkflowExecutor::new()
+            .with_client(client)
+            .execute(&chain)
+            .unwrap();
+
+        // The 30s step timeout loses to the 300ms budget
+        assert!(started.elapsed() < Duration::from_secs(2));
+        fast.assert();
+        assert!(result.deadline_exceeded);
+        assert_eq!(result.iterations.len(), 1);
+        assert_eq!(result.step_results.len(), 2);
+        for step in &result.step_results {
+            assert!(step.error.as_ref().unwrap().contains("--max-time"));
+        }
+        assert!(result.summary().contains("--max-time exceeded"));
+    }
+
+    #[test]
+    fn test_pre_request_script_reads_request() {
+        let mut server = mockito::Server::new();
+        let _mock = server.mock("POST", "/orders").with_status(201).create();
+
+        let step = WorkflowStep::new(
+            "Order".to_string(),
+            crate::http::HttpMethod::Post,
+            format!("{}/{{{{resource}}}}", server.url()),
+        )
+        .with_header("X-Trace:{{trace}}".to_string())
+        .with_body(r#"{"qty":{{qty}}}"#.to_string())
+        .with_pre_request_script(crate::scripts::Script::pre_request(
+            r#"let sent_method = request["method"];
+               let sent_url = request["url"];
+               let sent_headers = request["headers"];
+               let sent_body = request["body"];"#
+                .to_string(),
+        ));
+        let chain = RequestChain::new("Request data".to_string())
+            .with_variable("resource".to_string(), "orders".to_string())
+            .with_variable("trace".to_string(), "abc".to_string())
+            .with_variable("qty".to_string(), "2".to_string())
+            .add_step(step);
+
+        let result = WorkflowExecutor::new().execute(&chain).unwrap();
+        assert!(result.success);
+        let variables = &result.final_variables;
+        assert_eq!(variables["sent_method"], "POST");
+        assert_eq!(variables["sent_url"], format!("{}/orders", server.url()));
+        assert_eq!(variables["sent_headers"], "X-Trace:abc");
+        assert_eq!(variables["sent_body"], r#"{"qty":2}"#);
+    }
+
+    #[test]
+    fn test_step_snapshot() {
+        let mut server = mockito::Server::new();
+        let dir = tempfile::tempdir().unwrap();
+        let step = WorkflowStep::new(
+            "User".to_string(),
+            crate::http::HttpMethod::Get,
+            format!("{}/user", server.url()),
+        )
+        .with_snapshot(
+            "user".to_string(),
+            crate::assertions::SnapshotRules::new().with_ignore("$.id"),
+        );
+        let chain = RequestChain::new("Snapshots".to_string()).add_step(step);
+        let executor = WorkflowExecutor::new().with_snapshots(SnapshotStore::new(dir.path()));
+
+        let first = server
+            .mock("GET", "/user")
+            .with_body(r#"{"id":1,"name":"a"}"#)
+            .create();
+        assert!(executor.execute(&chain).unwrap().success);
+        first.remove();
+
+        let _second = server
+            .mock("GET", "/user")
+            .with_body(r#"{"id":2,"name":"b"}"#)
+            .create();
+        let result = executor.execute(&chain).unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            result.step_results[0].error.as_deref(),
+            Some("Snapshot 'user' changed:\n  ~ $.name: expected \"a\", got \"b\"")
+        );
+    }
+
+    #[test]
+    fn test_dry_run_sends_nothing() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", mockito::Matcher::Any)
+            .expect(0)
+            .create();
+
+        let login = WorkflowStep::new(
+            "Login".to_string(),
+            crate::http::HttpMethod::Post,
+            format!("{}/login", server.url()),
+        )
+        .with_header("Content-Type: application/json".to_string())
+        .with_body(r#"{"user":"{{user}}"}"#.to_string());
+        let order = WorkflowStep::new(
+            "Order".to_string(),
+            crate::http::HttpMethod::Post,
+            format!("{}/orders", server.url()),
+        )
+        .with_header("Authorization: Bearer {{token}}".to_string())
+        .with_pre_request_script(crate::scripts::Script::pre_request(
+            r#"let trace = "t-1";"#.to_string(),
+        ))
+        .with_query("trace={{trace}}".to_string());
+        let chain = RequestChain::new("Dry run".to_string())
+            .with_variable("user".to_string(), "alice".to_string())
+            .add_step(login)
+            .add_step(order);
+
+        let requests = WorkflowExecutor::new().dry_run(&chain).unwrap();
+        mock.assert();
+
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].0, "Login");
+        assert_eq!(requests[0].1.body.as_deref(), Some(r#"{"user":"alice"}"#));
+        assert_eq!(requests[1].1.query_params, vec!["trace=t-1"]);
+
+        let preview = requests[1].1.preview(true);
+        assert!(preview.contains(&format!("{}/orders?trace=t-1", server.url())));
+        assert!(preview.contains("Authorization: Bearer {{token}}"));
+    }
+
+    #[test]
+    fn test_execute_extracts_server_timing() {
+        let mut server = mockito::Server::new();
+        let _mock = server
+            .mock("GET", "/timed")
+            .with_header("server-timing", "db;dur=12.5, cache;dur=0.4")
+            .with_body("ok")
+            .create();
+
+        let step = WorkflowStep::new(
+            "Timed".to_string(),
+            crate::http::HttpMethod::Get,
+            format!("{}/timed", server.url()),
+        )
+        .extract_variable("db_ms".to_string(), "server-timing:db".to_string());
+        let chain = RequestChain::new("Timing".to_string()).add_step(step);
+
+        let result = WorkflowExecutor::new().execute(&chain).unwrap();
+        assert_eq!(result.final_variables["db_ms"], "12.5");
+    }
+
+    #[test]
+    fn test_execute_carries_cookies_between_steps() {
+        let mut server = mockito::Server::new();
+        let login = server
+            .mock("POST", "/login")
+            .with_header("set-cookie", "session=abc123; Path=/; HttpOnly")
+            .create();
+        let profile = server
+            .mock("GET", "/profile")
+            .match_header("cookie", "session=abc123")
+            .with_body("ok")
+            .create();
+
+        let chain = RequestChain::new("login".to_string())
+            .add_step(WorkflowStep::new(
+                "login".to_string(),
+                crate::http::HttpMethod::Post,
+                format!("{}/login", server.url()),
+            ))
+            .add_step(
+                WorkflowStep::new(
+                    "profile".to_string(),
+                    crate::http::HttpMethod::Get,
+                    format!("{}/profile", server.url()),
+                )
+                .with_assertion(crate::assertions::Assertion::status_code(
+                    crate::assertions::Matcher::equals(200),
+                )),
+            );
+
+        let result = WorkflowExecutor::new().execute(&chain).unwrap();
+        login.assert();
+        profile.assert();
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_execute_applies_auth_to_every_step() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", mockito::Matcher::Regex("^/items/[12]$".to_string()))
+            .match_header("authorization", "Bearer s3cret")
+            .expect(2)
+            .create();
+
+        let step = |n: usize| {
+            WorkflowStep::new(
+                format!("item {}", n),
+                crate::http::HttpMethod::Get,
+                format!("{}/items/{}", server.url(), n),
+            )
+        };
+        let chain = RequestChain::new("items".to_string())
+            .add_step(step(1))
+            .add_step(step(2));
+
+        let result = WorkflowExecutor::new()
+            .with_auth(AuthScheme::Bearer(crate::auth::BearerAuth::new(
+                "s3cret".to_string(),
+            )))
+            .execute(&chain)
+            .unwrap();
+        assert!(result.success);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_execute_without_cookie_jar() {
+        let mut server = mockito::Server::new();
+        let _login = server
+            .mock("POST", "/login")
+            .with_header("set-cookie", "session=abc123")
+            .create();
+        let profile = server
+            .mock("GET", "/profile")
+            .match_header("cookie", mockito::Matcher::Missing)
+            .create();
+
+        let chain = RequestChain::new("login".to_string())
+            .add_step(WorkflowStep::new(
+                "login".to_string(),
+                crate::http::HttpMethod::Post,
+                format!("{}/login", server.url()),
+            ))
+            .add_step(WorkflowStep::new(
+                "profile".to_string(),
+                crate::http::HttpMethod::Get,
+                format!("{}/profile", server.url()),
+            ));
+
+        WorkflowExecutor::new()
+            .with_client(HttpClient::new())
+            .execute(&chain)
+            .unwrap();
+        profile.assert();
+    }
+
     #[test]
     fn test_executor_creation() {
         let _executor = WorkflowExecutor::new();
@@ -1,12 +1,17 @@
 //! Request chaining and workflow execution
 
 pub mod chain;
+pub mod data;
+pub mod duration;
 pub mod executor;
 pub mod step;
+pub mod stream;
 
 pub use chain::{ChainConfig, RequestChain};
-pub use executor::{ExecutionResult, WorkflowExecutor};
-pub use step::{StepResult, WorkflowStep};
+pub use data::{load_data_file, DataRow};
+pub use executor::{ExecutionResult, IterationResult, WorkflowExecutor};
+pub use step::{PollingConfig, StepResult, WorkflowStep};
+pub use stream::{RequestSpec, StreamResult, StreamRunner};
 
 use crate::error::Result;
 
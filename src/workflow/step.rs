@@ -1,6 +1,6 @@
 //! Workflow step definition
 
-use crate::assertions::Assertion;
+use crate::assertions::{parse_assertion, Assertion, SnapshotRules};
 use crate::http::{HttpMethod, HttpResponse};
 use crate::scripts::Script;
 use serde::{Deserialize, Serialize};
@@ -20,31 +20,137 @@ pub struct WorkflowStep {
     pub url: String,
 
     /// Headers
+    #[serde(default)]
     pub headers: Vec<String>,
 
     /// Query parameters
+    #[serde(default)]
     pub query_params: Vec<String>,
 
     /// Request body
+    #[serde(default)]
     pub body: Option<String>,
 
     /// Pre-request script
+    #[serde(default)]
     pub pre_request_script: Option<Script>,
 
     /// Post-response script
+    #[serde(default)]
     pub post_response_script: Option<Script>,
 
-    /// Assertions to validate
+    /// Assertions to validate, written in full or as expressions like `status == 200`
+    #[serde(
+        default,
+        serialize_with = "serialize_assertions",
+        deserialize_with = "deserialize_assertions"
+    )]
     pub assertions: Vec<Assertion>,
 
     /// Whether to continue on failure
+    #[serde(default)]
     pub continue_on_error: bool,
 
     /// Timeout for this step
+    #[serde(default, with = "crate::workflow::duration::option")]
     pub timeout: Option<Duration>,
 
     /// Variables to extract from response
+    #[serde(default, alias = "extract")]
     pub extract_variables: HashMap<String, String>,
+
+    /// Name of the snapshot to compare the response with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<String>,
+
+    /// What the snapshot keeps and ignores
+    #[serde(default)]
+    pub snapshot_rules: SnapshotRules,
+
+    /// Re-send the request until a condition holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polling: Option<PollingConfig>,
+}
+
+/// How a step polls: re-send the request until `until` passes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollingConfig {
+    /// Wait between attempts
+    #[serde(with = "crate::workflow::duration")]
+    pub interval: Duration,
+
+    /// Most requests sent, including the first
+    pub max_attempts: usize,
+
+    /// Condition that ends polling
+    #[serde(
+        serialize_with = "serialize_assertion",
+        deserialize_with = "deserialize_assertion"
+    )]
+    pub until: Assertion,
+}
+
+/// An assertion as written in a workflow file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WrittenAssertion {
+    Expression(String),
+    Full(Assertion),
+}
+
+impl WrittenAssertion {
+    fn parse<E: serde::de::Error>(self) -> std::result::Result<Assertion, E> {
+        match self {
+            WrittenAssertion::Expression(expr) => parse_assertion(&expr).map_err(E::custom),
+            WrittenAssertion::Full(assertion) => Ok(assertion),
+        }
+    }
+}
+
+/// Write an assertion with its enums as maps, which YAML can read back
+/// (YAML tags can't be read through `WrittenAssertion`)
+fn serialize_assertion<S: serde::Serializer>(
+    assertion: &Assertion,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serde_json::to_value(assertion)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+fn serialize_assertions<S: serde::Serializer>(
+    assertions: &[Assertion],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serde_json::to_value(assertions)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+fn deserialize_assertion<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Assertion, D::Error> {
+    WrittenAssertion::deserialize(deserializer)?.parse()
+}
+
+fn deserialize_assertions<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<Assertion>, D::Error> {
+    Vec::<WrittenAssertion>::deserialize(deserializer)?
+        .into_iter()
+        .map(WrittenAssertion::parse)
+        .collect()
+}
+
+impl PollingConfig {
+    /// Create a polling config
+    pub fn new(interval: Duration, max_attempts: usize, until: Assertion) -> Self {
+        Self {
+            interval,
+            max_attempts,
+            until,
+        }
+    }
 }
 
 impl WorkflowStep {
@@ -63,6 +169,9 @@ impl WorkflowStep {
             continue_on_error: false,
             timeout: None,
             extract_variables: HashMap::new(),
+            snapshot: None,
+            snapshot_rules: SnapshotRules::new(),
+            polling: None,
         }
     }
 
@@ -114,6 +223,19 @@ impl WorkflowStep {
         self
     }
 
+    /// Compare the response with a named snapshot
+    pub fn with_snapshot(mut self, name: String, rules: SnapshotRules) -> Self {
+        self.snapshot = Some(name);
+        self.snapshot_rules = rules;
+        self
+    }
+
+    /// Re-send the request until the polling condition passes
+    pub fn with_polling(mut self, polling: PollingConfig) -> Self {
+        self.polling = Some(polling);
+        self
+    }
+
     /// Extract variable from response
     pub fn extract_variable(mut self, var_name: String, json_path: String) -> Self {
         self.extract_variables.insert(var_name, json_path);
@@ -141,6 +263,9 @@ pub struct StepResult {
 
     /// Execution duration
     pub duration: Duration,
+
+    /// Requests sent (more than one when the step polls)
+    pub attempts: usize,
 }
 
 impl StepResult {
@@ -158,6 +283,7 @@ impl StepResult {
             error: None,
             extracted_variables,
             duration,
+            attempts: 1,
         }
     }
 
@@ -170,19 +296,45 @@ impl StepResult {
             error: Some(error),
             extracted_variables: HashMap::new(),
             duration,
+            attempts: 1,
         }
     }
 
+    /// Attach the response the step failed on
+    pub fn with_response(mut self, response: HttpResponse) -> Self {
+        self.response = Some(response);
+        self
+    }
+
+    /// Record how many requests were sent
+    pub fn with_attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Status code of the last response, if any
+    pub fn last_status(&self) -> Option<u16> {
+        self.response
+            .as_ref()
+            .map(|response| response.status.as_u16())
+    }
+
     /// Get summary
     pub fn summary(&self) -> String {
+        let attempts = if self.attempts > 1 {
+            format!(", {} attempts", self.attempts)
+        } else {
+            String::new()
+        };
         if self.success {
-            format!("✓ {} - {:?}", self.step_name, self.duration)
+            format!("✓ {} - {:?}{}", self.step_name, self.duration, attempts)
         } else {
             format!(
-                "✗ {} - {} ({:?})",
+                "✗ {} - {} ({:?}{})",
                 self.step_name,
                 self.error.as_ref().unwrap_or(&"Unknown error".to_string()),
-                self.duration
+                self.duration,
+                attempts
             )
         }
     }
@@ -266,7 +418,9 @@ mod tests {
             status: StatusCode::OK,
             headers: HeaderMap::new(),
             body: "success".to_string(),
+            body_bytes: b"success".to_vec(),
             duration: Duration::from_millis(100),
+            timing: None,
         };
 
         let result = StepResult::success(
@@ -0,0 +1,369 @@
+//! Bulk execution of request specs streamed as JSON lines
+
+use crate::assertions::{parse_assertion, validate_response, Assertion};
+use crate::auth::AuthScheme;
+use crate::error::{Error, Result};
+use crate::http::{HttpClient, HttpMethod, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+/// One request spec (field names follow `RequestItem`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestSpec {
+    /// Optional name echoed back in the result
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// HTTP method
+    #[serde(default = "default_method")]
+    pub method: String,
+
+    /// Request URL
+    pub url: String,
+
+    /// Headers
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Query parameters
+    #[serde(default)]
+    pub query_params: HashMap<String, String>,
+
+    /// Body (strings are sent as-is, other JSON values are serialized)
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+
+    /// Assertion expressions (e.g. "status == 200")
+    #[serde(default)]
+    pub assert: Vec<String>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+impl RequestSpec {
+    /// Parse a spec from one JSON line
+    pub fn parse(line: &str) -> Result<Self> {
+        Ok(serde_json::from_str(line)?)
+    }
+
+    /// Convert to a request builder
+    pub fn to_request_builder(&self) -> Result<RequestBuilder> {
+        let method = HttpMethod::parse(&self.method)?;
+        let mut builder = RequestBuilder::new(method, self.url.clone());
+
+        for (key, value) in &self.headers {
+            builder = builder.header(format!("{}:{}", key, value));
+        }
+        for (key, value) in &self.query_params {
+            builder = builder.query(format!("{}={}", key, value));
+        }
+        match &self.body {
+            None | Some(serde_json::Value::Null) => {}
+            Some(serde_json::Value::String(body)) => builder = builder.body(body.clone()),
+            Some(body) => builder = builder.body(body.to_string()),
+        }
+
+        Ok(builder)
+    }
+
+    /// Parse the assertion expressions
+    pub fn assertions(&self) -> Result<Vec<Assertion>> {
+        self.assert
+            .iter()
+            .map(|expr| parse_assertion(expr))
+            .collect()
+    }
+}
+
+/// Result line emitted for each input line
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StreamResult {
+    /// Input line number (1-based)
+    pub line: usize,
+
+    /// Spec name, if given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// HTTP method
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+
+    /// Request URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Response status code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+
+    /// Duration in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u128>,
+
+    /// Number of assertions that passed
+    pub assertions_passed: usize,
+
+    /// Number of assertions that failed
+    pub assertions_failed: usize,
+
+    /// Descriptions of failed assertions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+
+    /// Error (malformed line or transport failure)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Overall success
+    pub success: bool,
+}
+
+impl StreamResult {
+    fn error(line: usize, spec: Option<&RequestSpec>, error: &Error) -> Self {
+        Self {
+            line,
+            name: spec.and_then(|s| s.name.clone()),
+            method: spec.map(|s| s.method.to_uppercase()),
+            url: spec.map(|s| s.url.clone()),
+            status: None,
+            duration_ms: None,
+            assertions_passed: 0,
+            assertions_failed: 0,
+            failures: Vec::new(),
+            error: Some(error.to_string()),
+            success: false,
+        }
+    }
+}
+
+/// Executes JSONL request specs and writes JSONL results
+pub struct StreamRunner {
+    client: HttpClient,
+    auth: AuthScheme,
+    concurrency: usize,
+}
+
+impl StreamRunner {
+    /// Create a new runner
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            auth: AuthScheme::None,
+            concurrency: 1,
+        }
+    }
+
+    /// Apply an auth scheme to every request
+    pub fn with_auth(mut self, auth: AuthScheme) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Set how many requests run at once
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Execute a single input line
+    pub fn execute_line(&self, line_number: usize, line: &str) -> StreamResult {
+        let spec = match RequestSpec::parse(line) {
+            Ok(spec) => spec,
+            Err(e) => return StreamResult::error(line_number, None, &e),
+        };
+
+        let prepared = spec.to_request_builder().and_then(|request| {
+            let assertions = spec.assertions()?;
+            Ok((request.auth(self.auth.clone()), assertions))
+        });
+        let (request, assertions) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => return StreamResult::error(line_number, Some(&spec), &e),
+        };
+
+        let response = match self.client.execute(&request) {
+            Ok(response) => response,
+            Err(e) => return StreamResult::error(line_number, Some(&spec), &e),
+        };
+
+        let mut result = StreamResult {
+            line: line_number,
+            name: spec.name.clone(),
+            method: Some(request.method.as_str().to_string()),
+            url: Some(spec.url.clone()),
+            status: Some(response.status.as_u16()),
+            duration_ms: Some(response.duration.as_millis()),
+            assertions_passed: 0,
+            assertions_failed: 0,
+            failures: Vec::new(),
+            error: None,
+            success: true,
+        };
+
+        if !assertions.is_empty() {
+            match validate_response(&response, &assertions) {
+                Ok(report) => {
+                    result.assertions_passed = report.passed;
+                    result.assertions_failed = report.failed;
+                    result.failures = report
+                        .results
+                        .iter()
+                        .filter(|r| !r.passed)
+                        .map(|r| r.summary())
+                        .collect();
+                    result.success = report.success;
+                }
+                Err(e) => {
+                    result.error = Some(e.to_string());
+                    result.success = false;
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Read specs from `input`, write one result line each to `output`
+    ///
+    /// Returns whether every request and assertion succeeded.
+    pub fn run<R: BufRead, W: Write>(&self, input: R, mut output: W) -> Result<bool> {
+        let mut all_passed = true;
+        let mut batch: Vec<(usize, String)> = Vec::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            batch.push((index + 1, line));
+
+            if batch.len() >= self.concurrency {
+                all_passed &= self.run_batch(&batch, &mut output)?;
+                batch.clear();
+            }
+        }
+
+        if !batch.is_empty() {
+            all_passed &= self.run_batch(&batch, &mut output)?;
+        }
+
+        Ok(all_passed)
+    }
+
+    /// Run a batch concurrently and write results in input order
+    fn run_batch<W: Write>(&self, batch: &[(usize, String)], output: &mut W) -> Result<bool> {
+        let results: Vec<StreamResult> = if batch.len() == 1 {
+            vec![self.execute_line(batch[0].0, &batch[0].1)]
+        } else {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|(number, line)| scope.spawn(move || self.execute_line(*number, line)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("request worker panicked"))
+                    .collect()
+            })
+        };
+
+        let mut all_passed = true;
+        for result in results {
+            all_passed &= result.success;
+            writeln!(output, "{}", serde_json::to_string(&result)?)?;
+        }
+        output.flush()?;
+
+        Ok(all_passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_spec_parse() {
+        let spec = RequestSpec::parse(
+            r#"{"method":"post","url":"https://example.com","headers":{"X-A":"1"},"body":{"k":"v"},"assert":["status == 200"]}"#,
+        )
+        .unwrap();
+
+        let request = spec.to_request_builder().unwrap();
+        assert_eq!(request.method, HttpMethod::Post);
+        assert_eq!(request.headers, vec!["X-A:1".to_string()]);
+        assert_eq!(request.body.as_deref(), Some(r#"{"k":"v"}"#));
+        assert_eq!(spec.assertions().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_request_spec_defaults() {
+        let spec = RequestSpec::parse(r#"{"url":"https://example.com","body":"raw"}"#).unwrap();
+        let request = spec.to_request_builder().unwrap();
+        assert_eq!(request.method, HttpMethod::Get);
+        assert_eq!(request.body.as_deref(), Some("raw"));
+    }
+
+    #[test]
+    fn test_run_stream() {
+        let mut server = mockito::Server::new();
+        let _ok = server
+            .mock("GET", "/ok")
+            .with_status(200)
+            .with_body(r#"{"count":2}"#)
+            .create();
+        let _missing = server.mock("GET", "/missing").with_status(404).create();
+
+        let url = server.url();
+        let input = [
+            format!(
+                r#"{{"name":"ok","url":"{}/ok","assert":["status == 200","$.count >= 1"]}}"#,
+                url
+            ),
+            "not json".to_string(),
+            String::new(),
+            format!(r#"{{"url":"{}/missing","assert":["status == 200"]}}"#, url),
+            format!(r#"{{"url":"{}/ok","assert":["bogus"]}}"#, url),
+        ]
+        .join("\n");
+
+        let runner = StreamRunner::new(HttpClient::new()).with_concurrency(2);
+        let mut output = Vec::new();
+        let all_passed = runner.run(input.as_bytes(), &mut output).unwrap();
+        assert!(!all_passed);
+
+        let results: Vec<StreamResult> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(results.len(), 4);
+
+        assert_eq!(results[0].line, 1);
+        assert_eq!(results[0].name.as_deref(), Some("ok"));
+        assert_eq!(results[0].status, Some(200));
+        assert_eq!(results[0].assertions_passed, 2);
+        assert!(results[0].success);
+
+        assert_eq!(results[1].line, 2);
+        assert!(results[1].error.is_some());
+        assert!(!results[1].success);
+
+        assert_eq!(results[2].line, 4);
+        assert_eq!(results[2].status, Some(404));
+        assert_eq!(results[2].assertions_failed, 1);
+        assert!(!results[2].success);
+
+        assert_eq!(results[3].line, 5);
+        assert!(results[3]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("Invalid assertion"));
+    }
+}
@@ -167,3 +167,727 @@ fn test_500_error() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_cli_json_output() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"ok":true}"#)
+        .create();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+        .args(["get", &format!("{}/json", server.url()), "--output", "json"])
+        .arg("--no-history")
+        .output()
+        .expect("failed to run binary");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["status"], 200);
+    assert_eq!(json["headers"]["content-type"], "application/json");
+    assert_eq!(json["body"], r#"{"ok":true}"#);
+    assert!(json["duration_ms"].is_u64());
+}
+
+#[test]
+fn test_cli_quiet_output() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/missing")
+        .with_status(404)
+        .with_body("nope")
+        .create();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+        .args(["get", &format!("{}/missing", server.url()), "--quiet"])
+        .arg("--no-history")
+        .output()
+        .expect("failed to run binary");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(stdout.starts_with("404 Not Found  "));
+    assert!(stdout.trim_end().ends_with("ms  4 B"));
+}
+
+#[test]
+fn test_cli_body_and_status_only() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/user")
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"name":"bazz"}"#)
+        .create();
+
+    let run = |flags: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+            .args(["get", &format!("{}/user", server.url()), "--no-history"])
+            .args(flags)
+            .output()
+            .expect("failed to run binary");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(run(&["--body-only"]), "{\n  \"name\": \"bazz\"\n}\n");
+    assert_eq!(run(&["--status-only"]), "201 Created\n");
+    assert_eq!(run(&["--quiet", "--status-only"]), "201 Created\n");
+}
+
+#[test]
+fn test_cli_dry_run_sends_nothing() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", mockito::Matcher::Any)
+        .expect(0)
+        .create();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+        .args([
+            "post",
+            &format!("{}/users", server.url()),
+            "--no-history",
+            "--dry-run",
+        ])
+        .args([
+            "-H",
+            "Content-Type: application/json",
+            "-b",
+            r#"{"name":"bazz"}"#,
+        ])
+        .args(["--color", "never"])
+        .output()
+        .expect("failed to run binary");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "> POST {}/users\n> Content-Type: application/json\n>\n{{\"name\":\"bazz\"}}\n",
+            server.url()
+        )
+    );
+
+    let step = bazzounquester::workflow::WorkflowStep::new(
+        "Create".to_string(),
+        HttpMethod::Post,
+        format!("{}/{{{{resource}}}}", server.url()),
+    );
+    let chain = bazzounquester::workflow::RequestChain::new("Dry".to_string())
+        .with_variable("resource".to_string(), "users".to_string())
+        .add_step(step);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.json");
+    std::fs::write(&path, serde_json::to_string(&chain).unwrap()).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+        .args([
+            "workflow",
+            "run",
+            path.to_str().unwrap(),
+            "--dry-run",
+            "--color",
+            "never",
+        ])
+        .output()
+        .expect("failed to run binary");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("Step 1: Create\n> POST {}/users\n>\n", server.url())
+    );
+    mock.assert();
+}
+
+#[test]
+fn test_cli_body_from_file() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("PATCH", "/users/1")
+        .match_body(r#"{"name":"from file"}"#)
+        .create();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("body.json");
+    std::fs::write(&path, r#"{"name":"from file"}"#).unwrap();
+
+    let run = |body: &str| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+            .args([
+                "patch",
+                &format!("{}/users/1", server.url()),
+                "--no-history",
+            ])
+            .args(["-b", body, "--quiet"])
+            .output()
+            .expect("failed to run binary")
+    };
+
+    assert!(run(&format!("@{}", path.display())).status.success());
+    mock.assert();
+
+    let missing = run("@missing.json");
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("missing.json"));
+}
+
+#[test]
+fn test_cli_fail_exit_codes() {
+    let mut server = mockito::Server::new();
+    let _not_found = server.mock("GET", "/missing").with_status(404).create();
+    let _broken = server.mock("GET", "/broken").with_status(500).create();
+    let _ok = server.mock("GET", "/ok").with_status(200).create();
+
+    let run = |path: &str, fail: bool| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"));
+        command.args(["get", &format!("{}{}", server.url(), path), "--no-history"]);
+        if fail {
+            command.arg("--fail");
+        }
+        command
+            .output()
+            .expect("failed to run binary")
+            .status
+            .code()
+    };
+
+    assert_eq!(run("/missing", true), Some(4));
+    assert_eq!(run("/broken", true), Some(5));
+    assert_eq!(run("/ok", true), Some(0));
+    assert_eq!(run("/missing", false), Some(0));
+}
+
+#[test]
+fn test_cli_graphql_fail_lists_errors_on_error_status() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("POST", "/graphql")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"errors":[{"message":"Cannot query field \"nope\""}]}"#)
+        .create();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+        .args([
+            "graphql",
+            &format!("{}/graphql", server.url()),
+            "--query",
+            "{ nope }",
+            "--no-history",
+            "--fail",
+        ])
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run binary");
+
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("GraphQL returned 1 error(s)"), "{}", stderr);
+    assert!(stderr.contains("Cannot query field"), "{}", stderr);
+}
+
+#[test]
+fn test_cli_fail_with_body() {
+    let mut server = mockito::Server::new();
+    let _not_found = server
+        .mock("GET", "/missing")
+        .with_status(404)
+        .with_body("no such thing")
+        .expect_at_least(1)
+        .create();
+
+    let run = |flag: &str| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+            .args([
+                "get",
+                &format!("{}/missing", server.url()),
+                "--no-history",
+                "--body-only",
+                flag,
+            ])
+            .output()
+            .expect("failed to run binary")
+    };
+
+    let with_body = run("--fail-with-body");
+    assert_eq!(with_body.status.code(), Some(4));
+    assert_eq!(
+        String::from_utf8_lossy(&with_body.stdout),
+        "no such thing\n"
+    );
+
+    let without_body = run("--fail");
+    assert_eq!(without_body.status.code(), Some(4));
+    assert!(without_body.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&without_body.stderr).contains("404 Not Found"));
+}
+
+#[test]
+fn test_cli_writes_assertion_reports() {
+    let mut server = mockito::Server::new();
+    let _ok = server.mock("GET", "/ok").with_body("{}").create();
+    let dir = tempfile::tempdir().unwrap();
+    let xml = dir.path().join("report.xml");
+    let json = dir.path().join("report.json");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+        .args([
+            "get",
+            &format!("{}/ok", server.url()),
+            "--no-history",
+            "--quiet",
+            "--assert-status",
+            "201",
+            "--report",
+            &format!("junit={}", xml.display()),
+            "--report",
+            &format!("json={}", json.display()),
+        ])
+        .output()
+        .expect("failed to run binary");
+
+    assert_eq!(output.status.code(), Some(1));
+    let xml = std::fs::read_to_string(xml).unwrap();
+    assert!(xml.contains(r#"<testsuite name="assertions" tests="1" failures="1""#));
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json).unwrap()).unwrap();
+    assert_eq!(json["testcases"][0]["status"], "failed");
+}
+
+#[test]
+fn test_cli_max_time_cuts_off_slow_request() {
+    // Accepts connections but never answers
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/slow", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let mut held = Vec::new();
+        for stream in listener.incoming().flatten() {
+            held.push(stream);
+        }
+    });
+
+    let started = std::time::Instant::now();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+        .args(["get", &url, "--max-time", "500ms", "--no-history"])
+        .output()
+        .expect("failed to run binary");
+
+    assert_eq!(output.status.code(), Some(124));
+    assert!(started.elapsed() < std::time::Duration::from_secs(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--max-time"));
+}
+
+#[test]
+fn test_cli_no_color() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/data")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"ok":true}"#)
+        .create();
+    let url = format!("{}/data", server.url());
+
+    let run = |args: &[&str], no_color: bool| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"));
+        command.arg("get").arg(&url).arg("--no-history").args(args);
+        if no_color {
+            command.env("NO_COLOR", "1");
+        } else {
+            command.env_remove("NO_COLOR");
+        }
+        let output = command.output().expect("failed to run binary");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert!(!run(&[], true).contains('\x1b'));
+    assert!(!run(&["--color", "never"], false).contains('\x1b'));
+    assert!(run(&["--color", "always"], true).contains('\x1b'));
+}
+
+#[test]
+fn test_cli_no_color_env_var_disables_auto_color() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("GET", "/data")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"ok":true}"#)
+        .create();
+    let url = format!("{}/data", server.url());
+
+    // CLICOLOR_FORCE makes the piped output count as a terminal, so only
+    // NO_COLOR can be what turns color off
+    let run = |no_color: bool| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"));
+        command
+            .args(["get", &url, "--no-history", "--color", "auto"])
+            .env("CLICOLOR_FORCE", "1");
+        if no_color {
+            command.env("NO_COLOR", "1");
+        } else {
+            command.env_remove("NO_COLOR");
+        }
+        let output = command.output().expect("failed to run binary");
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert!(run(false).contains('\x1b'));
+    let plain = run(true);
+    assert!(!plain.contains('\x1b'), "{:?}", plain);
+    assert!(plain.contains("200 OK"));
+}
+
+#[test]
+fn test_cli_highlights_json_body() {
+    let mut server = mockito::Server::new();
+    let _json = server
+        .mock("GET", "/json")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"name":"bazz","count":3,"ok":true}"#)
+        .create();
+    let _text = server
+        .mock("GET", "/text")
+        .with_body("plain \"text\" 42")
+        .create();
+
+    let body = |path: &str, args: &[&str], no_color: bool| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"));
+        command
+            .arg("get")
+            .arg(format!("{}{}", server.url(), path))
+            .arg("--no-history")
+            .args(args);
+        if no_color {
+            command.env("NO_COLOR", "1");
+        } else {
+            command.env_remove("NO_COLOR");
+        }
+        let output = command.output().expect("failed to run binary");
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let (_, body) = stdout.split_once("Response Body:").unwrap();
+        body.split_once('\n').unwrap().1.to_string()
+    };
+
+    let colored = body("/json", &["--color", "always"], false);
+    assert!(colored.contains("\x1b["), "{:?}", colored);
+    assert!(!colored.contains("\"name\": \"bazz\""));
+
+    let plain = body("/json", &[], true);
+    assert!(!plain.contains('\x1b'));
+    assert!(plain.contains("\"name\": \"bazz\""));
+
+    let text = body("/text", &["--color", "always"], false);
+    assert!(text.starts_with("plain \"text\" 42"), "{:?}", text);
+}
+
+#[test]
+fn test_cli_masks_secrets_in_displayed_body() {
+    let mut server = mockito::Server::new();
+    let _mock = server
+        .mock("POST", "/login")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"access_token":"tok-123","expires_in":3600}"#)
+        .create();
+    let url = format!("{}/login", server.url());
+    let home = tempfile::TempDir::new().unwrap();
+
+    let run = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+            .arg("post")
+            .arg(&url)
+            .args(args)
+            .env("HOME", home.path())
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run binary");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let masked = run(&[]);
+    assert!(!masked.contains("tok-123"));
+    assert!(masked.contains("use --show-secrets"));
+    assert!(masked.contains("3600"));
+
+    assert!(run(&["--show-secrets"]).contains("tok-123"));
+    assert!(!run(&["--secret-field", "expires_in"]).contains("3600"));
+}
+
+#[test]
+fn test_cli_session_cookies_persist() {
+    let mut server = mockito::Server::new();
+    let _login = server
+        .mock("GET", "/login")
+        .with_header("set-cookie", "sid=abc123; Path=/")
+        .create();
+    let profile = server
+        .mock("GET", "/profile")
+        .match_header("cookie", "sid=abc123")
+        .create();
+
+    let home = tempfile::TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+            .args(args)
+            .env("HOME", home.path())
+            .env("XDG_DATA_HOME", home.path().join("data"))
+            .output()
+            .expect("failed to run binary")
+    };
+
+    assert!(run(&["session", "create", "test"]).status.success());
+    assert!(run(&["get", &format!("{}/login", server.url())])
+        .status
+        .success());
+    assert!(run(&["get", &format!("{}/profile", server.url())])
+        .status
+        .success());
+    profile.assert();
+
+    let cookies = String::from_utf8(run(&["session", "cookies"]).stdout).unwrap();
+    assert!(cookies.contains("sid"));
+    assert!(cookies.contains("abc123"));
+}
+
+#[test]
+fn test_cli_history_list_and_replay() {
+    use bazzounquester::history::{HistoryEntry, HistoryStorage, RequestLog};
+
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/items")
+        .match_query(mockito::Matcher::UrlEncoded("page".into(), "2".into()))
+        .with_body("[]")
+        .create();
+
+    let home = tempfile::TempDir::new().unwrap();
+    let data = home.path().join("data");
+    let storage = HistoryStorage::new(data.join("bazzounquester").join("history")).unwrap();
+    let mut log = RequestLog::new("GET".to_string(), format!("{}/items", server.url()));
+    log.query_params.push(("page".to_string(), "2".to_string()));
+    let entry = HistoryEntry::new(log);
+    storage.save_entry(&entry).unwrap();
+    let short_id = entry.id.to_string()[..8].to_string();
+
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+            .args(args)
+            .env("HOME", home.path())
+            .env("XDG_DATA_HOME", &data)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run binary")
+    };
+
+    let listed = String::from_utf8(run(&["history", "list"]).stdout).unwrap();
+    assert!(listed.contains(&short_id));
+    assert!(listed.contains("/items"));
+
+    assert!(run(&["history", "replay", &short_id]).status.success());
+    mock.assert();
+    assert_eq!(storage.load_all().unwrap().len(), 2);
+
+    let filtered = String::from_utf8(run(&["history", "list", "--status", "200"]).stdout).unwrap();
+    assert_eq!(filtered.lines().count(), 1);
+    assert!(!filtered.contains(&short_id));
+}
+
+#[test]
+fn test_cli_requests_are_recorded_in_history() {
+    let mut server = mockito::Server::new();
+    let _small = server
+        .mock("POST", "/items")
+        .with_status(201)
+        .with_body(r#"{"id":1}"#)
+        .create();
+    let _large = server
+        .mock("GET", "/large")
+        .with_body("x".repeat(100))
+        .create();
+
+    let home = tempfile::TempDir::new().unwrap();
+    let data = home.path().join("data");
+    let history_dir = data.join("bazzounquester").join("history");
+    let run = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+            .args(args)
+            .env("HOME", home.path())
+            .env("XDG_DATA_HOME", &data)
+            .output()
+            .expect("failed to run binary");
+        assert!(output.status.success());
+    };
+    let saved_entries = || -> Vec<serde_json::Value> {
+        std::fs::read_dir(&history_dir)
+            .map(|dir| {
+                dir.flatten()
+                    .map(|file| {
+                        let json = std::fs::read_to_string(file.path()).unwrap();
+                        serde_json::from_str(&json).unwrap()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let url = format!("{}/items", server.url());
+    run(&["post", &url, "-b", r#"{"name":"a"}"#]);
+    let entries = saved_entries();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["request"]["method"], "POST");
+    assert_eq!(entries[0]["request"]["url"], url);
+    assert_eq!(entries[0]["request"]["body"], r#"{"name":"a"}"#);
+    assert_eq!(entries[0]["response"]["status_code"], 201);
+    assert_eq!(entries[0]["response"]["body"], r#"{"id":1}"#);
+
+    run(&["post", &url, "--no-history"]);
+    assert_eq!(saved_entries().len(), 1);
+
+    run(&[
+        "get",
+        &format!("{}/large", server.url()),
+        "--history-max-body",
+        "10",
+    ]);
+    let entries = saved_entries();
+    let large = entries
+        .iter()
+        .find(|e| e["request"]["method"] == "GET")
+        .unwrap();
+    assert!(large["response"]["body"]
+        .as_str()
+        .unwrap()
+        .starts_with("xxxxxxxxxx\n… [truncated"));
+    assert_eq!(large["response"]["body_size"], 100);
+}
+
+#[test]
+fn test_cli_matrix_across_environments() {
+    use bazzounquester::collections::{Collection, CollectionStorage, RequestItem};
+    use bazzounquester::env::{Environment, EnvironmentManager};
+
+    let mut server = mockito::Server::new();
+    let _dev = server
+        .mock("GET", "/dev/health")
+        .with_body(r#"{"version":"2.1"}"#)
+        .create();
+    let _prod = server.mock("GET", "/prod/health").with_status(500).create();
+
+    let home = tempfile::TempDir::new().unwrap();
+    let data = home.path().join("data");
+    let app_dir = data.join("bazzounquester");
+
+    let mut collection = Collection::new("API".to_string());
+    collection.add_request(RequestItem::new(
+        "Health".to_string(),
+        HttpMethod::Get,
+        "{{BASE_URL}}/health".to_string(),
+    ));
+    CollectionStorage::new(app_dir.join("collections"))
+        .unwrap()
+        .save(&collection)
+        .unwrap();
+
+    let mut manager = EnvironmentManager::new(app_dir.join("environments")).unwrap();
+    for name in ["dev", "prod"] {
+        let mut env = Environment::new(name.to_string());
+        env.set_variable("BASE_URL".to_string(), format!("{}/{}", server.url(), name));
+        manager.add_environment(env);
+    }
+    manager.save_all().unwrap();
+
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+            .args(["matrix", "--request", "API/Health", "--envs", "dev,prod"])
+            .args(args)
+            .env("HOME", home.path())
+            .env("XDG_DATA_HOME", &data)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run binary")
+    };
+
+    let output = run(&["--extract", "$.version"]);
+    assert_eq!(output.status.code(), Some(1));
+    let table = String::from_utf8(output.stdout).unwrap();
+    assert!(table.contains("2.1"));
+    assert!(table
+        .lines()
+        .any(|l| l.starts_with("prod") && l.contains("500")));
+
+    assert!(run(&["--best-effort"]).status.success());
+
+    let output = run(&["--output", "json", "--best-effort"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["environments"], serde_json::json!(["dev", "prod"]));
+    assert_eq!(json["cells"][1]["status"], 500);
+}
+
+#[test]
+fn test_cli_workflow_data_file() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/signup")
+        .match_body(mockito::Matcher::Regex(
+            r#"^\{"email":"\w+@example.com"\}$"#.to_string(),
+        ))
+        .with_status(201)
+        .expect(3)
+        .create();
+
+    let step = bazzounquester::workflow::WorkflowStep::new(
+        "Signup".to_string(),
+        HttpMethod::Post,
+        format!("{}/signup", server.url()),
+    )
+    .with_body(r#"{"email":"{{email}}"}"#.to_string());
+    let chain = bazzounquester::workflow::RequestChain::new("Signup".to_string()).add_step(step);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("chain.json");
+    std::fs::write(&path, serde_json::to_string(&chain).unwrap()).unwrap();
+    let data = dir.path().join("users.csv");
+    std::fs::write(
+        &data,
+        "email\nada@example.com\ngrace@example.com\nalan@example.com\n",
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+        .args(["workflow", "run", path.to_str().unwrap()])
+        .args(["--data", data.to_str().unwrap()])
+        .env("HOME", dir.path())
+        .env("XDG_DATA_HOME", dir.path().join("data"))
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("✓ Iteration 3, row 3 (email=alan@example.com)"));
+    mock.assert();
+}
+
+#[test]
+fn test_cli_workflow_validate() {
+    let dir = tempfile::tempdir().unwrap();
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_bazzounquester"))
+            .args(["workflow", "validate", "tests/fixtures/workflow.yaml"])
+            .args(args)
+            .env("HOME", dir.path())
+            .env("XDG_DATA_HOME", dir.path().join("data"))
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("failed to run binary")
+    };
+
+    let output = run(&[]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Step 1 'Login' uses {{password}} before it is defined"));
+
+    let output = run(&["--var", "password=hunter2"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("✓ Workflow 'Create user' is valid (3 steps)"));
+}
@@ -0,0 +1,40 @@
+//! The core http + assertions + env path, built with `--no-default-features`
+//!
+//! Only uses modules that are always compiled, so this also runs in the
+//! minimal feature check in CI.
+
+use bazzounquester::{
+    assertions::{validate_response, Assertion, Matcher},
+    env::{Environment, VariableSubstitutor},
+    http::{HttpClient, HttpMethod, RequestBuilder, ResponseFormatter},
+    Result,
+};
+
+#[test]
+fn test_core_request_with_env_and_assertions() -> Result<()> {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/v1/users")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"users":[{"name":"Ada"}]}"#)
+        .create();
+
+    let mut env = Environment::new("local".to_string());
+    env.set_variable("BASE_URL".to_string(), server.url());
+    let url =
+        VariableSubstitutor::new().substitute("{{BASE_URL}}/v1/users", &env.enabled_variables());
+
+    let request = RequestBuilder::new(HttpMethod::Get, url);
+    let response = HttpClient::new().execute(&request)?;
+    mock.assert();
+
+    let report = validate_response(&response, &[Assertion::status_code(Matcher::equals(200))])?;
+    assert!(report.success);
+
+    let output = ResponseFormatter::format(&response);
+    assert!(output.contains("200"));
+    assert!(output.contains("Ada"));
+
+    Ok(())
+}